@@ -0,0 +1,665 @@
+use std::any::TypeId;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+
+use crate::{
+    history::{EditTarget, EditorHistory},
+    play,
+};
+
+const NODE_SIZE: egui::Vec2 = egui::vec2(140.0, 40.0);
+const CURRENT_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 170, 90);
+const SELECTED_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 40);
+/// Distance from a transition within which clicks select it, in egui points.
+const TRANSITION_PICK_DISTANCE: f32 = 6.0;
+
+pub struct EditorAnimationGraphPlugin;
+impl Plugin for EditorAnimationGraphPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AnimationStateMachine>()
+            .register_type::<AnimationGraphState>()
+            .register_type::<AnimationGraphTransition>()
+            .register_type::<AnimationGraphParameter>()
+            .register_type::<TransitionCondition>()
+            .init_resource::<AnimationGraphEditor>()
+            .add_systems(Update, evaluate_state_machines.run_if(play::simulating));
+    }
+}
+
+/// A state of an [`AnimationStateMachine`], playing one clip.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct AnimationGraphState {
+    pub name: String,
+    /// Asset path of the clip, like `models/character.glb#Animation0`.
+    pub clip: String,
+    pub speed: f32,
+    pub repeat: bool,
+    /// Where the state is drawn in the graph tab.
+    pub position: Vec2,
+}
+
+impl Default for AnimationGraphState {
+    fn default() -> Self {
+        Self {
+            name: "State".to_string(),
+            clip: String::new(),
+            speed: 1.0,
+            repeat: true,
+            position: Vec2::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub enum TransitionCondition {
+    /// The clip of the state played to its end.
+    #[default]
+    ClipFinished,
+    Above {
+        parameter: String,
+        value: f32,
+    },
+    Below {
+        parameter: String,
+        value: f32,
+    },
+}
+
+/// A transition between two states of an [`AnimationStateMachine`], by their index.
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub struct AnimationGraphTransition {
+    pub from: usize,
+    pub to: usize,
+    pub condition: TransitionCondition,
+    /// Seconds over which the clips are blended.
+    pub duration: f32,
+}
+
+/// A value set by the game, which transitions depend on.
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+pub struct AnimationGraphParameter {
+    pub name: String,
+    pub value: f32,
+}
+
+/// Plays the clips of its states on the [`AnimationPlayer`] of the entity or of its first
+/// descendant with one, switching states along the first transition whose condition is met.
+///
+/// The state machine runs while the game is simulated, and is edited in the animation graph tab.
+/// Clips are blended only during transitions, since the player plays one clip at a time.
+#[derive(Component, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct AnimationStateMachine {
+    pub states: Vec<AnimationGraphState>,
+    pub transitions: Vec<AnimationGraphTransition>,
+    pub parameters: Vec<AnimationGraphParameter>,
+    /// Index of the state played first.
+    pub entry: usize,
+    #[reflect(ignore)]
+    current: Option<usize>,
+}
+
+impl AnimationStateMachine {
+    pub fn parameter(&self, name: &str) -> Option<f32> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .map(|parameter| parameter.value)
+    }
+
+    /// Sets the parameter `name`, adding it if the machine doesn't have it yet.
+    pub fn set_parameter(&mut self, name: &str, value: f32) {
+        match self
+            .parameters
+            .iter_mut()
+            .find(|parameter| parameter.name == name)
+        {
+            Some(parameter) => parameter.value = value,
+            None => self.parameters.push(AnimationGraphParameter {
+                name: name.to_string(),
+                value,
+            }),
+        }
+    }
+
+    /// Index of the state being played.
+    pub fn current_state(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Removes the state `index` with its transitions.
+    pub fn remove_state(&mut self, index: usize) {
+        self.states.remove(index);
+        self.transitions
+            .retain(|transition| transition.from != index && transition.to != index);
+        for transition in &mut self.transitions {
+            transition.from -= (transition.from > index) as usize;
+            transition.to -= (transition.to > index) as usize;
+        }
+        self.entry = match self.entry {
+            entry if entry > index => entry - 1,
+            entry if entry == index => 0,
+            entry => entry,
+        };
+        self.current = None;
+    }
+
+    fn condition_met(&self, condition: &TransitionCondition, clip_finished: bool) -> bool {
+        match condition {
+            TransitionCondition::ClipFinished => clip_finished,
+            TransitionCondition::Above { parameter, value } => self
+                .parameter(parameter)
+                .is_some_and(|current| current > *value),
+            TransitionCondition::Below { parameter, value } => self
+                .parameter(parameter)
+                .is_some_and(|current| current < *value),
+        }
+    }
+}
+
+/// The entity or its first descendant with an [`AnimationPlayer`].
+fn find_player(
+    entity: Entity,
+    children: &Query<&Children>,
+    players: &Query<&mut AnimationPlayer>,
+) -> Option<Entity> {
+    if players.contains(entity) {
+        return Some(entity);
+    }
+    children
+        .iter_descendants(entity)
+        .find(|&descendant| players.contains(descendant))
+}
+
+fn evaluate_state_machines(
+    mut machines: Query<(Entity, &mut AnimationStateMachine)>,
+    children: Query<&Children>,
+    mut players: Query<&mut AnimationPlayer>,
+    clips: Res<Assets<AnimationClip>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, mut machine) in &mut machines {
+        let Some(player) = find_player(entity, &children, &players) else {
+            continue;
+        };
+        let mut player = players.get_mut(player).unwrap();
+        let next = match machine.current {
+            None => (machine.entry < machine.states.len()).then_some((machine.entry, 0.0)),
+            Some(current) => {
+                let state = &machine.states[current];
+                let clip = asset_server.get_handle::<AnimationClip, _>(state.clip.as_str());
+                let clip_finished = clips
+                    .get(&clip)
+                    .is_none_or(|clip| player.elapsed() >= clip.duration());
+                machine
+                    .transitions
+                    .iter()
+                    .filter(|transition| transition.from == current)
+                    .find(|transition| machine.condition_met(&transition.condition, clip_finished))
+                    .map(|transition| (transition.to, transition.duration))
+            }
+        };
+        let Some((next, duration)) = next else {
+            continue;
+        };
+
+        let state = &machine.states[next];
+        let clip = asset_server.load(state.clip.as_str());
+        match duration > 0.0 {
+            true => player.play_with_transition(clip, std::time::Duration::from_secs_f32(duration)),
+            false => player.start(clip),
+        };
+        player.set_speed(state.speed);
+        match state.repeat {
+            true => player.repeat(),
+            false => player.stop_repeating(),
+        };
+        // the current state isn't reflected, so it's no edit
+        machine.bypass_change_detection().current = Some(next);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GraphItem {
+    State(usize),
+    Transition(usize),
+}
+
+/// State of the animation graph tab.
+#[derive(Resource, Default)]
+pub struct AnimationGraphEditor {
+    pan: egui::Vec2,
+    selected: Option<GraphItem>,
+    /// The state a transition is being dragged from.
+    connecting: Option<usize>,
+    /// Where the context menu of the graph was opened, in graph coordinates.
+    menu_pos: Vec2,
+}
+
+/// The first entity with an [`AnimationStateMachine`] at or below the last selected entity.
+fn selected_machine(world: &mut World, selected: &SelectedEntities) -> Option<Entity> {
+    let root = selected.iter().last()?;
+    crate::scene::with_descendants(world, &[root])
+        .into_iter()
+        .find(|&entity| world.get::<AnimationStateMachine>(entity).is_some())
+}
+
+/// Asset paths of the loaded animation clips.
+fn clip_paths(world: &World) -> Vec<String> {
+    let asset_server = world.resource::<AssetServer>();
+    let mut paths: Vec<_> = world
+        .resource::<Assets<AnimationClip>>()
+        .ids()
+        .filter_map(|id| asset_server.get_handle_path(id))
+        .map(|path| match path.label() {
+            Some(label) => format!("{}#{label}", path.path().display()),
+            None => path.path().display().to_string(),
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Edits the state machine of the selected entity as a graph of states connected by
+/// transitions, next to the properties of the selected state or transition.
+pub fn animation_graph_ui(world: &mut World, ui: &mut egui::Ui, selected: &SelectedEntities) {
+    let Some(entity) = selected_machine(world, selected) else {
+        match selected.iter().last() {
+            Some(entity) => {
+                if ui.button("Add AnimationStateMachine").clicked() {
+                    world.entity_mut(entity).insert(AnimationStateMachine {
+                        states: vec![AnimationGraphState {
+                            name: "Idle".to_string(),
+                            ..default()
+                        }],
+                        ..default()
+                    });
+                }
+            }
+            None => {
+                ui.label("Select an entity to edit its animation state machine");
+            }
+        }
+        return;
+    };
+    let before = world.get::<AnimationStateMachine>(entity).unwrap().clone();
+    let mut machine = before.clone();
+    let clip_paths = clip_paths(world);
+
+    world.resource_scope(|_, mut editor: Mut<AnimationGraphEditor>| {
+        // indices of removed states and transitions may now point to other ones
+        editor.selected = editor.selected.filter(|item| match *item {
+            GraphItem::State(index) => index < machine.states.len(),
+            GraphItem::Transition(index) => index < machine.transitions.len(),
+        });
+        egui::SidePanel::right("animation_graph_properties")
+            .resizable(true)
+            .default_width(240.0)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    properties_ui(ui, &mut machine, &mut editor, &clip_paths);
+                });
+            });
+        graph_ui(ui, &mut machine, &mut editor);
+    });
+
+    if machine != before {
+        let after = machine.clone_value();
+        *world.get_mut::<AnimationStateMachine>(entity).unwrap() = machine;
+        world.resource_mut::<EditorHistory>().record(
+            EditTarget::Component {
+                entity,
+                type_id: TypeId::of::<AnimationStateMachine>(),
+            },
+            before.clone_value(),
+            after,
+        );
+    }
+}
+
+fn properties_ui(
+    ui: &mut egui::Ui,
+    machine: &mut AnimationStateMachine,
+    editor: &mut AnimationGraphEditor,
+    clip_paths: &[String],
+) {
+    ui.heading("Parameters");
+    let mut removed = None;
+    egui::Grid::new("animation_graph_parameters")
+        .num_columns(3)
+        .show(ui, |ui| {
+            for (index, parameter) in machine.parameters.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut parameter.name).desired_width(100.0));
+                ui.add(egui::DragValue::new(&mut parameter.value).speed(0.05));
+                if ui.small_button("🗑").clicked() {
+                    removed = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+    if let Some(index) = removed {
+        machine.parameters.remove(index);
+    }
+    if ui.button("Add parameter").clicked() {
+        let name = format!("parameter{}", machine.parameters.len());
+        machine.set_parameter(&name, 0.0);
+    }
+    ui.separator();
+
+    match editor.selected {
+        Some(GraphItem::State(index)) => {
+            ui.heading("State");
+            let is_entry = machine.entry == index;
+            let state = &mut machine.states[index];
+            ui.text_edit_singleline(&mut state.name);
+            ui.horizontal(|ui| {
+                ui.label("Clip");
+                ui.text_edit_singleline(&mut state.clip);
+            });
+            egui::ComboBox::from_id_source("animation_graph_clip")
+                .selected_text("Loaded clips")
+                .width(ui.available_width())
+                .show_ui(ui, |ui| {
+                    for path in clip_paths {
+                        ui.selectable_value(&mut state.clip, path.clone(), path);
+                    }
+                });
+            ui.horizontal(|ui| {
+                ui.label("Speed");
+                ui.add(egui::DragValue::new(&mut state.speed).speed(0.01));
+                ui.checkbox(&mut state.repeat, "Loop");
+            });
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!is_entry, egui::Button::new("Set as entry"))
+                    .clicked()
+                {
+                    machine.entry = index;
+                }
+                if ui.button("Delete").clicked() {
+                    machine.remove_state(index);
+                    editor.selected = None;
+                }
+            });
+        }
+        Some(GraphItem::Transition(index)) => {
+            ui.heading("Transition");
+            let names = (
+                machine.states[machine.transitions[index].from].name.clone(),
+                machine.states[machine.transitions[index].to].name.clone(),
+            );
+            let parameters: Vec<_> = machine
+                .parameters
+                .iter()
+                .map(|parameter| parameter.name.clone())
+                .collect();
+            let transition = &mut machine.transitions[index];
+            ui.label(format!("{} → {}", names.0, names.1));
+            condition_ui(ui, &mut transition.condition, &parameters);
+            ui.horizontal(|ui| {
+                ui.label("Blend");
+                ui.add(
+                    egui::DragValue::new(&mut transition.duration)
+                        .speed(0.01)
+                        .clamp_range(0.0..=10.0)
+                        .suffix(" s"),
+                );
+            });
+            if ui.button("Delete").clicked() {
+                machine.transitions.remove(index);
+                editor.selected = None;
+            }
+        }
+        None => {
+            ui.weak("Right click the graph to add states, and drag from a state with Shift to connect it to another");
+        }
+    }
+
+    if let Some(current) = machine.current_state() {
+        ui.separator();
+        ui.label(format!("Playing {}", machine.states[current].name));
+    }
+}
+
+fn condition_ui(ui: &mut egui::Ui, condition: &mut TransitionCondition, parameters: &[String]) {
+    let first = parameters.first().cloned().unwrap_or_default();
+    let kinds = [
+        ("Clip finished", TransitionCondition::ClipFinished),
+        (
+            "Parameter above",
+            TransitionCondition::Above {
+                parameter: first.clone(),
+                value: 0.0,
+            },
+        ),
+        (
+            "Parameter below",
+            TransitionCondition::Below {
+                parameter: first,
+                value: 0.0,
+            },
+        ),
+    ];
+    let kind = |condition: &TransitionCondition| match condition {
+        TransitionCondition::ClipFinished => 0,
+        TransitionCondition::Above { .. } => 1,
+        TransitionCondition::Below { .. } => 2,
+    };
+    let current = kind(condition);
+    egui::ComboBox::from_id_source("animation_graph_condition")
+        .selected_text(kinds[current].0)
+        .show_ui(ui, |ui| {
+            for (name, default) in kinds.iter().cloned() {
+                if ui
+                    .selectable_label(kind(&default) == current, name)
+                    .clicked()
+                    && kind(&default) != current
+                {
+                    *condition = default;
+                }
+            }
+        });
+    if let TransitionCondition::Above { parameter, value }
+    | TransitionCondition::Below { parameter, value } = condition
+    {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("animation_graph_condition_parameter")
+                .selected_text(parameter.as_str())
+                .show_ui(ui, |ui| {
+                    for name in parameters {
+                        ui.selectable_value(parameter, name.clone(), name);
+                    }
+                });
+            ui.add(egui::DragValue::new(value).speed(0.05));
+        });
+    }
+}
+
+/// Where the segment from `from` to `to` leaves the rectangle around `from`.
+fn node_border(center: egui::Pos2, toward: egui::Pos2) -> egui::Pos2 {
+    let direction = toward - center;
+    let half = NODE_SIZE / 2.0;
+    let scale = (direction.x.abs() / half.x)
+        .max(direction.y.abs() / half.y)
+        .max(1.0);
+    center + direction / scale
+}
+
+fn distance_to_segment(point: egui::Pos2, a: egui::Pos2, b: egui::Pos2) -> f32 {
+    let ab = b - a;
+    let t = ((point - a).dot(ab) / ab.length_sq().max(f32::EPSILON)).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+/// Draws the states as nodes and the transitions as arrows between them. Nodes are dragged to
+/// move them, or with Shift to add a transition, and the background is dragged to pan the graph.
+fn graph_ui(
+    ui: &mut egui::Ui,
+    machine: &mut AnimationStateMachine,
+    editor: &mut AnimationGraphEditor,
+) {
+    let rect = ui.available_rect_before_wrap();
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals().clone();
+    painter.rect_filled(rect, 0.0, visuals.extreme_bg_color);
+    let origin = rect.min + editor.pan;
+    let to_screen = |position: Vec2| origin + egui::vec2(position.x, position.y);
+    let to_graph = |pos: egui::Pos2| Vec2::new(pos.x - origin.x, pos.y - origin.y);
+    let center = |state: &AnimationGraphState| to_screen(state.position) + NODE_SIZE / 2.0;
+
+    // transitions between the same states in both directions are drawn side by side
+    let transitions: Vec<_> = machine
+        .transitions
+        .iter()
+        .map(|transition| {
+            let (from, to) = (
+                center(&machine.states[transition.from]),
+                center(&machine.states[transition.to]),
+            );
+            let offset = (to - from).normalized().rot90() * 6.0;
+            let (a, b) = (
+                node_border(from, to) + offset,
+                node_border(to, from) + offset,
+            );
+            (a, b)
+        })
+        .collect();
+    for (index, (a, b)) in transitions.iter().copied().enumerate() {
+        let color = match editor.selected == Some(GraphItem::Transition(index)) {
+            true => SELECTED_COLOR,
+            false => visuals.text_color(),
+        };
+        painter.arrow(a, b - a, egui::Stroke::new(1.5, color));
+    }
+
+    // nodes interact before the background, so they own the clicks on them
+    let shift = ui.input(|input| input.modifiers.shift);
+    let mut hovered_state = None;
+    for index in 0..machine.states.len() {
+        let node = egui::Rect::from_min_size(to_screen(machine.states[index].position), NODE_SIZE);
+        let response = ui.interact(
+            node,
+            ui.id().with(("animation_state", index)),
+            egui::Sense::click_and_drag(),
+        );
+        if response.hovered() || ui.rect_contains_pointer(node) {
+            hovered_state = Some(index);
+        }
+        if response.drag_started() && shift {
+            editor.connecting = Some(index);
+        } else if response.dragged() && editor.connecting.is_none() {
+            let delta = response.drag_delta();
+            machine.states[index].position += Vec2::new(delta.x, delta.y);
+        }
+        if response.clicked() || response.drag_started() {
+            editor.selected = Some(GraphItem::State(index));
+        }
+        response.context_menu(|ui| {
+            if ui.button("Set as entry").clicked() {
+                machine.entry = index;
+                ui.close_menu();
+            }
+            if ui.button("Delete").clicked() {
+                machine.remove_state(index);
+                editor.selected = None;
+                ui.close_menu();
+            }
+        });
+        if index >= machine.states.len() {
+            break;
+        }
+
+        let state = &machine.states[index];
+        let fill = match machine.current_state() == Some(index) {
+            true => CURRENT_COLOR.linear_multiply(0.6),
+            false => visuals.widgets.inactive.bg_fill,
+        };
+        let stroke = match editor.selected == Some(GraphItem::State(index)) {
+            true => egui::Stroke::new(2.0, SELECTED_COLOR),
+            false => egui::Stroke::new(1.0, visuals.widgets.inactive.fg_stroke.color),
+        };
+        painter.rect(node, 4.0, fill, stroke);
+        let title = match machine.entry == index {
+            true => format!("▶ {}", state.name),
+            false => state.name.clone(),
+        };
+        painter.text(
+            node.center_top() + egui::vec2(0.0, 4.0),
+            egui::Align2::CENTER_TOP,
+            title,
+            egui::FontId::proportional(14.0),
+            visuals.strong_text_color(),
+        );
+        let clip = state.clip.rsplit(['/', '\\']).next().unwrap_or_default();
+        painter.text(
+            node.center_bottom() - egui::vec2(0.0, 4.0),
+            egui::Align2::CENTER_BOTTOM,
+            if clip.is_empty() { "no clip" } else { clip },
+            egui::FontId::proportional(11.0),
+            visuals.weak_text_color(),
+        );
+    }
+
+    // a transition is added when the drag from a state ends on another one
+    if let Some(from) = editor.connecting {
+        let pointer = ui.input(|input| input.pointer.hover_pos());
+        if let (Some(pointer), Some(state)) = (pointer, machine.states.get(from)) {
+            painter.arrow(
+                center(state),
+                pointer - center(state),
+                egui::Stroke::new(1.5, SELECTED_COLOR),
+            );
+        }
+        if !ui.input(|input| input.pointer.any_down()) {
+            editor.connecting = None;
+            if let Some(to) = hovered_state.filter(|&to| to != from && from < machine.states.len())
+            {
+                machine.transitions.push(AnimationGraphTransition {
+                    from,
+                    to,
+                    ..default()
+                });
+                editor.selected = Some(GraphItem::Transition(machine.transitions.len() - 1));
+            }
+        }
+    }
+
+    let background = ui.interact(
+        rect,
+        ui.id().with("animation_graph"),
+        egui::Sense::click_and_drag(),
+    );
+    if background.dragged() {
+        editor.pan += background.drag_delta();
+    }
+    if background.clicked() {
+        let pointer = background.interact_pointer_pos();
+        editor.selected = pointer.and_then(|pointer| {
+            transitions
+                .iter()
+                .enumerate()
+                .map(|(index, (a, b))| (index, distance_to_segment(pointer, *a, *b)))
+                .filter(|(_, distance)| *distance < TRANSITION_PICK_DISTANCE)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(index, _)| GraphItem::Transition(index))
+        });
+    }
+    if background.secondary_clicked() {
+        if let Some(pointer) = background.interact_pointer_pos() {
+            editor.menu_pos = to_graph(pointer);
+        }
+    }
+    background.context_menu(|ui| {
+        if ui.button("Add state").clicked() {
+            machine.states.push(AnimationGraphState {
+                name: format!("State {}", machine.states.len()),
+                position: editor.menu_pos - Vec2::new(NODE_SIZE.x, NODE_SIZE.y) / 2.0,
+                ..default()
+            });
+            editor.selected = Some(GraphItem::State(machine.states.len() - 1));
+            ui.close_menu();
+        }
+    });
+    ui.allocate_rect(rect, egui::Sense::hover());
+}
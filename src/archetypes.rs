@@ -0,0 +1,324 @@
+use std::cmp::Reverse;
+
+use bevy::{
+    ecs::component::{ComponentId, StorageType},
+    prelude::*,
+    utils::{get_short_name, HashMap},
+};
+
+/// Tables with fewer entities than this are counted as fragments.
+const SMALL_TABLE: usize = 4;
+
+pub struct EditorArchetypesPlugin;
+impl Plugin for EditorArchetypesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ArchetypesTab>();
+    }
+}
+
+struct ArchetypeStats {
+    id: usize,
+    table: usize,
+    entities: usize,
+    components: Vec<String>,
+}
+
+struct ComponentStats {
+    name: String,
+    storage: StorageType,
+    size: usize,
+    entities: usize,
+    archetypes: usize,
+}
+
+struct TableStats {
+    id: usize,
+    entities: usize,
+    capacity: usize,
+    /// The size of the components of an entity in the table.
+    row_size: usize,
+    components: Vec<String>,
+}
+
+/// Counts of the storages of the world, taken when the tab is refreshed since they can be slow to
+/// count in large worlds.
+struct WorldStats {
+    entities: usize,
+    archetypes: Vec<ArchetypeStats>,
+    components: Vec<ComponentStats>,
+    tables: Vec<TableStats>,
+}
+
+impl WorldStats {
+    fn new(world: &World) -> Self {
+        let components = world.components();
+        let name = |id: ComponentId| {
+            components
+                .get_info(id)
+                .map_or_else(|| format!("{id:?}"), |info| get_short_name(info.name()))
+        };
+        let mut component_stats: HashMap<ComponentId, ComponentStats> = HashMap::default();
+
+        let mut archetypes = Vec::new();
+        // archetypes are iterated in the order of their ids
+        for (id, archetype) in world.archetypes().iter().enumerate() {
+            for id in archetype.components() {
+                let Some(info) = components.get_info(id) else {
+                    continue;
+                };
+                let stats = component_stats.entry(id).or_insert_with(|| ComponentStats {
+                    name: get_short_name(info.name()),
+                    storage: info.storage_type(),
+                    size: info.layout().size(),
+                    entities: 0,
+                    archetypes: 0,
+                });
+                stats.entities += archetype.len();
+                stats.archetypes += 1;
+            }
+            archetypes.push(ArchetypeStats {
+                id,
+                table: archetype.table_id().index(),
+                entities: archetype.len(),
+                components: archetype.components().map(name).collect(),
+            });
+        }
+        archetypes.sort_by_key(|archetype| Reverse(archetype.entities));
+
+        let mut components: Vec<_> = component_stats.into_values().collect();
+        components.sort_by_key(|component| Reverse(component.size * component.entities));
+
+        let mut tables: Vec<_> = world
+            .storages()
+            .tables
+            .iter()
+            .enumerate()
+            .map(|(id, table)| {
+                let columns: Vec<_> = world
+                    .archetypes()
+                    .iter()
+                    .find(|archetype| archetype.table_id().index() == id)
+                    .map(|archetype| archetype.table_components().collect())
+                    .unwrap_or_default();
+                TableStats {
+                    id,
+                    entities: table.entity_count(),
+                    capacity: table.entity_capacity(),
+                    row_size: columns
+                        .iter()
+                        .filter_map(|id| world.components().get_info(*id))
+                        .map(|info| info.layout().size())
+                        .sum(),
+                    components: columns.into_iter().map(name).collect(),
+                }
+            })
+            .collect();
+        tables.sort_by_key(|table| Reverse(table.entities));
+
+        WorldStats {
+            entities: world.entities().len() as usize,
+            archetypes,
+            components,
+            tables,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq)]
+enum StatsSection {
+    #[default]
+    Archetypes,
+    Components,
+    Tables,
+}
+
+/// The statistics shown in the archetypes tab.
+#[derive(Resource, Default)]
+pub struct ArchetypesTab {
+    stats: Option<WorldStats>,
+    section: StatsSection,
+    filter: String,
+    hide_empty: bool,
+}
+
+fn bytes(bytes: usize) -> String {
+    match bytes {
+        0..=1023 => format!("{bytes} B"),
+        1024..=1048575 => format!("{:.1} KiB", bytes as f32 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f32 / 1048576.0),
+    }
+}
+
+/// Shows how the entities of the world are stored in archetypes and tables, and the memory their
+/// components take.
+pub fn archetypes_ui(world: &mut World, ui: &mut egui::Ui) {
+    world.resource_scope::<ArchetypesTab, _>(|world, mut tab| {
+        let mut refresh = tab.stats.is_none();
+        ui.horizontal(|ui| {
+            refresh |= ui.button("⟲ Refresh").clicked();
+            ui.separator();
+            ui.selectable_value(&mut tab.section, StatsSection::Archetypes, "Archetypes");
+            ui.selectable_value(&mut tab.section, StatsSection::Components, "Components");
+            ui.selectable_value(&mut tab.section, StatsSection::Tables, "Tables");
+            ui.separator();
+            ui.add(
+                egui::TextEdit::singleline(&mut tab.filter)
+                    .hint_text("Component")
+                    .desired_width(150.0),
+            );
+            ui.checkbox(&mut tab.hide_empty, "Hide empty");
+        });
+        if refresh {
+            tab.stats = Some(WorldStats::new(world));
+        }
+        let tab = &*tab;
+        let Some(stats) = &tab.stats else {
+            return;
+        };
+
+        let used_archetypes = stats.archetypes.iter().filter(|a| a.entities > 0).count();
+        let used_tables: Vec<_> = stats.tables.iter().filter(|t| t.entities > 0).collect();
+        let small_tables = used_tables
+            .iter()
+            .filter(|t| t.entities < SMALL_TABLE)
+            .count();
+        let allocated: usize = stats.tables.iter().map(|t| t.capacity * t.row_size).sum();
+        let used: usize = stats.tables.iter().map(|t| t.entities * t.row_size).sum();
+        egui::Grid::new("world_stats")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Entities");
+                ui.monospace(stats.entities.to_string());
+                ui.end_row();
+                ui.label("Archetypes");
+                ui.monospace(format!(
+                    "{} ({used_archetypes} with entities)",
+                    stats.archetypes.len()
+                ));
+                ui.end_row();
+                ui.label("Tables");
+                ui.monospace(format!(
+                    "{} ({} with entities, {small_tables} with fewer than {SMALL_TABLE})",
+                    stats.tables.len(),
+                    used_tables.len()
+                ));
+                ui.end_row();
+                ui.label("Entities per table");
+                let per_table = match used_tables.is_empty() {
+                    true => 0.0,
+                    false => {
+                        used_tables.iter().map(|t| t.entities).sum::<usize>() as f32
+                            / used_tables.len() as f32
+                    }
+                };
+                ui.monospace(format!("{per_table:.1}"));
+                ui.end_row();
+                ui.label("Table memory").on_hover_text(
+                "The memory of the components stored in tables, and the memory allocated for them",
+            );
+                ui.monospace(format!("{} of {}", bytes(used), bytes(allocated)));
+                ui.end_row();
+            });
+        ui.separator();
+
+        let filter = tab.filter.to_lowercase();
+        let matches = |components: &[String]| {
+            filter.is_empty()
+                || components
+                    .iter()
+                    .any(|name| name.to_lowercase().contains(&filter))
+        };
+        egui::ScrollArea::both()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| match tab.section {
+                StatsSection::Archetypes => {
+                    egui::Grid::new("archetype_stats")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Archetype");
+                            ui.strong("Entities");
+                            ui.strong("Table");
+                            ui.strong("Components");
+                            ui.end_row();
+                            for archetype in &stats.archetypes {
+                                if (tab.hide_empty && archetype.entities == 0)
+                                    || !matches(&archetype.components)
+                                {
+                                    continue;
+                                }
+                                ui.monospace(archetype.id.to_string());
+                                ui.monospace(archetype.entities.to_string());
+                                ui.monospace(archetype.table.to_string());
+                                ui.label(archetype.components.join(", "));
+                                ui.end_row();
+                            }
+                        });
+                }
+                StatsSection::Components => {
+                    egui::Grid::new("component_stats")
+                        .num_columns(6)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Component");
+                            ui.strong("Storage");
+                            ui.strong("Size");
+                            ui.strong("Entities");
+                            ui.strong("Memory");
+                            ui.strong("Archetypes")
+                                .on_hover_text("The archetypes the component is split across");
+                            ui.end_row();
+                            for component in &stats.components {
+                                if (tab.hide_empty && component.entities == 0)
+                                    || !matches(std::slice::from_ref(&component.name))
+                                {
+                                    continue;
+                                }
+                                ui.label(&component.name);
+                                ui.label(match component.storage {
+                                    StorageType::Table => "Table",
+                                    StorageType::SparseSet => "Sparse set",
+                                });
+                                ui.monospace(bytes(component.size));
+                                ui.monospace(component.entities.to_string());
+                                ui.monospace(bytes(component.size * component.entities));
+                                ui.monospace(component.archetypes.to_string());
+                                ui.end_row();
+                            }
+                        });
+                }
+                StatsSection::Tables => {
+                    egui::Grid::new("table_stats")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Table");
+                            ui.strong("Entities");
+                            ui.strong("Capacity");
+                            ui.strong("Row size");
+                            ui.strong("Columns");
+                            ui.end_row();
+                            for table in &stats.tables {
+                                if (tab.hide_empty && table.entities == 0)
+                                    || !matches(&table.components)
+                                {
+                                    continue;
+                                }
+                                ui.monospace(table.id.to_string());
+                                let entities = egui::RichText::new(table.entities.to_string());
+                                match table.entities > 0 && table.entities < SMALL_TABLE {
+                                    true => {
+                                        ui.monospace(entities.color(ui.visuals().warn_fg_color))
+                                    }
+                                    false => ui.monospace(entities),
+                                };
+                                ui.monospace(table.capacity.to_string());
+                                ui.monospace(bytes(table.row_size));
+                                ui.label(table.components.join(", "));
+                                ui.end_row();
+                            }
+                        });
+                }
+            });
+    });
+}
@@ -0,0 +1,573 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{ecs::event::ManualEventReader, prelude::*};
+use bevy_inspector_egui::{bevy_egui::EguiSettings, bevy_inspector::hierarchy::SelectedEntities};
+
+use crate::{
+    git_status::{self, GitStatus},
+    mode2d::EditorView2d,
+    nested_scene::NestedScene,
+    picking,
+    prefab::{self, PREFAB_EXTENSION},
+    snap, thumbnails,
+    views::EditorViewport,
+    EditorResource, InspectorSelection, UiState,
+};
+
+/// Directory the asset server loads from, which the browser starts in.
+pub const ASSET_DIR: &str = "assets";
+pub(crate) const SCENE_EXTENSION: &str = "scn.ron";
+
+/// Size of the thumbnails next to the listed files, in egui points.
+const ROW_THUMBNAIL_SIZE: f32 = 24.0;
+/// How often the listed directory is read again, in seconds.
+const REFRESH_INTERVAL: f32 = 1.0;
+
+pub struct EditorAssetBrowserPlugin;
+impl Plugin for EditorAssetBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetBrowser>()
+            .add_systems(Update, drop_os_files);
+    }
+}
+
+/// Kind of a file in the asset browser, from its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AssetKind {
+    Folder,
+    Scene,
+    Prefab,
+    Model,
+    Image,
+    Audio,
+    Shader,
+    Font,
+    Other,
+}
+
+impl AssetKind {
+    pub fn from_path(path: &Path) -> AssetKind {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        if path.is_dir() {
+            return AssetKind::Folder;
+        }
+        if name.ends_with(SCENE_EXTENSION) {
+            return AssetKind::Scene;
+        }
+        if name.ends_with(PREFAB_EXTENSION) {
+            return AssetKind::Prefab;
+        }
+        match name.rsplit_once('.').map_or("", |(_, extension)| extension) {
+            "gltf" | "glb" => AssetKind::Model,
+            "png" | "jpg" | "jpeg" | "ktx2" | "dds" | "tga" | "bmp" | "hdr" => AssetKind::Image,
+            "ogg" | "wav" | "mp3" | "flac" => AssetKind::Audio,
+            "wgsl" | "vert" | "frag" | "spv" => AssetKind::Shader,
+            "ttf" | "otf" => AssetKind::Font,
+            _ => AssetKind::Other,
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            AssetKind::Folder => "📁",
+            AssetKind::Scene => "🎬",
+            AssetKind::Prefab => "📦",
+            AssetKind::Model => "🗿",
+            AssetKind::Image => "🖼",
+            AssetKind::Audio => "🎵",
+            AssetKind::Shader => "✨",
+            AssetKind::Font => "🔤",
+            AssetKind::Other => "📄",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BrowserEntry {
+    path: PathBuf,
+    name: String,
+    kind: AssetKind,
+}
+
+/// State of the asset browser tab, which lists the files in [`ASSET_DIR`].
+#[derive(Resource)]
+pub struct AssetBrowser {
+    pub current_dir: PathBuf,
+    entries: Vec<BrowserEntry>,
+    since_refresh: f32,
+    /// File being dragged to a game view.
+    dragged: Option<PathBuf>,
+    /// Handles of the assets opened in the inspector, so they stay loaded.
+    opened: HashMap<PathBuf, HandleUntyped>,
+    /// Scene file opened this frame, loaded by the editor once the tabs are drawn.
+    pub(crate) open_scene: Option<String>,
+    git: GitStatus,
+}
+
+impl Default for AssetBrowser {
+    fn default() -> Self {
+        Self {
+            current_dir: PathBuf::from(ASSET_DIR),
+            entries: Vec::new(),
+            // read the directory on the first frame
+            since_refresh: REFRESH_INTERVAL,
+            dragged: None,
+            opened: HashMap::new(),
+            open_scene: None,
+            git: GitStatus::default(),
+        }
+    }
+}
+
+impl AssetBrowser {
+    fn refresh(&mut self) {
+        self.since_refresh = 0.0;
+        self.git.refresh();
+        let Ok(dir) = fs::read_dir(&self.current_dir) else {
+            self.entries.clear();
+            return;
+        };
+        self.entries = dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| {
+                let path = entry.path();
+                BrowserEntry {
+                    name: path
+                        .file_name()
+                        .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+                    kind: AssetKind::from_path(&path),
+                    path,
+                }
+            })
+            .filter(|entry| !entry.name.starts_with('.'))
+            .collect();
+        // folders first
+        self.entries.sort_by(|a, b| {
+            (a.kind != AssetKind::Folder, &a.name).cmp(&(b.kind != AssetKind::Folder, &b.name))
+        });
+    }
+
+    fn navigate(&mut self, dir: PathBuf) {
+        self.current_dir = dir;
+        self.refresh();
+    }
+}
+
+/// Path of `path` as the asset server expects it, relative to [`ASSET_DIR`].
+pub(crate) fn asset_path(path: &Path) -> String {
+    path.strip_prefix(ASSET_DIR)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn file_stem(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+    name.split('.').next().unwrap_or(&name).to_owned()
+}
+
+/// Loads the file at `path` and selects it in the inspector, for the kinds of assets the
+/// inspector can show.
+fn open_in_inspector(
+    world: &mut World,
+    path: &Path,
+    kind: AssetKind,
+    selection: &mut InspectorSelection,
+) {
+    let asset_server = world.resource::<AssetServer>();
+    let asset = asset_path(path);
+    let (handle, type_id, type_name) = match kind {
+        AssetKind::Image => (
+            asset_server.load::<Image, _>(asset).clone_untyped(),
+            TypeId::of::<Image>(),
+            "Image",
+        ),
+        AssetKind::Model => (
+            asset_server
+                .load::<Mesh, _>(format!("{asset}#Mesh0/Primitive0"))
+                .clone_untyped(),
+            TypeId::of::<Mesh>(),
+            "Mesh",
+        ),
+        AssetKind::Audio => (
+            asset_server.load::<AudioSource, _>(asset).clone_untyped(),
+            TypeId::of::<AudioSource>(),
+            "AudioSource",
+        ),
+        AssetKind::Shader => (
+            asset_server.load::<Shader, _>(asset).clone_untyped(),
+            TypeId::of::<Shader>(),
+            "Shader",
+        ),
+        AssetKind::Font => (
+            asset_server.load::<Font, _>(asset).clone_untyped(),
+            TypeId::of::<Font>(),
+            "Font",
+        ),
+        AssetKind::Folder | AssetKind::Scene | AssetKind::Prefab | AssetKind::Other => {
+            info!("{} can't be shown in the inspector", path.display());
+            return;
+        }
+    };
+    *selection = InspectorSelection::Asset(type_id, type_name.to_owned(), handle.id());
+    world
+        .resource_mut::<AssetBrowser>()
+        .opened
+        .insert(path.to_owned(), handle);
+}
+
+/// Where a file dropped at `pos` in the game view of `camera` is spawned: on the surface under
+/// the cursor, or on the ground plane if it misses everything.
+fn drop_position(
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+    pos: egui::Pos2,
+) -> Vec3 {
+    let Some(ray) = picking::cursor_ray(world, camera, viewport_rect, pos) else {
+        return Vec3::ZERO;
+    };
+    if let Some(hit) = snap::raycast_surface(world, ray, &[]) {
+        return hit.point;
+    }
+    // the XY plane in 2D mode
+    let normal = match world.get::<EditorView2d>(camera) {
+        Some(_) => Vec3::Z,
+        None => Vec3::Y,
+    };
+    ray.intersect_plane(Vec3::ZERO, normal)
+        .map_or(Vec3::ZERO, |distance| ray.get_point(distance))
+}
+
+/// Spawns the file at `path` at `position`, if it's a kind of asset which can be placed in the
+/// scene.
+fn spawn_asset(world: &mut World, path: &Path, kind: AssetKind, position: Vec3) -> Option<Entity> {
+    let name = Name::new(file_stem(path));
+    let transform = Transform::from_translation(position);
+    let asset = asset_path(path);
+    let entity = match kind {
+        AssetKind::Prefab => {
+            let path = path.to_string_lossy().replace('\\', "/");
+            match prefab::instantiate_prefab(world, &path, None) {
+                Ok(entity) => {
+                    if let Some(mut root) = world.get_mut::<Transform>(entity) {
+                        root.translation = position;
+                    }
+                    entity
+                }
+                Err(e) => {
+                    error!("Failed to instantiate prefab {path}: {e}");
+                    return None;
+                }
+            }
+        }
+        AssetKind::Model => {
+            let asset = format!("{asset}#Scene0");
+            let scene = world.resource::<AssetServer>().load(&asset);
+            world
+                .spawn((
+                    name,
+                    NestedScene { path: asset },
+                    SceneBundle {
+                        scene,
+                        transform,
+                        ..default()
+                    },
+                ))
+                .id()
+        }
+        AssetKind::Image => {
+            let texture: Handle<Image> = world.resource::<AssetServer>().load(asset);
+            // a sprite in 2D mode, a textured quad otherwise
+            let is_2d = world.query::<&EditorView2d>().iter(world).next().is_some();
+            if is_2d {
+                world
+                    .spawn((
+                        name,
+                        SpriteBundle {
+                            texture,
+                            transform,
+                            ..default()
+                        },
+                    ))
+                    .id()
+            } else {
+                let mesh = world
+                    .resource_mut::<Assets<Mesh>>()
+                    .add(shape::Quad::new(Vec2::ONE).into());
+                let material =
+                    world
+                        .resource_mut::<Assets<StandardMaterial>>()
+                        .add(StandardMaterial {
+                            base_color_texture: Some(texture),
+                            alpha_mode: AlphaMode::Blend,
+                            ..default()
+                        });
+                world
+                    .spawn((
+                        name,
+                        PbrBundle {
+                            mesh,
+                            material,
+                            transform,
+                            ..default()
+                        },
+                    ))
+                    .id()
+            }
+        }
+        AssetKind::Audio => {
+            let source = world.resource::<AssetServer>().load(asset);
+            world
+                .spawn((
+                    name,
+                    AudioBundle {
+                        source,
+                        settings: PlaybackSettings::LOOP.paused(),
+                    },
+                    SpatialBundle::from_transform(transform),
+                ))
+                .id()
+        }
+        AssetKind::Scene => {
+            // the entities of the scene are spawned as children of this one
+            let scene = world.resource::<AssetServer>().load(&asset);
+            world
+                .spawn((
+                    name,
+                    NestedScene { path: asset },
+                    DynamicSceneBundle {
+                        scene,
+                        transform,
+                        ..default()
+                    },
+                ))
+                .id()
+        }
+        AssetKind::Folder | AssetKind::Shader | AssetKind::Font | AssetKind::Other => {
+            info!("{} can't be placed in the scene", path.display());
+            return None;
+        }
+    };
+    info!("Spawned {} as {entity:?}", path.display());
+    Some(entity)
+}
+
+/// Spawns the file dropped at `pos` in the game view under it, if any.
+fn drop_asset(world: &mut World, path: &Path, pos: egui::Pos2) -> Option<Entity> {
+    let (camera, viewport_rect) = world
+        .query::<(Entity, &EditorViewport)>()
+        .iter(world)
+        .find(|(_, viewport)| viewport.rect.contains(pos))
+        .map(|(camera, viewport)| (camera, viewport.rect))?;
+    let position = drop_position(world, camera, viewport_rect, pos);
+    spawn_asset(world, path, AssetKind::from_path(path), position)
+}
+
+/// The path of a file dropped from outside the editor, relative to the working directory if it's
+/// in [`ASSET_DIR`] so the asset server doesn't load it twice under two paths.
+fn dropped_file_path(path: &Path) -> PathBuf {
+    fs::canonicalize(ASSET_DIR)
+        .ok()
+        .and_then(|root| {
+            path.strip_prefix(root)
+                .ok()
+                .map(|path| Path::new(ASSET_DIR).join(path))
+        })
+        .unwrap_or_else(|| path.to_owned())
+}
+
+/// Spawns files dropped onto a game view from the OS where they are dropped, like the files of
+/// the browser.
+fn drop_os_files(world: &mut World, mut reader: Local<ManualEventReader<FileDragAndDrop>>) {
+    let dropped: Vec<_> = reader
+        .iter(world.resource::<Events<FileDragAndDrop>>())
+        .filter_map(|event| match event {
+            FileDragAndDrop::DroppedFile { window, path_buf } => Some((*window, path_buf.clone())),
+            _ => None,
+        })
+        .collect();
+    if dropped.is_empty() || !world.resource::<EditorResource>().0 {
+        return;
+    }
+    let scale_factor = world.resource::<EguiSettings>().scale_factor as f32;
+    for (window, path) in dropped {
+        let Some(cursor) = world
+            .get::<Window>(window)
+            .and_then(|window| window.cursor_position())
+        else {
+            continue;
+        };
+        let pos = egui::pos2(cursor.x / scale_factor, cursor.y / scale_factor);
+        let path = dropped_file_path(&path);
+        let Some(entity) = drop_asset(world, &path, pos) else {
+            continue;
+        };
+        let mut ui_state = world.resource_mut::<UiState>();
+        ui_state.selected_entities.select_replace(entity);
+        ui_state.selection = InspectorSelection::Entities;
+    }
+}
+
+/// Lists the files of the current directory of the [`AssetBrowser`]. Double clicking a folder
+/// opens it, a scene loads it and any other asset shows it in the inspector. Files dragged to a
+/// game view are spawned where they are dropped.
+pub(crate) fn asset_browser_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selected_entities: &mut SelectedEntities,
+    selection: &mut InspectorSelection,
+) {
+    let delta = world.resource::<Time>().raw_delta_seconds();
+    let mut browser = world.resource_mut::<AssetBrowser>();
+    browser.since_refresh += delta;
+    if browser.since_refresh >= REFRESH_INTERVAL {
+        browser.refresh();
+    }
+
+    ui.horizontal(|ui| {
+        let root = Path::new(ASSET_DIR);
+        let at_root = browser.current_dir == root;
+        if ui.add_enabled(!at_root, egui::Button::new("⬆")).clicked() {
+            let parent = browser.current_dir.parent().map(Path::to_owned);
+            browser.navigate(parent.unwrap_or_else(|| root.to_owned()));
+        }
+        // every directory from the root to the current one
+        let dirs: Vec<_> = browser
+            .current_dir
+            .ancestors()
+            .take_while(|dir| dir.starts_with(root))
+            .map(Path::to_owned)
+            .collect();
+        for dir in dirs.into_iter().rev() {
+            let name = dir
+                .file_name()
+                .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+            if ui.link(name).clicked() {
+                browser.navigate(dir);
+            }
+            ui.weak("/");
+        }
+    });
+    ui.separator();
+
+    if browser.entries.is_empty() {
+        ui.label(format!("No files in {}", browser.current_dir.display()));
+    }
+    let dragged = browser.dragged.clone();
+    let entries = browser.entries.clone();
+    let statuses: Vec<_> = entries
+        .iter()
+        .map(|entry| browser.git.status(&entry.path))
+        .collect();
+    let mut opened = None;
+    let mut dropped = None;
+    let mut reverted = false;
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for (entry, status) in entries.into_iter().zip(statuses) {
+            let is_dragged = dragged.as_ref() == Some(&entry.path);
+            let thumbnail = thumbnails::thumbnail(world, &entry.path);
+            let response = ui
+                .horizontal(|ui| {
+                    let size = egui::Vec2::splat(ROW_THUMBNAIL_SIZE);
+                    match thumbnail {
+                        Some(texture) => ui.image(texture, size),
+                        None => ui.add_sized(size, egui::Label::new(entry.kind.icon())),
+                    };
+                    let response = ui
+                        .selectable_label(is_dragged, &entry.name)
+                        .interact(egui::Sense::drag());
+                    if let Some(status) = status {
+                        let badge = egui::RichText::new(status.badge())
+                            .small()
+                            .color(status.color(ui.visuals()));
+                        ui.label(badge).on_hover_text(match entry.kind {
+                            AssetKind::Folder => "Has changes",
+                            _ => status.description(),
+                        });
+                    }
+                    response
+                })
+                .inner
+                .on_hover_text(entry.path.display().to_string());
+            let revertible =
+                entry.kind != AssetKind::Folder && status.is_some_and(|status| status.revertible());
+            let response = if revertible {
+                response.context_menu(|ui| {
+                    if ui
+                        .button("Revert File")
+                        .on_hover_text("Discard the changes since the last commit")
+                        .clicked()
+                    {
+                        git_status::revert_file(&entry.path);
+                        reverted = true;
+                        ui.close_menu();
+                    }
+                })
+            } else {
+                response
+            };
+            if response.double_clicked() {
+                opened = Some(entry.clone());
+            }
+            if response.drag_started() && entry.kind != AssetKind::Folder {
+                world.resource_mut::<AssetBrowser>().dragged = Some(entry.path.clone());
+            }
+            if response.drag_released() && is_dragged {
+                world.resource_mut::<AssetBrowser>().dragged = None;
+                dropped = ui
+                    .input(|input| input.pointer.hover_pos())
+                    .map(|pos| (entry.path.clone(), pos));
+            }
+        }
+    });
+
+    if reverted {
+        world.resource_mut::<AssetBrowser>().refresh();
+    }
+
+    // the dragged file can disappear from the list before it's dropped
+    if dropped.is_none() && !ui.input(|input| input.pointer.any_down()) {
+        world.resource_mut::<AssetBrowser>().dragged = None;
+    } else if let Some(path) = &dragged {
+        egui::show_tooltip_at_pointer(ui.ctx(), egui::Id::new("asset_browser_drag"), |ui| {
+            let kind = AssetKind::from_path(path);
+            ui.label(format!("{} {}", kind.icon(), file_stem(path)));
+        });
+    }
+    if let Some((path, pos)) = dropped {
+        if let Some(entity) = drop_asset(world, &path, pos) {
+            selected_entities.select_replace(entity);
+            *selection = InspectorSelection::Entities;
+        }
+    }
+
+    let Some(entry) = opened else {
+        return;
+    };
+    match entry.kind {
+        AssetKind::Folder => world.resource_mut::<AssetBrowser>().navigate(entry.path),
+        AssetKind::Scene => {
+            world.resource_mut::<AssetBrowser>().open_scene =
+                Some(entry.path.to_string_lossy().replace('\\', "/"));
+        }
+        AssetKind::Prefab => {
+            if let Some(entity) = spawn_asset(world, &entry.path, entry.kind, Vec3::ZERO) {
+                selected_entities.select_replace(entity);
+                *selection = InspectorSelection::Entities;
+            }
+        }
+        kind => open_in_inspector(world, &entry.path, kind, selection),
+    }
+}
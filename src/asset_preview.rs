@@ -0,0 +1,776 @@
+use std::{any::TypeId, f32::consts::TAU};
+
+use bevy::{
+    asset::HandleId,
+    audio::Volume,
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        mesh::{Indices, MeshVertexAttribute},
+        primitives::Aabb,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+            VertexFormat,
+        },
+        view::{NoFrustumCulling, RenderLayers},
+    },
+};
+use bevy_inspector_egui::bevy_egui::EguiUserTextures;
+
+use crate::EditorOnly;
+
+const STUDIO_SIZE: Extent3d = Extent3d {
+    width: 256,
+    height: 256,
+    depth_or_array_layers: 1,
+};
+/// Render layer of the preview studio, which no other camera renders.
+const STUDIO_LAYER: u8 = 30;
+/// Where previewed assets are rendered, away from the scene and the thumbnail studio.
+const STUDIO_ORIGIN: Vec3 = Vec3::new(0.0, -20_000.0, 0.0);
+
+pub struct EditorAssetPreviewPlugin;
+impl Plugin for EditorAssetPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ImagePreview>()
+            .init_resource::<MaterialPreview>()
+            .init_resource::<AudioPreview>()
+            .add_systems(Last, update_studio);
+    }
+}
+
+/// Marks the cameras, lights and models of the off-screen studios rendering thumbnails and
+/// previews, which aren't part of the scene.
+#[derive(Component)]
+pub struct EditorStudio;
+
+/// An image which cameras can render to, to be shown in egui.
+pub fn render_target(
+    images: &mut Assets<Image>,
+    label: &'static str,
+    size: Extent3d,
+) -> Handle<Image> {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some(label),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    images.add(image)
+}
+
+/// The studio rendering the previewed asset under a neutral lighting rig. Spawned the first time
+/// an asset is previewed, and only rendered while a preview is shown.
+#[derive(Resource)]
+struct PreviewStudio {
+    texture: egui::TextureId,
+    camera: Entity,
+    subject: Entity,
+    /// Meshes of the [`PreviewShape`]s, in the order of [`PreviewShape::ALL`].
+    shapes: [Handle<Mesh>; 3],
+    /// Material of previewed meshes.
+    neutral: Handle<StandardMaterial>,
+    /// Whether a preview was shown this frame.
+    shown: bool,
+}
+
+fn spawn_studio(world: &mut World) {
+    let image = render_target(
+        &mut world.resource_mut::<Assets<Image>>(),
+        "editor_preview",
+        STUDIO_SIZE,
+    );
+    let texture = world
+        .resource_mut::<EguiUserTextures>()
+        .add_image(image.clone());
+    let mut meshes = world.resource_mut::<Assets<Mesh>>();
+    let shapes = [
+        meshes.add(Mesh::from(shape::UVSphere::default())),
+        meshes.add(Mesh::from(shape::Cube { size: 1.4 })),
+        meshes.add(Mesh::from(shape::Plane::from_size(2.0))),
+    ];
+    let neutral = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(StandardMaterial {
+            base_color: Color::rgb(0.7, 0.7, 0.7),
+            perceptual_roughness: 0.6,
+            ..default()
+        });
+    let layer = RenderLayers::layer(STUDIO_LAYER);
+
+    let camera = world
+        .spawn((
+            Name::new("Editor Preview Camera"),
+            Camera3dBundle {
+                camera: Camera {
+                    target: RenderTarget::Image(image),
+                    is_active: false,
+                    order: -3,
+                    ..default()
+                },
+                camera_3d: Camera3d {
+                    clear_color: ClearColorConfig::Custom(Color::rgb(0.18, 0.18, 0.2)),
+                    ..default()
+                },
+                transform: Transform::from_translation(STUDIO_ORIGIN + Vec3::new(0.0, 1.2, 3.0))
+                    .looking_at(STUDIO_ORIGIN, Vec3::Y),
+                ..default()
+            },
+            layer,
+            EditorStudio,
+            EditorOnly,
+        ))
+        .id();
+    let subject = world
+        .spawn((
+            Name::new("Editor Preview Subject"),
+            PbrBundle {
+                mesh: shapes[0].clone(),
+                transform: Transform::from_translation(STUDIO_ORIGIN),
+                ..default()
+            },
+            // the bounds of the first mesh aren't updated for the ones shown after it
+            NoFrustumCulling,
+            layer,
+            EditorStudio,
+            EditorOnly,
+        ))
+        .id();
+    // key, fill and rim lights, short ranged so they don't reach the scene
+    for (name, offset, intensity) in [
+        (
+            "Editor Preview Key Light",
+            Vec3::new(-2.5, 3.0, 2.5),
+            2400.0,
+        ),
+        ("Editor Preview Fill Light", Vec3::new(3.0, 0.5, 2.0), 800.0),
+        (
+            "Editor Preview Rim Light",
+            Vec3::new(0.5, 2.0, -3.0),
+            1600.0,
+        ),
+    ] {
+        world.spawn((
+            Name::new(name),
+            PointLightBundle {
+                point_light: PointLight {
+                    intensity,
+                    range: 10.0,
+                    ..default()
+                },
+                transform: Transform::from_translation(STUDIO_ORIGIN + offset),
+                ..default()
+            },
+            layer,
+            EditorStudio,
+            EditorOnly,
+        ));
+    }
+
+    world.insert_resource(PreviewStudio {
+        texture,
+        camera,
+        subject,
+        shapes,
+        neutral,
+        shown: false,
+    });
+}
+
+fn studio(world: &mut World) -> Mut<'_, PreviewStudio> {
+    if !world.contains_resource::<PreviewStudio>() {
+        spawn_studio(world);
+    }
+    world.resource_mut::<PreviewStudio>()
+}
+
+/// Renders `mesh` with `material` in the preview studio this frame, placed by `transform`
+/// relative to the center of the studio. Returns the texture it's rendered to.
+fn show_in_studio(
+    world: &mut World,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    transform: Transform,
+) -> egui::TextureId {
+    let mut studio = studio(world);
+    studio.shown = true;
+    let (subject, texture) = (studio.subject, studio.texture);
+    if let Some(mut shown_transform) = world.get_mut::<Transform>(subject) {
+        let transform = transform.with_translation(STUDIO_ORIGIN + transform.translation);
+        if *shown_transform != transform {
+            *shown_transform = transform;
+        }
+    }
+    if let Some(mut shown_mesh) = world.get_mut::<Handle<Mesh>>(subject) {
+        if *shown_mesh != mesh {
+            *shown_mesh = mesh;
+        }
+    }
+    if let Some(mut shown_material) = world.get_mut::<Handle<StandardMaterial>>(subject) {
+        if *shown_material != material {
+            *shown_material = material;
+        }
+    }
+    texture
+}
+
+/// Renders the studio only in frames which show a preview.
+fn update_studio(studio: Option<ResMut<PreviewStudio>>, mut cameras: Query<&mut Camera>) {
+    let Some(mut studio) = studio else {
+        return;
+    };
+    if let Ok(mut camera) = cameras.get_mut(studio.camera) {
+        if camera.is_active != studio.shown {
+            camera.is_active = studio.shown;
+        }
+    }
+    studio.shown = false;
+}
+
+/// Channels of an image shown by its preview.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImageChannels {
+    #[default]
+    Rgba,
+    Rgb,
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+impl ImageChannels {
+    const ALL: [ImageChannels; 6] = [
+        ImageChannels::Rgba,
+        ImageChannels::Rgb,
+        ImageChannels::Red,
+        ImageChannels::Green,
+        ImageChannels::Blue,
+        ImageChannels::Alpha,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ImageChannels::Rgba => "RGBA",
+            ImageChannels::Rgb => "RGB",
+            ImageChannels::Red => "R",
+            ImageChannels::Green => "G",
+            ImageChannels::Blue => "B",
+            ImageChannels::Alpha => "A",
+        }
+    }
+
+    /// The color shown for an `[r, g, b, a]` pixel.
+    fn apply(self, [r, g, b, a]: [u8; 4]) -> [u8; 4] {
+        match self {
+            ImageChannels::Rgba => [r, g, b, a],
+            ImageChannels::Rgb => [r, g, b, 255],
+            ImageChannels::Red => [r, r, r, 255],
+            ImageChannels::Green => [g, g, g, 255],
+            ImageChannels::Blue => [b, b, b, 255],
+            ImageChannels::Alpha => [a, a, a, 255],
+        }
+    }
+}
+
+/// What the preview of an image shows, and the image made from it.
+#[derive(Resource)]
+pub struct ImagePreview {
+    pub zoom: f32,
+    pub channels: ImageChannels,
+    pub mip_level: u32,
+    /// The source image, options and texture of the image shown for the current options.
+    shown: Option<(HandleId, ImageChannels, u32, Handle<Image>, egui::TextureId)>,
+}
+
+impl Default for ImagePreview {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            channels: ImageChannels::Rgba,
+            mip_level: 0,
+            shown: None,
+        }
+    }
+}
+
+/// Shape which the preview of a material is rendered on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PreviewShape {
+    #[default]
+    Sphere,
+    Cube,
+    Plane,
+}
+
+impl PreviewShape {
+    const ALL: [PreviewShape; 3] = [
+        PreviewShape::Sphere,
+        PreviewShape::Cube,
+        PreviewShape::Plane,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            PreviewShape::Sphere => "Sphere",
+            PreviewShape::Cube => "Cube",
+            PreviewShape::Plane => "Plane",
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MaterialPreview {
+    pub shape: PreviewShape,
+}
+
+/// Shows a preview of the asset above its reflected fields, for the kinds of assets which have
+/// one.
+pub fn asset_preview_ui(world: &mut World, ui: &mut egui::Ui, type_id: TypeId, handle: HandleId) {
+    if type_id == TypeId::of::<Image>() {
+        image_preview_ui(world, ui, handle);
+    } else if type_id == TypeId::of::<StandardMaterial>() {
+        material_preview_ui(world, ui, handle);
+    } else if type_id == TypeId::of::<Mesh>() {
+        mesh_preview_ui(world, ui, handle);
+    } else if type_id == TypeId::of::<AudioSource>() {
+        audio_preview_ui(world, ui, handle);
+    }
+}
+
+/// Shows the material `handle` on a shape in the preview studio, which follows its edits.
+fn material_preview_ui(world: &mut World, ui: &mut egui::Ui, handle: HandleId) {
+    let material = world
+        .resource::<Assets<StandardMaterial>>()
+        .get_handle(handle);
+    let mut preview = world.resource_mut::<MaterialPreview>();
+    ui.horizontal(|ui| {
+        for shape in PreviewShape::ALL {
+            ui.selectable_value(&mut preview.shape, shape, shape.name());
+        }
+    });
+    let shape = preview.shape;
+
+    let mesh = studio(world).shapes[shape as usize].clone();
+    let texture = show_in_studio(world, mesh, material, Transform::IDENTITY);
+    ui.image(
+        texture,
+        egui::vec2(STUDIO_SIZE.width as f32, STUDIO_SIZE.height as f32),
+    );
+    ui.separator();
+}
+
+/// Vertex attributes known by name.
+const MESH_ATTRIBUTES: [MeshVertexAttribute; 7] = [
+    Mesh::ATTRIBUTE_POSITION,
+    Mesh::ATTRIBUTE_NORMAL,
+    Mesh::ATTRIBUTE_UV_0,
+    Mesh::ATTRIBUTE_TANGENT,
+    Mesh::ATTRIBUTE_COLOR,
+    Mesh::ATTRIBUTE_JOINT_WEIGHT,
+    Mesh::ATTRIBUTE_JOINT_INDEX,
+];
+/// Turns of previewed meshes per second.
+const MESH_PREVIEW_SPEED: f32 = 0.1;
+
+/// The number of primitives of the glTF mesh which the mesh `handle` is a primitive of, or `None`
+/// for meshes which aren't loaded from a glTF file.
+fn submesh_count(world: &World, handle: HandleId) -> Option<usize> {
+    let asset_server = world.resource::<AssetServer>();
+    let path = asset_server.get_handle_path(handle)?;
+    // primitives are labeled `Mesh0/Primitive0`
+    let (gltf_mesh, _) = path.label()?.split_once("/Primitive")?;
+    let prefix = format!("{gltf_mesh}/Primitive");
+    let count = world
+        .resource::<Assets<Mesh>>()
+        .ids()
+        .filter(|id| {
+            asset_server.get_handle_path(*id).is_some_and(|other| {
+                other.path() == path.path()
+                    && other
+                        .label()
+                        .is_some_and(|label| label.starts_with(&prefix))
+            })
+        })
+        .count();
+    Some(count)
+}
+
+/// Shows the vertex, index and submesh counts, attributes and bounds of the mesh `handle`, with a
+/// render of it turning in the preview studio.
+fn mesh_preview_ui(world: &mut World, ui: &mut egui::Ui, handle: HandleId) {
+    let Some(mesh) = world
+        .resource::<Assets<Mesh>>()
+        .get(&Handle::<Mesh>::weak(handle))
+    else {
+        ui.label("Loading…");
+        return;
+    };
+    let indices = match mesh.indices() {
+        Some(Indices::U16(indices)) => format!("{} (u16)", indices.len()),
+        Some(Indices::U32(indices)) => format!("{} (u32)", indices.len()),
+        None => "none".to_string(),
+    };
+    let attributes: Vec<_> = mesh
+        .attributes()
+        .map(|(id, values)| {
+            let name = MESH_ATTRIBUTES
+                .iter()
+                .find(|attribute| attribute.id == id)
+                .map_or_else(|| format!("{id:?}"), |attribute| attribute.name.to_string());
+            (name, VertexFormat::from(values), values.len())
+        })
+        .collect();
+    let bounds = mesh.compute_aabb();
+    let submeshes = submesh_count(world, handle);
+
+    egui::Grid::new("mesh_stats").num_columns(2).show(ui, |ui| {
+        ui.label("Topology");
+        ui.label(format!("{:?}", mesh.primitive_topology()));
+        ui.end_row();
+        ui.label("Vertices");
+        ui.label(mesh.count_vertices().to_string());
+        ui.end_row();
+        ui.label("Indices");
+        ui.label(indices);
+        ui.end_row();
+        ui.label("Submeshes");
+        ui.label(match submeshes {
+            Some(count) => format!("{count} in its model"),
+            None => "1".to_string(),
+        });
+        ui.end_row();
+        if let Some(bounds) = bounds {
+            ui.label("Bounds");
+            let (min, max) = (bounds.min(), bounds.max());
+            ui.label(format!(
+                "({:.3}, {:.3}, {:.3}) to ({:.3}, {:.3}, {:.3})",
+                min.x, min.y, min.z, max.x, max.y, max.z
+            ));
+            ui.end_row();
+        }
+    });
+    ui.collapsing("Attributes", |ui| {
+        egui::Grid::new("mesh_attributes")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for (name, format, len) in attributes {
+                    ui.label(name);
+                    ui.label(format!("{format:?}"));
+                    ui.label(len.to_string());
+                    ui.end_row();
+                }
+            });
+    });
+
+    let texture = show_mesh(world, handle, bounds);
+    ui.image(texture, egui::Vec2::splat(160.0));
+    ui.separator();
+}
+
+/// Renders the mesh `handle` in the preview studio, scaled to the size of the material preview
+/// shapes and turning around its center.
+fn show_mesh(world: &mut World, handle: HandleId, bounds: Option<Aabb>) -> egui::TextureId {
+    let (center, radius) = match bounds {
+        Some(bounds) => (
+            Vec3::from(bounds.center),
+            Vec3::from(bounds.half_extents).length().max(f32::EPSILON),
+        ),
+        None => (Vec3::ZERO, 1.0),
+    };
+    let angle = world.resource::<Time>().elapsed_seconds() * MESH_PREVIEW_SPEED * TAU;
+    let rotation = Quat::from_rotation_y(angle);
+    let scale = 1.0 / radius;
+    let transform = Transform {
+        translation: rotation * -center * scale,
+        rotation,
+        scale: Vec3::splat(scale),
+    };
+    let mesh = world.resource::<Assets<Mesh>>().get_handle(handle);
+    let material = studio(world).neutral.clone();
+    show_in_studio(world, mesh, material, transform)
+}
+
+/// Playback of the previewed audio clip.
+#[derive(Resource)]
+pub struct AudioPreview {
+    pub volume: f32,
+    /// The clip being played and the entity playing it, which despawns once it's done.
+    playing: Option<(HandleId, Entity)>,
+}
+
+impl Default for AudioPreview {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            playing: None,
+        }
+    }
+}
+
+/// Shows play, pause and stop buttons and a volume slider for the audio clip `handle`.
+fn audio_preview_ui(world: &mut World, ui: &mut egui::Ui, handle: HandleId) {
+    let loaded = world
+        .resource::<Assets<AudioSource>>()
+        .contains(&Handle::<AudioSource>::weak(handle));
+    // only the inspected clip plays
+    let playing = world.resource::<AudioPreview>().playing;
+    let playing = match playing {
+        Some((clip, entity)) if clip != handle || world.get_entity(entity).is_none() => {
+            if let Some(entity) = world.get_entity_mut(entity) {
+                entity.despawn_recursive();
+            }
+            world.resource_mut::<AudioPreview>().playing = None;
+            None
+        }
+        playing => playing.map(|(_, entity)| entity),
+    };
+    let paused = playing
+        .and_then(|entity| world.get::<AudioSink>(entity))
+        .map(|sink| sink.is_paused());
+
+    let mut volume = world.resource::<AudioPreview>().volume;
+    let (mut play, mut pause, mut stop) = (false, false, false);
+    ui.add_enabled_ui(loaded, |ui| {
+        ui.horizontal(|ui| {
+            match paused {
+                Some(false) => pause = ui.button("⏸ Pause").clicked(),
+                _ => play = ui.button("▶ Play").clicked(),
+            }
+            stop = ui
+                .add_enabled(playing.is_some(), egui::Button::new("⏹ Stop"))
+                .clicked();
+            ui.add(egui::Slider::new(&mut volume, 0.0..=2.0).text("Volume"));
+        });
+    });
+    if !loaded {
+        ui.label("Loading…");
+    }
+
+    world.resource_mut::<AudioPreview>().volume = volume;
+    if let Some(sink) = playing.and_then(|entity| world.get::<AudioSink>(entity)) {
+        sink.set_volume(volume);
+        if play {
+            sink.play();
+        }
+        if pause {
+            sink.pause();
+        }
+    } else if play && playing.is_none() {
+        // the sink is only added a frame after the clip starts
+        let source = world.resource::<Assets<AudioSource>>().get_handle(handle);
+        let entity = world
+            .spawn((
+                Name::new("Editor Audio Preview"),
+                AudioBundle {
+                    source,
+                    settings: PlaybackSettings::DESPAWN.with_volume(Volume::new_relative(volume)),
+                },
+                EditorStudio,
+                EditorOnly,
+            ))
+            .id();
+        world.resource_mut::<AudioPreview>().playing = Some((handle, entity));
+    }
+    if stop {
+        if let Some(entity) = playing.and_then(|entity| world.get_entity_mut(entity)) {
+            entity.despawn_recursive();
+        }
+        world.resource_mut::<AudioPreview>().playing = None;
+    }
+    ui.separator();
+}
+
+/// Bytes of the pixels of `image` in mip level `level`, for 8 bit RGBA and BGRA images.
+fn mip_pixels(image: &Image, level: u32) -> Option<(UVec2, Vec<[u8; 4]>)> {
+    let descriptor = &image.texture_descriptor;
+    let bgra = match descriptor.format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+        _ => return None,
+    };
+    // mip levels are stored one after the other, largest first
+    let size = |level: u32| {
+        UVec2::new(
+            (descriptor.size.width >> level).max(1),
+            (descriptor.size.height >> level).max(1),
+        )
+    };
+    let offset: usize = (0..level)
+        .map(|level| (size(level).x * size(level).y) as usize * 4)
+        .sum();
+    let size = size(level);
+    let bytes = image
+        .data
+        .get(offset..offset + (size.x * size.y) as usize * 4)?;
+    let pixels = bytes
+        .chunks_exact(4)
+        .map(|pixel| match bgra {
+            true => [pixel[2], pixel[1], pixel[0], pixel[3]],
+            false => [pixel[0], pixel[1], pixel[2], pixel[3]],
+        })
+        .collect();
+    Some((size, pixels))
+}
+
+/// Makes the image shown for `source` with the current options of the [`ImagePreview`], unless
+/// it's already shown. Images in formats which can't be read are shown as they are.
+fn update_shown_image(world: &mut World, source: HandleId) -> Option<egui::TextureId> {
+    let preview = world.resource::<ImagePreview>();
+    let (channels, mip_level) = (preview.channels, preview.mip_level);
+    if let Some((id, shown_channels, shown_level, _, texture)) = &preview.shown {
+        if *id == source && *shown_channels == channels && *shown_level == mip_level {
+            return Some(*texture);
+        }
+    }
+
+    let image = world
+        .resource::<Assets<Image>>()
+        .get(&Handle::<Image>::weak(source))?;
+    let shown = match mip_pixels(image, mip_level) {
+        // the source itself needs no changes
+        Some(_) if channels == ImageChannels::Rgba && mip_level == 0 => None,
+        Some((size, pixels)) => Some(Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            pixels
+                .into_iter()
+                .flat_map(|pixel| channels.apply(pixel))
+                .collect(),
+            match image.texture_descriptor.format.is_srgb() {
+                true => TextureFormat::Rgba8UnormSrgb,
+                false => TextureFormat::Rgba8Unorm,
+            },
+        )),
+        None => None,
+    };
+    let mut images = world.resource_mut::<Assets<Image>>();
+    let handle = match shown {
+        Some(shown) => images.add(shown),
+        None => images.get_handle(source),
+    };
+
+    let previous = world.resource_mut::<ImagePreview>().shown.take();
+    let mut textures = world.resource_mut::<EguiUserTextures>();
+    // the source may also be shown elsewhere, like in the asset browser
+    if let Some((previous_source, .., previous, _)) = previous {
+        if previous.id() != previous_source {
+            textures.remove_image(&previous);
+        }
+    }
+    let texture = textures.add_image(handle.clone());
+    world.resource_mut::<ImagePreview>().shown =
+        Some((source, channels, mip_level, handle, texture));
+    Some(texture)
+}
+
+/// Shows the image `handle` with its size and format, with controls to zoom, isolate channels
+/// and pick a mip level.
+fn image_preview_ui(world: &mut World, ui: &mut egui::Ui, handle: HandleId) {
+    let Some(image) = world
+        .resource::<Assets<Image>>()
+        .get(&Handle::<Image>::weak(handle))
+    else {
+        ui.label("Loading…");
+        return;
+    };
+    let descriptor = &image.texture_descriptor;
+    let mip_count = descriptor.mip_level_count;
+    // mip levels are shown at the size of the full image, to compare their detail
+    let full_size = egui::vec2(descriptor.size.width as f32, descriptor.size.height as f32);
+    let readable = mip_pixels(image, 0).is_some();
+    ui.label(format!(
+        "{} x {}, {:?}, {} mip levels",
+        descriptor.size.width, descriptor.size.height, descriptor.format, mip_count
+    ));
+
+    let mut preview = world.resource_mut::<ImagePreview>();
+    preview.mip_level = preview.mip_level.min(mip_count.saturating_sub(1));
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::Slider::new(&mut preview.zoom, 0.1..=8.0)
+                .logarithmic(true)
+                .text("Zoom"),
+        );
+        if ui.button("1:1").clicked() {
+            preview.zoom = 1.0;
+        }
+    });
+    ui.add_enabled_ui(readable, |ui| {
+        ui.horizontal(|ui| {
+            for channels in ImageChannels::ALL {
+                ui.selectable_value(&mut preview.channels, channels, channels.name());
+            }
+            if mip_count > 1 {
+                ui.separator();
+                ui.add(egui::Slider::new(&mut preview.mip_level, 0..=mip_count - 1).text("Mip"));
+            }
+        })
+        .response
+        .on_disabled_hover_text("Only 8 bit RGBA images can be inspected by channel");
+    });
+    let zoom = preview.zoom;
+    if !readable {
+        preview.channels = ImageChannels::Rgba;
+        preview.mip_level = 0;
+    }
+
+    let Some(texture) = update_shown_image(world, handle) else {
+        return;
+    };
+    egui::ScrollArea::both()
+        .max_height(512.0)
+        .id_source("image_preview")
+        .show(ui, |ui| {
+            let (rect, _) = ui.allocate_exact_size(full_size * zoom, egui::Sense::hover());
+            paint_checkerboard(ui, rect);
+            let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+            ui.painter().image(texture, rect, uv, egui::Color32::WHITE);
+        });
+    ui.separator();
+}
+
+/// Paints a checkerboard behind transparent images.
+fn paint_checkerboard(ui: &egui::Ui, rect: egui::Rect) {
+    const CELL: f32 = 8.0;
+    let painter = ui.painter_at(rect.intersect(ui.clip_rect()));
+    painter.rect_filled(rect, 0.0, egui::Color32::from_gray(90));
+    let visible = rect.intersect(ui.clip_rect());
+    let (first_column, first_row) = (
+        ((visible.min.x - rect.min.x) / CELL).floor() as i32,
+        ((visible.min.y - rect.min.y) / CELL).floor() as i32,
+    );
+    let (columns, rows) = (
+        (visible.width() / CELL).ceil() as i32 + 1,
+        (visible.height() / CELL).ceil() as i32 + 1,
+    );
+    for row in first_row..first_row + rows {
+        for column in first_column..first_column + columns {
+            if (row + column) % 2 == 0 {
+                continue;
+            }
+            let min = rect.min + egui::vec2(column as f32, row as f32) * CELL;
+            painter.rect_filled(
+                egui::Rect::from_min_size(min, egui::Vec2::splat(CELL)),
+                0.0,
+                egui::Color32::from_gray(140),
+            );
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::{asset::HandleId, prelude::*, reflect::ReflectRef};
+use bevy_reflect::TypeRegistry;
+
+use crate::EditorOnly;
+
+/// A component of `entity` holding a handle to the asset looked for, at `field`.
+#[derive(Debug, Clone)]
+pub struct AssetUsage {
+    pub entity: Entity,
+    /// The short type name of the component, with the path of the field in it.
+    pub field: String,
+}
+
+fn find_handles(value: &dyn Reflect, path: String, found: &mut Vec<(HandleId, String)>) {
+    let field = |name: &dyn std::fmt::Display| match path.is_empty() {
+        true => name.to_string(),
+        false => format!("{path}.{name}"),
+    };
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for index in 0..value.field_len() {
+                let name = value.name_at(index).unwrap_or_default();
+                // a handle is found as its id, named after the handle rather than the id
+                let path = match name {
+                    "id" if value.type_name().starts_with("bevy_asset::handle::Handle<") => {
+                        path.clone()
+                    }
+                    name => field(&name),
+                };
+                find_handles(value.field_at(index).unwrap(), path, found);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for index in 0..value.field_len() {
+                find_handles(value.field(index).unwrap(), field(&index), found);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for index in 0..value.field_len() {
+                find_handles(value.field(index).unwrap(), field(&index), found);
+            }
+        }
+        ReflectRef::List(value) => {
+            for (index, item) in value.iter().enumerate() {
+                find_handles(item, format!("{path}[{index}]"), found);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for (index, item) in value.iter().enumerate() {
+                find_handles(item, format!("{path}[{index}]"), found);
+            }
+        }
+        ReflectRef::Map(value) => {
+            for (key, item) in value.iter() {
+                find_handles(item, format!("{path}[{key:?}]"), found);
+            }
+        }
+        ReflectRef::Enum(value) => {
+            for (index, variant_field) in value.iter_fields().enumerate() {
+                let name = variant_field
+                    .name()
+                    .map_or_else(|| index.to_string(), str::to_owned);
+                find_handles(variant_field.value(), field(&name), found);
+            }
+        }
+        ReflectRef::Value(value) => {
+            if let Some(&handle) = value.as_any().downcast_ref::<HandleId>() {
+                found.push((handle, path));
+            }
+        }
+    }
+}
+
+/// The handles held by the reflected components of the entities of the scene, with where they're
+/// held.
+pub fn handle_usages(world: &World, type_registry: &TypeRegistry) -> Vec<(HandleId, AssetUsage)> {
+    let mut usages = Vec::new();
+    for entity in world.iter_entities() {
+        if entity.contains::<EditorOnly>() {
+            continue;
+        }
+        for component_id in entity.archetype().components() {
+            let Some(type_id) = world
+                .components()
+                .get_info(component_id)
+                .and_then(|info| info.type_id())
+            else {
+                continue;
+            };
+            let Some(registration) = type_registry.get(type_id) else {
+                continue;
+            };
+            let Some(component) = registration
+                .data::<ReflectComponent>()
+                .and_then(|reflect_component| reflect_component.reflect(entity))
+            else {
+                continue;
+            };
+            let mut found = Vec::new();
+            let name = registration.short_name().to_owned();
+            find_handles(component, name, &mut found);
+            usages.extend(found.into_iter().map(|(handle, field)| {
+                let usage = AssetUsage {
+                    entity: entity.id(),
+                    field,
+                };
+                (handle, usage)
+            }));
+        }
+    }
+    usages.sort_by_key(|(_, usage)| usage.entity);
+    usages
+}
+
+/// The reflected components of the entities of the scene holding a handle to the asset `handle`.
+pub fn asset_usages(
+    world: &World,
+    type_registry: &TypeRegistry,
+    handle: HandleId,
+) -> Vec<AssetUsage> {
+    handle_usages(world, type_registry)
+        .into_iter()
+        .filter(|(other, _)| *other == handle)
+        .map(|(_, usage)| usage)
+        .collect()
+}
@@ -0,0 +1,142 @@
+use std::{
+    fs,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{play::EditorState, scene, settings::EditorSettings, EditorResource};
+
+pub const AUTOSAVE_DIR: &str = "assets/scenes/autosave";
+const AUTOSAVE_EXTENSION: &str = "scn.ron";
+
+pub struct EditorAutosavePlugin;
+impl Plugin for EditorAutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AutosaveSettings>()
+            .init_resource::<Autosave>()
+            .add_systems(Last, autosave);
+    }
+}
+
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutosaveSettings {
+    pub enabled: bool,
+    pub interval_minutes: f32,
+    /// Number of backups kept in [`AUTOSAVE_DIR`], the oldest are deleted first.
+    pub max_backups: usize,
+}
+
+impl Default for AutosaveSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: 5.0,
+            max_backups: 10,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Autosave {
+    since_save: Duration,
+    last_save: Option<Instant>,
+    last_error: Option<String>,
+}
+
+/// Saves the scene every [`AutosaveSettings::interval_minutes`] of real time while the editor is
+/// open and not playing, so a running simulation never ends up in a backup.
+fn autosave(world: &mut World) {
+    let settings = world.resource::<EditorSettings>().autosave.clone();
+    let editing = world.resource::<EditorResource>().0
+        && *world.resource::<State<EditorState>>().get() == EditorState::Edit;
+    if !settings.enabled || !editing {
+        return;
+    }
+
+    let delta = world.resource::<Time>().raw_delta();
+    let mut autosave = world.resource_mut::<Autosave>();
+    autosave.since_save += delta;
+    if autosave.since_save.as_secs_f32() < settings.interval_minutes.max(0.1) * 60.0 {
+        return;
+    }
+    autosave.since_save = Duration::ZERO;
+
+    let result = save_backup(world, settings.max_backups);
+    let mut autosave = world.resource_mut::<Autosave>();
+    match result {
+        Ok(path) => {
+            info!("Autosaved scene to {path}");
+            autosave.last_save = Some(Instant::now());
+            autosave.last_error = None;
+        }
+        Err(e) => {
+            error!("Autosave failed: {e}");
+            autosave.last_error = Some(e.to_string());
+        }
+    }
+}
+
+fn save_backup(world: &mut World, max_backups: usize) -> Result<String, scene::SceneFileError> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = format!("{AUTOSAVE_DIR}/autosave_{timestamp}.{AUTOSAVE_EXTENSION}");
+    let entities = scene::scene_entities(world);
+    scene::save_scene(world, &entities, &path)?;
+    remove_old_backups(max_backups.max(1));
+    Ok(path)
+}
+
+fn remove_old_backups(max_backups: usize) {
+    let Ok(dir) = fs::read_dir(AUTOSAVE_DIR) else {
+        return;
+    };
+    let mut backups: Vec<_> = dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("autosave_"))
+        })
+        .collect();
+    // timestamps all have the same number of digits, so this sorts oldest first
+    backups.sort();
+    let excess = backups.len().saturating_sub(max_backups);
+    for path in &backups[..excess] {
+        if let Err(e) = fs::remove_file(path) {
+            warn!("Failed to remove old autosave {}: {e}", path.display());
+        }
+    }
+}
+
+/// Time since the last autosave, for the status bar.
+pub fn autosave_status_ui(world: &World, ui: &mut egui::Ui) {
+    let settings = &world.resource::<EditorSettings>().autosave;
+    let autosave = world.resource::<Autosave>();
+    if !settings.enabled {
+        ui.weak("Autosave off");
+        return;
+    }
+    if let Some(e) = &autosave.last_error {
+        ui.colored_label(egui::Color32::from_rgb(230, 80, 80), "Autosave failed")
+            .on_hover_text(e);
+        return;
+    }
+    match autosave.last_save {
+        Some(last_save) => {
+            let minutes = last_save.elapsed().as_secs() / 60;
+            match minutes {
+                0 => ui.weak("Autosaved just now"),
+                1 => ui.weak("Autosaved 1 minute ago"),
+                minutes => ui.weak(format!("Autosaved {minutes} minutes ago")),
+            }
+        }
+        None => ui.weak("Not autosaved yet"),
+    }
+    .on_hover_text(format!("Backups are written to {AUTOSAVE_DIR}"));
+}
@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use editor::remote_client::EditorRemoteClientPlugin;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Remote Editor".to_owned(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins(EditorRemoteClientPlugin)
+        .run();
+}
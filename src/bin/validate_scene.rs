@@ -0,0 +1,63 @@
+//! Checks scene files for missing assets, numbers which aren't finite and references to entities
+//! outside of the scene, exiting with an error if any is found:
+//!
+//! ```text
+//! validate_scene [--assets <dir>] <scene>...
+//! ```
+//!
+//! Only the types of bevy are known, games with types of their own call
+//! [`editor::validation::validate_scene_files`] from an app with them registered.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use bevy::{
+    prelude::*,
+    render::{settings::WgpuSettings, RenderPlugin},
+    winit::WinitPlugin,
+};
+use editor::{asset_browser::ASSET_DIR, validation};
+
+fn main() -> ExitCode {
+    let mut asset_dir = PathBuf::from(ASSET_DIR);
+    let mut scenes = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--assets" => match args.next() {
+                Some(dir) => asset_dir = dir.into(),
+                None => {
+                    eprintln!("--assets needs a folder");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => scenes.push(PathBuf::from(arg)),
+        }
+    }
+    if scenes.is_empty() {
+        eprintln!("usage: validate_scene [--assets <dir>] <scene>...");
+        return ExitCode::FAILURE;
+    }
+
+    // the plugins are only built to register their types, no window or renderer is made
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                ..default()
+            })
+            .set(RenderPlugin {
+                wgpu_settings: WgpuSettings {
+                    backends: None,
+                    ..default()
+                },
+            })
+            .disable::<WinitPlugin>()
+            .disable::<bevy::log::LogPlugin>()
+            .disable::<bevy::audio::AudioPlugin>(),
+    );
+    match validation::validate_scene_files(&app, &scenes, &asset_dir) {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
@@ -0,0 +1,476 @@
+use bevy::{
+    input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
+    prelude::*,
+    render::primitives::Aabb,
+    window::PrimaryWindow,
+};
+use bevy_inspector_egui::bevy_egui::EguiSettings;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input::{EditorAction, EditorInputCapture},
+    mode2d::EditorView2d,
+    scene,
+    settings::EditorSettings,
+    views::EditorViewport,
+    EditorResource, UiState,
+};
+
+/// How quickly framing the selection moves the camera, higher is faster.
+const FRAMING_SPEED: f32 = 12.0;
+/// Radius framed around selections without bounds, or with empty ones.
+const MIN_FRAMING_RADIUS: f32 = 0.5;
+
+pub struct EditorCameraPlugin;
+impl Plugin for EditorCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<EditorCameraController>()
+            .register_type::<CameraSettings>()
+            .add_systems(
+                Update,
+                (
+                    apply_camera_settings,
+                    focus_selection_hotkey,
+                    editor_camera_controller,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraDrag {
+    #[default]
+    None,
+    Fly,
+    Orbit,
+    Pan,
+}
+
+/// Editor navigation for the [`MainCamera`](crate::MainCamera) and the cameras of additional
+/// game views, each moving while the cursor is over its own view.
+///
+/// Hold the right mouse button to fly with WASD/QE and Shift to fly faster, Alt + left drag to
+/// orbit around the selection, drag with the middle mouse button to pan and scroll to zoom. In 2D
+/// mode, both the right and middle mouse buttons pan. F frames the selection. The keys are those
+/// of the default [`EditorKeybinds`](crate::input::EditorKeybinds).
+///
+/// On touch screens, drag a finger to orbit, or to pan in 2D mode, and two fingers to pan and
+/// pinch to zoom.
+#[derive(Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct EditorCameraController {
+    pub focus: Vec3,
+    pub fly_speed: f32,
+    pub sensitivity: f32,
+    pub zoom_speed: f32,
+    #[reflect(ignore)]
+    pub drag: CameraDrag,
+    /// Whether the drag is made with fingers rather than the mouse.
+    #[reflect(ignore)]
+    pub touch: bool,
+    /// Where the camera is smoothly moving to frame the selection.
+    #[reflect(ignore)]
+    pub framing: Option<Framing>,
+}
+
+/// How the editor cameras move, given to every [`EditorCameraController`].
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraSettings {
+    pub fly_speed: f32,
+    pub sensitivity: f32,
+    pub zoom_speed: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        let controller = EditorCameraController::default();
+        Self {
+            fly_speed: controller.fly_speed,
+            sensitivity: controller.sensitivity,
+            zoom_speed: controller.zoom_speed,
+        }
+    }
+}
+
+fn apply_camera_settings(
+    settings: Res<EditorSettings>,
+    mut controllers: Query<&mut EditorCameraController>,
+) {
+    let camera = &settings.camera;
+    for mut controller in &mut controllers {
+        if settings.is_changed() || controller.is_added() {
+            controller.fly_speed = camera.fly_speed;
+            controller.sensitivity = camera.sensitivity;
+            controller.zoom_speed = camera.zoom_speed;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Framing {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub focus: Vec3,
+    /// Scale of orthographic projections.
+    pub scale: Option<f32>,
+}
+
+impl Default for EditorCameraController {
+    fn default() -> Self {
+        Self {
+            focus: Vec3::ZERO,
+            fly_speed: 5.0,
+            sensitivity: 0.005,
+            zoom_speed: 0.1,
+            drag: CameraDrag::None,
+            touch: false,
+            framing: None,
+        }
+    }
+}
+
+impl UiState {
+    pub(crate) fn selection_centroid(&self, transforms: &Query<&GlobalTransform>) -> Option<Vec3> {
+        let positions: Vec<_> = self
+            .selected_entities
+            .iter()
+            .filter_map(|entity| transforms.get(entity).ok())
+            .map(|transform| transform.translation())
+            .collect();
+        (!positions.is_empty()).then(|| positions.iter().sum::<Vec3>() / positions.len() as f32)
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn editor_camera_controller(
+    ed: Res<EditorResource>,
+    ui_state: Res<UiState>,
+    egui_settings: Res<EguiSettings>,
+    time: Res<Time>,
+    actions: Res<Input<EditorAction>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+    touches: Res<Touches>,
+    capture: Res<EditorInputCapture>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    transforms: Query<&GlobalTransform>,
+    mut cameras: Query<(
+        &mut Transform,
+        &mut EditorCameraController,
+        Option<&mut Projection>,
+        Option<&mut OrthographicProjection>,
+        &EditorViewport,
+        Option<&EditorView2d>,
+    )>,
+) {
+    let motion: Vec2 = mouse_motion.iter().map(|motion| motion.delta).sum();
+    let scroll: f32 = mouse_wheel
+        .iter()
+        .map(|wheel| match wheel.unit {
+            MouseScrollUnit::Line => wheel.y,
+            MouseScrollUnit::Pixel => wheel.y / 100.0,
+        })
+        .sum();
+
+    let touches: Vec<_> = touches.iter().collect();
+    let touch_motion = match touches.len() {
+        0 => Vec2::ZERO,
+        count => touches.iter().map(|touch| touch.delta()).sum::<Vec2>() / count as f32,
+    };
+    let pinch = match touches[..] {
+        [a, b, ..] => {
+            let distance = a.position().distance(b.position());
+            let previous = a.previous_position().distance(b.previous_position());
+            (distance > 0.0 && previous > 0.0).then(|| previous / distance)
+        }
+        _ => None,
+    };
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let cursor = window.cursor_position();
+
+    for (mut transform, mut controller, mut projection, mut orthographic, viewport, view_2d) in
+        &mut cameras
+    {
+        if !ed.0 {
+            controller.drag = CameraDrag::None;
+            continue;
+        }
+        let hovered = cursor
+            .is_some_and(|cursor| viewport.contains_cursor(cursor, egui_settings.scale_factor));
+        let touched = touches.first().is_some_and(|touch| {
+            viewport.contains_cursor(touch.start_position(), egui_settings.scale_factor)
+        });
+
+        let alt = actions.pressed(EditorAction::Orbit);
+        let is_2d = view_2d.is_some();
+        // a finger on the gizmo moves the selection instead
+        if controller.drag == CameraDrag::None && touched && !capture.pointer {
+            controller.touch = true;
+        }
+        if controller.touch {
+            controller.drag = match touches.len() {
+                _ if capture.pointer => CameraDrag::None,
+                0 => CameraDrag::None,
+                1 if !is_2d => CameraDrag::Orbit,
+                _ => CameraDrag::Pan,
+            };
+            controller.touch = controller.drag != CameraDrag::None;
+        } else if controller.drag == CameraDrag::None && hovered {
+            if is_2d {
+                if mouse_buttons.any_just_pressed([MouseButton::Right, MouseButton::Middle]) {
+                    controller.drag = CameraDrag::Pan;
+                }
+            } else if mouse_buttons.just_pressed(MouseButton::Right) {
+                controller.drag = CameraDrag::Fly;
+            } else if alt && mouse_buttons.just_pressed(MouseButton::Left) {
+                controller.drag = CameraDrag::Orbit;
+                if let Some(centroid) = ui_state.selection_centroid(&transforms) {
+                    controller.focus = centroid;
+                    transform.look_at(centroid, Vec3::Y);
+                }
+            } else if mouse_buttons.just_pressed(MouseButton::Middle) {
+                controller.drag = CameraDrag::Pan;
+            }
+        }
+
+        if controller.drag != CameraDrag::None {
+            controller.framing = None;
+        }
+        if let Some(framing) = controller.framing {
+            let t = 1.0 - (-FRAMING_SPEED * time.raw_delta_seconds()).exp();
+            transform.translation = transform.translation.lerp(framing.translation, t);
+            transform.rotation = transform.rotation.slerp(framing.rotation, t);
+            controller.focus = controller.focus.lerp(framing.focus, t);
+            let ortho = match (projection.as_deref_mut(), orthographic.as_deref_mut()) {
+                (Some(Projection::Orthographic(ortho)), _) | (_, Some(ortho)) => Some(ortho),
+                _ => None,
+            };
+            let mut done = transform.translation.distance(framing.translation) < 1e-3
+                && transform.rotation.angle_between(framing.rotation) < 1e-3;
+            if let (Some(ortho), Some(scale)) = (ortho, framing.scale) {
+                ortho.scale += (scale - ortho.scale) * t;
+                done &= (ortho.scale - scale).abs() < 1e-3 * scale;
+            }
+            if done {
+                controller.focus = framing.focus;
+                controller.framing = None;
+            }
+        }
+
+        let motion = match controller.touch {
+            true => touch_motion,
+            false => motion,
+        };
+        let released = match controller.drag {
+            _ if controller.touch => false,
+            CameraDrag::None => false,
+            CameraDrag::Fly => !mouse_buttons.pressed(MouseButton::Right),
+            CameraDrag::Orbit => !mouse_buttons.pressed(MouseButton::Left),
+            CameraDrag::Pan if is_2d => {
+                !mouse_buttons.any_pressed([MouseButton::Right, MouseButton::Middle])
+            }
+            CameraDrag::Pan => !mouse_buttons.pressed(MouseButton::Middle),
+        };
+        if released {
+            controller.drag = CameraDrag::None;
+        }
+
+        let distance = transform.translation.distance(controller.focus).max(0.1);
+        match controller.drag {
+            CameraDrag::None => {}
+            CameraDrag::Fly => {
+                rotate(&mut transform, motion * controller.sensitivity);
+
+                let mut direction = Vec3::ZERO;
+                for (action, axis) in [
+                    (EditorAction::FlyForward, transform.forward()),
+                    (EditorAction::FlyBack, transform.back()),
+                    (EditorAction::FlyLeft, transform.left()),
+                    (EditorAction::FlyRight, transform.right()),
+                    (EditorAction::FlyUp, Vec3::Y),
+                    (EditorAction::FlyDown, Vec3::NEG_Y),
+                ] {
+                    if actions.pressed(action) {
+                        direction += axis;
+                    }
+                }
+                let mut speed = controller.fly_speed;
+                if actions.pressed(EditorAction::FlyFast) {
+                    speed *= 3.0;
+                }
+                transform.translation +=
+                    direction.normalize_or_zero() * speed * time.raw_delta_seconds();
+
+                // keep the orbit focus in front of the camera
+                controller.focus = transform.translation + transform.forward() * distance;
+            }
+            CameraDrag::Orbit => {
+                let focus = controller.focus;
+                rotate(&mut transform, motion * controller.sensitivity);
+                transform.translation = focus - transform.forward() * distance;
+            }
+            CameraDrag::Pan if is_2d => {
+                // follow the cursor, using the visible area of the orthographic projection
+                let area = match (projection.as_deref(), orthographic.as_deref()) {
+                    (Some(Projection::Orthographic(ortho)), _) | (_, Some(ortho)) => ortho.area,
+                    _ => Rect::default(),
+                };
+                let height = viewport.rect.height() * egui_settings.scale_factor as f32;
+                let units_per_pixel = area.height() / height.max(1.0);
+                let pan = Vec3::new(-motion.x, motion.y, 0.0) * units_per_pixel;
+                transform.translation += pan;
+                controller.focus += pan;
+            }
+            CameraDrag::Pan => {
+                let pan = (transform.left() * motion.x + transform.up() * motion.y)
+                    * controller.sensitivity
+                    * distance
+                    * 0.2;
+                transform.translation += pan;
+                controller.focus += pan;
+            }
+        }
+
+        let zoom = match (controller.touch, pinch) {
+            (true, Some(pinch)) => Some(pinch),
+            (false, _) if hovered && scroll != 0.0 => Some(1.0 - scroll * controller.zoom_speed),
+            _ => None,
+        };
+        if let Some(factor) = zoom {
+            controller.framing = None;
+            let factor = factor.clamp(0.1, 10.0);
+            match (
+                projection.map(|projection| projection.into_inner()),
+                orthographic,
+            ) {
+                (Some(Projection::Orthographic(ortho)), _) => ortho.scale *= factor,
+                (_, Some(mut ortho)) => ortho.scale *= factor,
+                _ => {
+                    let offset = transform.translation - controller.focus;
+                    transform.translation = controller.focus + offset * factor;
+                }
+            }
+        }
+    }
+}
+
+fn focus_selection_hotkey(world: &mut World) {
+    if !world.resource::<EditorResource>().0 {
+        return;
+    }
+
+    if world
+        .resource::<Input<EditorAction>>()
+        .just_pressed(EditorAction::FocusSelection)
+    {
+        focus_selection(world);
+    }
+}
+
+/// Center and radius of a sphere around the bounds of the selected entities and their
+/// descendants. Entities without bounds count as a point at their origin.
+fn selection_bounds(world: &World, selected: &[Entity]) -> Option<(Vec3, f32)> {
+    let (mut min, mut max) = (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY));
+    for entity in scene::with_descendants(world, selected) {
+        let Some(transform) = world.get::<GlobalTransform>(entity) else {
+            continue;
+        };
+        let corners = match world.get::<Aabb>(entity) {
+            Some(aabb) => {
+                let (aabb_min, aabb_max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+                (0..8)
+                    .map(|corner| {
+                        Vec3::select(
+                            BVec3::new(corner & 1 != 0, corner & 2 != 0, corner & 4 != 0),
+                            aabb_max,
+                            aabb_min,
+                        )
+                    })
+                    .map(|corner| transform.transform_point(corner))
+                    .collect()
+            }
+            None => vec![transform.translation()],
+        };
+        for corner in corners {
+            min = min.min(corner);
+            max = max.max(corner);
+        }
+    }
+    (min.x <= max.x).then(|| {
+        let radius = (max - min).length() / 2.0;
+        ((min + max) / 2.0, radius.max(MIN_FRAMING_RADIUS))
+    })
+}
+
+/// Smoothly moves every editor camera so the selection fills its view, keeping the direction it
+/// looks in. Orthographic cameras zoom to fit instead of moving closer.
+pub fn focus_selection(world: &mut World) {
+    let selected = world
+        .resource::<UiState>()
+        .selected_entities
+        .as_slice()
+        .to_vec();
+    focus_entities(world, &selected);
+}
+
+/// Like [`focus_selection`], framing `entities` rather than the selection.
+pub fn focus_entities(world: &mut World, entities: &[Entity]) {
+    let Some((center, radius)) = selection_bounds(world, entities) else {
+        info!("Nothing selected to focus on");
+        return;
+    };
+
+    let mut cameras = world.query::<(
+        &Transform,
+        &mut EditorCameraController,
+        Option<&Projection>,
+        Option<&OrthographicProjection>,
+        Option<&EditorView2d>,
+    )>();
+    for (transform, mut controller, projection, orthographic, view_2d) in cameras.iter_mut(world) {
+        let forward = transform.forward();
+        let ortho = match (projection, orthographic) {
+            (Some(Projection::Orthographic(ortho)), _) | (_, Some(ortho)) => Some(ortho),
+            _ => None,
+        };
+        let (distance, scale) = match (projection, ortho) {
+            (Some(Projection::Perspective(perspective)), _) => {
+                // fit the sphere in the narrower of the vertical and horizontal field of view
+                let half_fov = (perspective.fov / 2.0).tan();
+                let half_fov = half_fov.min(half_fov * perspective.aspect_ratio).atan();
+                (radius / half_fov.sin(), None)
+            }
+            (_, Some(ortho)) => {
+                // the visible area grows with the scale
+                let size = ortho.area.width().min(ortho.area.height()) / ortho.scale;
+                let distance = transform.translation.distance(controller.focus);
+                (distance, Some(radius * 2.0 / size.max(f32::EPSILON)))
+            }
+            _ => (radius * 2.0, None),
+        };
+        let mut translation = center - forward * distance;
+        if view_2d.is_some() {
+            // 2D views stay in front of the XY plane
+            translation.z = transform.translation.z;
+        }
+        controller.framing = Some(Framing {
+            translation,
+            rotation: transform.rotation,
+            focus: center,
+            scale,
+        });
+    }
+}
+
+pub(crate) fn rotate(transform: &mut Transform, delta: Vec2) {
+    let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+    let yaw = yaw - delta.x;
+    let pitch = (pitch - delta.y).clamp(-1.54, 1.54);
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+}
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+
+use crate::{
+    input::EditorAction,
+    scene::{self, SceneFileError},
+    settings::EditorSettings,
+    views::EditorView,
+    EditorResource, InspectorSelection, MainCamera, UiState,
+};
+
+pub struct EditorClipboardPlugin;
+impl Plugin for EditorClipboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorClipboard>()
+            .add_systems(Update, clipboard_hotkeys);
+    }
+}
+
+/// Entities cloned through reflection, ready to be spawned any number of times.
+pub struct CopiedEntities {
+    scene: DynamicScene,
+    /// Every copied root with the parent it had when it was copied.
+    roots: Vec<(Entity, Option<Entity>)>,
+}
+
+#[derive(Resource, Default)]
+pub struct EditorClipboard {
+    pub copied: Option<CopiedEntities>,
+}
+
+/// Copies `entities` and their descendants. The main camera and windows are never copied.
+pub fn copy_entities(world: &World, entities: &[Entity]) -> CopiedEntities {
+    let roots: Vec<_> = scene::top_level_entities(world, entities)
+        .into_iter()
+        .filter(|&entity| {
+            world.get::<MainCamera>(entity).is_none()
+                && world.get::<EditorView>(entity).is_none()
+                && world.get::<Window>(entity).is_none()
+        })
+        .collect();
+    let scene = scene::build_scene(world, &scene::with_descendants(world, &roots));
+    let roots = roots
+        .into_iter()
+        .map(|root| (root, world.get::<Parent>(root).map(|parent| parent.get())))
+        .collect();
+    CopiedEntities { scene, roots }
+}
+
+/// Spawns a copy of `copied`, putting each copied root back under its previous parent if that
+/// still exists. Returns the new roots.
+pub fn paste_entities(
+    world: &mut World,
+    copied: &CopiedEntities,
+) -> Result<Vec<Entity>, SceneFileError> {
+    let entity_map = scene::spawn_scene_mapped(world, &copied.scene)?;
+
+    let mut roots = Vec::new();
+    for &(root, parent) in &copied.roots {
+        let Some(new_root) = entity_map.get(root) else {
+            continue;
+        };
+        if let Some(parent) = parent.filter(|&parent| world.get_entity(parent).is_some()) {
+            world.entity_mut(new_root).set_parent(parent);
+        }
+        roots.push(new_root);
+    }
+    Ok(roots)
+}
+
+pub fn copy_selection(world: &mut World) {
+    let selected = world
+        .resource::<UiState>()
+        .selected_entities
+        .as_slice()
+        .to_vec();
+    if selected.is_empty() {
+        return;
+    }
+    let copied = copy_entities(world, &selected);
+    world.resource_mut::<EditorClipboard>().copied = Some(copied);
+}
+
+pub fn paste(world: &mut World) {
+    let result = world.resource_scope(|world, clipboard: Mut<EditorClipboard>| {
+        clipboard
+            .copied
+            .as_ref()
+            .map(|copied| paste_entities(world, copied))
+    });
+    match result {
+        Some(Ok(roots)) => select_entities(world, &roots),
+        Some(Err(e)) => error!("Failed to paste entities: {e}"),
+        None => {}
+    }
+}
+
+/// Duplicates the selection next to the originals and selects the copies.
+pub fn duplicate_selection(world: &mut World) {
+    let selected = world
+        .resource::<UiState>()
+        .selected_entities
+        .as_slice()
+        .to_vec();
+    if selected.is_empty() {
+        return;
+    }
+    let roots = duplicate_entities(world, &selected);
+    select_entities(world, &roots);
+}
+
+/// Duplicates `entities` and their descendants next to the originals. Returns the new roots.
+pub fn duplicate_entities(world: &mut World, entities: &[Entity]) -> Vec<Entity> {
+    let copied = copy_entities(world, entities);
+    let roots = match paste_entities(world, &copied) {
+        Ok(roots) => roots,
+        Err(e) => {
+            error!("Failed to duplicate entities: {e}");
+            return Vec::new();
+        }
+    };
+
+    let offset = world.resource::<EditorSettings>().duplicate_offset;
+    for &root in &roots {
+        if let Some(mut transform) = world.get_mut::<Transform>(root) {
+            transform.translation += offset;
+        }
+    }
+    roots
+}
+
+pub(crate) fn select_entities(world: &mut World, entities: &[Entity]) {
+    let mut ui_state = world.resource_mut::<UiState>();
+    ui_state.selected_entities.clear();
+    for &entity in entities {
+        ui_state.selected_entities.select_maybe_add(entity, true);
+    }
+    ui_state.selection = InspectorSelection::Entities;
+}
+
+fn clipboard_hotkeys(world: &mut World) {
+    if !world.resource::<EditorResource>().0 {
+        return;
+    }
+
+    let actions = world.resource::<Input<EditorAction>>();
+    if actions.just_pressed(EditorAction::Copy) {
+        copy_selection(world);
+    } else if actions.just_pressed(EditorAction::Paste) {
+        paste(world);
+    } else if actions.just_pressed(EditorAction::Duplicate) {
+        duplicate_selection(world);
+    }
+}
@@ -0,0 +1,573 @@
+//! Editing a scene together from several editors, enabled with the `remote` feature.
+//!
+//! Every editor hosts a session through its [`remote`](crate::remote) server, which other
+//! editors with the same scene loaded can join. The component edits made in each editor, undo
+//! and redo included, are sent to the others, and everyone's selection is outlined in their
+//! color. Entities are matched by the names from the root of their hierarchy, so entities
+//! spawned or renamed after joining aren't matched until the others do the same.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    env,
+    hash::{Hash, Hasher},
+};
+
+use bevy::{
+    prelude::*,
+    reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer},
+    render::primitives::Aabb,
+    transform::TransformSystem,
+};
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    history::{self, EditTarget, EditorHistory},
+    outline,
+    remote::RemoteConnection,
+    scene, EditorResource, UiState,
+};
+
+/// How often a joined editor sends its edits and selection to the host.
+const SYNC_INTERVAL: f32 = 0.1;
+/// How long the host waits on a joined editor before dropping it from the session.
+const USER_TIMEOUT: f64 = 5.0;
+/// How many edits the host keeps for the joined editors which haven't received them yet.
+const MAX_LOGGED_EDITS: usize = 4096;
+/// The id of the host in the session.
+const HOST: u64 = 0;
+
+pub struct EditorCollabPlugin;
+impl Plugin for EditorCollabPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Session>()
+            .add_systems(Last, sync_session.before(history::end_history_frame))
+            .add_systems(
+                PostUpdate,
+                draw_user_selections.after(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// A component changed in one of the editors of the session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Edit {
+    /// The path of the entity, see [`entity_paths`].
+    entity: String,
+    /// The full type name of the component.
+    component: String,
+    value: Value,
+}
+
+/// Another editor in the session.
+pub struct SessionUser {
+    id: u64,
+    pub name: String,
+    pub color: Color,
+    /// The paths of the entities the user selected.
+    selection: Vec<String>,
+    /// The entities of this world matching the selection.
+    selected: Vec<Entity>,
+    /// When the host last heard from the user.
+    seen_at: f64,
+}
+
+/// The editor session hosted by the host of a joined editor.
+struct HostConnection {
+    address: String,
+    connection: RemoteConnection,
+    /// The id given by the host once joined.
+    user: Option<u64>,
+    /// The last edit of the host received.
+    since: u64,
+    awaiting: bool,
+    since_sync: f32,
+    /// Edits made here since the last update of the host.
+    outgoing: Vec<Edit>,
+}
+
+/// The users editing with this editor, and the edits they haven't received yet.
+#[derive(Resource)]
+pub struct Session {
+    pub name: String,
+    pub color: Color,
+    /// The address of the host typed in the session menu.
+    pub join_address: String,
+    users: Vec<SessionUser>,
+    next_user: u64,
+    /// The edits hosted, with their number and who made them.
+    log: VecDeque<(u64, u64, Edit)>,
+    next_edit: u64,
+    host: Option<HostConnection>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        let name = env::var("USER")
+            .or_else(|_| env::var("USERNAME"))
+            .unwrap_or_else(|_| "Editor".to_owned());
+        // a color told apart from the others, which stays the same for a name
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        name.hash(&mut hasher);
+        let hue = (hasher.finish() % 360) as f32;
+        Self {
+            name,
+            color: Color::hsl(hue, 0.8, 0.6),
+            join_address: crate::remote::REMOTE_ADDRESS.to_owned(),
+            users: Vec::new(),
+            next_user: HOST + 1,
+            log: VecDeque::new(),
+            next_edit: 1,
+            host: None,
+        }
+    }
+}
+
+impl Session {
+    pub fn users(&self) -> impl Iterator<Item = &SessionUser> {
+        self.users.iter()
+    }
+
+    /// Whether this editor joined the session of another.
+    pub fn is_joined(&self) -> bool {
+        self.host.is_some()
+    }
+
+    pub fn host_address(&self) -> Option<&str> {
+        self.host.as_ref().map(|host| host.address.as_str())
+    }
+
+    /// Joins the session hosted by the editor at `address`.
+    pub fn join(&mut self, address: &str) {
+        self.leave();
+        let mut connection = match RemoteConnection::connect(address) {
+            Ok(connection) => connection,
+            Err(e) => {
+                error!("Failed to join the session at {address}: {e}");
+                return;
+            }
+        };
+        let request = json!({
+            "method": "join",
+            "name": self.name,
+            "color": self.color.as_rgba_f32(),
+        });
+        if let Err(e) = connection.send(&request) {
+            error!("Failed to join the session at {address}: {e}");
+            return;
+        }
+        self.host = Some(HostConnection {
+            address: address.to_owned(),
+            connection,
+            user: None,
+            since: 0,
+            awaiting: true,
+            since_sync: 0.0,
+            outgoing: Vec::new(),
+        });
+    }
+
+    pub fn leave(&mut self) {
+        if let Some(host) = self.host.take() {
+            info!("Left the session at {}", host.address);
+        }
+        self.users.clear();
+    }
+
+    fn log(&mut self, user: u64, edit: Edit) {
+        self.log.push_back((self.next_edit, user, edit));
+        self.next_edit += 1;
+        if self.log.len() > MAX_LOGGED_EDITS {
+            self.log.pop_front();
+        }
+    }
+}
+
+/// Paths of the entities of the scene made of their names from the root of their hierarchy, with
+/// siblings of the same name numbered, so the same entity has the same path in every editor with
+/// the same scene.
+fn entity_paths(world: &mut World) -> HashMap<Entity, String> {
+    let entities = scene::scene_entities(world);
+    let mut paths = HashMap::new();
+    let mut stack = vec![(String::new(), scene::top_level_entities(world, &entities))];
+    while let Some((parent, siblings)) = stack.pop() {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for entity in siblings {
+            let name = world.get::<Name>(entity).map_or("", |name| name.as_str());
+            let count = counts.entry(name).or_default();
+            let name = match *count {
+                0 => name.to_owned(),
+                n => format!("{name}#{n}"),
+            };
+            *count += 1;
+            let path = match parent.is_empty() {
+                true => name,
+                false => format!("{parent}/{name}"),
+            };
+            if let Some(children) = world.get::<Children>(entity) {
+                stack.push((path.clone(), children.to_vec()));
+            }
+            paths.insert(entity, path);
+        }
+    }
+    paths
+}
+
+fn entities_by_path(paths: &HashMap<Entity, String>) -> HashMap<&str, Entity> {
+    paths
+        .iter()
+        .map(|(&entity, path)| (path.as_str(), entity))
+        .collect()
+}
+
+/// The component edits made here this frame.
+fn local_edits(world: &World, paths: &HashMap<Entity, String>) -> Vec<Edit> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let mut edits: Vec<Edit> = Vec::new();
+    for &target in world.resource::<EditorHistory>().changed() {
+        let EditTarget::Component { entity, type_id } = target else {
+            continue;
+        };
+        let (Some(path), Some(registration)) = (paths.get(&entity), type_registry.get(type_id))
+        else {
+            continue;
+        };
+        let value = registration
+            .data::<ReflectComponent>()
+            .zip(world.get_entity(entity))
+            .and_then(|(reflect_component, entity)| reflect_component.reflect(entity));
+        let Some(value) = value else {
+            continue;
+        };
+        let value = match serde_json::to_value(TypedReflectSerializer::new(value, &type_registry)) {
+            Ok(value) => value,
+            Err(e) => {
+                warn!("Can't share the edit of {}: {e}", registration.short_name());
+                continue;
+            }
+        };
+        let edit = Edit {
+            entity: path.clone(),
+            component: registration.type_name().to_owned(),
+            value,
+        };
+        // only the last value of a component changed several times is sent
+        edits.retain(|other| other.entity != edit.entity || other.component != edit.component);
+        edits.push(edit);
+    }
+    edits
+}
+
+/// Applies an edit made in another editor, without recording it in the history which is
+/// everyone's own.
+fn apply_edit(world: &mut World, entities: &HashMap<&str, Entity>, edit: &Edit) {
+    let Some(&entity) = entities.get(edit.entity.as_str()) else {
+        warn!(
+            "No entity {} to apply the edit of another editor to",
+            edit.entity
+        );
+        return;
+    };
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let Some((registration, reflect_component)) = type_registry
+        .get_with_name(&edit.component)
+        .and_then(|registration| Some((registration, registration.data::<ReflectComponent>()?)))
+    else {
+        warn!("No registered component {} to apply", edit.component);
+        return;
+    };
+    match TypedReflectDeserializer::new(registration, &type_registry).deserialize(&edit.value) {
+        Ok(value) => reflect_component.apply_or_insert(&mut world.entity_mut(entity), &*value),
+        Err(e) => warn!("Invalid edit of {}: {e}", edit.component),
+    }
+}
+
+fn selection_paths(world: &World, paths: &HashMap<Entity, String>) -> Vec<String> {
+    world
+        .resource::<UiState>()
+        .selected_entities
+        .iter()
+        .filter_map(|entity| paths.get(&entity).cloned())
+        .collect()
+}
+
+fn color(rgba: &Value) -> Color {
+    let channel = |index: usize| rgba[index].as_f64().unwrap_or(1.0) as f32;
+    Color::rgba(channel(0), channel(1), channel(2), channel(3))
+}
+
+/// Answers the `join` request of another editor, returning its id in the session.
+pub(crate) fn join(world: &mut World, name: String, color: [f32; 4]) -> Value {
+    let now = world.resource::<Time>().raw_elapsed_seconds_f64();
+    let mut session = world.resource_mut::<Session>();
+    let id = session.next_user;
+    session.next_user += 1;
+    info!("{name} joined the session");
+    session.users.push(SessionUser {
+        id,
+        name,
+        color: Color::rgba(color[0], color[1], color[2], color[3]),
+        selection: Vec::new(),
+        selected: Vec::new(),
+        seen_at: now,
+    });
+    json!({ "user": id, "since": session.next_edit - 1 })
+}
+
+/// Answers the `session_update` request of a joined editor: applies its edits and selection, and
+/// returns the edits it hasn't received yet with the selections of the others.
+pub(crate) fn session_update(
+    world: &mut World,
+    user: u64,
+    since: u64,
+    edits: Vec<Edit>,
+    selection: Vec<String>,
+) -> Result<Value, String> {
+    let now = world.resource::<Time>().raw_elapsed_seconds_f64();
+    let paths = entity_paths(world);
+    let entities = entities_by_path(&paths);
+    for edit in &edits {
+        apply_edit(world, &entities, edit);
+    }
+    let host_selection = selection_paths(world, &paths);
+
+    let mut session = world.resource_mut::<Session>();
+    let Some(joined) = session.users.iter_mut().find(|joined| joined.id == user) else {
+        return Err("Not in the session, join again".to_owned());
+    };
+    joined.seen_at = now;
+    joined.selected = selection
+        .iter()
+        .filter_map(|path| entities.get(path.as_str()).copied())
+        .collect();
+    joined.selection = selection;
+    for edit in edits {
+        session.log(user, edit);
+    }
+
+    let missed: Vec<_> = session
+        .log
+        .iter()
+        .filter(|(number, by, _)| *number > since && *by != user)
+        .map(|(_, _, edit)| edit)
+        .collect();
+    let users: Vec<_> = std::iter::once(json!({
+        "name": session.name,
+        "color": session.color.as_rgba_f32(),
+        "selection": host_selection,
+    }))
+    .chain(
+        session
+            .users
+            .iter()
+            .filter(|other| other.id != user)
+            .map(|other| {
+                json!({
+                    "name": other.name,
+                    "color": other.color.as_rgba_f32(),
+                    "selection": other.selection,
+                })
+            }),
+    )
+    .collect();
+    Ok(json!({
+        "since": session.next_edit - 1,
+        "edits": missed,
+        "users": users,
+    }))
+}
+
+/// Handles the response of the host to the last request of this joined editor.
+fn receive_from_host(world: &mut World, response: Value) {
+    let mut session = world.resource_mut::<Session>();
+    let Some(host) = &mut session.host else {
+        return;
+    };
+    host.awaiting = false;
+    if let Some(error) = response.get("error") {
+        let address = host.address.clone();
+        error!("The session at {address} failed: {error}");
+        session.leave();
+        return;
+    }
+    let result = &response["result"];
+    host.since = result["since"].as_u64().unwrap_or(host.since);
+    if host.user.is_none() {
+        host.user = result["user"].as_u64();
+        info!("Joined the session at {}", host.address);
+        return;
+    }
+
+    let edits: Vec<Edit> = serde_json::from_value(result["edits"].clone()).unwrap_or_default();
+    let paths = entity_paths(world);
+    let entities = entities_by_path(&paths);
+    for edit in &edits {
+        apply_edit(world, &entities, edit);
+    }
+    let users = result["users"].as_array().cloned().unwrap_or_default();
+    world.resource_mut::<Session>().users = users
+        .iter()
+        .enumerate()
+        .map(|(index, user)| {
+            let selection: Vec<String> =
+                serde_json::from_value(user["selection"].clone()).unwrap_or_default();
+            SessionUser {
+                id: index as u64,
+                name: user["name"].as_str().unwrap_or_default().to_owned(),
+                color: color(&user["color"]),
+                selected: selection
+                    .iter()
+                    .filter_map(|path| entities.get(path.as_str()).copied())
+                    .collect(),
+                selection,
+                seen_at: 0.0,
+            }
+        })
+        .collect();
+}
+
+/// Shares the edits made this frame, and for a joined editor exchanges them with the host.
+fn sync_session(world: &mut World) {
+    let session = world.resource::<Session>();
+    let shared = session.is_joined() || !session.users.is_empty();
+    if !shared {
+        return;
+    }
+    let paths = entity_paths(world);
+    let edits = local_edits(world, &paths);
+    let now = world.resource::<Time>().raw_elapsed_seconds_f64();
+    let delta = world.resource::<Time>().raw_delta_seconds();
+
+    let mut session = world.resource_mut::<Session>();
+    let Some(host) = &mut session.host else {
+        for edit in edits {
+            session.log(HOST, edit);
+        }
+        session.users.retain(|user| {
+            let active = now - user.seen_at < USER_TIMEOUT;
+            if !active {
+                info!("{} left the session", user.name);
+            }
+            active
+        });
+        return;
+    };
+    host.outgoing.extend(edits);
+    let (responses, disconnected) = host.connection.receive();
+    if disconnected {
+        let address = host.address.clone();
+        warn!("The host of the session at {address} closed the connection");
+        session.leave();
+        return;
+    }
+    for response in responses {
+        receive_from_host(world, response);
+    }
+
+    let selection = selection_paths(world, &paths);
+    let mut session = world.resource_mut::<Session>();
+    let Some(host) = &mut session.host else {
+        return;
+    };
+    host.since_sync += delta;
+    let (Some(user), false) = (host.user, host.awaiting) else {
+        return;
+    };
+    if host.since_sync < SYNC_INTERVAL {
+        return;
+    }
+    host.since_sync = 0.0;
+    let request = json!({
+        "method": "session_update",
+        "user": user,
+        "since": host.since,
+        "edits": std::mem::take(&mut host.outgoing),
+        "selection": selection,
+    });
+    match host.connection.send(&request) {
+        Ok(()) => host.awaiting = true,
+        Err(e) => {
+            let address = host.address.clone();
+            error!("Failed to update the session at {address}: {e}");
+            session.leave();
+        }
+    }
+}
+
+fn draw_user_selections(
+    editor: Res<EditorResource>,
+    session: Res<Session>,
+    bounds: Query<(&GlobalTransform, &Aabb)>,
+    mut gizmos: Gizmos,
+) {
+    if !editor.0 {
+        return;
+    }
+    for user in &session.users {
+        for &entity in &user.selected {
+            if let Ok((transform, aabb)) = bounds.get(entity) {
+                outline::draw_bounds(&mut gizmos, transform, aabb, user.color);
+            }
+        }
+    }
+}
+
+/// The session menu: who is in the session, and joining or leaving another.
+pub fn session_menu(world: &mut World, ui: &mut egui::Ui) {
+    let mut session = world.resource_mut::<Session>();
+    ui.horizontal(|ui| {
+        ui.label("Name");
+        ui.add_enabled(
+            !session.is_joined(),
+            egui::TextEdit::singleline(&mut session.name).desired_width(120.0),
+        );
+        let mut color = session.color.as_rgba_f32();
+        if ui
+            .add_enabled_ui(!session.is_joined(), |ui| {
+                ui.color_edit_button_rgba_unmultiplied(&mut color)
+            })
+            .inner
+            .changed()
+        {
+            session.color = Color::rgba(color[0], color[1], color[2], color[3]);
+        }
+    });
+    ui.separator();
+
+    match session.host_address().map(str::to_owned) {
+        Some(address) => {
+            ui.label(format!("Joined the session at {address}"));
+            if ui.button("Leave").clicked() {
+                session.leave();
+                ui.close_menu();
+            }
+        }
+        None => {
+            ui.horizontal(|ui| {
+                ui.label("Host");
+                ui.text_edit_singleline(&mut session.join_address);
+            });
+            if ui.button("Join").clicked() {
+                let address = session.join_address.clone();
+                session.join(&address);
+                ui.close_menu();
+            }
+        }
+    }
+
+    ui.separator();
+    if session.users.is_empty() {
+        ui.weak("Nobody else in the session");
+    }
+    for user in session.users() {
+        ui.horizontal(|ui| {
+            let [r, g, b, _] = user.color.as_rgba_u8();
+            ui.colored_label(egui::Color32::from_rgb(r, g, b), "⏺");
+            ui.label(&user.name);
+            if !user.selection.is_empty() {
+                ui.weak(format!("{} selected", user.selection.len()));
+            }
+        });
+    }
+}
@@ -0,0 +1,168 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, render::primitives::Aabb, transform::TransformSystem};
+
+use crate::{EditorResource, UiState};
+
+const COLLIDER_COLOR: Color = Color::rgba(0.3, 0.9, 0.4, 0.6);
+/// Segments of the half circles closing capsules.
+const ARC_SEGMENTS: usize = 16;
+
+/// Shape of a collider of a physics engine, in the local space of its entity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColliderShape {
+    Cuboid {
+        half_extents: Vec3,
+    },
+    Sphere {
+        radius: f32,
+    },
+    /// Capsule along the local Y axis.
+    Capsule {
+        half_height: f32,
+        radius: f32,
+    },
+}
+
+impl ColliderShape {
+    pub fn aabb(&self) -> Aabb {
+        let half_extents = match *self {
+            ColliderShape::Cuboid { half_extents } => half_extents,
+            ColliderShape::Sphere { radius } => Vec3::splat(radius),
+            ColliderShape::Capsule {
+                half_height,
+                radius,
+            } => Vec3::new(radius, half_height + radius, radius),
+        };
+        Aabb::from_min_max(-half_extents, half_extents)
+    }
+
+    fn draw(&self, gizmos: &mut Gizmos, transform: &GlobalTransform, color: Color) {
+        let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+        match *self {
+            ColliderShape::Cuboid { half_extents } => {
+                gizmos.cuboid(
+                    transform.mul_transform(Transform::from_scale(half_extents * 2.0)),
+                    color,
+                );
+            }
+            ColliderShape::Sphere { radius } => {
+                gizmos.sphere(translation, rotation, radius * scale.max_element(), color);
+            }
+            ColliderShape::Capsule {
+                half_height,
+                radius,
+            } => {
+                let point = |local: Vec3| transform.transform_point(local);
+                let up = rotation * Vec3::Y;
+                let radius_scale = scale.x.max(scale.z);
+                for y in [-half_height, half_height] {
+                    gizmos.circle(point(Vec3::Y * y), up, radius * radius_scale, color);
+                }
+                for side in [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::NEG_Z] {
+                    let offset = side * radius;
+                    gizmos.line(
+                        point(offset - Vec3::Y * half_height),
+                        point(offset + Vec3::Y * half_height),
+                        color,
+                    );
+                }
+                // half circles closing both ends, in two perpendicular planes
+                for (y, sign) in [(half_height, 1.0), (-half_height, -1.0)] {
+                    for side in [Vec3::X, Vec3::Z] {
+                        gizmos.linestrip(
+                            (0..=ARC_SEGMENTS).map(|i| {
+                                let angle = i as f32 / ARC_SEGMENTS as f32 * PI;
+                                let local = side * angle.cos() + Vec3::Y * sign * angle.sin();
+                                point(Vec3::Y * y + local * radius)
+                            }),
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Marks [`Aabb`]s inserted by the editor from a collider shape, which aren't saved.
+#[derive(Component)]
+pub(crate) struct EditorColliderBounds;
+
+pub trait AddEditorCollider {
+    /// Draws the colliders of a physics engine in the game view, and gives entities without
+    /// other bounds the [`Aabb`] of their collider, so they can be picked and outlined.
+    ///
+    /// ```ignore
+    /// app.add_editor_collider(|collider: &bevy_rapier3d::prelude::Collider| {
+    ///     match collider.as_typed_shape() {
+    ///         ColliderView::Ball(ball) => ColliderShape::Sphere { radius: ball.radius() },
+    ///         // ...
+    ///     }
+    /// });
+    /// ```
+    fn add_editor_collider<C: Component>(&mut self, shape: fn(&C) -> ColliderShape) -> &mut Self;
+}
+
+impl AddEditorCollider for App {
+    fn add_editor_collider<C: Component>(&mut self, shape: fn(&C) -> ColliderShape) -> &mut Self {
+        self.insert_resource(ColliderShapeOf(shape)).add_systems(
+            PostUpdate,
+            (
+                update_collider_bounds::<C>,
+                draw_colliders::<C>.after(TransformSystem::TransformPropagate),
+            ),
+        )
+    }
+}
+
+/// Shape of the collider component `C`.
+#[derive(Resource)]
+struct ColliderShapeOf<C>(fn(&C) -> ColliderShape);
+
+fn draw_colliders<C: Component>(
+    editor: Res<EditorResource>,
+    ui_state: Res<UiState>,
+    shape: Res<ColliderShapeOf<C>>,
+    colliders: Query<(Entity, &GlobalTransform, &C)>,
+    mut gizmos: Gizmos,
+) {
+    if !editor.0 {
+        return;
+    }
+    for (entity, transform, collider) in &colliders {
+        let color = match ui_state.selected_entities.contains(entity) {
+            true => COLLIDER_COLOR.with_a(1.0),
+            false => COLLIDER_COLOR,
+        };
+        (shape.0)(collider).draw(&mut gizmos, transform, color);
+    }
+}
+
+/// Keeps the bounds of colliders without other bounds up to date.
+#[allow(clippy::type_complexity)]
+fn update_collider_bounds<C: Component>(
+    mut commands: Commands,
+    shape: Res<ColliderShapeOf<C>>,
+    changed: Query<
+        (Entity, &C, Option<&EditorColliderBounds>, Option<&Aabb>),
+        Or<(Changed<C>, Without<Aabb>)>,
+    >,
+    mut removed: RemovedComponents<C>,
+    bounds: Query<(), With<EditorColliderBounds>>,
+) {
+    for (entity, collider, ours, aabb) in &changed {
+        if aabb.is_none() || ours.is_some() {
+            commands
+                .entity(entity)
+                .insert(((shape.0)(collider).aabb(), EditorColliderBounds));
+        }
+    }
+    for entity in removed.iter() {
+        if bounds.contains(entity) {
+            commands
+                .entity(entity)
+                .remove::<(Aabb, EditorColliderBounds)>();
+        }
+    }
+}
@@ -0,0 +1,478 @@
+use bevy::prelude::*;
+use egui_gizmo::{GizmoMode, GizmoOrientation};
+
+use crate::{
+    camera, clipboard,
+    hierarchy::EntityPreset,
+    history::EditorHistory,
+    input::{EditorAction, EditorKeybinds},
+    macros::{EditorMacros, MacroStep},
+    measure::MeasureTool,
+    navigation, play,
+    replay::InputRecorder,
+    scene, screenshot,
+    settings::EditorSettings,
+    snap,
+    tabs::EditorTabs,
+    views::{self, AxisView},
+    EditorResource, EguiWindow, InspectorSelection, UiState,
+};
+
+pub struct EditorCommandsPlugin;
+impl Plugin for EditorCommandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorCommands>()
+            .init_resource::<CommandPalette>()
+            .add_systems(Startup, register_builtin_commands)
+            .add_systems(Update, (toggle_palette, run_queued_commands).chain());
+    }
+}
+
+type CommandFn = Box<dyn Fn(&mut World) + Send + Sync>;
+
+pub struct EditorCommand {
+    pub name: String,
+    /// Action whose keybind is shown next to the command.
+    pub action: Option<EditorAction>,
+    run: CommandFn,
+}
+
+/// Every command listed in the command palette.
+///
+/// Commands never run while the editor UI is drawn, they are queued and run by an exclusive
+/// system afterwards so they can access any resource, including [`UiState`].
+#[derive(Resource, Default)]
+pub struct EditorCommands {
+    commands: Vec<EditorCommand>,
+    queued: Vec<usize>,
+}
+
+impl EditorCommands {
+    pub fn add(
+        &mut self,
+        name: impl Into<String>,
+        run: impl Fn(&mut World) + Send + Sync + 'static,
+    ) -> &mut EditorCommand {
+        self.commands.push(EditorCommand {
+            name: name.into(),
+            action: None,
+            run: Box::new(run),
+        });
+        self.commands.last_mut().unwrap()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &EditorCommand> {
+        self.commands.iter()
+    }
+
+    /// Removes the command with the given name, like one made for something deleted since.
+    pub fn remove(&mut self, name: &str) {
+        let Some(index) = self
+            .commands
+            .iter()
+            .position(|command| command.name == name)
+        else {
+            return;
+        };
+        self.commands.remove(index);
+        self.queued.retain(|&queued| queued != index);
+        for queued in &mut self.queued {
+            if *queued > index {
+                *queued -= 1;
+            }
+        }
+    }
+
+    /// Queues the command with the given name, returning `false` if there is none.
+    pub fn queue(&mut self, name: &str) -> bool {
+        match self
+            .commands
+            .iter()
+            .position(|command| command.name == name)
+        {
+            Some(index) => {
+                self.queued.push(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl EditorCommand {
+    pub fn with_action(&mut self, action: EditorAction) -> &mut Self {
+        self.action = Some(action);
+        self
+    }
+}
+
+pub trait AddEditorCommand {
+    /// Registers a command which can be run from the command palette.
+    fn add_editor_command(
+        &mut self,
+        name: impl Into<String>,
+        run: impl Fn(&mut World) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl AddEditorCommand for App {
+    fn add_editor_command(
+        &mut self,
+        name: impl Into<String>,
+        run: impl Fn(&mut World) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(EditorCommands::default)
+            .add(name, run);
+        self
+    }
+}
+
+pub(crate) fn run_queued_commands(world: &mut World) {
+    if world.resource::<EditorCommands>().queued.is_empty() {
+        return;
+    }
+    world.resource_scope(|world, mut commands: Mut<EditorCommands>| {
+        for index in std::mem::take(&mut commands.queued) {
+            let command = &commands.commands[index];
+            info!("Running editor command {}", command.name);
+            world
+                .resource_mut::<EditorMacros>()
+                .record(MacroStep::Command(command.name.clone()));
+            world
+                .resource_mut::<InputRecorder>()
+                .record_command(&command.name);
+            (command.run)(world);
+        }
+    });
+}
+
+/// Runs the command with the given name right away, returning `false` if there is none.
+pub fn run_command(world: &mut World, name: &str) -> bool {
+    world.resource_scope(|world, commands: Mut<EditorCommands>| {
+        match commands
+            .commands
+            .iter()
+            .find(|command| command.name == name)
+        {
+            Some(command) => {
+                (command.run)(world);
+                true
+            }
+            None => false,
+        }
+    })
+}
+
+fn gizmo_mode(mode: GizmoMode) -> impl Fn(&mut World) {
+    move |world| world.resource_mut::<UiState>().gizmo_mode = mode
+}
+
+fn register_builtin_commands(mut commands: ResMut<EditorCommands>, tabs: Res<EditorTabs>) {
+    commands
+        .add("Toggle Editor", |world| {
+            let mut editor = world.resource_mut::<EditorResource>();
+            editor.0 = !editor.0;
+        })
+        .with_action(EditorAction::ToggleEditor);
+    commands
+        .add("Gizmo: Translate", gizmo_mode(GizmoMode::Translate))
+        .with_action(EditorAction::TranslateGizmo);
+    commands
+        .add("Gizmo: Rotate", gizmo_mode(GizmoMode::Rotate))
+        .with_action(EditorAction::RotateGizmo);
+    commands
+        .add("Gizmo: Scale", gizmo_mode(GizmoMode::Scale))
+        .with_action(EditorAction::ScaleGizmo);
+    commands
+        .add("Gizmo: Toggle Local/Global", |world| {
+            let mut ui_state = world.resource_mut::<UiState>();
+            ui_state.gizmo_orientation = match ui_state.gizmo_orientation {
+                GizmoOrientation::Local => GizmoOrientation::Global,
+                GizmoOrientation::Global => GizmoOrientation::Local,
+            };
+        })
+        .with_action(EditorAction::ToggleGizmoOrientation);
+    commands
+        .add("Focus Selection", camera::focus_selection)
+        .with_action(EditorAction::FocusSelection);
+    commands
+        .add("Selection: Back", |world| navigation::go(world, -1))
+        .with_action(EditorAction::SelectionBack);
+    commands
+        .add("Selection: Forward", |world| navigation::go(world, 1))
+        .with_action(EditorAction::SelectionForward);
+    commands
+        .add("Delete Selected", |world| {
+            let mut ui_state = world.resource_mut::<UiState>();
+            if !ui_state.selected_entities.is_empty() {
+                ui_state.pending_delete = Some(ui_state.selected_entities.as_slice().to_vec());
+            }
+        })
+        .with_action(EditorAction::DeleteSelected);
+    commands
+        .add("Undo", EditorHistory::undo)
+        .with_action(EditorAction::Undo);
+    commands
+        .add("Redo", EditorHistory::redo)
+        .with_action(EditorAction::Redo);
+    commands
+        .add("Play / Stop", play::play_stop)
+        .with_action(EditorAction::PlayStop);
+    commands
+        .add("Pause / Resume", play::pause_resume)
+        .with_action(EditorAction::PauseResume);
+    commands
+        .add("Step Frame", play::request_step)
+        .with_action(EditorAction::Step);
+
+    commands
+        .add("Copy", clipboard::copy_selection)
+        .with_action(EditorAction::Copy);
+    commands
+        .add("Paste", clipboard::paste)
+        .with_action(EditorAction::Paste);
+    commands
+        .add("Duplicate", clipboard::duplicate_selection)
+        .with_action(EditorAction::Duplicate);
+    commands
+        .add("Screenshot", screenshot::take_screenshot)
+        .with_action(EditorAction::Screenshot);
+    commands
+        .add("Drop to Ground", |world| snap::drop_to_ground(world, false))
+        .with_action(EditorAction::DropToGround);
+    commands
+        .add("Drop to Ground (Align to Surface)", |world| {
+            snap::drop_to_ground(world, true);
+        })
+        .with_action(EditorAction::DropToGroundAligned);
+
+    commands.add("Save Scene", |world| {
+        world.resource_scope(|world, ui_state: Mut<UiState>| {
+            let entities = scene::scene_entities(world);
+            ui_state.save_scene(world, &entities);
+        });
+    });
+    commands.add("Load Scene", |world| {
+        world.resource_scope(|world, mut ui_state: Mut<UiState>| ui_state.load_scene(world));
+    });
+    for preset in EntityPreset::ALL {
+        commands.add(format!("Spawn: {}", preset.name()), move |world| {
+            let entity = preset.spawn(world, None);
+            let mut ui_state = world.resource_mut::<UiState>();
+            ui_state.selected_entities.select_replace(entity);
+            ui_state.selection = InspectorSelection::Entities;
+        });
+    }
+
+    let custom = tabs.names().map(|name| EguiWindow::Custom(name.to_owned()));
+    for window in EguiWindow::BUILTIN.into_iter().chain(custom) {
+        commands.add(format!("Toggle Panel: {}", window.title()), move |world| {
+            world
+                .resource_mut::<UiState>()
+                .toggle_window(window.clone());
+        });
+    }
+    commands.add("New GameView", views::open_view);
+    for axis in AxisView::ALL {
+        commands.add(format!("Open {axis:?} View"), move |world| {
+            views::open_axis_view(world, axis);
+        });
+    }
+    commands.add("Reset Layout", |world| {
+        world.resource_mut::<UiState>().reset_layout();
+    });
+    commands.add("Toggle Grid", |world| {
+        let mut settings = world.resource_mut::<EditorSettings>();
+        settings.grid.visible = !settings.grid.visible;
+    });
+    commands.add("Toggle Selection Bounds", |world| {
+        let mut settings = world.resource_mut::<EditorSettings>();
+        settings.show_bounds = !settings.show_bounds;
+    });
+    commands.add("Toggle 2D Mode", |world| {
+        let mut ui_state = world.resource_mut::<UiState>();
+        ui_state.mode_2d = !ui_state.mode_2d;
+    });
+    commands.add("Toggle Measure Tool", |world| {
+        let mut tool = world.resource_mut::<MeasureTool>();
+        tool.active = !tool.active;
+        tool.start = None;
+        tool.end = None;
+    });
+    commands.add("Toggle Stats Overlay", |world| {
+        let mut settings = world.resource_mut::<EditorSettings>();
+        settings.stats_overlay = !settings.stats_overlay;
+    });
+    commands.add("Open Settings", |world| {
+        world.resource_mut::<UiState>().settings_open = true;
+    });
+}
+
+/// Scores how well `query` matches `candidate` as a case insensitive subsequence, favouring
+/// consecutive characters and matches at the start of words. `None` if it doesn't match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut previous_match = None;
+    let mut candidate_chars = candidate.char_indices();
+    let mut previous_char = ' ';
+
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let query_char = query_char.to_ascii_lowercase();
+        loop {
+            let (index, char) = candidate_chars.next()?;
+            let word_start = !previous_char.is_alphanumeric();
+            previous_char = char;
+            if char.to_ascii_lowercase() != query_char {
+                continue;
+            }
+            score += 1;
+            if word_start {
+                score += 8;
+            }
+            if previous_match.is_some_and(|previous| previous + char.len_utf8() >= index) {
+                score += 4;
+            }
+            previous_match = Some(index);
+            break;
+        }
+    }
+    // shorter names win among equally good matches
+    Some(score * 100 - candidate.len() as i32)
+}
+
+#[derive(Resource, Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    query: String,
+    selected: usize,
+}
+
+fn toggle_palette(
+    actions: Res<Input<EditorAction>>,
+    ed: Res<EditorResource>,
+    mut palette: ResMut<CommandPalette>,
+) {
+    if ed.0 && actions.just_pressed(EditorAction::CommandPalette) {
+        palette.open = !palette.open;
+        palette.query.clear();
+        palette.selected = 0;
+    }
+}
+
+pub(crate) fn command_palette_ui(world: &mut World, ctx: &egui::Context) {
+    if !world.resource::<CommandPalette>().open {
+        return;
+    }
+    world.resource_scope(|world, mut palette: Mut<CommandPalette>| {
+        let keybinds = world.resource::<EditorKeybinds>().clone();
+        let mut commands = world.resource_mut::<EditorCommands>();
+
+        let mut matches: Vec<_> = commands
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(index, command)| {
+                fuzzy_score(&palette.query, &command.name).map(|score| (score, index))
+            })
+            .collect();
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+        matches.truncate(15);
+
+        let (up, down, enter, escape) = ctx.input(|input| {
+            (
+                input.key_pressed(egui::Key::ArrowUp),
+                input.key_pressed(egui::Key::ArrowDown),
+                input.key_pressed(egui::Key::Enter),
+                input.key_pressed(egui::Key::Escape),
+            )
+        });
+        if down {
+            palette.selected += 1;
+        }
+        if up {
+            palette.selected = palette.selected.saturating_sub(1);
+        }
+        palette.selected = palette.selected.min(matches.len().saturating_sub(1));
+
+        let mut run = enter
+            .then(|| matches.get(palette.selected).map(|&(_, index)| index))
+            .flatten();
+
+        egui::Window::new("Command Palette")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 40.0))
+            .fixed_size(egui::vec2(400.0, 0.0))
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text("Type a command")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    palette.selected = 0;
+                }
+                ui.separator();
+
+                for (row, &(_, index)) in matches.iter().enumerate() {
+                    let command = &commands.commands[index];
+                    ui.horizontal(|ui| {
+                        let label = ui.selectable_label(row == palette.selected, &command.name);
+                        if let Some(action) = command.action {
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| ui.weak(keybinds.chord(action).to_string()),
+                            );
+                        }
+                        if label.clicked() {
+                            run = Some(index);
+                        }
+                    });
+                }
+                if matches.is_empty() {
+                    ui.weak("No matching commands");
+                }
+            });
+
+        if let Some(index) = run {
+            commands.queued.push(index);
+        }
+        if run.is_some() || escape {
+            palette.open = false;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_score;
+
+    #[test]
+    fn matches_case_insensitive_subsequences() {
+        assert!(fuzzy_score("sv", "Save Scene").is_some());
+        assert!(fuzzy_score("SAVE", "save scene").is_some());
+        assert!(fuzzy_score("save scene", "Save Scene").is_some());
+        assert_eq!(fuzzy_score("vs", "Save"), None);
+        assert_eq!(fuzzy_score("saves", "Save"), None);
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert!(fuzzy_score("", "Undo").is_some());
+        assert!(fuzzy_score("  ", "Undo").is_some());
+    }
+
+    #[test]
+    fn favours_word_starts_and_consecutive_characters() {
+        // "ss" at the start of both words beats two letters in the middle of a word
+        assert!(fuzzy_score("ss", "Save Scene") > fuzzy_score("ss", "Toss It"));
+        assert!(fuzzy_score("sce", "Scene") > fuzzy_score("sce", "Sauce Sine"));
+    }
+
+    #[test]
+    fn shorter_candidates_win_ties() {
+        assert!(fuzzy_score("undo", "Undo") > fuzzy_score("undo", "Undo All"));
+    }
+}
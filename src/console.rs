@@ -0,0 +1,285 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    prelude::*,
+    utils::tracing::{
+        field::{Field, Visit},
+        Event, Level, Subscriber,
+    },
+};
+use tracing_subscriber::{
+    layer::{Context, SubscriberExt},
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+
+use crate::profiler::SystemProfile;
+
+const MAX_ENTRIES: usize = 2000;
+
+/// Replacement for bevy's `LogPlugin` which also captures every log event for the editor
+/// console, and times systems for the profiler. Disable `LogPlugin` when adding it:
+///
+/// ```ignore
+/// App::new()
+///     .add_plugins(DefaultPlugins.build().disable::<bevy::log::LogPlugin>())
+///     .add_plugins(EditorLogPlugin::default())
+/// ```
+pub struct EditorLogPlugin {
+    /// Filters logs using the [`EnvFilter`] format
+    pub filter: String,
+    /// Filters out logs that are "less than" the given level.
+    pub level: Level,
+}
+
+impl Default for EditorLogPlugin {
+    fn default() -> Self {
+        Self {
+            filter: "wgpu=error,naga=warn".to_string(),
+            level: Level::INFO,
+        }
+    }
+}
+
+impl Plugin for EditorLogPlugin {
+    fn build(&self, app: &mut App) {
+        let log = EditorLog::default();
+        let profile = SystemProfile::default();
+
+        let default_filter = format!("{},{}", self.level, self.filter);
+        let filter_layer = EnvFilter::try_from_default_env()
+            .or_else(|_| EnvFilter::try_new(&default_filter))
+            .unwrap();
+        let fmt_layer = tracing_subscriber::fmt::Layer::default().with_writer(std::io::stderr);
+        let subscriber = Registry::default()
+            .with(filter_layer)
+            .with(fmt_layer)
+            .with(EditorLogLayer { log: log.clone() })
+            .with(profile.layer());
+
+        if subscriber.try_init().is_err() {
+            warn!("Could not set the global logger, the editor console will stay empty. Is bevy's LogPlugin disabled?");
+        }
+        app.insert_resource(log)
+            .insert_resource(profile)
+            .init_resource::<ConsoleState>();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    /// Time since the [`EditorLogPlugin`] was built.
+    pub time: Duration,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.level, self.target, self.message)
+    }
+}
+
+struct LogBuffer {
+    start: Instant,
+    entries: VecDeque<LogEntry>,
+}
+
+/// Log events captured by [`EditorLogPlugin`], the most recent last.
+#[derive(Resource, Clone)]
+pub struct EditorLog(Arc<Mutex<LogBuffer>>);
+
+impl Default for EditorLog {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(LogBuffer {
+            start: Instant::now(),
+            entries: VecDeque::new(),
+        })))
+    }
+}
+
+impl EditorLog {
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.0.lock().unwrap().entries.iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().entries.clear();
+    }
+
+    fn push(&self, level: Level, target: String, message: String) {
+        let mut buffer = self.0.lock().unwrap();
+        let time = buffer.start.elapsed();
+        if buffer.entries.len() >= MAX_ENTRIES {
+            buffer.entries.pop_front();
+        }
+        buffer.entries.push_back(LogEntry {
+            level,
+            target,
+            message,
+            time,
+        });
+    }
+}
+
+struct EditorLogLayer {
+    log: EditorLog,
+}
+
+impl<S: Subscriber> Layer<S> for EditorLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        self.log.push(
+            *metadata.level(),
+            metadata.target().to_owned(),
+            visitor.message + &visitor.fields,
+        );
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message.push_str(value),
+            name => {
+                let _ = write!(self.fields, " {name}={value}");
+            }
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => {
+                let _ = write!(self.message, "{value:?}");
+            }
+            name => {
+                let _ = write!(self.fields, " {name}={value:?}");
+            }
+        }
+    }
+}
+
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+fn level_color(level: Level) -> egui::Color32 {
+    match level {
+        Level::ERROR => egui::Color32::from_rgb(230, 80, 80),
+        Level::WARN => egui::Color32::from_rgb(230, 190, 70),
+        Level::INFO => egui::Color32::from_rgb(110, 200, 110),
+        Level::DEBUG => egui::Color32::from_rgb(100, 160, 230),
+        _ => egui::Color32::GRAY,
+    }
+}
+
+#[derive(Resource)]
+pub struct ConsoleState {
+    /// Whether each of [`LEVELS`] is shown.
+    shown_levels: [bool; 5],
+    search: String,
+    auto_scroll: bool,
+}
+
+impl Default for ConsoleState {
+    fn default() -> Self {
+        Self {
+            shown_levels: [true; 5],
+            search: String::new(),
+            auto_scroll: true,
+        }
+    }
+}
+
+pub fn console_ui(world: &mut World, ui: &mut egui::Ui) {
+    let Some(log) = world.get_resource::<EditorLog>().cloned() else {
+        ui.label("Add EditorLogPlugin in place of bevy's LogPlugin to see logs here.");
+        return;
+    };
+    let mut state = world.resource_mut::<ConsoleState>();
+
+    ui.horizontal(|ui| {
+        for (level, shown) in LEVELS.iter().zip(&mut state.shown_levels) {
+            ui.toggle_value(
+                shown,
+                egui::RichText::new(level.as_str()).color(level_color(*level)),
+            );
+        }
+        ui.separator();
+        ui.add(
+            egui::TextEdit::singleline(&mut state.search)
+                .hint_text("Search")
+                .desired_width(150.0),
+        );
+        ui.checkbox(&mut state.auto_scroll, "Auto scroll");
+        if ui.button("Clear").clicked() {
+            log.clear();
+        }
+    });
+    ui.separator();
+
+    #[cfg(feature = "scripting")]
+    egui::TopBottomPanel::bottom("script_input")
+        .frame(egui::Frame::none())
+        .show_inside(ui, |ui| crate::script::script_input_ui(world, ui));
+    let state = world.resource::<ConsoleState>();
+
+    let search = state.search.to_lowercase();
+    let entries: Vec<_> = log
+        .entries()
+        .into_iter()
+        .filter(|entry| {
+            LEVELS
+                .iter()
+                .position(|level| *level == entry.level)
+                .is_some_and(|index| state.shown_levels[index])
+        })
+        .filter(|entry| {
+            search.is_empty()
+                || entry.message.to_lowercase().contains(&search)
+                || entry.target.to_lowercase().contains(&search)
+        })
+        .collect();
+
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+    egui::ScrollArea::both()
+        .auto_shrink([false; 2])
+        .stick_to_bottom(state.auto_scroll)
+        .show_rows(ui, row_height, entries.len(), |ui, rows| {
+            for entry in &entries[rows] {
+                ui.horizontal(|ui| {
+                    ui.monospace(format!("{:>9.3}", entry.time.as_secs_f32()));
+                    ui.label(
+                        egui::RichText::new(format!("{:<5}", entry.level.as_str()))
+                            .monospace()
+                            .color(level_color(entry.level)),
+                    );
+                    ui.weak(&entry.target);
+                    let message = ui
+                        .add(egui::Label::new(&entry.message).sense(egui::Sense::click()))
+                        .on_hover_text("Click to copy");
+                    if message.clicked() {
+                        ui.output_mut(|output| output.copied_text = entry.to_string());
+                    }
+                });
+            }
+        });
+}
@@ -0,0 +1,705 @@
+use std::any::{Any, TypeId};
+
+use bevy::{
+    prelude::*,
+    reflect::{GetPath, ReflectRef},
+};
+use bevy_inspector_egui::{
+    bevy_inspector::hierarchy::SelectedEntities, inspector_egui_impls::InspectorEguiImpl,
+    reflect_inspector::InspectorUi,
+};
+
+use crate::history;
+
+const CURVE_HEIGHT: f32 = 140.0;
+const KEY_RADIUS: f32 = 4.0;
+/// Length of the tangent handles of the selected key, in egui points.
+const TANGENT_LENGTH: f32 = 36.0;
+const CURVE_COLOR: egui::Color32 = egui::Color32::from_rgb(110, 190, 255);
+const SELECTED_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 40);
+/// Points the curve is drawn with across the width of the editor.
+const CURVE_SAMPLES: usize = 128;
+
+pub struct EditorCurvePlugin;
+impl Plugin for EditorCurvePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Curve>()
+            .register_type::<CurveKey>()
+            .register_type::<KeyInterpolation>()
+            .init_resource::<CurvesTab>();
+        app.world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .get_mut(TypeId::of::<Curve>())
+            .unwrap()
+            .insert(InspectorEguiImpl::new(
+                curve_inspector_ui,
+                curve_inspector_ui_readonly,
+                curve_inspector_ui_many,
+            ));
+    }
+}
+
+/// How a curve goes from a key to the next one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum KeyInterpolation {
+    /// Keeps the value of the key until the next one.
+    Constant,
+    Linear,
+    /// A cubic Hermite spline following the tangents of both keys.
+    #[default]
+    Hermite,
+}
+
+impl KeyInterpolation {
+    const ALL: [KeyInterpolation; 3] = [
+        KeyInterpolation::Constant,
+        KeyInterpolation::Linear,
+        KeyInterpolation::Hermite,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+pub struct CurveKey {
+    pub time: f32,
+    pub value: f32,
+    /// Slope of the curve arriving at the key.
+    pub in_tangent: f32,
+    /// Slope of the curve leaving the key.
+    pub out_tangent: f32,
+    /// Interpolation towards the next key.
+    pub interpolation: KeyInterpolation,
+}
+
+impl CurveKey {
+    pub fn new(time: f32, value: f32) -> Self {
+        Self {
+            time,
+            value,
+            ..default()
+        }
+    }
+}
+
+/// A value changing over time through keys, like an easing curve or an animated parameter.
+/// Fields of this type are edited with a curve editor in the inspector and the curves tab.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Curve {
+    /// Keys sorted by time.
+    pub keys: Vec<CurveKey>,
+}
+
+impl Default for Curve {
+    /// Eases in and out from 0 to 1.
+    fn default() -> Self {
+        Self {
+            keys: vec![CurveKey::new(0.0, 0.0), CurveKey::new(1.0, 1.0)],
+        }
+    }
+}
+
+impl Curve {
+    pub fn linear() -> Self {
+        let key = |time| CurveKey {
+            time,
+            value: time,
+            in_tangent: 1.0,
+            out_tangent: 1.0,
+            interpolation: KeyInterpolation::Linear,
+        };
+        Self {
+            keys: vec![key(0.0), key(1.0)],
+        }
+    }
+
+    /// The value at `time`, which is the value of the first or last key outside of them.
+    pub fn sample(&self, time: f32) -> f32 {
+        let (Some(first), Some(last)) = (self.keys.first(), self.keys.last()) else {
+            return 0.0;
+        };
+        if time <= first.time {
+            return first.value;
+        }
+        if time >= last.time {
+            return last.value;
+        }
+        let next = self.keys.partition_point(|key| key.time <= time);
+        let (a, b) = (&self.keys[next - 1], &self.keys[next]);
+        let duration = b.time - a.time;
+        if duration <= f32::EPSILON {
+            return b.value;
+        }
+        let t = (time - a.time) / duration;
+        match a.interpolation {
+            KeyInterpolation::Constant => a.value,
+            KeyInterpolation::Linear => a.value + (b.value - a.value) * t,
+            KeyInterpolation::Hermite => {
+                let (t2, t3) = (t * t, t * t * t);
+                (2.0 * t3 - 3.0 * t2 + 1.0) * a.value
+                    + (t3 - 2.0 * t2 + t) * duration * a.out_tangent
+                    + (-2.0 * t3 + 3.0 * t2) * b.value
+                    + (t3 - t2) * duration * b.in_tangent
+            }
+        }
+    }
+
+    /// Adds a key at `time` on the curve, with its current slope there. Returns its index.
+    pub fn insert_key(&mut self, time: f32, value: f32) -> usize {
+        let delta = 1e-3;
+        let slope = (self.sample(time + delta) - self.sample(time - delta)) / (2.0 * delta);
+        let index = self.keys.partition_point(|key| key.time <= time);
+        self.keys.insert(
+            index,
+            CurveKey {
+                time,
+                value,
+                in_tangent: slope,
+                out_tangent: slope,
+                interpolation: self
+                    .keys
+                    .get(index.saturating_sub(1))
+                    .map_or(default(), |key| key.interpolation),
+            },
+        );
+        index
+    }
+}
+
+/// The part of a curve shown by its editor, kept in egui memory.
+#[derive(Debug, Clone, Copy)]
+struct CurveView {
+    min: egui::Vec2,
+    max: egui::Vec2,
+    selected: Option<usize>,
+}
+
+impl CurveView {
+    /// A view showing all keys and the curve between them.
+    fn fit(curve: &Curve) -> Self {
+        let (mut min, mut max) = (egui::vec2(0.0, 0.0), egui::vec2(1.0, 1.0));
+        if let (Some(first), Some(last)) = (curve.keys.first(), curve.keys.last()) {
+            min.x = first.time;
+            max.x = last.time.max(first.time + 0.1);
+            (min.y, max.y) = (f32::INFINITY, f32::NEG_INFINITY);
+            for i in 0..=CURVE_SAMPLES {
+                let value = curve.sample(min.x + (max.x - min.x) * i as f32 / CURVE_SAMPLES as f32);
+                min.y = min.y.min(value);
+                max.y = max.y.max(value);
+            }
+            if max.y - min.y < 0.1 {
+                (min.y, max.y) = (min.y - 0.5, max.y + 0.5);
+            }
+        }
+        let margin = (max - min) * 0.1;
+        Self {
+            min: min - margin,
+            max: max + margin,
+            selected: None,
+        }
+    }
+}
+
+/// Edits the keys of `curve`: dragging moves keys and the tangent handles of the selected one,
+/// double clicking adds a key, right clicking one sets its interpolation or deletes it, dragging
+/// the background pans, scrolling zooms and double clicking the background fits the view.
+pub fn curve_editor(
+    ui: &mut egui::Ui,
+    curve: &mut Curve,
+    id: egui::Id,
+    height: f32,
+) -> egui::Response {
+    let mut view = ui
+        .data_mut(|data| data.get_temp::<CurveView>(id))
+        .unwrap_or_else(|| CurveView::fit(curve));
+    view.selected = view.selected.filter(|&index| index < curve.keys.len());
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), height),
+        egui::Sense::hover(),
+    );
+    let (min, max) = (view.min, view.max);
+    let size = max - min;
+    let to_screen = |time: f32, value: f32| {
+        egui::pos2(
+            rect.left() + (time - min.x) / size.x * rect.width(),
+            rect.bottom() - (value - min.y) / size.y * rect.height(),
+        )
+    };
+    let to_curve = |pos: egui::Pos2| {
+        egui::vec2(
+            min.x + (pos.x - rect.left()) / rect.width() * size.x,
+            min.y + (rect.bottom() - pos.y) / rect.height() * size.y,
+        )
+    };
+    // screen offset of a tangent handle for `slope`
+    let tangent_offset = |slope: f32| {
+        let direction = egui::vec2(rect.width() / size.x, -slope * rect.height() / size.y);
+        direction.normalized() * TANGENT_LENGTH
+    };
+
+    let visuals = ui.visuals().clone();
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+    for (value_axis, start, end) in [(false, min.x, max.x), (true, min.y, max.y)] {
+        let step = 10f32.powf(((end - start) / 4.0).log10().floor());
+        let mut line = (start / step).ceil() * step;
+        while line <= end {
+            let (a, b) = match value_axis {
+                false => (to_screen(line, min.y), to_screen(line, max.y)),
+                true => (to_screen(min.x, line), to_screen(max.x, line)),
+            };
+            let color = match line.abs() < step * 1e-3 {
+                true => visuals.weak_text_color(),
+                false => visuals.faint_bg_color,
+            };
+            painter.line_segment([a, b], (1.0, color));
+            painter.text(
+                a + egui::vec2(2.0, -2.0),
+                egui::Align2::LEFT_BOTTOM,
+                format!("{}", (line / step).round() * step),
+                egui::FontId::monospace(9.0),
+                visuals.weak_text_color(),
+            );
+            line += step;
+        }
+    }
+    let points = (0..=CURVE_SAMPLES)
+        .map(|i| {
+            let time = min.x + size.x * i as f32 / CURVE_SAMPLES as f32;
+            to_screen(time, curve.sample(time))
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, (1.5, CURVE_COLOR)));
+
+    let mut changed = false;
+    // tangent handles of the selected key, which interact before the keys they overlap
+    if let Some(index) = view.selected {
+        let key = curve.keys[index];
+        let center = to_screen(key.time, key.value);
+        for out in [false, true] {
+            let slope = if out { key.out_tangent } else { key.in_tangent };
+            let sign = if out { 1.0 } else { -1.0 };
+            let handle = center + tangent_offset(slope) * sign;
+            let handle_response = ui.interact(
+                egui::Rect::from_center_size(handle, egui::Vec2::splat(KEY_RADIUS * 3.0)),
+                id.with(("tangent", out)),
+                egui::Sense::drag(),
+            );
+            if let Some(pointer) = handle_response
+                .interact_pointer_pos()
+                .filter(|_| handle_response.dragged())
+            {
+                let delta = to_curve(pointer) - to_curve(center);
+                // handles stay on their side of the key
+                if delta.x * sign > f32::EPSILON {
+                    let slope = delta.y / delta.x;
+                    let key = &mut curve.keys[index];
+                    // tangents are broken with Alt, and stay smooth otherwise
+                    if out || !ui.input(|input| input.modifiers.alt) {
+                        key.out_tangent = slope;
+                    }
+                    if !out || !ui.input(|input| input.modifiers.alt) {
+                        key.in_tangent = slope;
+                    }
+                    changed = true;
+                }
+            }
+            painter.line_segment([center, handle], (1.0, visuals.weak_text_color()));
+            painter.circle_filled(handle, KEY_RADIUS * 0.75, visuals.strong_text_color());
+        }
+    }
+
+    let mut removed = None;
+    for index in 0..curve.keys.len() {
+        let key = curve.keys[index];
+        let center = to_screen(key.time, key.value);
+        let key_response = ui.interact(
+            egui::Rect::from_center_size(center, egui::Vec2::splat(KEY_RADIUS * 3.0)),
+            id.with(("key", index)),
+            egui::Sense::click_and_drag(),
+        );
+        if key_response.clicked() || key_response.drag_started() {
+            view.selected = Some(index);
+        }
+        if let Some(pointer) = key_response
+            .interact_pointer_pos()
+            .filter(|_| key_response.dragged())
+        {
+            let position = to_curve(pointer);
+            // keys can't pass their neighbours, so they stay sorted
+            let min = index
+                .checked_sub(1)
+                .map_or(f32::NEG_INFINITY, |i| curve.keys[i].time);
+            let max = curve
+                .keys
+                .get(index + 1)
+                .map_or(f32::INFINITY, |key| key.time);
+            let key = &mut curve.keys[index];
+            key.time = position.x.clamp(min, max);
+            key.value = position.y;
+            changed = true;
+        }
+        key_response
+            .on_hover_text(format!("{:.3}, {:.3}", key.time, key.value))
+            .context_menu(|ui| {
+                for interpolation in KeyInterpolation::ALL {
+                    let label = format!("{interpolation:?}");
+                    if ui
+                        .selectable_label(key.interpolation == interpolation, label)
+                        .clicked()
+                    {
+                        curve.keys[index].interpolation = interpolation;
+                        changed = true;
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                if ui.button("Delete key").clicked() {
+                    removed = Some(index);
+                    ui.close_menu();
+                }
+            });
+        let color = match view.selected == Some(index) {
+            true => SELECTED_COLOR,
+            false => visuals.strong_text_color(),
+        };
+        painter.circle_filled(center, KEY_RADIUS, color);
+    }
+
+    // the background interacts last, so keys and handles own the clicks on them
+    let mut response = ui.interact(rect, id.with("background"), egui::Sense::click_and_drag());
+    if let Some(index) = view.selected.filter(|_| {
+        response.hovered()
+            && ui.input(|input| {
+                input.key_pressed(egui::Key::Delete) || input.key_pressed(egui::Key::Backspace)
+            })
+    }) {
+        removed = Some(index);
+    }
+    if let Some(index) = removed {
+        curve.keys.remove(index);
+        view.selected = None;
+        changed = true;
+    }
+
+    if response.double_clicked() {
+        match response.interact_pointer_pos() {
+            // double clicks on the curve add a key, elsewhere they fit the view
+            Some(pointer)
+                if (to_screen(to_curve(pointer).x, curve.sample(to_curve(pointer).x)).y
+                    - pointer.y)
+                    .abs()
+                    < KEY_RADIUS * 2.0
+                    || curve.keys.is_empty() =>
+            {
+                let position = to_curve(pointer);
+                view.selected = Some(curve.insert_key(position.x, position.y));
+                changed = true;
+            }
+            _ => {
+                view = CurveView {
+                    selected: view.selected,
+                    ..CurveView::fit(curve)
+                }
+            }
+        }
+    } else if response.clicked() {
+        view.selected = None;
+    }
+    if response.dragged() {
+        let delta =
+            response.drag_delta() * egui::vec2(size.x / rect.width(), -size.y / rect.height());
+        view.min -= delta;
+        view.max -= delta;
+    }
+    if response.hovered() {
+        let scroll = ui.input(|input| input.scroll_delta.y);
+        if scroll != 0.0 {
+            // zooms around the pointer
+            let zoom = (-scroll * 0.002).exp();
+            if let Some(pointer) = response.hover_pos() {
+                let anchor = to_curve(pointer);
+                view.min = anchor + (view.min - anchor) * zoom;
+                view.max = anchor + (view.max - anchor) * zoom;
+            }
+        }
+    }
+
+    ui.data_mut(|data| data.insert_temp(id, view));
+    if changed {
+        response.mark_changed();
+    }
+    response
+}
+
+fn curve_inspector_ui(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    id: egui::Id,
+    _: InspectorUi<'_, '_>,
+) -> bool {
+    let curve = value.downcast_mut::<Curve>().unwrap();
+    curve_editor(ui, curve, id, CURVE_HEIGHT).changed()
+}
+
+fn curve_inspector_ui_readonly(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    id: egui::Id,
+    _: InspectorUi<'_, '_>,
+) {
+    let mut curve = value.downcast_ref::<Curve>().unwrap().clone();
+    ui.add_enabled_ui(false, |ui| curve_editor(ui, &mut curve, id, CURVE_HEIGHT));
+}
+
+fn curve_inspector_ui_many(
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    _: egui::Id,
+    _: InspectorUi<'_, '_>,
+    _: &mut [&mut dyn Reflect],
+    _: &dyn Fn(&mut dyn Reflect) -> &mut dyn Reflect,
+) -> bool {
+    ui.label("Curves of several entities can't be edited at once");
+    false
+}
+
+/// State of the curves tab.
+#[derive(Resource, Default)]
+pub struct CurvesTab {
+    /// The component and path to the curve field being edited.
+    selected: Option<(TypeId, String)>,
+}
+
+/// Paths to the [`Curve`]s within `value`, in the syntax of [`GetPath`].
+fn curve_paths(value: &dyn Reflect, path: String, paths: &mut Vec<String>) {
+    if value.is::<Curve>() {
+        paths.push(path);
+        return;
+    }
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for i in 0..value.field_len() {
+                let name = value.name_at(i).unwrap();
+                curve_paths(value.field_at(i).unwrap(), format!("{path}.{name}"), paths);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for (i, field) in value.iter_fields().enumerate() {
+                curve_paths(field, format!("{path}.{i}"), paths);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for (i, field) in value.iter_fields().enumerate() {
+                curve_paths(field, format!("{path}.{i}"), paths);
+            }
+        }
+        ReflectRef::List(value) => {
+            for (i, item) in value.iter().enumerate() {
+                curve_paths(item, format!("{path}[{i}]"), paths);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for (i, item) in value.iter().enumerate() {
+                curve_paths(item, format!("{path}[{i}]"), paths);
+            }
+        }
+        ReflectRef::Enum(value) => {
+            for i in 0..value.field_len() {
+                let field = value.field_at(i).unwrap();
+                match value.name_at(i) {
+                    Some(name) => curve_paths(field, format!("{path}.{name}"), paths),
+                    None => curve_paths(field, format!("{path}.{i}"), paths),
+                }
+            }
+        }
+        ReflectRef::Map(_) | ReflectRef::Value(_) => {}
+    }
+}
+
+/// Lists the curves in the components of the selected entity, and edits the picked one with a
+/// large curve editor and the values of its keys.
+pub fn curves_ui(world: &mut World, ui: &mut egui::Ui, selected: &SelectedEntities) {
+    let Some(entity) = selected.iter().last() else {
+        ui.label("Select an entity to edit the curves of its components");
+        return;
+    };
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let curves: Vec<_> = history::snapshot_components(world, entity)
+        .into_iter()
+        .flat_map(|(type_id, component)| {
+            let mut paths = Vec::new();
+            curve_paths(component.as_ref(), String::new(), &mut paths);
+            let name = type_registry
+                .get(type_id)
+                .map_or("?", |registration| registration.short_name());
+            paths
+                .into_iter()
+                .map(move |path| (type_id, format!("{name}{path}"), path))
+        })
+        .collect();
+
+    let mut tab = world.resource_mut::<CurvesTab>();
+    if tab.selected.as_ref().is_none_or(|selected| {
+        !curves
+            .iter()
+            .any(|(id, _, path)| (id, path) == (&selected.0, &selected.1))
+    }) {
+        tab.selected = curves.first().map(|(id, _, path)| (*id, path.clone()));
+    }
+    let Some((type_id, path)) = tab.selected.clone() else {
+        ui.label("The selected entity has no curves");
+        return;
+    };
+    ui.horizontal(|ui| {
+        let selected_text = curves
+            .iter()
+            .find(|(id, _, curve_path)| (*id, curve_path) == (type_id, &path))
+            .map_or("", |(_, name, _)| name.as_str());
+        egui::ComboBox::from_id_source("curves_tab_field")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for (id, name, curve_path) in &curves {
+                    let is_selected = (*id, curve_path) == (type_id, &path);
+                    if ui.selectable_label(is_selected, name).clicked() {
+                        tab.selected = Some((*id, curve_path.clone()));
+                    }
+                }
+            });
+    });
+
+    let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id) else {
+        return;
+    };
+    let before = history::snapshot_components(world, entity);
+    let mut entity_mut = world.entity_mut(entity);
+    let Some(mut component) = reflect_component.reflect_mut(&mut entity_mut) else {
+        return;
+    };
+    let Ok(curve) = component.path_mut::<Curve>(&path) else {
+        return;
+    };
+    let mut edited = curve.clone();
+    let height = (ui.available_height() - 120.0).max(CURVE_HEIGHT);
+    let mut changed =
+        curve_editor(ui, &mut edited, ui.id().with((type_id, &path)), height).changed();
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        egui::Grid::new("curves_tab_keys")
+            .num_columns(5)
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Time", "Value", "In", "Out", "Interpolation"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for (index, key) in edited.keys.iter_mut().enumerate() {
+                    for value in [
+                        &mut key.time,
+                        &mut key.value,
+                        &mut key.in_tangent,
+                        &mut key.out_tangent,
+                    ] {
+                        changed |= ui.add(egui::DragValue::new(value).speed(0.01)).changed();
+                    }
+                    egui::ComboBox::from_id_source(("curves_tab_interpolation", index))
+                        .selected_text(format!("{:?}", key.interpolation))
+                        .show_ui(ui, |ui| {
+                            for interpolation in KeyInterpolation::ALL {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut key.interpolation,
+                                        interpolation,
+                                        format!("{interpolation:?}"),
+                                    )
+                                    .changed();
+                            }
+                        });
+                    ui.end_row();
+                }
+            });
+    });
+    if changed {
+        // edited times may have passed other keys
+        edited.keys.sort_by(|a, b| a.time.total_cmp(&b.time));
+        *curve = edited;
+        history::record_component_changes(world, entity, before);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(interpolation: KeyInterpolation) -> Curve {
+        let mut curve = Curve::default();
+        for key in &mut curve.keys {
+            key.interpolation = interpolation;
+        }
+        curve
+    }
+
+    #[test]
+    fn samples_each_interpolation() {
+        let constant = curve(KeyInterpolation::Constant);
+        assert_eq!(constant.sample(0.75), 0.0);
+        let linear = curve(KeyInterpolation::Linear);
+        assert_eq!(linear.sample(0.25), 0.25);
+        // flat tangents ease in and out
+        let hermite = curve(KeyInterpolation::Hermite);
+        assert_eq!(hermite.sample(0.5), 0.5);
+        assert!(hermite.sample(0.1) < 0.1);
+        assert!(hermite.sample(0.9) > 0.9);
+        assert!((Curve::linear().sample(0.3) - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn samples_the_first_and_last_keys_outside_of_them() {
+        let curve = Curve::default();
+        assert_eq!(curve.sample(-1.0), 0.0);
+        assert_eq!(curve.sample(2.0), 1.0);
+        assert_eq!(Curve { keys: Vec::new() }.sample(0.5), 0.0);
+    }
+
+    #[test]
+    fn inserted_keys_keep_the_shape_of_the_curve() {
+        let mut curve = Curve::default();
+        let before: Vec<_> = (0..=10).map(|i| curve.sample(i as f32 / 10.0)).collect();
+        let value = curve.sample(0.4);
+        assert_eq!(curve.insert_key(0.4, value), 1);
+
+        assert_eq!(curve.keys.len(), 3);
+        assert!(curve
+            .keys
+            .windows(2)
+            .all(|keys| keys[0].time <= keys[1].time));
+        for (i, before) in before.into_iter().enumerate() {
+            assert!((curve.sample(i as f32 / 10.0) - before).abs() < 1e-3);
+        }
+    }
+
+    #[derive(Reflect, Default)]
+    struct Curves {
+        speed: Curve,
+        stages: Vec<Curve>,
+        pair: (f32, Curve),
+        other: f32,
+    }
+
+    #[test]
+    fn finds_the_paths_to_nested_curves() {
+        let mut curves = Curves {
+            stages: vec![Curve::default(), Curve::linear()],
+            ..default()
+        };
+        let mut paths = Vec::new();
+        curve_paths(&curves, String::new(), &mut paths);
+        assert_eq!(paths, [".speed", ".stages[0]", ".stages[1]", ".pair.1"]);
+
+        for path in &paths {
+            assert!(curves.path_mut::<Curve>(path).is_ok(), "{path}");
+        }
+        assert_eq!(curves.path::<Curve>(&paths[2]), Ok(&Curve::linear()));
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{load_internal_asset, HandleId},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+use crate::{EditorOnly, EditorResource};
+
+const DEBUG_VIEW_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 8_214_611_982_375_340_173);
+
+pub struct EditorDebugViewPlugin;
+impl Plugin for EditorDebugViewPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            DEBUG_VIEW_SHADER_HANDLE,
+            "debug_view.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins(MaterialPlugin::<DebugMaterial>::default())
+            .init_resource::<ViewportDebugView>()
+            .add_systems(PostUpdate, apply_debug_view);
+    }
+}
+
+/// What the viewport shows in place of the lit scene. The values are passed to the shader.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DebugView {
+    #[default]
+    Lit = 0,
+    Normals = 1,
+    Depth = 2,
+    Albedo = 3,
+    UvChecker = 4,
+}
+
+impl DebugView {
+    pub const ALL: [DebugView; 5] = [
+        DebugView::Lit,
+        DebugView::Normals,
+        DebugView::Depth,
+        DebugView::Albedo,
+        DebugView::UvChecker,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DebugView::Lit => "Lit",
+            DebugView::Normals => "World Normals",
+            DebugView::Depth => "Depth",
+            DebugView::Albedo => "Unlit Albedo",
+            DebugView::UvChecker => "UV Checker",
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct ViewportDebugView {
+    pub view: DebugView,
+    /// Distance from the camera at which the depth view turns black.
+    pub depth_range: f32,
+    applied: (DebugView, f32),
+    /// Debug material replacing each standard material, by the standard material's id.
+    materials: HashMap<HandleId, Handle<DebugMaterial>>,
+}
+
+impl Default for ViewportDebugView {
+    fn default() -> Self {
+        Self {
+            view: DebugView::Lit,
+            depth_range: 50.0,
+            applied: (DebugView::Lit, 50.0),
+            materials: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, AsBindGroup, TypeUuid, TypePath)]
+#[uuid = "5d0c3bde-4ad6-4f87-9a55-9f3b0f7c2e61"]
+pub struct DebugMaterial {
+    #[uniform(0)]
+    base_color: Color,
+    #[uniform(0)]
+    mode: u32,
+    #[uniform(0)]
+    depth_range: f32,
+    #[texture(1)]
+    #[sampler(2)]
+    base_color_texture: Option<Handle<Image>>,
+}
+
+impl Material for DebugMaterial {
+    fn fragment_shader() -> ShaderRef {
+        DEBUG_VIEW_SHADER_HANDLE.typed().into()
+    }
+}
+
+/// Standard material of an entity while it's rendered with a [`DebugMaterial`], put back in its
+/// place when the entity is saved.
+#[derive(Component)]
+pub(crate) struct OriginalMaterial(pub(crate) Handle<StandardMaterial>);
+
+/// Swaps the standard materials of the scene for debug materials while a debug view is shown.
+/// Saved scenes and the play snapshot get the standard materials back, see
+/// [`scene::restore_debug_materials`](crate::scene::restore_debug_materials).
+#[allow(clippy::too_many_arguments)]
+fn apply_debug_view(
+    mut commands: Commands,
+    editor: Res<EditorResource>,
+    mut debug_view: ResMut<ViewportDebugView>,
+    standard_materials: Res<Assets<StandardMaterial>>,
+    mut debug_materials: ResMut<Assets<DebugMaterial>>,
+    // the material previews show the material itself
+    shaded: Query<(Entity, &Handle<StandardMaterial>), Without<EditorOnly>>,
+    swapped: Query<(Entity, &OriginalMaterial)>,
+) {
+    let view = match editor.0 {
+        true => debug_view.view,
+        false => DebugView::Lit,
+    };
+    let debug_view = &mut *debug_view;
+
+    if view == DebugView::Lit {
+        for (entity, original) in &swapped {
+            commands
+                .entity(entity)
+                .remove::<(OriginalMaterial, Handle<DebugMaterial>)>()
+                .insert(original.0.clone());
+        }
+        debug_view.materials.clear();
+        debug_view.applied.0 = view;
+        return;
+    }
+
+    if debug_view.applied != (view, debug_view.depth_range) {
+        debug_view.applied = (view, debug_view.depth_range);
+        for handle in debug_view.materials.values() {
+            if let Some(material) = debug_materials.get_mut(handle) {
+                material.mode = view as u32;
+                material.depth_range = debug_view.depth_range;
+            }
+        }
+    }
+
+    for (entity, handle) in &shaded {
+        let debug_handle = debug_view
+            .materials
+            .entry(handle.id())
+            .or_insert_with(|| {
+                let standard = standard_materials.get(handle);
+                debug_materials.add(DebugMaterial {
+                    base_color: standard.map_or(Color::WHITE, |material| material.base_color),
+                    mode: view as u32,
+                    depth_range: debug_view.depth_range,
+                    base_color_texture: standard
+                        .and_then(|material| material.base_color_texture.clone()),
+                })
+            })
+            .clone();
+        commands
+            .entity(entity)
+            .remove::<Handle<StandardMaterial>>()
+            .insert((debug_handle, OriginalMaterial(handle.clone())));
+    }
+}
+
+pub fn debug_view_toolbar(ui: &mut egui::Ui, world: &mut World) {
+    let mut debug_view = world.resource_mut::<ViewportDebugView>();
+    egui::ComboBox::from_id_source("viewport_debug_view")
+        .selected_text(debug_view.view.name())
+        .show_ui(ui, |ui| {
+            for view in DebugView::ALL {
+                ui.selectable_value(&mut debug_view.view, view, view.name());
+            }
+        });
+    if debug_view.view == DebugView::Depth {
+        ui.add(
+            egui::DragValue::new(&mut debug_view.depth_range)
+                .clamp_range(0.1..=10_000.0)
+                .speed(0.5)
+                .prefix("Range: "),
+        );
+    }
+}
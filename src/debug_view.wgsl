@@ -0,0 +1,45 @@
+#import bevy_pbr::mesh_vertex_output MeshVertexOutput
+#import bevy_pbr::mesh_view_bindings view
+
+struct DebugMaterial {
+    base_color: vec4<f32>,
+    mode: u32,
+    depth_range: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> material: DebugMaterial;
+@group(1) @binding(1)
+var base_color_texture: texture_2d<f32>;
+@group(1) @binding(2)
+var base_color_sampler: sampler;
+
+// keep in sync with `DebugView` in debug_view.rs
+const NORMALS: u32 = 1u;
+const DEPTH: u32 = 2u;
+const ALBEDO: u32 = 3u;
+
+@fragment
+fn fragment(mesh: MeshVertexOutput) -> @location(0) vec4<f32> {
+    if material.mode == NORMALS {
+        return vec4(normalize(mesh.world_normal) * 0.5 + 0.5, 1.0);
+    }
+    if material.mode == DEPTH {
+        let dist = distance(view.world_position, mesh.world_position.xyz);
+        return vec4(vec3(1.0 - clamp(dist / material.depth_range, 0.0, 1.0)), 1.0);
+    }
+#ifdef VERTEX_UVS
+    if material.mode == ALBEDO {
+        return material.base_color * textureSample(base_color_texture, base_color_sampler, mesh.uv);
+    }
+    let cell = floor(mesh.uv * 8.0);
+    let checker = (i32(cell.x) + i32(cell.y)) & 1;
+    return select(vec4(0.9, 0.9, 0.9, 1.0), vec4(0.15, 0.15, 0.15, 1.0), checker == 1);
+#else
+    if material.mode == ALBEDO {
+        return material.base_color;
+    }
+    // meshes without UVs stand out in the checker view
+    return vec4(1.0, 0.0, 1.0, 1.0);
+#endif
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, utils::Uuid, window::Window};
+
+use crate::{play::EditorState, EditorOnly, EditorResource, MainCamera};
+
+pub struct EditorIdPlugin;
+impl Plugin for EditorIdPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Uuid>()
+            .register_type::<EditorId>()
+            .init_resource::<EditorIds>()
+            .add_systems(Last, (assign_editor_ids, index_editor_ids).chain());
+    }
+}
+
+/// Identifies an authored entity for good, unlike its [`Entity`] which changes when it's
+/// despawned and spawned again, or when its scene is loaded. Added to the entities of the scene
+/// while editing, and saved with them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Reflect)]
+#[reflect(Component)]
+pub struct EditorId(pub Uuid);
+
+impl Default for EditorId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EditorId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+
+    /// The id of the entity of a prefab instance: the same for every respawn of the instance,
+    /// and different from the entity of the prefab in other instances.
+    pub fn in_instance(self, instance: EditorId) -> Self {
+        Self(Uuid::from_u128(self.0.as_u128() ^ instance.0.as_u128()))
+    }
+}
+
+/// The entities with an [`EditorId`], and the last id of the despawned ones.
+#[derive(Resource, Default)]
+pub struct EditorIds {
+    entities: HashMap<EditorId, Entity>,
+    ids: HashMap<Entity, EditorId>,
+}
+
+impl EditorIds {
+    /// The entity with `id`, if it was seen since it was last spawned.
+    pub fn entity(&self, world: &World, id: EditorId) -> Option<Entity> {
+        let entity = *self.entities.get(&id)?;
+        (world.get::<EditorId>(entity) == Some(&id)).then_some(entity)
+    }
+
+    /// The entity `entity` was respawned as, or `entity` itself while it's alive.
+    pub fn resolve(&self, world: &World, entity: Entity) -> Option<Entity> {
+        if world.get_entity(entity).is_some() {
+            return Some(entity);
+        }
+        self.entity(world, *self.ids.get(&entity)?)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn assign_editor_ids(
+    mut commands: Commands,
+    ed: Res<EditorResource>,
+    state: Res<State<EditorState>>,
+    entities: Query<
+        Entity,
+        (
+            Without<EditorId>,
+            Without<Window>,
+            Without<MainCamera>,
+            Without<EditorOnly>,
+        ),
+    >,
+) {
+    // the entities spawned while playing aren't authored
+    if !ed.0 || *state.get() != EditorState::Edit {
+        return;
+    }
+    for entity in &entities {
+        commands.entity(entity).insert(EditorId::new());
+    }
+}
+
+/// Indexes the new ids, and gives copies of an entity, like a duplicate or a scene loaded twice,
+/// an id of their own. Forgets the despawned entities once nothing has their id anymore.
+#[allow(clippy::type_complexity)]
+fn index_editor_ids(
+    mut ids: ParamSet<(
+        Query<(Entity, &mut EditorId), Changed<EditorId>>,
+        Query<&EditorId>,
+    )>,
+    mut removed: RemovedComponents<EditorId>,
+    mut index: ResMut<EditorIds>,
+) {
+    let mut changed: Vec<_> = ids.p0().iter().map(|(entity, id)| (entity, *id)).collect();
+    // the entities already indexed under their id keep it, the others are copies of them
+    changed.sort_by_key(|&(entity, id)| (index.entities.get(&id) != Some(&entity), entity));
+    for (entity, mut id) in changed {
+        let taken = index.entities.get(&id).is_some_and(|&other| {
+            other != entity && ids.p1().get(other).is_ok_and(|other_id| *other_id == id)
+        });
+        if taken {
+            id = EditorId::new();
+            if let Ok((_, mut current)) = ids.p0().get_mut(entity) {
+                *current = id;
+            }
+        }
+        if let Some(old) = index.ids.insert(entity, id) {
+            if old != id && index.entities.get(&old) == Some(&entity) {
+                index.entities.remove(&old);
+            }
+        }
+        index.entities.insert(id, entity);
+    }
+
+    for entity in removed.iter() {
+        if ids.p1().contains(entity) {
+            continue;
+        }
+        let Some(&id) = index.ids.get(&entity) else {
+            continue;
+        };
+        // kept while the id was taken over by a respawn, to resolve the entity to it
+        if index.entities.get(&id) == Some(&entity) {
+            index.entities.remove(&id);
+            index.ids.retain(|_, other| *other != id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world() -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<EditorIds>();
+        let mut schedule = Schedule::new();
+        schedule.add_systems(index_editor_ids);
+        (world, schedule)
+    }
+
+    fn update(world: &mut World, schedule: &mut Schedule) {
+        schedule.run(world);
+        world.clear_trackers();
+    }
+
+    #[test]
+    fn copies_get_an_id_of_their_own() {
+        let (mut world, mut schedule) = world();
+        let id = EditorId::new();
+        let original = world.spawn(id).id();
+        update(&mut world, &mut schedule);
+        let copy = world.spawn(id).id();
+        update(&mut world, &mut schedule);
+
+        assert_eq!(world.get::<EditorId>(original), Some(&id));
+        assert_ne!(world.get::<EditorId>(copy), Some(&id));
+        let ids = world.resource::<EditorIds>();
+        assert_eq!(ids.entity(&world, id), Some(original));
+        assert_eq!(
+            ids.entity(&world, world.get::<EditorId>(copy).copied().unwrap()),
+            Some(copy)
+        );
+    }
+
+    #[test]
+    fn the_indexed_entity_keeps_its_id_when_changed_with_a_copy() {
+        let (mut world, mut schedule) = world();
+        // the original reuses the index of a despawned entity, so that the copy sorts before it
+        let placeholder = world.spawn_empty().id();
+        world.despawn(placeholder);
+        let id = EditorId::new();
+        let original = world.spawn(id).id();
+        update(&mut world, &mut schedule);
+        // a copy in the same frame the original's id is written again, like a reloaded scene
+        world.get_mut::<EditorId>(original).unwrap().set_changed();
+        let copy = world.spawn(id).id();
+        update(&mut world, &mut schedule);
+
+        assert!(copy < original);
+        assert_eq!(world.get::<EditorId>(original), Some(&id));
+        assert_ne!(world.get::<EditorId>(copy), Some(&id));
+    }
+
+    #[test]
+    fn resolves_respawned_entities() {
+        let (mut world, mut schedule) = world();
+        let id = EditorId::new();
+        let despawned = world.spawn(id).id();
+        update(&mut world, &mut schedule);
+        world.despawn(despawned);
+        let respawned = world.spawn(id).id();
+        update(&mut world, &mut schedule);
+
+        let ids = world.resource::<EditorIds>();
+        assert_eq!(ids.resolve(&world, despawned), Some(respawned));
+        assert_eq!(ids.resolve(&world, respawned), Some(respawned));
+    }
+
+    #[test]
+    fn forgets_despawned_entities() {
+        let (mut world, mut schedule) = world();
+        let id = EditorId::new();
+        let despawned = world.spawn(id).id();
+        update(&mut world, &mut schedule);
+        world.despawn(despawned);
+        let respawned = world.spawn(id).id();
+        update(&mut world, &mut schedule);
+        world.despawn(respawned);
+        update(&mut world, &mut schedule);
+
+        let ids = world.resource::<EditorIds>();
+        assert!(ids.entities.is_empty());
+        assert!(ids.ids.is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+use bevy::{prelude::*, render::camera::CameraUpdateSystem, transform::TransformSystem};
+
+use crate::{settings::EditorSettings, views::EditorView, EditorResource, MainCamera, UiState};
+
+pub struct EditorFrustumPlugin;
+impl Plugin for EditorFrustumPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            draw_frustums
+                .after(TransformSystem::TransformPropagate)
+                .after(CameraUpdateSystem),
+        );
+    }
+}
+
+/// Corners of the plane `distance` in front of a perspective camera, in its local space.
+fn perspective_corners(projection: &PerspectiveProjection, distance: f32) -> [Vec3; 4] {
+    let half_height = distance * (projection.fov / 2.0).tan();
+    let half_width = half_height * projection.aspect_ratio;
+    [
+        Vec3::new(-half_width, -half_height, -distance),
+        Vec3::new(half_width, -half_height, -distance),
+        Vec3::new(half_width, half_height, -distance),
+        Vec3::new(-half_width, half_height, -distance),
+    ]
+}
+
+/// Corners of the plane `distance` in front of an orthographic camera, in its local space.
+fn orthographic_corners(projection: &OrthographicProjection, distance: f32) -> [Vec3; 4] {
+    let area = projection.area;
+    [
+        Vec3::new(area.min.x, area.min.y, -distance),
+        Vec3::new(area.max.x, area.min.y, -distance),
+        Vec3::new(area.max.x, area.max.y, -distance),
+        Vec3::new(area.min.x, area.max.y, -distance),
+    ]
+}
+
+/// Near and far corners of the frustum of a camera with the given projection, in its local
+/// space.
+fn frustum_corners(
+    projection: Option<&Projection>,
+    orthographic: Option<&OrthographicProjection>,
+) -> Option<([Vec3; 4], [Vec3; 4])> {
+    let orthographic = match projection {
+        Some(Projection::Perspective(perspective)) => {
+            return Some((
+                perspective_corners(perspective, perspective.near),
+                perspective_corners(perspective, perspective.far),
+            ))
+        }
+        Some(Projection::Orthographic(orthographic)) => orthographic,
+        None => orthographic?,
+    };
+    Some((
+        orthographic_corners(orthographic, orthographic.near),
+        orthographic_corners(orthographic, orthographic.far),
+    ))
+}
+
+/// Draws the view frustum of every selected game camera: its near and far planes and the edges
+/// between them.
+#[allow(clippy::type_complexity)]
+fn draw_frustums(
+    editor: Res<EditorResource>,
+    ui_state: Res<UiState>,
+    settings: Res<EditorSettings>,
+    cameras: Query<
+        (
+            &GlobalTransform,
+            Option<&Projection>,
+            Option<&OrthographicProjection>,
+        ),
+        (With<Camera>, Without<MainCamera>, Without<EditorView>),
+    >,
+    mut gizmos: Gizmos,
+) {
+    if !editor.0 {
+        return;
+    }
+    let color = settings.selection_outline;
+    for entity in ui_state.selected_entities.iter() {
+        let Ok((transform, projection, orthographic)) = cameras.get(entity) else {
+            continue;
+        };
+        let Some((near, far)) = frustum_corners(projection, orthographic) else {
+            continue;
+        };
+        let near = near.map(|corner| transform.transform_point(corner));
+        let far = far.map(|corner| transform.transform_point(corner));
+        for i in 0..4 {
+            let next = (i + 1) % 4;
+            gizmos.line(near[i], near[next], color);
+            gizmos.line(far[i], far[next], color);
+            gizmos.line(near[i], far[i], color);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::{self, EditorCameraController},
+    input::{self, EditorKeybinds},
+    play::EditorState,
+    scene, EditorResource, InspectorSelection, MainCamera, UiState,
+};
+
+pub struct EditorGamepadPlugin;
+impl Plugin for EditorGamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (gamepad_selection, gamepad_camera)
+                .run_if(editing)
+                .before(camera::editor_camera_controller),
+        );
+    }
+}
+
+/// Whether the gamepads drive the editor rather than the game, which has them while playing.
+fn editing(ed: Res<EditorResource>, state: Res<State<EditorState>>) -> bool {
+    ed.0 && *state.get() == EditorState::Edit
+}
+
+/// Cycles the selection through the entities of the scene and frames it.
+fn gamepad_selection(world: &mut World) {
+    let binds = world.resource::<EditorKeybinds>().gamepad.clone();
+    let gamepads = world.resource::<Gamepads>();
+    let input = world.resource::<Input<GamepadButton>>();
+    let offset = match (
+        input::gamepad_just_pressed(binds.select_next, gamepads, input),
+        input::gamepad_just_pressed(binds.select_previous, gamepads, input),
+    ) {
+        (true, false) => Some(1),
+        (false, true) => Some(-1),
+        _ => None,
+    };
+    let focus = input::gamepad_just_pressed(binds.focus_selection, gamepads, input);
+
+    if let Some(offset) = offset {
+        let entities = scene::scene_entities(world);
+        if !entities.is_empty() {
+            let ui_state = world.resource::<UiState>();
+            let index = match ui_state
+                .selected_entities
+                .iter()
+                .next()
+                .and_then(|selected| entities.iter().position(|&entity| entity == selected))
+            {
+                Some(index) => (index as isize + offset).rem_euclid(entities.len() as isize),
+                None if offset > 0 => 0,
+                None => entities.len() as isize - 1,
+            };
+            let mut ui_state = world.resource_mut::<UiState>();
+            ui_state
+                .selected_entities
+                .select_replace(entities[index as usize]);
+            ui_state.selection = InspectorSelection::Entities;
+        }
+    }
+    if focus {
+        camera::focus_selection(world);
+    }
+}
+
+/// Flies the main editor camera with the left stick and the triggers, turning it with the right
+/// stick.
+fn gamepad_camera(
+    keybinds: Res<EditorKeybinds>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    triggers: Res<Axis<GamepadButton>>,
+    time: Res<Time>,
+    mut cameras: Query<(&mut Transform, &mut EditorCameraController), With<MainCamera>>,
+) {
+    let binds = &keybinds.gamepad;
+    if !binds.move_camera {
+        return;
+    }
+    let axis = |gamepad, axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or_default()
+    };
+    let trigger = |gamepad, button_type| {
+        triggers
+            .get(GamepadButton::new(gamepad, button_type))
+            .unwrap_or_default()
+    };
+    let (mut movement, mut look) = (Vec3::ZERO, Vec2::ZERO);
+    for gamepad in gamepads.iter() {
+        movement += Vec3::new(
+            axis(gamepad, GamepadAxisType::LeftStickX),
+            trigger(gamepad, GamepadButtonType::RightTrigger2)
+                - trigger(gamepad, GamepadButtonType::LeftTrigger2),
+            axis(gamepad, GamepadAxisType::LeftStickY),
+        );
+        look += Vec2::new(
+            axis(gamepad, GamepadAxisType::RightStickX),
+            -axis(gamepad, GamepadAxisType::RightStickY),
+        );
+    }
+    if movement == Vec3::ZERO && look == Vec2::ZERO {
+        return;
+    }
+
+    let delta = time.raw_delta_seconds();
+    for (mut transform, mut controller) in &mut cameras {
+        controller.framing = None;
+        let distance = transform.translation.distance(controller.focus).max(0.1);
+        camera::rotate(&mut transform, look * binds.look_speed * delta);
+        let direction = transform.right() * movement.x
+            + Vec3::Y * movement.y
+            + transform.forward() * movement.z;
+        transform.translation += direction.clamp_length_max(1.0) * controller.fly_speed * delta;
+        // keep the orbit focus in front of the camera, like flying with the mouse
+        controller.focus = transform.translation + transform.forward() * distance;
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use bevy::prelude::*;
+
+/// How a file differs from `HEAD`, from `git status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Untracked,
+    Conflicted,
+}
+
+impl FileStatus {
+    /// Parses the two letter status of `git status --porcelain`.
+    fn from_porcelain(status: &str) -> Option<FileStatus> {
+        let mut chars = status.chars();
+        let (index, worktree) = (chars.next()?, chars.next()?);
+        Some(match (index, worktree) {
+            ('?', '?') => FileStatus::Untracked,
+            ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D') => FileStatus::Conflicted,
+            ('R', _) => FileStatus::Renamed,
+            ('A', _) => FileStatus::Added,
+            ('D', _) | (_, 'D') => FileStatus::Deleted,
+            ('!', '!') => return None,
+            _ => FileStatus::Modified,
+        })
+    }
+
+    pub fn badge(self) -> &'static str {
+        match self {
+            FileStatus::Modified => "M",
+            FileStatus::Added => "A",
+            FileStatus::Deleted => "D",
+            FileStatus::Renamed => "R",
+            FileStatus::Untracked => "U",
+            FileStatus::Conflicted => "!",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            FileStatus::Modified => "Modified",
+            FileStatus::Added => "Added",
+            FileStatus::Deleted => "Deleted",
+            FileStatus::Renamed => "Renamed",
+            FileStatus::Untracked => "Untracked",
+            FileStatus::Conflicted => "Conflicted",
+        }
+    }
+
+    pub fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            FileStatus::Untracked | FileStatus::Added => egui::Color32::from_rgb(110, 200, 110),
+            FileStatus::Conflicted | FileStatus::Deleted => visuals.error_fg_color,
+            FileStatus::Modified | FileStatus::Renamed => visuals.warn_fg_color,
+        }
+    }
+
+    /// Whether the file is tracked, so it can be reverted to `HEAD`.
+    pub fn revertible(self) -> bool {
+        !matches!(self, FileStatus::Untracked | FileStatus::Added)
+    }
+}
+
+type Statuses = HashMap<PathBuf, FileStatus>;
+
+/// The statuses of the files of the repository the working directory is in, read by running
+/// `git` on another thread so the browser doesn't wait on it.
+#[derive(Default)]
+pub struct GitStatus {
+    files: Statuses,
+    /// Set by the thread running `git status` when it's done, to `None` if it failed.
+    pending: Option<Arc<Mutex<Option<Option<Statuses>>>>>,
+    /// Whether the working directory isn't in a repository or git couldn't be run.
+    unavailable: bool,
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn read_statuses() -> Option<Statuses> {
+    let root = PathBuf::from(git(&["rev-parse", "--show-toplevel"])?.trim());
+    let root = root.canonicalize().unwrap_or(root);
+    let status = git(&["status", "--porcelain=v1", "-z", "--untracked-files=all"])?;
+    let mut files = HashMap::new();
+    let mut entries = status.split('\0');
+    while let Some(entry) = entries.next() {
+        let Some((code, path)) = entry.get(..2).zip(entry.get(3..)) else {
+            continue;
+        };
+        // renames are followed by the path they were renamed from
+        if code.starts_with('R') || code.starts_with('C') {
+            entries.next();
+        }
+        if let Some(status) = FileStatus::from_porcelain(code) {
+            files.insert(root.join(path), status);
+        }
+    }
+    Some(files)
+}
+
+impl GitStatus {
+    /// Starts reading the statuses again, unless they are still being read.
+    pub fn refresh(&mut self) {
+        if let Some(pending) = &self.pending {
+            let Some(files) = pending.lock().unwrap().take() else {
+                return;
+            };
+            self.unavailable = files.is_none();
+            self.files = files.unwrap_or_default();
+        }
+        if self.unavailable {
+            return;
+        }
+        let pending = Arc::new(Mutex::new(None));
+        self.pending = Some(pending.clone());
+        thread::spawn(move || {
+            let files = read_statuses();
+            *pending.lock().unwrap() = Some(files);
+        });
+    }
+
+    /// The status of the file at `path`, or for folders the status of the files in them if any
+    /// changed.
+    pub fn status(&self, path: &Path) -> Option<FileStatus> {
+        let path = path.canonicalize().ok()?;
+        if let Some(&status) = self.files.get(&path) {
+            return Some(status);
+        }
+        if !path.is_dir() {
+            return None;
+        }
+        self.files
+            .keys()
+            .any(|file| file.starts_with(&path))
+            .then_some(FileStatus::Modified)
+    }
+}
+
+/// Discards the changes to the file at `path`, restoring it as it is in `HEAD`.
+pub fn revert_file(path: &Path) {
+    let result = Command::new("git")
+        .args(["restore", "--source=HEAD", "--staged", "--worktree", "--"])
+        .arg(path)
+        .output();
+    match result {
+        Ok(output) if output.status.success() => info!("Reverted {}", path.display()),
+        Ok(output) => error!(
+            "Failed to revert {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => error!("Failed to revert {}: {e}", path.display()),
+    }
+}
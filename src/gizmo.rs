@@ -0,0 +1,284 @@
+use std::any::TypeId;
+
+use bevy::{prelude::*, render::camera::CameraProjection};
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use egui_gizmo::{Gizmo, GizmoMode, GizmoOrientation, GizmoVisuals};
+
+use crate::{
+    hierarchy::EditorLocked,
+    history::{EditTarget, EditorHistory},
+    input::{EditorAction, EditorInputCapture, EditorKeybinds},
+    mode2d::{self, EditorView2d},
+    scene,
+    settings::EditorSettings,
+    snap,
+};
+
+/// View and projection matrices of `camera`, for either projection kind.
+pub fn camera_matrices(world: &mut World, camera: Entity) -> Option<(Mat4, Mat4)> {
+    let (cam_transform, projection_matrix) = match world
+        .query::<(&GlobalTransform, &Projection)>()
+        .get(world, camera)
+    {
+        Ok((transform, projection)) => (*transform, projection.get_projection_matrix()),
+        Err(_) => {
+            let (transform, projection) = world
+                .query::<(&GlobalTransform, &OrthographicProjection)>()
+                .get(world, camera)
+                .ok()?;
+            (*transform, projection.get_projection_matrix())
+        }
+    };
+    let view_matrix = Mat4::from(cam_transform.affine().inverse());
+    Some((view_matrix, projection_matrix))
+}
+
+/// Selected entities with a [`Transform`], skipping those whose ancestor is also selected
+/// so that moving a parent and its child doesn't apply the transformation twice.
+pub(crate) fn gizmo_targets(world: &World, selected: &[Entity]) -> Vec<Entity> {
+    scene::top_level_entities(world, selected)
+        .into_iter()
+        .filter(|&entity| {
+            world.get::<Transform>(entity).is_some() && world.get::<EditorLocked>(entity).is_none()
+        })
+        .collect()
+}
+
+pub(crate) fn global_matrix(world: &World, entity: Entity) -> Mat4 {
+    world
+        .get::<GlobalTransform>(entity)
+        .map_or_else(
+            || {
+                world
+                    .get::<Transform>(entity)
+                    .copied()
+                    .unwrap_or_default()
+                    .into()
+            },
+            |global| *global,
+        )
+        .compute_matrix()
+}
+
+pub(crate) fn parent_matrix(world: &World, entity: Entity) -> Mat4 {
+    world
+        .get::<Parent>(entity)
+        .map_or(Mat4::IDENTITY, |parent| global_matrix(world, parent.get()))
+}
+
+/// Point the gizmo transforms multiple entities about.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GizmoPivot {
+    /// The center of the selected entities.
+    #[default]
+    Center,
+    /// The origin of the entity selected last, also orienting the gizmo in local mode.
+    Active,
+    /// Each entity's own origin, so they rotate and scale in place.
+    Individual,
+}
+
+impl GizmoPivot {
+    pub const ALL: [GizmoPivot; 3] = [
+        GizmoPivot::Center,
+        GizmoPivot::Active,
+        GizmoPivot::Individual,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GizmoPivot::Center => "Selection Center",
+            GizmoPivot::Active => "Active Entity",
+            GizmoPivot::Individual => "Individual Origins",
+        }
+    }
+}
+
+pub fn gizmo_toolbar(
+    ui: &mut egui::Ui,
+    keybinds: &EditorKeybinds,
+    gizmo_mode: &mut GizmoMode,
+    gizmo_orientation: &mut GizmoOrientation,
+    gizmo_pivot: &mut GizmoPivot,
+) {
+    ui.horizontal(|ui| {
+        for (mode, name, chord) in [
+            (GizmoMode::Translate, "Translate", keybinds.translate_gizmo),
+            (GizmoMode::Rotate, "Rotate", keybinds.rotate_gizmo),
+            (GizmoMode::Scale, "Scale", keybinds.scale_gizmo),
+        ] {
+            ui.selectable_value(gizmo_mode, mode, format!("{name} ({chord})"));
+        }
+        ui.separator();
+        let toggle = format!("Toggle with {}", keybinds.toggle_gizmo_orientation);
+        ui.selectable_value(gizmo_orientation, GizmoOrientation::Local, "Local")
+            .on_hover_text(&toggle);
+        ui.selectable_value(gizmo_orientation, GizmoOrientation::Global, "Global")
+            .on_hover_text(&toggle);
+        ui.separator();
+        egui::ComboBox::from_id_source("gizmo_pivot")
+            .selected_text(gizmo_pivot.name())
+            .show_ui(ui, |ui| {
+                for pivot in GizmoPivot::ALL {
+                    ui.selectable_value(gizmo_pivot, pivot, pivot.name());
+                }
+            })
+            .response
+            .on_hover_text("Pivot of multiple entities");
+    });
+}
+
+/// The pivot and targets as they were when the gizmo started being dragged, which the whole
+/// transformation of the drag is applied to.
+#[derive(Clone)]
+struct GizmoDrag {
+    start_pivot: Mat4,
+    /// The transformation of the pivot so far.
+    pivot: Mat4,
+    targets: Vec<(Entity, Mat4)>,
+}
+
+pub fn draw_gizmo(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    camera: Entity,
+    selected_entities: &SelectedEntities,
+    gizmo_mode: GizmoMode,
+    gizmo_orientation: GizmoOrientation,
+    gizmo_pivot: GizmoPivot,
+) {
+    let Some((view_matrix, projection_matrix)) = camera_matrices(world, camera) else {
+        return;
+    };
+
+    let targets = gizmo_targets(world, selected_entities.as_slice());
+    let active = selected_entities
+        .as_slice()
+        .last()
+        .filter(|&active| targets.contains(active) && gizmo_pivot == GizmoPivot::Active);
+    let pivot_matrix = match (targets.as_slice(), active) {
+        ([], _) => return,
+        (&[entity], _) | (_, Some(&entity)) => global_matrix(world, entity),
+        (targets, None) => {
+            let centroid = targets
+                .iter()
+                .map(|&entity| global_matrix(world, entity).w_axis.truncate())
+                .sum::<Vec3>()
+                / targets.len() as f32;
+            Mat4::from_translation(centroid)
+        }
+    };
+
+    let is_2d = world.get::<EditorView2d>(camera).is_some();
+    let mut visuals = GizmoVisuals::default();
+    if is_2d {
+        // only the handles moving within the XY plane or rotating around Z are useful
+        visuals.z_color = egui::Color32::TRANSPARENT;
+        if gizmo_mode == GizmoMode::Rotate {
+            visuals.x_color = egui::Color32::TRANSPARENT;
+            visuals.y_color = egui::Color32::TRANSPARENT;
+        }
+    }
+
+    // holding V snaps a vertex of the selection onto a vertex under the cursor instead
+    let vertex_snap = gizmo_mode == GizmoMode::Translate
+        && world
+            .resource::<Input<EditorAction>>()
+            .pressed(EditorAction::VertexSnap);
+
+    // egui-gizmo scales from the model matrix at the start of the drag but moves and rotates from
+    // the current one, so it's given the pivot transformed so far
+    let drag_id = egui::Id::new(("gizmo_drag", camera));
+    let drag = ui.memory(|memory| memory.data.get_temp::<GizmoDrag>(drag_id));
+    let model_matrix = drag.as_ref().map_or(pivot_matrix, |drag| drag.pivot);
+
+    let snap = &world.resource::<EditorSettings>().snapping;
+    // one gizmo state per view, so dragging in one view doesn't move the gizmo of the others
+    let Some(result) = Gizmo::new((camera, &targets))
+        .model_matrix(model_matrix.to_cols_array_2d())
+        .view_matrix(view_matrix.to_cols_array_2d())
+        .projection_matrix(projection_matrix.to_cols_array_2d())
+        .orientation(gizmo_orientation)
+        .mode(gizmo_mode)
+        .visuals(visuals)
+        .snapping(ui.input(|input| input.modifiers.ctrl))
+        .snap_distance(snap.translate)
+        .snap_angle(snap.rotate_degrees.to_radians())
+        .snap_scale(snap.scale)
+        .interact(ui)
+    else {
+        ui.memory_mut(|memory| memory.data.remove::<GizmoDrag>(drag_id));
+        snap::end_vertex_snap(ui, camera);
+        return;
+    };
+    world.resource_mut::<EditorInputCapture>().gizmo_dragged = true;
+    let mut drag = drag.unwrap_or_else(|| GizmoDrag {
+        start_pivot: pivot_matrix,
+        pivot: pivot_matrix,
+        targets: targets
+            .iter()
+            .map(|&entity| (entity, global_matrix(world, entity)))
+            .collect(),
+    });
+
+    let mut new_pivot_matrix = Mat4::from_scale_rotation_translation(
+        Vec3::from(<[f32; 3]>::from(result.scale)),
+        Quat::from_array(<[f32; 4]>::from(result.rotation)),
+        Vec3::from(<[f32; 3]>::from(result.translation)),
+    );
+    if vertex_snap {
+        let pivot = pivot_matrix.w_axis.truncate();
+        if let Some(translation) = snap::vertex_snap_translation(ui, world, camera, &targets, pivot)
+        {
+            new_pivot_matrix.w_axis = translation.extend(1.0);
+        }
+    } else {
+        snap::end_vertex_snap(ui, camera);
+    }
+    if is_2d {
+        new_pivot_matrix = mode2d::constrain_to_xy(model_matrix, new_pivot_matrix);
+    }
+    drag.pivot = new_pivot_matrix;
+    ui.memory_mut(|memory| memory.data.insert_temp(drag_id, drag.clone()));
+
+    // the transformation since the drag started, applied to every target as it was then so
+    // their offsets relative to the pivot are preserved
+    let delta = new_pivot_matrix * drag.start_pivot.inverse();
+    let pivot = Mat4::from_translation(drag.start_pivot.w_axis.truncate());
+
+    for (entity, start_global) in drag.targets {
+        let delta = match gizmo_pivot {
+            // the same transformation, moved from the pivot to the entity's origin
+            GizmoPivot::Individual => {
+                let origin = Mat4::from_translation(start_global.w_axis.truncate());
+                origin * pivot.inverse() * delta * pivot * origin.inverse()
+            }
+            GizmoPivot::Center | GizmoPivot::Active => delta,
+        };
+        let new_global = delta * start_global;
+        let new_local = Transform::from_matrix(parent_matrix(world, entity).inverse() * new_global);
+
+        let Some(mut transform) = world.get_mut::<Transform>(entity) else {
+            continue;
+        };
+        let before = *transform;
+        *transform = new_local;
+        record_transform_change(world, entity, before, new_local);
+    }
+}
+
+pub(crate) fn record_transform_change(
+    world: &mut World,
+    entity: Entity,
+    before: Transform,
+    after: Transform,
+) {
+    world.resource_mut::<EditorHistory>().record(
+        EditTarget::Component {
+            entity,
+            type_id: TypeId::of::<Transform>(),
+        },
+        Box::new(before),
+        Box::new(after),
+    );
+}
@@ -0,0 +1,516 @@
+use std::{fs, io, path::Path};
+
+use bevy::{
+    asset::HandleId,
+    prelude::*,
+    render::{
+        mesh::{Indices, VertexAttributeValues},
+        render_resource::PrimitiveTopology,
+    },
+    utils::HashMap,
+};
+use serde_json::{json, Value};
+
+use crate::{commands::AddEditorCommand, scene, UiState};
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_SHORT: u32 = 5123;
+const UNSIGNED_INT: u32 = 5125;
+
+pub struct EditorGltfExportPlugin;
+impl Plugin for EditorGltfExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GltfExport>()
+            .add_editor_command("Export Selection to glTF", |world| {
+                let path = world.resource::<GltfExport>().path.clone();
+                let selected = world
+                    .resource::<UiState>()
+                    .selected_entities
+                    .as_slice()
+                    .to_vec();
+                export_selection(world, &selected, &path);
+            });
+    }
+}
+
+/// Where the selection is exported to, as a binary `.glb` or as a `.gltf` with its buffer in a
+/// `.bin` next to it.
+#[derive(Resource)]
+pub struct GltfExport {
+    pub path: String,
+}
+
+impl Default for GltfExport {
+    fn default() -> Self {
+        Self {
+            path: "exports/selection.glb".to_owned(),
+        }
+    }
+}
+
+/// Collects the nodes, meshes and materials of the document, and the buffer they point into.
+#[derive(Default)]
+struct GltfBuilder {
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    meshes: Vec<Value>,
+    materials: Vec<Value>,
+    nodes: Vec<Value>,
+    /// Meshes already written, by mesh and material, since a glTF material belongs to the mesh.
+    mesh_indices: HashMap<(HandleId, Option<HandleId>), usize>,
+    material_indices: HashMap<HandleId, usize>,
+    skipped_textures: usize,
+}
+
+fn f32_bytes(values: impl IntoIterator<Item = f32>) -> Vec<u8> {
+    values.into_iter().flat_map(f32::to_le_bytes).collect()
+}
+
+impl GltfBuilder {
+    fn accessor(
+        &mut self,
+        bytes: &[u8],
+        target: u32,
+        component_type: u32,
+        count: usize,
+        kind: &str,
+    ) -> usize {
+        // accessors have to be aligned to their component size
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        self.buffer.extend_from_slice(bytes);
+        self.accessors.push(json!({
+            "bufferView": self.buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": kind,
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn attribute(&mut self, values: &VertexAttributeValues) -> Option<usize> {
+        let (bytes, kind) = match values {
+            VertexAttributeValues::Float32x2(values) => (f32_bytes(values.concat()), "VEC2"),
+            VertexAttributeValues::Float32x3(values) => (f32_bytes(values.concat()), "VEC3"),
+            VertexAttributeValues::Float32x4(values) => (f32_bytes(values.concat()), "VEC4"),
+            _ => return None,
+        };
+        Some(self.accessor(&bytes, ARRAY_BUFFER, FLOAT, values.len(), kind))
+    }
+
+    fn material(&mut self, world: &World, handle: &Handle<StandardMaterial>) -> Option<usize> {
+        if let Some(&index) = self.material_indices.get(&handle.id()) {
+            return Some(index);
+        }
+        let material = world.resource::<Assets<StandardMaterial>>().get(handle)?;
+        self.skipped_textures += [
+            &material.base_color_texture,
+            &material.emissive_texture,
+            &material.metallic_roughness_texture,
+            &material.normal_map_texture,
+            &material.occlusion_texture,
+        ]
+        .into_iter()
+        .filter(|texture| texture.is_some())
+        .count();
+
+        let [r, g, b, _] = material.emissive.as_linear_rgba_f32();
+        let mut json = json!({
+            "pbrMetallicRoughness": {
+                "baseColorFactor": material.base_color.as_linear_rgba_f32(),
+                "metallicFactor": material.metallic,
+                "roughnessFactor": material.perceptual_roughness,
+            },
+            "emissiveFactor": [r, g, b],
+            "doubleSided": material.double_sided,
+        });
+        match material.alpha_mode {
+            AlphaMode::Opaque => {}
+            AlphaMode::Mask(cutoff) => {
+                json["alphaMode"] = json!("MASK");
+                json["alphaCutoff"] = json!(cutoff);
+            }
+            _ => json["alphaMode"] = json!("BLEND"),
+        }
+        if let Some(path) = world.resource::<AssetServer>().get_handle_path(handle) {
+            json["name"] = json!(path.path().to_string_lossy());
+        }
+        self.materials.push(json);
+        self.material_indices
+            .insert(handle.id(), self.materials.len() - 1);
+        Some(self.materials.len() - 1)
+    }
+
+    fn mesh(
+        &mut self,
+        world: &World,
+        handle: &Handle<Mesh>,
+        material: Option<&Handle<StandardMaterial>>,
+    ) -> Option<usize> {
+        let key = (handle.id(), material.map(Handle::id));
+        if let Some(&index) = self.mesh_indices.get(&key) {
+            return Some(index);
+        }
+        let mesh = world.resource::<Assets<Mesh>>().get(handle)?;
+        let mode = match mesh.primitive_topology() {
+            PrimitiveTopology::PointList => 0,
+            PrimitiveTopology::LineList => 1,
+            PrimitiveTopology::LineStrip => 3,
+            PrimitiveTopology::TriangleList => 4,
+            PrimitiveTopology::TriangleStrip => 5,
+        };
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            return None;
+        };
+        // glTF requires the bounds of the positions
+        let (min, max) = positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), &position| (min.min(position.into()), max.max(position.into())),
+        );
+        let mut attributes = serde_json::Map::new();
+        let position = self.attribute(mesh.attribute(Mesh::ATTRIBUTE_POSITION)?)?;
+        self.accessors[position]["min"] = json!(min.to_array());
+        self.accessors[position]["max"] = json!(max.to_array());
+        attributes.insert("POSITION".to_owned(), json!(position));
+        for (name, attribute) in [
+            ("NORMAL", Mesh::ATTRIBUTE_NORMAL),
+            ("TANGENT", Mesh::ATTRIBUTE_TANGENT),
+            ("TEXCOORD_0", Mesh::ATTRIBUTE_UV_0),
+            ("COLOR_0", Mesh::ATTRIBUTE_COLOR),
+        ] {
+            if let Some(accessor) = mesh
+                .attribute(attribute)
+                .and_then(|values| self.attribute(values))
+            {
+                attributes.insert(name.to_owned(), json!(accessor));
+            }
+        }
+
+        let mut primitive = json!({ "attributes": attributes, "mode": mode });
+        if let Some(indices) = mesh.indices() {
+            let (bytes, component_type): (Vec<u8>, _) = match indices {
+                Indices::U16(indices) => (
+                    indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+                    UNSIGNED_SHORT,
+                ),
+                Indices::U32(indices) => (
+                    indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+                    UNSIGNED_INT,
+                ),
+            };
+            let accessor = self.accessor(
+                &bytes,
+                ELEMENT_ARRAY_BUFFER,
+                component_type,
+                indices.len(),
+                "SCALAR",
+            );
+            primitive["indices"] = json!(accessor);
+        }
+        if let Some(material) = material.and_then(|material| self.material(world, material)) {
+            primitive["material"] = json!(material);
+        }
+
+        let mut json = json!({ "primitives": [primitive] });
+        if let Some(path) = world.resource::<AssetServer>().get_handle_path(handle) {
+            json["name"] = json!(path.path().to_string_lossy());
+        }
+        self.meshes.push(json);
+        self.mesh_indices.insert(key, self.meshes.len() - 1);
+        Some(self.meshes.len() - 1)
+    }
+
+    /// Adds `entity` and its descendants as nodes, returning the index of its node.
+    fn node(&mut self, world: &World, entity: Entity, transform: Transform) -> usize {
+        let index = self.nodes.len();
+        self.nodes.push(json!({
+            "translation": transform.translation.to_array(),
+            "rotation": transform.rotation.to_array(),
+            "scale": transform.scale.to_array(),
+        }));
+        if let Some(name) = world.get::<Name>(entity) {
+            self.nodes[index]["name"] = json!(name.as_str());
+        }
+        if let Some(mesh) = world.get::<Handle<Mesh>>(entity) {
+            let material = world.get::<Handle<StandardMaterial>>(entity);
+            if let Some(mesh) = self.mesh(world, mesh, material) {
+                self.nodes[index]["mesh"] = json!(mesh);
+            }
+        }
+
+        let children: Vec<_> = world
+            .get::<Children>(entity)
+            .map(|children| {
+                children
+                    .iter()
+                    .map(|&child| {
+                        let transform = world.get::<Transform>(child).copied().unwrap_or_default();
+                        self.node(world, child, transform)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !children.is_empty() {
+            self.nodes[index]["children"] = json!(children);
+        }
+        index
+    }
+
+    fn document(&self, uri: Option<String>, roots: Vec<usize>) -> Value {
+        let mut buffer = json!({ "byteLength": self.buffer.len() });
+        if let Some(uri) = uri {
+            buffer["uri"] = json!(uri);
+        }
+        let mut json = json!({
+            "asset": { "version": "2.0", "generator": "bevy_editor" },
+            "scene": 0,
+            "scenes": [{ "nodes": roots }],
+            "nodes": self.nodes,
+            "meshes": self.meshes,
+            "materials": self.materials,
+            "accessors": self.accessors,
+            "bufferViews": self.buffer_views,
+            "buffers": match self.buffer.is_empty() {
+                true => vec![],
+                false => vec![buffer],
+            },
+        });
+        // glTF doesn't allow empty arrays
+        if let Value::Object(object) = &mut json {
+            object.retain(|_, value| value.as_array().is_none_or(|array| !array.is_empty()));
+        }
+        json
+    }
+}
+
+/// Pads `bytes` to a multiple of 4 bytes, as the chunks of a `.glb` need to be.
+fn pad(mut bytes: Vec<u8>, padding: u8) -> Vec<u8> {
+    bytes.resize(bytes.len().next_multiple_of(4), padding);
+    bytes
+}
+
+fn glb(json: &Value, buffer: Vec<u8>) -> io::Result<Vec<u8>> {
+    let json = pad(serde_json::to_vec(json)?, b' ');
+    let buffer = pad(buffer, 0);
+    let bin_length = match buffer.is_empty() {
+        true => 0,
+        false => 8 + buffer.len(),
+    };
+    let length = 12 + 8 + json.len() + bin_length;
+
+    let mut glb = Vec::with_capacity(length);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(length as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(&json);
+    if !buffer.is_empty() {
+        glb.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&buffer);
+    }
+    Ok(glb)
+}
+
+/// Writes `entities` and their descendants to the glTF file at `path`, with the meshes and
+/// standard materials they use. The selected entities keep where they are in the world.
+///
+/// Textures aren't exported, materials keep their factors only.
+pub fn export_gltf(world: &World, entities: &[Entity], path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut builder = GltfBuilder::default();
+    let roots = scene::top_level_entities(world, entities)
+        .into_iter()
+        .map(|entity| {
+            let transform = world
+                .get::<GlobalTransform>(entity)
+                .map(GlobalTransform::compute_transform)
+                .unwrap_or_default();
+            builder.node(world, entity, transform)
+        })
+        .collect();
+    if builder.skipped_textures > 0 {
+        warn!(
+            "Exported materials without their {} textures",
+            builder.skipped_textures
+        );
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "gltf")
+    {
+        let bin = path.with_extension("bin");
+        let uri = bin
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        let json = builder.document(uri, roots);
+        fs::write(&bin, &builder.buffer)?;
+        fs::write(path, serde_json::to_vec_pretty(&json)?)
+    } else {
+        let json = builder.document(None, roots);
+        fs::write(path, glb(&json, builder.buffer)?)
+    }
+}
+
+pub fn export_selection(world: &World, selected: &[Entity], path: &str) {
+    if selected.is_empty() {
+        warn!("Select the entities to export to glTF first");
+        return;
+    }
+    match export_gltf(world, selected, path) {
+        Ok(()) => info!("Exported {} entities to {path}", selected.len()),
+        Err(e) => error!("Failed to export the selection to {path}: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    /// A cube with a material, and two children with the same cube, one without the material.
+    fn world() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>();
+        let world = &mut app.world;
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(shape::Cube::new(1.0).into());
+        let material = world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial {
+                alpha_mode: AlphaMode::Mask(0.25),
+                ..Color::RED.into()
+            });
+        let transform = Transform::from_xyz(1.0, 2.0, 3.0);
+        let root = world
+            .spawn((
+                Name::new("Root"),
+                transform,
+                GlobalTransform::from(transform),
+                mesh.clone(),
+                material.clone(),
+            ))
+            .id();
+        let child = world
+            .spawn((Transform::from_xyz(1.0, 0.0, 0.0), mesh.clone(), material))
+            .set_parent(root)
+            .id();
+        world.spawn((Transform::default(), mesh)).set_parent(root);
+        (app, root, child)
+    }
+
+    #[test]
+    fn exports_the_hierarchy_with_shared_meshes_and_materials() {
+        let (app, root, child) = world();
+        let mut builder = GltfBuilder::default();
+        let roots = scene::top_level_entities(&app.world, &[root, child])
+            .into_iter()
+            .map(|entity| builder.node(&app.world, entity, Transform::from_xyz(1.0, 2.0, 3.0)))
+            .collect();
+        let json = builder.document(None, roots);
+
+        assert_eq!(json["scenes"][0]["nodes"], json!([0]));
+        assert_eq!(json["nodes"][0]["name"], "Root");
+        assert_eq!(json["nodes"][0]["translation"], json!([1.0, 2.0, 3.0]));
+        assert_eq!(json["nodes"][0]["children"], json!([1, 2]));
+        assert_eq!(json["nodes"][1]["translation"], json!([1.0, 0.0, 0.0]));
+        // the cube is written once with the material and once without it
+        assert_eq!(json["nodes"][0]["mesh"], json!(0));
+        assert_eq!(json["nodes"][1]["mesh"], json!(0));
+        assert_eq!(json["nodes"][2]["mesh"], json!(1));
+        assert_eq!(json["meshes"][0]["primitives"][0]["material"], json!(0));
+        assert!(json["meshes"][1]["primitives"][0].get("material").is_none());
+        assert_eq!(json["materials"].as_array().unwrap().len(), 1);
+        assert_eq!(json["materials"][0]["alphaMode"], "MASK");
+        assert_eq!(json["materials"][0]["alphaCutoff"], json!(0.25));
+
+        let primitive = &json["meshes"][0]["primitives"][0];
+        assert_eq!(primitive["mode"], json!(4));
+        let position =
+            &json["accessors"][primitive["attributes"]["POSITION"].as_u64().unwrap() as usize];
+        assert_eq!(position["min"], json!([-0.5, -0.5, -0.5]));
+        assert_eq!(position["max"], json!([0.5, 0.5, 0.5]));
+        assert_eq!(position["type"], "VEC3");
+        let indices = &json["accessors"][primitive["indices"].as_u64().unwrap() as usize];
+        assert_eq!(indices["componentType"], json!(UNSIGNED_INT));
+        assert_eq!(indices["count"], json!(36));
+        for view in json["bufferViews"].as_array().unwrap() {
+            assert_eq!(view["byteOffset"].as_u64().unwrap() % 4, 0);
+        }
+        assert_eq!(
+            json["buffers"][0]["byteLength"],
+            json!(builder.buffer.len())
+        );
+    }
+
+    #[test]
+    fn leaves_out_empty_arrays() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut builder = GltfBuilder::default();
+        let root = builder.node(&world, entity, Transform::IDENTITY);
+        let json = builder.document(None, vec![root]);
+
+        let object = json.as_object().unwrap();
+        for key in ["meshes", "materials", "accessors", "bufferViews", "buffers"] {
+            assert!(!object.contains_key(key), "{key}");
+        }
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn writes_padded_glb_chunks() {
+        let json = json!({ "asset": { "version": "2.0" } });
+        let glb = glb(&json, vec![1, 2, 3, 4, 5]).unwrap();
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap());
+
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32_at(4), 2);
+        assert_eq!(u32_at(8) as usize, glb.len());
+        let json_length = u32_at(12) as usize;
+        assert_eq!(json_length % 4, 0);
+        assert_eq!(&glb[16..20], b"JSON");
+        let parsed: Value = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+        assert_eq!(parsed, json);
+
+        let bin = 20 + json_length;
+        assert_eq!(u32_at(bin), 8);
+        assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+        assert_eq!(&glb[bin + 8..], [1, 2, 3, 4, 5, 0, 0, 0]);
+    }
+
+    #[test]
+    fn writes_gltf_files_next_to_their_buffer() {
+        let (app, root, _) = world();
+        let dir = env::temp_dir().join("bevy_editor").join("gltf_export_test");
+        let path = dir.join("cube.gltf");
+        export_gltf(&app.world, &[root], &path).unwrap();
+
+        let json: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        let bin = fs::read(dir.join("cube.bin")).unwrap();
+        assert_eq!(json["buffers"][0]["uri"], "cube.bin");
+        assert_eq!(json["buffers"][0]["byteLength"], json!(bin.len()));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
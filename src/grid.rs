@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{settings::EditorSettings, EditorResource, MainCamera};
+
+/// Lines drawn in each direction from the grid center, at most.
+const MAX_LINES: f32 = 100.0;
+
+pub struct EditorGridPlugin;
+impl Plugin for EditorGridPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<GridSettings>()
+            .add_systems(PostUpdate, draw_grid);
+    }
+}
+
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridSettings {
+    pub visible: bool,
+    /// Distance between minor lines, grown by `major_every` as the camera gets further away.
+    pub spacing: f32,
+    /// Every how many minor lines a major line is drawn.
+    pub major_every: u32,
+    pub minor_color: Color,
+    pub major_color: Color,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            spacing: 1.0,
+            major_every: 10,
+            minor_color: Color::rgba(0.5, 0.5, 0.5, 0.15),
+            major_color: Color::rgba(0.6, 0.6, 0.6, 0.4),
+        }
+    }
+}
+
+/// Draws the grid on the XZ plane around the point below the camera. The grid follows the
+/// camera in whole major cells and coarsens with its height, so it looks infinite.
+fn draw_grid(
+    editor: Res<EditorResource>,
+    settings: Res<EditorSettings>,
+    cameras: Query<&GlobalTransform, With<MainCamera>>,
+    mut gizmos: Gizmos,
+) {
+    let grid = &settings.grid;
+    if !editor.0 || !grid.visible || grid.spacing <= 0.0 {
+        return;
+    }
+    let Ok(camera) = cameras.get_single() else {
+        return;
+    };
+    let camera = camera.translation();
+
+    let major_every = grid.major_every.max(2) as f32;
+    let extent = (camera.y.abs() * 10.0).max(grid.spacing * 20.0);
+    let mut spacing = grid.spacing;
+    while extent / spacing > MAX_LINES {
+        spacing *= major_every;
+    }
+    let major_spacing = spacing * major_every;
+
+    let lines = (extent / spacing).ceil() as i32;
+    let center_x = (camera.x / major_spacing).round() * major_spacing;
+    let center_z = (camera.z / major_spacing).round() * major_spacing;
+    let half = lines as f32 * spacing;
+
+    for i in -lines..=lines {
+        let offset = i as f32 * spacing;
+        let (x, z) = (center_x + offset, center_z + offset);
+        let color = |position: f32, axis_color: Color| {
+            let index = (position / spacing).round() as i64;
+            if index == 0 {
+                axis_color
+            } else if index % major_every as i64 == 0 {
+                grid.major_color
+            } else {
+                grid.minor_color
+            }
+        };
+        // lines along Z, the one through x = 0 being the Z axis
+        gizmos.line(
+            Vec3::new(x, 0.0, center_z - half),
+            Vec3::new(x, 0.0, center_z + half),
+            color(x, Color::rgb(0.2, 0.4, 0.9)),
+        );
+        // lines along X, the one through z = 0 being the X axis
+        gizmos.line(
+            Vec3::new(center_x - half, 0.0, z),
+            Vec3::new(center_x + half, 0.0, z),
+            color(z, Color::rgb(0.9, 0.25, 0.25)),
+        );
+    }
+}
@@ -0,0 +1,681 @@
+use std::{any::TypeId, collections::HashSet};
+
+use bevy::prelude::*;
+use bevy_inspector_egui::{
+    bevy_inspector::hierarchy::{SelectedEntities, SelectionMode},
+    egui::collapsing_header::CollapsingState,
+};
+use bevy_reflect::TypeRegistry;
+
+use crate::{
+    camera, clipboard,
+    history::{EditTarget, EditorHistory},
+    inspector::entity_name,
+    macros::{EditorMacros, MacroStep},
+    nested_scene::{self, NestedScene},
+    prefab::{self, PrefabAction},
+    views::EditorView,
+    EditorOnly, MainCamera,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityPreset {
+    Empty,
+    Camera,
+    PointLight,
+    Cube,
+}
+
+impl EntityPreset {
+    pub const ALL: [EntityPreset; 4] = [
+        EntityPreset::Empty,
+        EntityPreset::Camera,
+        EntityPreset::PointLight,
+        EntityPreset::Cube,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EntityPreset::Empty => "Empty",
+            EntityPreset::Camera => "Camera",
+            EntityPreset::PointLight => "Point Light",
+            EntityPreset::Cube => "Cube",
+        }
+    }
+
+    pub fn spawn(self, world: &mut World, parent: Option<Entity>) -> Entity {
+        let mut entity = world.spawn(Name::new(self.name()));
+        let id = entity.id();
+        match self {
+            EntityPreset::Empty => {
+                entity.insert(SpatialBundle::default());
+            }
+            EntityPreset::Camera => {
+                // spawned inactive so it doesn't fight the main camera for the window
+                entity.insert(Camera3dBundle {
+                    camera: Camera {
+                        is_active: false,
+                        ..default()
+                    },
+                    ..default()
+                });
+            }
+            EntityPreset::PointLight => {
+                entity.insert(PointLightBundle::default());
+            }
+            EntityPreset::Cube => {
+                let mesh = world
+                    .resource_mut::<Assets<Mesh>>()
+                    .add(shape::Cube { size: 1.0 }.into());
+                let material = world
+                    .resource_mut::<Assets<StandardMaterial>>()
+                    .add(StandardMaterial::default());
+                world.entity_mut(id).insert(PbrBundle {
+                    mesh,
+                    material,
+                    ..default()
+                });
+            }
+        }
+
+        if let Some(parent) = parent {
+            world.entity_mut(id).set_parent(parent);
+        }
+        id
+    }
+}
+
+/// Recursively despawns `entities`, skipping the [`MainCamera`] and [`EditorView`] cameras the
+/// editor renders through.
+pub fn despawn_entities(world: &mut World, entities: &[Entity]) {
+    for &entity in entities {
+        if world.get::<MainCamera>(entity).is_some() || world.get::<EditorView>(entity).is_some() {
+            warn!("Refusing to delete the editor camera {entity:?}, close its view instead");
+            continue;
+        }
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+/// Marks entities hidden from the hierarchy, holding the [`Visibility`] they had before so it can
+/// be restored, and saved in its place.
+#[derive(Component)]
+pub(crate) struct EditorHidden(pub Visibility);
+
+/// Marks entities which can't be picked in the viewport, moved by the gizmo or edited in the
+/// inspector until unlocked from the hierarchy.
+#[derive(Component)]
+pub(crate) struct EditorLocked;
+
+pub fn toggle_locked(world: &mut World, entity: Entity) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    match entity_mut.contains::<EditorLocked>() {
+        true => entity_mut.remove::<EditorLocked>(),
+        false => entity_mut.insert(EditorLocked),
+    };
+}
+
+/// Hides `entity` if it's shown and restores its visibility if it was hidden by the editor.
+pub fn toggle_hidden(world: &mut World, entity: Entity) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    match entity_mut.take::<EditorHidden>() {
+        Some(EditorHidden(visibility)) => {
+            entity_mut.insert(visibility);
+        }
+        None => {
+            let Some(&visibility) = entity_mut.get::<Visibility>() else {
+                return;
+            };
+            entity_mut.insert((Visibility::Hidden, EditorHidden(visibility)));
+        }
+    }
+}
+
+#[derive(Default)]
+struct RowActions {
+    spawned: Option<Entity>,
+    duplicate: Option<Entity>,
+    delete: Option<Entity>,
+    focus: Option<Entity>,
+    prefab: Option<(Entity, PrefabAction)>,
+    toggle_hidden: Option<Entity>,
+    toggle_locked: Option<Entity>,
+    open_nested: Option<Entity>,
+}
+
+/// Filters applied to the hierarchy, kept between frames.
+#[derive(Default)]
+pub struct HierarchyState {
+    name_filter: String,
+    component_filter: Option<(TypeId, String)>,
+    /// Entity whose name is being edited inline, with the edited text.
+    renaming: Option<(Entity, String)>,
+}
+
+impl HierarchyState {
+    fn is_filtering(&self) -> bool {
+        !self.name_filter.is_empty() || self.component_filter.is_some()
+    }
+
+    fn matches(&self, world: &World, entity: Entity) -> bool {
+        let name_matches = self.name_filter.is_empty()
+            || entity_name(world, entity)
+                .to_lowercase()
+                .contains(&self.name_filter.to_lowercase());
+        let component_matches = self.component_filter.as_ref().is_none_or(|&(type_id, _)| {
+            world
+                .get_entity(entity)
+                .is_some_and(|entity| entity.contains_type_id(type_id))
+        });
+        name_matches && component_matches
+    }
+
+    fn filter_ui(&mut self, ui: &mut egui::Ui, type_registry: &TypeRegistry) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.name_filter)
+                    .hint_text("Filter by name")
+                    .desired_width(120.0),
+            );
+
+            let selected_text = self
+                .component_filter
+                .as_ref()
+                .map_or("Any component", |(_, name)| name.as_str());
+            egui::ComboBox::from_id_source("hierarchy_component_filter")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.component_filter, None, "Any component");
+                    let mut components: Vec<_> = type_registry
+                        .iter()
+                        .filter(|registration| registration.data::<ReflectComponent>().is_some())
+                        .map(|registration| {
+                            (registration.type_id(), registration.short_name().to_owned())
+                        })
+                        .collect();
+                    components.sort_by(|(_, a), (_, b)| a.cmp(b));
+                    for component in components {
+                        let name = component.1.clone();
+                        ui.selectable_value(&mut self.component_filter, Some(component), name);
+                    }
+                });
+
+            if self.is_filtering()
+                && ui
+                    .small_button("✖")
+                    .on_hover_text("Clear filters")
+                    .clicked()
+            {
+                self.name_filter.clear();
+                self.component_filter = None;
+            }
+        });
+    }
+}
+
+struct Filtered {
+    matches: HashSet<Entity>,
+    /// Matches together with all of their ancestors.
+    visible: HashSet<Entity>,
+}
+
+fn filter_entities(world: &mut World, state: &HierarchyState) -> Option<Filtered> {
+    if !state.is_filtering() {
+        return None;
+    }
+    let entities: Vec<_> = world.query::<Entity>().iter(world).collect();
+    let matches: HashSet<_> = entities
+        .into_iter()
+        .filter(|&entity| state.matches(world, entity))
+        .collect();
+    let visible = matches
+        .iter()
+        .flat_map(|&entity| {
+            std::iter::successors(Some(entity), |&entity| {
+                world.get::<Parent>(entity).map(|parent| parent.get())
+            })
+        })
+        .collect();
+    Some(Filtered { matches, visible })
+}
+
+struct HierarchyRows<'a> {
+    world: &'a mut World,
+    selected: &'a mut SelectedEntities,
+    filtered: Option<Filtered>,
+    always_open: HashSet<Entity>,
+    renaming: &'a mut Option<(Entity, String)>,
+    actions: RowActions,
+    new_selection: bool,
+}
+
+impl HierarchyRows<'_> {
+    fn visible(&self, entity: Entity) -> bool {
+        self.filtered
+            .as_ref()
+            .is_none_or(|filtered| filtered.visible.contains(&entity))
+    }
+
+    fn visible_children(&self, entity: Entity) -> Vec<Entity> {
+        self.world
+            .get::<Children>(entity)
+            .map(|children| {
+                children
+                    .iter()
+                    .copied()
+                    .filter(|&child| self.visible(child))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn entity_ui(&mut self, ui: &mut egui::Ui, entity: Entity, at_same_level: &[Entity]) {
+        let selected = self.selected.contains(entity);
+        let mut name = egui::RichText::new(entity_name(self.world, entity));
+        if selected {
+            name = name.strong();
+        }
+        let matched = self
+            .filtered
+            .as_ref()
+            .is_none_or(|filtered| filtered.matches.contains(&entity));
+        let hidden = self.world.get::<EditorHidden>(entity).is_some();
+        if !matched || hidden {
+            // only shown as the ancestor of a match, or hidden in the viewport
+            name = name.weak();
+        }
+
+        let children = self.visible_children(entity);
+        let has_children = !children.is_empty();
+        let open = if !has_children {
+            Some(false)
+        } else if self.always_open.contains(&entity) {
+            Some(true)
+        } else {
+            None
+        };
+
+        if self
+            .renaming
+            .as_ref()
+            .is_some_and(|&(renamed, _)| renamed == entity)
+        {
+            self.rename_ui(ui, entity, &children, open);
+            return;
+        }
+
+        let locked = self.world.get::<EditorLocked>(entity).is_some();
+        let (glyph, hover) = match locked {
+            true => ("🔒", "Unlock"),
+            false => ("🔓", "Lock"),
+        };
+        if row_toggle_ui(ui, ("hierarchy_lock", entity), 1, glyph, !locked, hover) {
+            self.actions.toggle_locked = Some(entity);
+        }
+        if self.world.get::<Visibility>(entity).is_some() {
+            let (glyph, hover) = match hidden {
+                true => ("–", "Show"),
+                false => ("👁", "Hide"),
+            };
+            if row_toggle_ui(
+                ui,
+                ("hierarchy_visibility", entity),
+                0,
+                glyph,
+                hidden,
+                hover,
+            ) {
+                self.actions.toggle_hidden = Some(entity);
+            }
+        }
+
+        #[allow(deprecated)] // the suggested replacement doesn't support selection
+        let response = egui::CollapsingHeader::new(name)
+            .id_source(entity)
+            .icon(move |ui, openness, response| {
+                if has_children {
+                    egui::collapsing_header::paint_default_icon(ui, openness, response);
+                }
+            })
+            .selectable(true)
+            .selected(selected)
+            .open(open)
+            .show(ui, |ui| {
+                for &child in &children {
+                    self.entity_ui(ui, child, &children);
+                }
+            });
+        let header = response.header_response;
+
+        if header.double_clicked() {
+            self.start_renaming(entity);
+        }
+        if header.clicked() {
+            let mode = ui.input(|input| {
+                SelectionMode::from_ctrl_shift(input.modifiers.ctrl, input.modifiers.shift)
+            });
+            self.selected.select(mode, entity, |from, to| {
+                let from = at_same_level.iter().position(|&entity| entity == from);
+                let to = at_same_level.iter().position(|&entity| entity == to);
+                from.zip(to)
+                    .map(|(from, to)| at_same_level[from.min(to)..=from.max(to)].to_vec())
+                    .unwrap_or_default()
+            });
+            self.new_selection = true;
+        }
+
+        header.context_menu(|ui| {
+            self.actions.spawned =
+                spawn_menu(ui, self.world, Some(entity)).or(self.actions.spawned);
+            ui.separator();
+            if ui.button("Rename").clicked() {
+                self.start_renaming(entity);
+                ui.close_menu();
+            }
+            if ui.button("Duplicate").clicked() {
+                self.actions.duplicate = Some(entity);
+                ui.close_menu();
+            }
+            if ui.button("Focus in Viewport").clicked() {
+                self.actions.focus = Some(entity);
+                ui.close_menu();
+            }
+            if ui
+                .button("Copy Entity Id")
+                .on_hover_text(format!("{entity:?}"))
+                .clicked()
+            {
+                ui.output_mut(|output| output.copied_text = format!("{entity:?}"));
+                ui.close_menu();
+            }
+            ui.separator();
+            if let Some(action) = prefab::prefab_menu(ui, self.world, entity) {
+                self.actions.prefab = Some((entity, action));
+            }
+            let editable = self
+                .world
+                .get::<NestedScene>(entity)
+                .is_some_and(NestedScene::editable);
+            if editable && ui.button("Open for Editing").clicked() {
+                self.actions.open_nested = Some(entity);
+                ui.close_menu();
+            }
+            if ui.button("Delete").clicked() {
+                self.actions.delete = Some(entity);
+                ui.close_menu();
+            }
+        });
+    }
+}
+
+/// Toggle button at the right end of the row about to be drawn, `slot` buttons from the right.
+/// Interacted with before the row, which it overlaps, so the row doesn't take its clicks.
+///
+/// Returns whether it was clicked.
+fn row_toggle_ui(
+    ui: &mut egui::Ui,
+    id_source: impl std::hash::Hash,
+    slot: usize,
+    glyph: &str,
+    dimmed: bool,
+    hover_text: &str,
+) -> bool {
+    let size = egui::Vec2::splat(ui.spacing().interact_size.y);
+    let rect = egui::Rect::from_min_size(
+        egui::pos2(
+            ui.max_rect().right() - size.x * (slot + 1) as f32,
+            ui.cursor().top(),
+        ),
+        size,
+    );
+    let response = ui
+        .interact(rect, egui::Id::new(id_source), egui::Sense::click())
+        .on_hover_text(hover_text);
+    let color = match (dimmed, response.hovered()) {
+        (_, true) => ui.visuals().strong_text_color(),
+        (true, false) => ui.visuals().weak_text_color(),
+        (false, false) => ui.visuals().text_color(),
+    };
+    ui.painter().text(
+        rect.center(),
+        egui::Align2::CENTER_CENTER,
+        glyph,
+        egui::TextStyle::Body.resolve(ui.style()),
+        color,
+    );
+    response.clicked()
+}
+
+impl HierarchyRows<'_> {
+    fn start_renaming(&mut self, entity: Entity) {
+        let name = self
+            .world
+            .get::<Name>(entity)
+            .map(|name| name.as_str().to_owned())
+            .unwrap_or_default();
+        *self.renaming = Some((entity, name));
+    }
+
+    /// Same row as in [`HierarchyRows::entity_ui`], with a text field in place of the name.
+    fn rename_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        entity: Entity,
+        children: &[Entity],
+        open: Option<bool>,
+    ) {
+        // same id as the `CollapsingHeader` so the open state carries over
+        let id = ui.make_persistent_id(entity);
+        let mut collapsing = CollapsingState::load_with_default_open(ui.ctx(), id, false);
+        if let Some(open) = open {
+            collapsing.set_open(open);
+        }
+
+        let mut finished = None;
+        collapsing
+            .show_header(ui, |ui| {
+                let (_, name) = self.renaming.as_mut().unwrap();
+                let response = ui.text_edit_singleline(name);
+                if response.lost_focus() {
+                    let cancelled = ui.input(|input| input.key_pressed(egui::Key::Escape));
+                    finished = Some((!cancelled).then(|| name.clone()));
+                } else {
+                    response.request_focus();
+                }
+            })
+            .body(|ui| {
+                for &child in children {
+                    self.entity_ui(ui, child, children);
+                }
+            });
+
+        if let Some(name) = finished {
+            *self.renaming = None;
+            if let Some(name) = name {
+                rename_entity(self.world, entity, name);
+            }
+        }
+    }
+}
+
+/// Sets the [`Name`] of `entity`, inserting it if missing. Renaming an existing name can be
+/// undone.
+pub fn rename_entity(world: &mut World, entity: Entity, name: String) {
+    let name = Name::new(name);
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let before = entity_mut.get::<Name>().cloned();
+    if before.as_ref() == Some(&name) {
+        return;
+    }
+    entity_mut.insert(name.clone());
+
+    if let Some(before) = before {
+        world.resource_mut::<EditorHistory>().record(
+            EditTarget::Component {
+                entity,
+                type_id: TypeId::of::<Name>(),
+            },
+            Box::new(before),
+            Box::new(name),
+        );
+    }
+}
+
+/// Draws the entity hierarchy with name and component filters and context menus for spawning,
+/// editing and deleting entities.
+///
+/// Returns `true` if the selection changed.
+pub fn hierarchy_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    state: &mut HierarchyState,
+    selected: &mut SelectedEntities,
+    pending_delete: &mut Option<Vec<Entity>>,
+) -> bool {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    nested_scene::isolation_ui(world, ui, selected);
+    state.filter_ui(ui, &type_registry);
+    ui.separator();
+
+    let filtered = filter_entities(world, state);
+    let mut always_open: HashSet<Entity> = selected
+        .iter()
+        .flat_map(|selected| {
+            std::iter::successors(Some(selected), |&entity| {
+                world.get::<Parent>(entity).map(|parent| parent.get())
+            })
+            .skip(1)
+        })
+        .collect();
+    if let Some(filtered) = &filtered {
+        always_open.extend(filtered.visible.difference(&filtered.matches));
+    }
+
+    // the entities the editor spawns for itself, like the off-screen studios of the asset
+    // previews and the cameras of the views, aren't part of the scene
+    let mut roots: Vec<_> = world
+        .query_filtered::<Entity, (Without<Parent>, Without<EditorOnly>)>()
+        .iter(world)
+        .filter(|entity| {
+            filtered
+                .as_ref()
+                .is_none_or(|filtered| filtered.visible.contains(entity))
+        })
+        .collect();
+    roots.sort();
+
+    let mut rows = HierarchyRows {
+        world,
+        selected,
+        filtered,
+        always_open,
+        renaming: &mut state.renaming,
+        actions: RowActions::default(),
+        new_selection: false,
+    };
+    for &root in &roots {
+        rows.entity_ui(ui, root, &roots);
+    }
+    let HierarchyRows {
+        world,
+        selected,
+        actions:
+            RowActions {
+                mut spawned,
+                duplicate,
+                delete,
+                focus,
+                prefab,
+                toggle_hidden,
+                toggle_locked,
+                open_nested,
+            },
+        new_selection: mut changed,
+        ..
+    } = rows;
+
+    // acting on a selected row acts on the whole selection
+    let targets = |entity| match selected.contains(entity) {
+        true => selected.as_slice().to_vec(),
+        false => vec![entity],
+    };
+    if let Some(entity) = delete {
+        *pending_delete = Some(targets(entity));
+    }
+    if let Some(entity) = focus {
+        camera::focus_entities(world, &targets(entity));
+    }
+    if let Some(entity) = duplicate {
+        let roots = clipboard::duplicate_entities(world, &targets(entity));
+        if !roots.is_empty() {
+            selected.clear();
+            for root in roots {
+                selected.select_maybe_add(root, true);
+            }
+            changed = true;
+        }
+    }
+
+    if let Some(entity) = toggle_hidden {
+        self::toggle_hidden(world, entity);
+    }
+    if let Some(entity) = toggle_locked {
+        self::toggle_locked(world, entity);
+    }
+    if let Some(entity) = open_nested {
+        nested_scene::open_for_editing(world, entity, selected);
+    }
+
+    // applied after drawing every row since syncing respawns the other instances
+    if let Some((entity, action)) = prefab {
+        prefab::apply_prefab_action(world, entity, action, selected);
+    }
+
+    ui.allocate_response(ui.available_size(), egui::Sense::click())
+        .context_menu(|ui| spawned = spawn_menu(ui, world, None).or(spawned));
+
+    if let Some(entity) = spawned {
+        selected.select_replace(entity);
+        changed = true;
+    }
+    changed
+}
+
+fn spawn_menu(ui: &mut egui::Ui, world: &mut World, entity: Option<Entity>) -> Option<Entity> {
+    let mut spawn = None;
+
+    if ui.button("Create Empty").clicked() {
+        spawn = Some((EntityPreset::Empty, None));
+    }
+    if let Some(entity) = entity {
+        if ui.button("Create Child").clicked() {
+            spawn = Some((EntityPreset::Empty, Some(entity)));
+        }
+    }
+    ui.separator();
+    for preset in EntityPreset::ALL {
+        if preset != EntityPreset::Empty && ui.button(preset.name()).clicked() {
+            spawn = Some((preset, None));
+        }
+    }
+
+    let (preset, parent) = spawn?;
+    ui.close_menu();
+    world
+        .resource_mut::<EditorMacros>()
+        .record(MacroStep::Spawn {
+            preset: preset.name().to_owned(),
+            child: parent.is_some(),
+        });
+    Some(preset.spawn(world, parent))
+}
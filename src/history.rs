@@ -0,0 +1,465 @@
+use std::any::TypeId;
+
+use bevy::prelude::*;
+
+use crate::{editor_id::EditorIds, input::EditorAction, EditorResource};
+
+pub struct EditorHistoryPlugin;
+impl Plugin for EditorHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorHistory>()
+            .add_systems(Update, undo_redo_system)
+            .add_systems(Last, end_history_frame);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditTarget {
+    Component { entity: Entity, type_id: TypeId },
+    Resource { type_id: TypeId },
+}
+
+/// A value before and after a change, `None` if the component was missing, so undoing or
+/// redoing it removes the component.
+#[derive(Debug)]
+pub struct ValueChange {
+    pub target: EditTarget,
+    pub before: Option<Box<dyn Reflect>>,
+    pub after: Option<Box<dyn Reflect>>,
+}
+
+/// One undo step, made of all values changed during a single interaction.
+#[derive(Debug, Default)]
+pub struct EditorChange {
+    pub changes: Vec<ValueChange>,
+}
+
+/// Undo/redo stacks of reflected component and resource mutations.
+///
+/// Changes recorded on consecutive frames are merged into one [`EditorChange`],
+/// so dragging a value or a gizmo produces a single undo step.
+#[derive(Resource)]
+pub struct EditorHistory {
+    undo: Vec<EditorChange>,
+    redo: Vec<EditorChange>,
+    pub max_len: usize,
+    merging: bool,
+    recorded_this_frame: bool,
+    /// Targets edited, undone or redone this frame.
+    changed: Vec<EditTarget>,
+}
+
+impl Default for EditorHistory {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            max_len: 256,
+            merging: false,
+            recorded_this_frame: false,
+            changed: Vec::new(),
+        }
+    }
+}
+
+impl EditorHistory {
+    pub fn record(
+        &mut self,
+        target: EditTarget,
+        before: Box<dyn Reflect>,
+        after: Box<dyn Reflect>,
+    ) {
+        self.record_change(target, Some(before), Some(after));
+    }
+
+    /// Records a component added, with no value `before`, or removed, with no value `after`.
+    pub fn record_change(
+        &mut self,
+        target: EditTarget,
+        before: Option<Box<dyn Reflect>>,
+        after: Option<Box<dyn Reflect>>,
+    ) {
+        self.redo.clear();
+
+        if !self.merging && !self.recorded_this_frame || self.undo.is_empty() {
+            self.undo.push(EditorChange::default());
+            if self.undo.len() > self.max_len {
+                self.undo.remove(0);
+            }
+        }
+        self.recorded_this_frame = true;
+        self.changed.push(target);
+
+        let entry = self.undo.last_mut().unwrap();
+        match entry
+            .changes
+            .iter_mut()
+            .find(|change| change.target == target)
+        {
+            Some(change) => change.after = after,
+            None => entry.changes.push(ValueChange {
+                target,
+                before,
+                after,
+            }),
+        }
+    }
+
+    /// Targets edited, undone or redone this frame, in the order they changed.
+    pub fn changed(&self) -> &[EditTarget] {
+        &self.changed
+    }
+
+    /// The undo step recorded to this frame, unless nothing was edited.
+    pub(crate) fn last_change(&self) -> Option<&EditorChange> {
+        self.undo.last().filter(|_| self.recorded_this_frame)
+    }
+
+    /// Whether the changes recorded this frame were merged into the undo step of the last frames.
+    pub(crate) fn merging(&self) -> bool {
+        self.merging && self.recorded_this_frame
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Sets the undo and redo stacks aside, so the edits made while playing can't be undone
+    /// once the scene is restored.
+    pub(crate) fn take_stacks(&mut self) -> (Vec<EditorChange>, Vec<EditorChange>) {
+        self.merging = false;
+        (
+            std::mem::take(&mut self.undo),
+            std::mem::take(&mut self.redo),
+        )
+    }
+
+    pub(crate) fn restore_stacks(&mut self, (undo, redo): (Vec<EditorChange>, Vec<EditorChange>)) {
+        self.merging = false;
+        self.undo = undo;
+        self.redo = redo;
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.merging = false;
+    }
+
+    pub fn undo(world: &mut World) {
+        let Some(entry) = world.resource_mut::<EditorHistory>().pop_undo() else {
+            return;
+        };
+        for change in entry.changes.iter().rev() {
+            apply_value(world, change.target, change.before.as_deref());
+        }
+        let mut history = world.resource_mut::<EditorHistory>();
+        history
+            .changed
+            .extend(entry.changes.iter().map(|change| change.target));
+        history.redo.push(entry);
+    }
+
+    pub fn redo(world: &mut World) {
+        let Some(entry) = world.resource_mut::<EditorHistory>().redo.pop() else {
+            return;
+        };
+        for change in &entry.changes {
+            apply_value(world, change.target, change.after.as_deref());
+        }
+        let mut history = world.resource_mut::<EditorHistory>();
+        history
+            .changed
+            .extend(entry.changes.iter().map(|change| change.target));
+        history.undo.push(entry);
+    }
+
+    fn pop_undo(&mut self) -> Option<EditorChange> {
+        self.merging = false;
+        self.undo.pop()
+    }
+}
+
+/// Returns `true` if `after` differs from `before`, falling back to comparing
+/// debug output for types without a reflected `PartialEq`.
+pub fn reflect_changed(before: &dyn Reflect, after: &dyn Reflect) -> bool {
+    match before.reflect_partial_eq(after) {
+        Some(equal) => !equal,
+        None => format!("{before:?}") != format!("{after:?}"),
+    }
+}
+
+pub fn snapshot_components(world: &World, entity: Entity) -> Vec<(TypeId, Box<dyn Reflect>)> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let Some(entity_ref) = world.get_entity(entity) else {
+        return Vec::new();
+    };
+
+    entity_ref
+        .archetype()
+        .components()
+        .filter_map(|component_id| {
+            let type_id = world.components().get_info(component_id)?.type_id()?;
+            let reflect_component = type_registry.get_type_data::<ReflectComponent>(type_id)?;
+            let value = reflect_component.reflect(entity_ref)?;
+            Some((type_id, value.clone_value()))
+        })
+        .collect()
+}
+
+pub fn snapshot_resource(world: &World, type_id: TypeId) -> Option<Box<dyn Reflect>> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let reflect_resource = type_registry.get_type_data::<ReflectResource>(type_id)?;
+    Some(reflect_resource.reflect(world)?.clone_value())
+}
+
+/// Compares the current component values of `entity` against `before` and
+/// records every difference in the [`EditorHistory`].
+pub fn record_component_changes(
+    world: &mut World,
+    entity: Entity,
+    before: Vec<(TypeId, Box<dyn Reflect>)>,
+) {
+    // despawned, or respawned like a prefab instance, rather than stripped of its components
+    if world.get_entity(entity).is_none() {
+        return;
+    }
+    let mut after = snapshot_components(world, entity);
+    let mut history = world.resource_mut::<EditorHistory>();
+
+    for (type_id, before) in before {
+        let target = EditTarget::Component { entity, type_id };
+        match after.iter().position(|(id, _)| *id == type_id) {
+            Some(index) => {
+                let (_, after) = after.swap_remove(index);
+                if reflect_changed(before.as_ref(), after.as_ref()) {
+                    history.record(target, before, after);
+                }
+            }
+            None => history.record_change(target, Some(before), None),
+        }
+    }
+    // the components left were added
+    for (type_id, after) in after {
+        history.record_change(EditTarget::Component { entity, type_id }, None, Some(after));
+    }
+}
+
+pub fn record_resource_change(
+    world: &mut World,
+    type_id: TypeId,
+    before: Option<Box<dyn Reflect>>,
+) {
+    let (Some(before), Some(after)) = (before, snapshot_resource(world, type_id)) else {
+        return;
+    };
+    if reflect_changed(before.as_ref(), after.as_ref()) {
+        world.resource_mut::<EditorHistory>().record(
+            EditTarget::Resource { type_id },
+            before,
+            after,
+        );
+    }
+}
+
+fn apply_value(world: &mut World, target: EditTarget, value: Option<&dyn Reflect>) {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+
+    match target {
+        EditTarget::Component { entity, type_id } => {
+            let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id)
+            else {
+                return;
+            };
+            // the entity can have been respawned since, like a prefab instance
+            let Some(entity) = world.resource::<EditorIds>().resolve(world, entity) else {
+                warn!("Cannot undo change to despawned entity {entity:?}");
+                return;
+            };
+            let mut entity_mut = world.entity_mut(entity);
+            match value {
+                Some(value) => reflect_component.apply_or_insert(&mut entity_mut, value),
+                None => reflect_component.remove(&mut entity_mut),
+            }
+        }
+        EditTarget::Resource { type_id } => {
+            let Some(reflect_resource) = type_registry.get_type_data::<ReflectResource>(type_id)
+            else {
+                return;
+            };
+            match value {
+                Some(value) => reflect_resource.apply_or_insert(world, value),
+                None => reflect_resource.remove(world),
+            }
+        }
+    }
+}
+
+fn undo_redo_system(world: &mut World) {
+    if !world.resource::<EditorResource>().0 {
+        return;
+    }
+
+    let actions = world.resource::<Input<EditorAction>>();
+    if actions.just_pressed(EditorAction::Redo) {
+        EditorHistory::redo(world);
+    } else if actions.just_pressed(EditorAction::Undo) {
+        EditorHistory::undo(world);
+    }
+}
+
+pub(crate) fn end_history_frame(mut history: ResMut<EditorHistory>) {
+    history.merging = history.recorded_this_frame;
+    history.recorded_this_frame = false;
+    history.changed.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world() -> World {
+        let mut world = World::new();
+        let type_registry = AppTypeRegistry::default();
+        type_registry.write().register::<Transform>();
+        world.insert_resource(type_registry);
+        world.init_resource::<EditorHistory>();
+        world.init_resource::<EditorIds>();
+        world
+    }
+
+    fn end_frame(world: &mut World) {
+        let mut system = IntoSystem::into_system(end_history_frame);
+        system.initialize(world);
+        system.run((), world);
+    }
+
+    fn move_to(world: &mut World, entity: Entity, x: f32) {
+        let before = *world.get::<Transform>(entity).unwrap();
+        let after = Transform::from_xyz(x, 0.0, 0.0);
+        world.entity_mut(entity).insert(after);
+        world.resource_mut::<EditorHistory>().record(
+            EditTarget::Component {
+                entity,
+                type_id: TypeId::of::<Transform>(),
+            },
+            Box::new(before),
+            Box::new(after),
+        );
+    }
+
+    fn x(world: &World, entity: Entity) -> f32 {
+        world.get::<Transform>(entity).unwrap().translation.x
+    }
+
+    #[test]
+    fn consecutive_frames_merge_into_one_step() {
+        let mut world = world();
+        let entity = world.spawn(Transform::default()).id();
+        for x in [1.0, 2.0, 3.0] {
+            move_to(&mut world, entity, x);
+            end_frame(&mut world);
+        }
+        assert_eq!(world.resource::<EditorHistory>().undo.len(), 1);
+
+        EditorHistory::undo(&mut world);
+        assert_eq!(x(&world, entity), 0.0);
+        assert!(!world.resource::<EditorHistory>().can_undo());
+        EditorHistory::redo(&mut world);
+        assert_eq!(x(&world, entity), 3.0);
+    }
+
+    #[test]
+    fn a_frame_without_edits_starts_a_new_step() {
+        let mut world = world();
+        let entity = world.spawn(Transform::default()).id();
+        move_to(&mut world, entity, 1.0);
+        end_frame(&mut world);
+        end_frame(&mut world);
+        move_to(&mut world, entity, 2.0);
+        end_frame(&mut world);
+        assert_eq!(world.resource::<EditorHistory>().undo.len(), 2);
+
+        EditorHistory::undo(&mut world);
+        assert_eq!(x(&world, entity), 1.0);
+        EditorHistory::undo(&mut world);
+        assert_eq!(x(&world, entity), 0.0);
+    }
+
+    #[test]
+    fn undoing_stops_the_merge() {
+        let mut world = world();
+        let entity = world.spawn(Transform::default()).id();
+        move_to(&mut world, entity, 1.0);
+        end_frame(&mut world);
+        move_to(&mut world, entity, 2.0);
+        end_frame(&mut world);
+        EditorHistory::undo(&mut world);
+        end_frame(&mut world);
+        move_to(&mut world, entity, 3.0);
+        end_frame(&mut world);
+
+        let history = world.resource::<EditorHistory>();
+        assert_eq!(history.undo.len(), 1);
+        // a new edit drops what could be redone
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut world = world();
+        world.resource_mut::<EditorHistory>().max_len = 2;
+        let entity = world.spawn(Transform::default()).id();
+        for x in [1.0, 2.0, 3.0] {
+            move_to(&mut world, entity, x);
+            end_frame(&mut world);
+            end_frame(&mut world);
+        }
+        assert_eq!(world.resource::<EditorHistory>().undo.len(), 2);
+        EditorHistory::undo(&mut world);
+        EditorHistory::undo(&mut world);
+        assert_eq!(x(&world, entity), 1.0);
+    }
+
+    #[test]
+    fn removed_and_added_components_can_be_undone() {
+        let mut world = world();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Name>();
+        let entity = world
+            .spawn((Transform::from_xyz(1.0, 0.0, 0.0), Name::new("Cube")))
+            .id();
+
+        let before = snapshot_components(&world, entity);
+        world.entity_mut(entity).remove::<Transform>();
+        record_component_changes(&mut world, entity, before);
+        end_frame(&mut world);
+        end_frame(&mut world);
+        let before = snapshot_components(&world, entity);
+        world
+            .entity_mut(entity)
+            .remove::<Name>()
+            .insert(Transform::default());
+        record_component_changes(&mut world, entity, before);
+        end_frame(&mut world);
+
+        EditorHistory::undo(&mut world);
+        assert_eq!(world.get::<Name>(entity).map(Name::as_str), Some("Cube"));
+        assert!(world.get::<Transform>(entity).is_none());
+        EditorHistory::undo(&mut world);
+        assert_eq!(x(&world, entity), 1.0);
+
+        EditorHistory::redo(&mut world);
+        assert!(world.get::<Transform>(entity).is_none());
+        EditorHistory::redo(&mut world);
+        assert!(world.get::<Name>(entity).is_none());
+        assert_eq!(x(&world, entity), 0.0);
+    }
+}
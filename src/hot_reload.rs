@@ -0,0 +1,209 @@
+use std::{collections::HashMap, fs, path::Path, time::SystemTime};
+
+use bevy::{
+    asset::{Asset, HandleId, LoadState, ReflectAsset},
+    gltf::Gltf,
+    prelude::*,
+    render::render_resource::Shader,
+};
+
+use crate::asset_browser::ASSET_DIR;
+
+pub struct EditorHotReloadPlugin;
+impl Plugin for EditorHotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HotReload>()
+            .add_editor_hot_reload::<Image>()
+            .add_editor_hot_reload::<Mesh>()
+            .add_editor_hot_reload::<StandardMaterial>()
+            .add_editor_hot_reload::<ColorMaterial>()
+            .add_editor_hot_reload::<Shader>()
+            .add_editor_hot_reload::<Scene>()
+            .add_editor_hot_reload::<DynamicScene>()
+            .add_editor_hot_reload::<Gltf>()
+            .add_editor_hot_reload::<Font>()
+            .add_editor_hot_reload::<AudioSource>()
+            .add_editor_hot_reload::<AnimationClip>();
+    }
+}
+
+#[derive(Default)]
+struct Reload {
+    /// When the file was last written as of loading it, to tell reloads from edits made in the
+    /// editor.
+    modified: Option<SystemTime>,
+    count: u32,
+    /// When the asset was last reloaded, in seconds since startup.
+    at: Option<f64>,
+}
+
+/// State of the hot reload tab, which lists the assets loaded from files with when they were
+/// reloaded.
+#[derive(Resource, Default)]
+pub struct HotReload {
+    reloads: HashMap<HandleId, Reload>,
+    filter: String,
+    /// Also list the assets loaded from a part of a file, like the meshes of a glTF.
+    pub show_labeled: bool,
+}
+
+fn file_modified(asset_server: &AssetServer, id: HandleId) -> Option<SystemTime> {
+    let path = asset_server.get_handle_path(id)?;
+    fs::metadata(Path::new(ASSET_DIR).join(path.path()))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn track_reloads<T: Asset>(
+    mut events: EventReader<AssetEvent<T>>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut hot_reload: ResMut<HotReload>,
+) {
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle } => {
+                let modified = file_modified(&asset_server, handle.id());
+                hot_reload.reloads.entry(handle.id()).or_default().modified = modified;
+            }
+            AssetEvent::Modified { handle } => {
+                let Some(modified) = file_modified(&asset_server, handle.id()) else {
+                    continue;
+                };
+                let reload = hot_reload.reloads.entry(handle.id()).or_default();
+                if reload.modified != Some(modified) {
+                    reload.modified = Some(modified);
+                    reload.count += 1;
+                    reload.at = Some(time.elapsed_seconds_f64());
+                }
+            }
+            AssetEvent::Removed { handle } => {
+                hot_reload.reloads.remove(&handle.id());
+            }
+        }
+    }
+}
+
+pub trait AddEditorHotReload {
+    /// Tracks the reloads of the assets of type `T` in the hot reload tab. The asset types of
+    /// bevy are tracked already.
+    ///
+    /// ```ignore
+    /// app.add_editor_hot_reload::<LevelData>();
+    /// ```
+    fn add_editor_hot_reload<T: Asset>(&mut self) -> &mut Self;
+}
+
+impl AddEditorHotReload for App {
+    fn add_editor_hot_reload<T: Asset>(&mut self) -> &mut Self {
+        // the plugin of the asset type can be disabled
+        if self.world.contains_resource::<Events<AssetEvent<T>>>() {
+            self.add_systems(PreUpdate, track_reloads::<T>);
+        }
+        self
+    }
+}
+
+fn load_state_label(state: LoadState) -> &'static str {
+    match state {
+        LoadState::NotLoaded => "Not loaded",
+        LoadState::Loading => "Loading",
+        LoadState::Loaded => "Loaded",
+        LoadState::Failed => "Failed",
+        LoadState::Unloaded => "Unloaded",
+    }
+}
+
+/// Lists the assets loaded from files, with their load state and when they were last reloaded,
+/// and reloads the one clicked.
+pub fn hot_reload_ui(world: &mut World, ui: &mut egui::Ui) {
+    world.resource_scope(|world, mut hot_reload: Mut<HotReload>| {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut hot_reload.filter)
+                    .hint_text("Filter")
+                    .desired_width(160.0),
+            );
+            ui.checkbox(&mut hot_reload.show_labeled, "Labeled")
+                .on_hover_text("Also list the assets loaded from a part of a file");
+        });
+        ui.weak("Changed files are reloaded when the AssetPlugin watches for changes");
+        ui.separator();
+
+        let asset_server = world.resource::<AssetServer>().clone();
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        let filter = hot_reload.filter.to_lowercase();
+        let mut assets: Vec<_> = type_registry
+            .iter()
+            .filter_map(|registration| {
+                let reflect_asset = registration.data::<ReflectAsset>()?;
+                Some((registration.short_name(), reflect_asset))
+            })
+            .flat_map(|(type_name, reflect_asset)| {
+                let asset_server = &asset_server;
+                reflect_asset.ids(world).filter_map(move |id| {
+                    let labeled = asset_server.get_handle_path(id)?.label().is_some();
+                    Some((
+                        crate::asset_label(asset_server, id)?,
+                        labeled,
+                        type_name,
+                        id,
+                    ))
+                })
+            })
+            .filter(|(path, labeled, ..)| {
+                (hot_reload.show_labeled || !labeled) && path.to_lowercase().contains(&filter)
+            })
+            .collect();
+        assets.sort_by(|a, b| a.0.cmp(&b.0));
+
+        if assets.is_empty() {
+            ui.weak("No asset loaded from a file");
+            return;
+        }
+        let now = world.resource::<Time>().elapsed_seconds_f64();
+        let mut reload = None;
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("hot_reload_assets")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Asset");
+                    ui.strong("State");
+                    ui.strong("Reloads");
+                    ui.strong("Last Reload");
+                    ui.end_row();
+                    for (path, _, type_name, id) in assets {
+                        ui.label(&path).on_hover_text(type_name);
+                        let state = asset_server.get_load_state(id);
+                        match state {
+                            LoadState::Failed => {
+                                ui.colored_label(ui.visuals().error_fg_color, "Failed")
+                                    .on_hover_text("The error is in the console");
+                            }
+                            state => {
+                                ui.label(load_state_label(state));
+                            }
+                        }
+                        let info = hot_reload.reloads.get(&id);
+                        ui.label(info.map_or(0, |info| info.count).to_string());
+                        match info.and_then(|info| info.at) {
+                            Some(at) => ui.label(format!("{:.0} s ago", now - at)),
+                            None => ui.weak("Never"),
+                        };
+                        if ui.small_button("Reload").clicked() {
+                            reload = Some((path, id));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+        if let Some((path, id)) = reload {
+            info!("Reloading {path}");
+            // counted as a reload even if the file didn't change
+            hot_reload.reloads.entry(id).or_default().modified = None;
+            asset_server.reload_asset(path);
+        }
+    });
+}
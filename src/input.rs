@@ -1,16 +1,701 @@
+use std::fmt;
+
 pub use bevy::prelude::*;
+use bevy::{
+    input::{
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseMotion, MouseWheel},
+    },
+    window::PrimaryWindow,
+};
+use bevy_inspector_egui::bevy_egui::{EguiContext, EguiSet};
+use serde::{Deserialize, Serialize};
 
-use crate::EditorResource;
+use crate::{views::EditorViewport, EditorResource};
 
 pub struct EditorInputPlugin;
 impl Plugin for EditorInputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, editor_input_system);
+        app.register_type::<KeyChord>()
+            .register_type::<GamepadBinds>()
+            .register_type::<EditorKeybinds>()
+            .init_resource::<EditorKeybinds>()
+            .init_resource::<EditorInputCapture>()
+            .init_resource::<Input<EditorAction>>()
+            .add_systems(
+                PreUpdate,
+                (
+                    capture_game_input.after(EguiSet::ProcessInput),
+                    update_editor_actions,
+                )
+                    .chain(),
+            )
+            .add_systems(Update, editor_input_system);
+    }
+}
+
+/// A key together with the modifiers which have to be held for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub struct KeyChord {
+    pub key: KeyCode,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub shift: bool,
+    #[serde(default)]
+    pub alt: bool,
+}
+
+impl KeyChord {
+    pub const fn new(key: KeyCode) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub const fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub const fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub const fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Whether the key was just pressed with exactly these modifiers held, so that Ctrl+Z
+    /// doesn't also trigger on Ctrl+Shift+Z.
+    pub fn just_pressed(&self, input: &Input<KeyCode>) -> bool {
+        input.just_pressed(self.key)
+            && self.ctrl == input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+            && self.shift == input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+            && self.alt == input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+    }
+
+    /// Whether the key is held with at least these modifiers, so that holding Shift to fly
+    /// faster doesn't stop W from flying forward. Either Shift, Ctrl or Alt key holds a chord of
+    /// the left or right one.
+    pub fn pressed(&self, input: &Input<KeyCode>) -> bool {
+        let either_side = match self.key {
+            KeyCode::ShiftLeft | KeyCode::ShiftRight => [KeyCode::ShiftLeft, KeyCode::ShiftRight],
+            KeyCode::ControlLeft | KeyCode::ControlRight => {
+                [KeyCode::ControlLeft, KeyCode::ControlRight]
+            }
+            KeyCode::AltLeft | KeyCode::AltRight => [KeyCode::AltLeft, KeyCode::AltRight],
+            key => [key, key],
+        };
+        input.any_pressed(either_side)
+            && (!self.ctrl || input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]))
+            && (!self.shift || input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]))
+            && (!self.alt || input.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        write!(f, "{:?}", self.key)
+    }
+}
+
+/// Every editor action which can be bound to a key. Editor systems read them from the
+/// `Input<EditorAction>` resource rather than polling keys, pressed once its keys are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EditorAction {
+    ToggleEditor,
+    TranslateGizmo,
+    RotateGizmo,
+    ScaleGizmo,
+    ToggleGizmoOrientation,
+    DeleteSelected,
+    Undo,
+    Redo,
+    PlayStop,
+    PauseResume,
+    Step,
+    CommandPalette,
+    Copy,
+    Paste,
+    Duplicate,
+    Screenshot,
+    DropToGround,
+    DropToGroundAligned,
+    FocusSelection,
+    SelectionBack,
+    SelectionForward,
+    FlyForward,
+    FlyBack,
+    FlyLeft,
+    FlyRight,
+    FlyUp,
+    FlyDown,
+    FlyFast,
+    Orbit,
+    VertexSnap,
+}
+
+impl EditorAction {
+    pub const ALL: [EditorAction; 30] = [
+        EditorAction::ToggleEditor,
+        EditorAction::TranslateGizmo,
+        EditorAction::RotateGizmo,
+        EditorAction::ScaleGizmo,
+        EditorAction::ToggleGizmoOrientation,
+        EditorAction::DeleteSelected,
+        EditorAction::Undo,
+        EditorAction::Redo,
+        EditorAction::PlayStop,
+        EditorAction::PauseResume,
+        EditorAction::Step,
+        EditorAction::CommandPalette,
+        EditorAction::Copy,
+        EditorAction::Paste,
+        EditorAction::Duplicate,
+        EditorAction::Screenshot,
+        EditorAction::DropToGround,
+        EditorAction::DropToGroundAligned,
+        EditorAction::FocusSelection,
+        EditorAction::SelectionBack,
+        EditorAction::SelectionForward,
+        EditorAction::FlyForward,
+        EditorAction::FlyBack,
+        EditorAction::FlyLeft,
+        EditorAction::FlyRight,
+        EditorAction::FlyUp,
+        EditorAction::FlyDown,
+        EditorAction::FlyFast,
+        EditorAction::Orbit,
+        EditorAction::VertexSnap,
+    ];
+
+    /// Whether the action is held down rather than triggered, pressed while its keys are held
+    /// whatever other modifiers are. Held actions only apply while flying the camera, orbiting
+    /// or dragging the gizmo, so they can share keys with the others.
+    pub fn is_held(self) -> bool {
+        matches!(
+            self,
+            EditorAction::FlyForward
+                | EditorAction::FlyBack
+                | EditorAction::FlyLeft
+                | EditorAction::FlyRight
+                | EditorAction::FlyUp
+                | EditorAction::FlyDown
+                | EditorAction::FlyFast
+                | EditorAction::Orbit
+                | EditorAction::VertexSnap
+        )
+    }
+}
+
+/// Keys bound to every [`EditorAction`]. Can be edited from the settings window or loaded
+/// from any serde format.
+#[derive(Debug, Clone, Resource, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct EditorKeybinds {
+    pub toggle_editor: KeyChord,
+    pub translate_gizmo: KeyChord,
+    pub rotate_gizmo: KeyChord,
+    pub scale_gizmo: KeyChord,
+    pub toggle_gizmo_orientation: KeyChord,
+    pub delete_selected: KeyChord,
+    pub undo: KeyChord,
+    pub redo: KeyChord,
+    pub play_stop: KeyChord,
+    pub pause_resume: KeyChord,
+    pub step: KeyChord,
+    pub command_palette: KeyChord,
+    pub copy: KeyChord,
+    pub paste: KeyChord,
+    pub duplicate: KeyChord,
+    pub screenshot: KeyChord,
+    pub drop_to_ground: KeyChord,
+    pub drop_to_ground_aligned: KeyChord,
+    pub focus_selection: KeyChord,
+    pub selection_back: KeyChord,
+    pub selection_forward: KeyChord,
+    pub fly_forward: KeyChord,
+    pub fly_back: KeyChord,
+    pub fly_left: KeyChord,
+    pub fly_right: KeyChord,
+    pub fly_up: KeyChord,
+    pub fly_down: KeyChord,
+    /// Held while flying to fly faster.
+    pub fly_fast: KeyChord,
+    /// Held while dragging with the left mouse button to orbit around the selection.
+    pub orbit: KeyChord,
+    /// Held while moving the selection to snap one of its vertices onto the vertex under the
+    /// cursor.
+    pub vertex_snap: KeyChord,
+    pub gamepad: GamepadBinds,
+}
+
+impl Default for EditorKeybinds {
+    fn default() -> Self {
+        Self {
+            toggle_editor: KeyChord::new(KeyCode::F1),
+            translate_gizmo: KeyChord::new(KeyCode::T),
+            rotate_gizmo: KeyChord::new(KeyCode::R),
+            scale_gizmo: KeyChord::new(KeyCode::S),
+            toggle_gizmo_orientation: KeyChord::new(KeyCode::O),
+            delete_selected: KeyChord::new(KeyCode::Delete),
+            undo: KeyChord::new(KeyCode::Z).ctrl(),
+            redo: KeyChord::new(KeyCode::Z).ctrl().shift(),
+            play_stop: KeyChord::new(KeyCode::F5),
+            pause_resume: KeyChord::new(KeyCode::F6),
+            step: KeyChord::new(KeyCode::F7),
+            command_palette: KeyChord::new(KeyCode::P).ctrl().shift(),
+            copy: KeyChord::new(KeyCode::C).ctrl(),
+            paste: KeyChord::new(KeyCode::V).ctrl(),
+            duplicate: KeyChord::new(KeyCode::D).ctrl(),
+            screenshot: KeyChord::new(KeyCode::F12),
+            drop_to_ground: KeyChord::new(KeyCode::End),
+            drop_to_ground_aligned: KeyChord::new(KeyCode::End).shift(),
+            focus_selection: KeyChord::new(KeyCode::F),
+            selection_back: KeyChord::new(KeyCode::Left).alt(),
+            selection_forward: KeyChord::new(KeyCode::Right).alt(),
+            fly_forward: KeyChord::new(KeyCode::W),
+            fly_back: KeyChord::new(KeyCode::S),
+            fly_left: KeyChord::new(KeyCode::A),
+            fly_right: KeyChord::new(KeyCode::D),
+            fly_up: KeyChord::new(KeyCode::E),
+            fly_down: KeyChord::new(KeyCode::Q),
+            fly_fast: KeyChord::new(KeyCode::ShiftLeft),
+            orbit: KeyChord::new(KeyCode::AltLeft),
+            vertex_snap: KeyChord::new(KeyCode::V),
+            gamepad: GamepadBinds::default(),
+        }
+    }
+}
+
+impl EditorKeybinds {
+    pub fn chord(&self, action: EditorAction) -> KeyChord {
+        match action {
+            EditorAction::ToggleEditor => self.toggle_editor,
+            EditorAction::TranslateGizmo => self.translate_gizmo,
+            EditorAction::RotateGizmo => self.rotate_gizmo,
+            EditorAction::ScaleGizmo => self.scale_gizmo,
+            EditorAction::ToggleGizmoOrientation => self.toggle_gizmo_orientation,
+            EditorAction::DeleteSelected => self.delete_selected,
+            EditorAction::Undo => self.undo,
+            EditorAction::Redo => self.redo,
+            EditorAction::PlayStop => self.play_stop,
+            EditorAction::PauseResume => self.pause_resume,
+            EditorAction::Step => self.step,
+            EditorAction::CommandPalette => self.command_palette,
+            EditorAction::Copy => self.copy,
+            EditorAction::Paste => self.paste,
+            EditorAction::Duplicate => self.duplicate,
+            EditorAction::Screenshot => self.screenshot,
+            EditorAction::DropToGround => self.drop_to_ground,
+            EditorAction::DropToGroundAligned => self.drop_to_ground_aligned,
+            EditorAction::FocusSelection => self.focus_selection,
+            EditorAction::SelectionBack => self.selection_back,
+            EditorAction::SelectionForward => self.selection_forward,
+            EditorAction::FlyForward => self.fly_forward,
+            EditorAction::FlyBack => self.fly_back,
+            EditorAction::FlyLeft => self.fly_left,
+            EditorAction::FlyRight => self.fly_right,
+            EditorAction::FlyUp => self.fly_up,
+            EditorAction::FlyDown => self.fly_down,
+            EditorAction::FlyFast => self.fly_fast,
+            EditorAction::Orbit => self.orbit,
+            EditorAction::VertexSnap => self.vertex_snap,
+        }
+    }
+
+    /// The pairs of actions bound to the same keys, which trigger together. Held actions only
+    /// conflict with each other.
+    pub fn conflicts(&self) -> Vec<(EditorAction, EditorAction)> {
+        let mut conflicts = Vec::new();
+        for (index, &a) in EditorAction::ALL.iter().enumerate() {
+            for &b in &EditorAction::ALL[index + 1..] {
+                if self.chord(a) == self.chord(b) && a.is_held() == b.is_held() {
+                    conflicts.push((a, b));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// Gamepad buttons bound to the editor, for when no keyboard is at hand. While editing, the left
+/// stick and the triggers move the editor camera and the right stick turns it.
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadBinds {
+    pub toggle_editor: GamepadButtonType,
+    /// Held while pressing `toggle_editor`, so the game can still use the button alone.
+    pub toggle_editor_held: Option<GamepadButtonType>,
+    pub select_next: GamepadButtonType,
+    pub select_previous: GamepadButtonType,
+    pub focus_selection: GamepadButtonType,
+    pub move_camera: bool,
+    /// How fast the right stick turns the camera when fully tilted, in radians per second.
+    pub look_speed: f32,
+}
+
+impl Default for GamepadBinds {
+    fn default() -> Self {
+        Self {
+            toggle_editor: GamepadButtonType::Start,
+            toggle_editor_held: Some(GamepadButtonType::Select),
+            select_next: GamepadButtonType::DPadDown,
+            select_previous: GamepadButtonType::DPadUp,
+            focus_selection: GamepadButtonType::North,
+            move_camera: true,
+            look_speed: 2.0,
+        }
+    }
+}
+
+/// Whether `button` was just pressed on any gamepad.
+pub fn gamepad_just_pressed(
+    button: GamepadButtonType,
+    gamepads: &Gamepads,
+    input: &Input<GamepadButton>,
+) -> bool {
+    gamepads
+        .iter()
+        .any(|gamepad| input.just_pressed(GamepadButton::new(gamepad, button)))
+}
+
+impl GamepadBinds {
+    pub fn toggle_editor_pressed(&self, gamepads: &Gamepads, input: &Input<GamepadButton>) -> bool {
+        gamepads.iter().any(|gamepad| {
+            input.just_pressed(GamepadButton::new(gamepad, self.toggle_editor))
+                && self
+                    .toggle_editor_held
+                    .is_none_or(|held| input.pressed(GamepadButton::new(gamepad, held)))
+        })
+    }
+}
+
+/// Presses the actions whose keys were just pressed, and releases those whose key was let go.
+/// Runs after the keys taken by the editor UI are cleared, so typing doesn't trigger actions.
+pub(crate) fn update_editor_actions(
+    keys: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    keybinds: Res<EditorKeybinds>,
+    mut actions: ResMut<Input<EditorAction>>,
+) {
+    actions.clear();
+    let toggle_button = keybinds.gamepad.toggle_editor;
+    let toggle_held = gamepads
+        .iter()
+        .any(|gamepad| gamepad_buttons.pressed(GamepadButton::new(gamepad, toggle_button)));
+    for action in EditorAction::ALL {
+        let chord = keybinds.chord(action);
+        if action.is_held() {
+            if chord.pressed(&keys) {
+                actions.press(action);
+            } else {
+                actions.release(action);
+            }
+            continue;
+        }
+        let toggle = action == EditorAction::ToggleEditor;
+        if chord.just_pressed(&keys)
+            || toggle
+                && keybinds
+                    .gamepad
+                    .toggle_editor_pressed(&gamepads, &gamepad_buttons)
+        {
+            actions.press(action);
+        } else if !(keys.pressed(chord.key) || toggle && toggle_held) {
+            actions.release(action);
+        }
     }
 }
 
-pub fn editor_input_system(mut editor: ResMut<EditorResource>, kb: Res<Input<KeyCode>>) {
-    if kb.just_pressed(KeyCode::F1) {
+pub fn editor_input_system(mut editor: ResMut<EditorResource>, actions: Res<Input<EditorAction>>) {
+    if actions.just_pressed(EditorAction::ToggleEditor) {
         editor.0 = !editor.0;
     }
 }
+
+/// Which input the editor UI took this frame, cleared from the [`Input`] resources and the input
+/// events before the game systems run so the player doesn't move while typing a name.
+#[derive(Resource, Default)]
+pub struct EditorInputCapture {
+    /// Whether a text field has the keyboard focus.
+    pub keyboard: bool,
+    /// Whether the pointer is over a panel rather than a game view, or dragging a widget or a
+    /// gizmo.
+    pub pointer: bool,
+    /// Whether the pointer is over one of the game views.
+    pub view_hovered: bool,
+    pub(crate) gizmo_dragged: bool,
+}
+
+pub(crate) fn capture_game_input(world: &mut World) {
+    let gizmo_dragged =
+        std::mem::take(&mut world.resource_mut::<EditorInputCapture>().gizmo_dragged);
+    if !world.resource::<EditorResource>().0 {
+        *world.resource_mut::<EditorInputCapture>() = EditorInputCapture::default();
+        return;
+    }
+    let views: Vec<_> = world
+        .query::<&EditorViewport>()
+        .iter(world)
+        .map(|viewport| viewport.rect)
+        .collect();
+    let over_view = |pos: egui::Pos2| views.iter().any(|rect| rect.contains(pos));
+    let Ok(mut ctx) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    else {
+        return;
+    };
+    let ctx = ctx.get_mut();
+    let keyboard = ctx.wants_keyboard_input();
+    let press_origin = ctx.input(|input| {
+        input
+            .pointer
+            .any_down()
+            .then(|| input.pointer.press_origin())
+            .flatten()
+    });
+    let view_hovered = ctx.pointer_hover_pos().is_some_and(over_view);
+    let pointer = match press_origin {
+        // a drag started in a game view belongs to the game, unless it moves the gizmo
+        Some(origin) => !over_view(origin) || gizmo_dragged,
+        None => ctx.is_pointer_over_area() && !view_hovered,
+    };
+    *world.resource_mut::<EditorInputCapture>() = EditorInputCapture {
+        keyboard,
+        pointer,
+        view_hovered,
+        gizmo_dragged: false,
+    };
+
+    if keyboard {
+        world.resource_mut::<Input<KeyCode>>().reset_all();
+        world.resource_mut::<Input<ScanCode>>().reset_all();
+        world.resource_mut::<Events<KeyboardInput>>().clear();
+        world.resource_mut::<Events<ReceivedCharacter>>().clear();
+    }
+    if pointer {
+        world.resource_mut::<Input<MouseButton>>().reset_all();
+        world.resource_mut::<Events<MouseButtonInput>>().clear();
+        world.resource_mut::<Events<MouseMotion>>().clear();
+        world.resource_mut::<Events<MouseWheel>>().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressed(keys: &[KeyCode]) -> Input<KeyCode> {
+        let mut input = Input::default();
+        for &key in keys {
+            input.press(key);
+        }
+        input
+    }
+
+    #[test]
+    fn chord_matches_exact_modifiers() {
+        let undo = KeyChord::new(KeyCode::Z).ctrl();
+        assert!(undo.just_pressed(&pressed(&[KeyCode::ControlLeft, KeyCode::Z])));
+        assert!(undo.just_pressed(&pressed(&[KeyCode::ControlRight, KeyCode::Z])));
+        assert!(!undo.just_pressed(&pressed(&[KeyCode::Z])));
+        assert!(!undo.just_pressed(&pressed(&[
+            KeyCode::ControlLeft,
+            KeyCode::ShiftLeft,
+            KeyCode::Z
+        ])));
+
+        let redo = KeyChord::new(KeyCode::Z).ctrl().shift();
+        assert!(redo.just_pressed(&pressed(&[
+            KeyCode::ControlLeft,
+            KeyCode::ShiftRight,
+            KeyCode::Z
+        ])));
+        assert!(!redo.just_pressed(&pressed(&[KeyCode::ControlLeft, KeyCode::Z])));
+    }
+
+    #[test]
+    fn chord_only_triggers_when_the_key_is_just_pressed() {
+        let chord = KeyChord::new(KeyCode::Z).ctrl();
+        let mut input = pressed(&[KeyCode::Z]);
+        input.clear();
+        input.press(KeyCode::ControlLeft);
+        assert!(!chord.just_pressed(&input));
+    }
+
+    #[test]
+    fn default_keybinds_have_no_conflicts() {
+        assert_eq!(EditorKeybinds::default().conflicts(), Vec::new());
+    }
+
+    #[test]
+    fn conflicts_lists_actions_bound_to_the_same_chord() {
+        let mut keybinds = EditorKeybinds::default();
+        keybinds.screenshot = keybinds.undo;
+        keybinds.paste = keybinds.copy;
+        assert_eq!(
+            keybinds.conflicts(),
+            vec![
+                (EditorAction::Undo, EditorAction::Screenshot),
+                (EditorAction::Copy, EditorAction::Paste),
+            ]
+        );
+
+        // the same key with other modifiers doesn't conflict
+        let mut keybinds = EditorKeybinds::default();
+        keybinds.screenshot = KeyChord::new(keybinds.undo.key);
+        assert_eq!(keybinds.conflicts(), Vec::new());
+    }
+
+    fn world_with(keybinds: EditorKeybinds) -> World {
+        let mut world = World::new();
+        world.init_resource::<Input<KeyCode>>();
+        world.init_resource::<Gamepads>();
+        world.init_resource::<Input<GamepadButton>>();
+        world.init_resource::<Input<EditorAction>>();
+        world.insert_resource(keybinds);
+        world.insert_resource(EditorResource(false));
+        world
+    }
+
+    /// Presses `keys` for a frame, returning the actions triggered.
+    fn press(world: &mut World, keys: &[KeyCode]) -> Vec<EditorAction> {
+        world.insert_resource(pressed(keys));
+        let mut update = IntoSystem::into_system(update_editor_actions);
+        update.initialize(world);
+        update.run((), world);
+        let mut toggle = IntoSystem::into_system(editor_input_system);
+        toggle.initialize(world);
+        toggle.run((), world);
+        world
+            .resource::<Input<EditorAction>>()
+            .get_just_pressed()
+            .copied()
+            .filter(|action| !action.is_held())
+            .collect()
+    }
+
+    /// Holds `keys` for a frame, returning the held actions pressed.
+    fn hold(world: &mut World, keys: &[KeyCode]) -> Vec<EditorAction> {
+        press(world, keys);
+        let actions = world.resource::<Input<EditorAction>>();
+        EditorAction::ALL
+            .into_iter()
+            .filter(|&action| action.is_held() && actions.pressed(action))
+            .collect()
+    }
+
+    #[test]
+    fn toggles_the_editor_with_its_keybind() {
+        let mut world = world_with(EditorKeybinds::default());
+        press(&mut world, &[KeyCode::F1]);
+        assert!(world.resource::<EditorResource>().0);
+        // taken by the game
+        press(&mut world, &[KeyCode::ControlLeft, KeyCode::F1]);
+        assert!(world.resource::<EditorResource>().0);
+
+        let mut world = world_with(EditorKeybinds {
+            toggle_editor: KeyChord::new(KeyCode::E).ctrl(),
+            ..default()
+        });
+        press(&mut world, &[KeyCode::F1]);
+        assert!(!world.resource::<EditorResource>().0);
+        press(&mut world, &[KeyCode::ControlLeft, KeyCode::E]);
+        assert!(world.resource::<EditorResource>().0);
+    }
+
+    #[test]
+    fn switches_gizmos_with_their_keybinds() {
+        let mut world = world_with(EditorKeybinds::default());
+        for (key, action) in [
+            (KeyCode::T, EditorAction::TranslateGizmo),
+            (KeyCode::R, EditorAction::RotateGizmo),
+            (KeyCode::S, EditorAction::ScaleGizmo),
+        ] {
+            assert_eq!(press(&mut world, &[key]), vec![action]);
+            assert_eq!(press(&mut world, &[KeyCode::ControlLeft, key]), Vec::new());
+        }
+
+        let mut world = world_with(EditorKeybinds {
+            scale_gizmo: KeyChord::new(KeyCode::G).shift(),
+            ..default()
+        });
+        assert_eq!(press(&mut world, &[KeyCode::S]), Vec::new());
+        assert_eq!(
+            press(&mut world, &[KeyCode::ShiftLeft, KeyCode::G]),
+            vec![EditorAction::ScaleGizmo]
+        );
+    }
+
+    #[test]
+    fn held_actions_ignore_other_modifiers() {
+        let mut world = world_with(EditorKeybinds::default());
+        assert_eq!(
+            hold(&mut world, &[KeyCode::W]),
+            vec![EditorAction::FlyForward]
+        );
+        assert_eq!(
+            hold(&mut world, &[KeyCode::ShiftRight, KeyCode::W, KeyCode::E]),
+            vec![
+                EditorAction::FlyForward,
+                EditorAction::FlyUp,
+                EditorAction::FlyFast
+            ]
+        );
+        assert_eq!(
+            hold(&mut world, &[KeyCode::AltRight]),
+            vec![EditorAction::Orbit]
+        );
+        assert_eq!(hold(&mut world, &[]), Vec::new());
+    }
+
+    #[test]
+    fn flies_with_rebound_keys() {
+        let mut world = world_with(EditorKeybinds {
+            fly_forward: KeyChord::new(KeyCode::Up),
+            fly_fast: KeyChord::new(KeyCode::ControlLeft),
+            vertex_snap: KeyChord::new(KeyCode::B).shift(),
+            ..default()
+        });
+        assert_eq!(hold(&mut world, &[KeyCode::W]), Vec::new());
+        assert_eq!(
+            hold(&mut world, &[KeyCode::ControlRight, KeyCode::Up]),
+            vec![EditorAction::FlyForward, EditorAction::FlyFast]
+        );
+        assert_eq!(hold(&mut world, &[KeyCode::B]), Vec::new());
+        assert_eq!(
+            hold(&mut world, &[KeyCode::ShiftLeft, KeyCode::B]),
+            vec![EditorAction::VertexSnap]
+        );
+    }
+
+    #[test]
+    fn held_actions_only_conflict_with_each_other() {
+        let mut keybinds = EditorKeybinds::default();
+        keybinds.fly_up = keybinds.fly_down;
+        keybinds.vertex_snap = keybinds.translate_gizmo;
+        assert_eq!(
+            keybinds.conflicts(),
+            vec![(EditorAction::FlyUp, EditorAction::FlyDown)]
+        );
+    }
+}
@@ -0,0 +1,590 @@
+use std::any::TypeId;
+
+use bevy::{
+    ecs::system::CommandQueue,
+    prelude::*,
+    reflect::{ReflectFromReflect, ReflectMut, ReflectRef},
+};
+use bevy_inspector_egui::{
+    egui::collapsing_header::CollapsingState,
+    inspector_egui_impls::InspectorEguiImpl,
+    inspector_options::{ReflectInspectorOptions, Target},
+    reflect_inspector::{Context, InspectorUi},
+    restricted_world_view::RestrictedWorldView,
+};
+use bevy_reflect::TypeRegistry;
+
+use crate::prefab::{self, Override, OverrideAction};
+
+pub fn entity_name(world: &World, entity: Entity) -> String {
+    match world.get::<Name>(entity) {
+        Some(name) => name.as_str().to_owned(),
+        None => format!("Entity ({:?})", entity),
+    }
+}
+
+struct ComponentInfo {
+    name: String,
+    type_id: Option<TypeId>,
+    size: usize,
+}
+
+fn components_of_entity(world: &World, entity: Entity) -> Option<Vec<ComponentInfo>> {
+    let entity_ref = world.get_entity(entity)?;
+    let mut components: Vec<_> = entity_ref
+        .archetype()
+        .components()
+        .filter_map(|component_id| {
+            let info = world.components().get_info(component_id)?;
+            Some(ComponentInfo {
+                name: bevy::utils::get_short_name(info.name()),
+                type_id: info.type_id(),
+                size: info.layout().size(),
+            })
+        })
+        .collect();
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(components)
+}
+
+/// Displays every component of `entity` with a remove button each, an "Add Component" menu, and
+/// the same for all of its children.
+///
+/// Returns the prefab instances respawned by applying an override to the prefab, as
+/// `(old, new)` pairs.
+pub fn ui_for_entity_with_children(
+    world: &mut World,
+    entity: Entity,
+    ui: &mut egui::Ui,
+) -> Vec<(Entity, Entity)> {
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+
+    ui.label(entity_name(world, entity));
+    let mut respawned = Vec::new();
+    ui_for_entity_with_children_inner(
+        world,
+        entity,
+        ui,
+        egui::Id::new(entity),
+        &type_registry,
+        &mut respawned,
+    );
+    respawned
+}
+
+fn ui_for_entity_with_children_inner(
+    world: &mut World,
+    entity: Entity,
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    type_registry: &TypeRegistry,
+    respawned: &mut Vec<(Entity, Entity)>,
+) {
+    respawned.extend(ui_for_entity_components(
+        world,
+        entity,
+        ui,
+        id,
+        type_registry,
+    ));
+
+    let children = world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    if !children.is_empty() {
+        ui.label("Children");
+        for child in children {
+            let id = id.with(child);
+            let child_name = entity_name(world, child);
+            egui::CollapsingHeader::new(&child_name)
+                .id_source(id)
+                .show(ui, |ui| {
+                    ui.label(&child_name);
+                    ui_for_entity_with_children_inner(
+                        world,
+                        child,
+                        ui,
+                        id,
+                        type_registry,
+                        respawned,
+                    );
+                });
+        }
+    }
+}
+
+fn ui_for_entity_components(
+    world: &mut World,
+    entity: Entity,
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    type_registry: &TypeRegistry,
+) -> Vec<(Entity, Entity)> {
+    let Some(components) = components_of_entity(world, entity) else {
+        ui.label(format!("Entity {entity:?} does not exist"));
+        return Vec::new();
+    };
+
+    let overrides = prefab::overrides(world, entity);
+    let in_instance = prefab::in_instance(world, entity);
+    let mut override_action = None;
+    let mut remove = None;
+    let mut queue = CommandQueue::default();
+    let mut world_view = RestrictedWorldView::new(world);
+
+    for ComponentInfo {
+        name,
+        type_id,
+        size,
+    } in components
+    {
+        let id = id.with(&name);
+        let removable = type_id.is_some_and(|type_id| {
+            !is_hierarchy_component(type_id)
+                && type_registry
+                    .get_type_data::<ReflectComponent>(type_id)
+                    .is_some()
+        });
+
+        let component_override =
+            type_id.and_then(|type_id| Some((type_id, overrides.get(&type_id)?)));
+
+        CollapsingState::load_with_default_open(ui.ctx(), id, false)
+            .show_header(ui, |ui| {
+                match component_override {
+                    Some((type_id, component_override)) => {
+                        let hover = match component_override {
+                            Override::Added => "Added to the prefab instance",
+                            Override::Value | Override::Fields(_) => "Overrides the prefab",
+                        };
+                        let label = egui::Label::new(egui::RichText::new(&name).strong())
+                            .sense(egui::Sense::click());
+                        ui.add(label).on_hover_text(hover).context_menu(|ui| {
+                            override_menu(ui, &mut override_action, type_id, None)
+                        });
+                    }
+                    None => {
+                        ui.label(&name);
+                    }
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let button = ui
+                        .add_enabled(removable, egui::Button::new("🗑").small())
+                        .on_hover_text("Remove component");
+                    if button.clicked() {
+                        remove = type_id;
+                    }
+                });
+            })
+            .body(|ui| {
+                let Some(type_id) = type_id else {
+                    ui.label(format!("{name} has no TypeId"));
+                    return;
+                };
+                if size == 0 {
+                    return;
+                }
+
+                let (mut component_view, world_view) =
+                    world_view.split_off_component((entity, type_id));
+                let mut cx = Context {
+                    world: Some(world_view),
+                    queue: Some(&mut queue),
+                };
+
+                match component_view.get_entity_component_reflect(entity, type_id, type_registry) {
+                    Ok((value, _, set_changed)) => {
+                        let mut env = InspectorUi::for_bevy(type_registry, &mut cx);
+                        let names = match type_registry.get_type_data::<InspectorEguiImpl>(type_id)
+                        {
+                            None if in_instance => field_names(value),
+                            _ => Vec::new(),
+                        };
+                        let changed = match names.is_empty() {
+                            true => env.ui_for_reflect_with_options(value, ui, id, &()),
+                            false => {
+                                let overridden = match component_override {
+                                    Some((_, Override::Fields(fields))) => fields.as_slice(),
+                                    _ => &[],
+                                };
+                                let fields = InstanceFields {
+                                    type_id,
+                                    names: &names,
+                                    overridden,
+                                };
+                                fields.ui(&mut env, value, ui, id, &mut override_action)
+                            }
+                        };
+                        if changed {
+                            set_changed();
+                        }
+                    }
+                    Err(e) => {
+                        ui.label(format!("{name}: {e:?}"));
+                    }
+                };
+            });
+    }
+
+    queue.apply(world);
+
+    let respawned = match override_action {
+        Some((type_id, field_index, action)) => {
+            prefab::apply_override_action(world, entity, type_id, field_index, action)
+        }
+        None => Vec::new(),
+    };
+    if !respawned.is_empty() {
+        // `entity` was respawned along with its instance
+        return respawned;
+    }
+    if let Some(type_id) = remove {
+        if let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id) {
+            reflect_component.remove(&mut world.entity_mut(entity));
+        }
+    }
+
+    add_component_menu(world, entity, ui, id, type_registry);
+    respawned
+}
+
+/// The fields of a component of a prefab instance, shown one by one to mark the overridden ones.
+struct InstanceFields<'a> {
+    type_id: TypeId,
+    names: &'a [String],
+    overridden: &'a [usize],
+}
+
+impl InstanceFields<'_> {
+    fn ui(
+        &self,
+        env: &mut InspectorUi,
+        value: &mut dyn Reflect,
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        override_action: &mut Option<(TypeId, Option<usize>, OverrideAction)>,
+    ) -> bool {
+        let options = env
+            .type_registry
+            .get_type_data::<ReflectInspectorOptions>(self.type_id);
+        let mut changed = false;
+        egui::Grid::new(id).show(ui, |ui| {
+            for (index, name) in self.names.iter().enumerate() {
+                if self.overridden.contains(&index) {
+                    let label = egui::Label::new(egui::RichText::new(name).strong())
+                        .sense(egui::Sense::click());
+                    ui.add(label)
+                        .on_hover_text("Overrides the prefab")
+                        .context_menu(|ui| {
+                            override_menu(ui, override_action, self.type_id, Some(index))
+                        });
+                } else {
+                    ui.label(name);
+                }
+                let options = options
+                    .and_then(|options| options.0.get(Target::Field(index)))
+                    .unwrap_or(&());
+                let field = field_mut(value, index).unwrap();
+                changed |= env.ui_for_reflect_with_options(field, ui, id.with(index), options);
+                ui.end_row();
+            }
+        });
+        changed
+    }
+}
+
+/// The actions on an overridden component of a prefab instance, or on its field `field_index`.
+fn override_menu(
+    ui: &mut egui::Ui,
+    action: &mut Option<(TypeId, Option<usize>, OverrideAction)>,
+    type_id: TypeId,
+    field_index: Option<usize>,
+) {
+    if ui.button("Revert to Prefab").clicked() {
+        *action = Some((type_id, field_index, OverrideAction::Revert));
+        ui.close_menu();
+    }
+    if ui.button("Apply to Prefab").clicked() {
+        *action = Some((type_id, field_index, OverrideAction::Apply));
+        ui.close_menu();
+    }
+}
+
+/// `Parent` and `Children` are kept in sync by the hierarchy, so they aren't added or removed on
+/// their own.
+fn is_hierarchy_component(type_id: TypeId) -> bool {
+    type_id == TypeId::of::<Parent>() || type_id == TypeId::of::<Children>()
+}
+
+fn add_component_menu(
+    world: &mut World,
+    entity: Entity,
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    type_registry: &TypeRegistry,
+) {
+    let filter_id = id.with("add_component_filter");
+    ui.menu_button("Add Component", |ui| {
+        let mut filter = ui.data_mut(|data| data.get_temp::<String>(filter_id).unwrap_or_default());
+        ui.text_edit_singleline(&mut filter).request_focus();
+        let filter_lowercase = filter.to_lowercase();
+        ui.data_mut(|data| data.insert_temp(filter_id, filter));
+
+        let entity_ref = world.entity(entity);
+        let mut candidates: Vec<_> = type_registry
+            .iter()
+            .filter(|registration| !is_hierarchy_component(registration.type_id()))
+            .filter_map(|registration| {
+                let reflect_component = registration.data::<ReflectComponent>()?;
+                let name = registration.short_name();
+                let matches = name.to_lowercase().contains(&filter_lowercase);
+                (matches && !reflect_component.contains(entity_ref))
+                    .then(|| (name.to_owned(), reflect_component.clone()))
+            })
+            .collect();
+        candidates.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut add = None;
+        egui::ScrollArea::vertical()
+            .max_height(300.0)
+            .show(ui, |ui| {
+                for (name, reflect_component) in candidates {
+                    if ui.button(&name).clicked() {
+                        add = Some(reflect_component);
+                    }
+                }
+            });
+
+        if let Some(reflect_component) = add {
+            let value = reflect_component.from_world(world);
+            reflect_component.insert(&mut world.entity_mut(entity), value.as_ref());
+            ui.data_mut(|data| data.remove::<String>(filter_id));
+            ui.close_menu();
+        }
+    });
+}
+
+/// Which part of a shared component was edited on the first entity, to copy to the others.
+#[derive(Clone, Copy)]
+enum SharedEdit {
+    Whole,
+    Field(usize),
+}
+
+pub(crate) fn field_names(value: &dyn Reflect) -> Vec<String> {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => (0..value.field_len())
+            .map(|index| value.name_at(index).unwrap().to_owned())
+            .collect(),
+        ReflectRef::TupleStruct(value) => (0..value.field_len()).map(|i| i.to_string()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub(crate) fn field(value: &dyn Reflect, index: usize) -> Option<&dyn Reflect> {
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => value.field_at(index),
+        ReflectRef::TupleStruct(value) => value.field(index),
+        _ => None,
+    }
+}
+
+pub(crate) fn field_mut(value: &mut dyn Reflect, index: usize) -> Option<&mut dyn Reflect> {
+    match value.reflect_mut() {
+        ReflectMut::Struct(value) => value.field_at_mut(index),
+        ReflectMut::TupleStruct(value) => value.field_mut(index),
+        _ => None,
+    }
+}
+
+pub(crate) fn reflect_eq(a: Option<&dyn Reflect>, b: Option<&dyn Reflect>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a
+            .reflect_partial_eq(b)
+            .unwrap_or_else(|| format!("{a:?}") == format!("{b:?}")),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+/// Copies `value` over `target`, replacing it rather than applying it where the type can be built
+/// from reflection, so that lists and maps shrink too.
+pub(crate) fn copy_value(
+    target: &mut dyn Reflect,
+    value: &dyn Reflect,
+    type_registry: &TypeRegistry,
+) {
+    let owned = type_registry
+        .get_type_data::<ReflectFromReflect>(value.type_id())
+        .and_then(|from_reflect| from_reflect.from_reflect(value))
+        .unwrap_or_else(|| value.clone_value());
+    if let Err(owned) = target.set(owned) {
+        target.apply(owned.as_ref());
+    }
+}
+
+/// Shows the components all `entities` have, editing the first entity and copying every edited
+/// field to the others. Fields whose values differ between the entities are marked as mixed.
+pub fn ui_for_entities_shared_components(
+    world: &mut World,
+    entities: &[Entity],
+    ui: &mut egui::Ui,
+) {
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+
+    let Some((&first, others)) = entities.split_first() else {
+        return;
+    };
+    let Some(mut components) = components_of_entity(world, first) else {
+        ui.label(format!("Entity {first:?} does not exist"));
+        return;
+    };
+    components.retain(|component| {
+        component.type_id.is_some_and(|type_id| {
+            others.iter().all(|&entity| {
+                world
+                    .get_entity(entity)
+                    .is_some_and(|entity| entity.contains_type_id(type_id))
+            })
+        })
+    });
+    ui.label(format!("{} entities", entities.len()));
+
+    let mut queue = CommandQueue::default();
+    let id = egui::Id::new("shared_components");
+    for ComponentInfo {
+        name,
+        type_id,
+        size,
+    } in components
+    {
+        let id = id.with(&name);
+        let Some(type_id) = type_id else {
+            continue;
+        };
+        let Some(reflect_component) = type_registry
+            .get_type_data::<ReflectComponent>(type_id)
+            .cloned()
+        else {
+            ui.label(format!("{name} isn't reflected"));
+            continue;
+        };
+
+        let values: Vec<_> = entities
+            .iter()
+            .filter_map(|&entity| reflect_component.reflect(world.entity(entity)))
+            .collect();
+        let Some((&first_value, other_values)) = values.split_first() else {
+            continue;
+        };
+        // a custom widget shows the component as a whole
+        let names = match type_registry.get_type_data::<InspectorEguiImpl>(type_id) {
+            Some(_) => Vec::new(),
+            None => field_names(first_value),
+        };
+        let mixed = other_values
+            .iter()
+            .any(|&value| !reflect_eq(Some(first_value), Some(value)));
+        let mixed_fields: Vec<_> = (0..names.len())
+            .map(|index| {
+                other_values
+                    .iter()
+                    .any(|&value| !reflect_eq(field(first_value, index), field(value, index)))
+            })
+            .collect();
+
+        let mut edit = None;
+        CollapsingState::load_with_default_open(ui.ctx(), id, false)
+            .show_header(ui, |ui| {
+                ui.label(&name);
+                if mixed {
+                    ui.weak("(mixed)")
+                        .on_hover_text("The selected entities have different values");
+                }
+            })
+            .body(|ui| {
+                if size == 0 {
+                    return;
+                }
+                let mut world_view = RestrictedWorldView::new(world);
+                let (mut component_view, world_view) =
+                    world_view.split_off_component((first, type_id));
+                let mut cx = Context {
+                    world: Some(world_view),
+                    queue: Some(&mut queue),
+                };
+                let (value, _, set_changed) =
+                    match component_view.get_entity_component_reflect(first, type_id, &type_registry)
+                    {
+                        Ok(value) => value,
+                        Err(e) => {
+                            ui.label(format!("{name}: {e:?}"));
+                            return;
+                        }
+                    };
+                let mut env = InspectorUi::for_bevy(&type_registry, &mut cx);
+
+                if names.is_empty() {
+                    if env.ui_for_reflect_with_options(value, ui, id, &()) {
+                        edit = Some(SharedEdit::Whole);
+                    }
+                } else {
+                    let options = type_registry.get_type_data::<ReflectInspectorOptions>(type_id);
+                    egui::Grid::new(id).show(ui, |ui| {
+                        for (index, (field_name, mixed)) in names.iter().zip(mixed_fields).enumerate()
+                        {
+                            ui.horizontal(|ui| {
+                                ui.label(field_name);
+                                if mixed {
+                                    ui.weak("≠").on_hover_text(
+                                        "The selected entities have different values, editing sets them all",
+                                    );
+                                }
+                            });
+                            let options = options
+                                .and_then(|options| options.0.get(Target::Field(index)))
+                                .unwrap_or(&());
+                            let field = field_mut(value, index).unwrap();
+                            if env.ui_for_reflect_with_options(field, ui, id.with(index), options)
+                            {
+                                edit = Some(SharedEdit::Field(index));
+                            }
+                            ui.end_row();
+                        }
+                    });
+                }
+                if edit.is_some() {
+                    set_changed();
+                }
+            });
+
+        let Some(edit) = edit else {
+            continue;
+        };
+        let first_value = reflect_component.reflect(world.entity(first)).unwrap();
+        let value = match edit {
+            SharedEdit::Whole => Some(first_value),
+            SharedEdit::Field(index) => field(first_value, index),
+        };
+        let Some(value) = value.map(Reflect::clone_value) else {
+            continue;
+        };
+        for &entity in others {
+            let mut entity_mut = world.entity_mut(entity);
+            let Some(mut component) = reflect_component.reflect_mut(&mut entity_mut) else {
+                continue;
+            };
+            let target = match edit {
+                SharedEdit::Whole => Some(&mut *component),
+                SharedEdit::Field(index) => field_mut(&mut *component, index),
+            };
+            if let Some(target) = target {
+                copy_value(target, value.as_ref(), &type_registry);
+            }
+        }
+    }
+
+    queue.apply(world);
+}
@@ -0,0 +1,349 @@
+use std::{fmt, fs, io, path::Path};
+
+use bevy::{app::AppExit, prelude::*};
+use egui_dock::{Node, NodeIndex, Split, TabIndex, Tree};
+use serde::{Deserialize, Serialize};
+
+use crate::{tabs::EditorTabs, EguiWindow, UiState};
+
+pub const LAYOUT_PATH: &str = "editor_layout.ron";
+/// Where the named layouts are kept, next to the settings file.
+pub const PRESETS_PATH: &str = "editor_layouts.ron";
+
+pub struct EditorLayoutPlugin;
+impl Plugin for EditorLayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LayoutPresets::load())
+            .add_systems(Startup, restore_layout)
+            .add_systems(Last, save_layout_on_exit);
+    }
+}
+
+#[derive(Debug)]
+pub enum LayoutFileError {
+    Io(io::Error),
+    Ron(ron::Error),
+}
+
+impl fmt::Display for LayoutFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutFileError::Io(e) => write!(f, "io error: {e}"),
+            LayoutFileError::Ron(e) => write!(f, "ron error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LayoutFileError {}
+
+impl From<io::Error> for LayoutFileError {
+    fn from(e: io::Error) -> Self {
+        LayoutFileError::Io(e)
+    }
+}
+
+impl From<ron::Error> for LayoutFileError {
+    fn from(e: ron::Error) -> Self {
+        LayoutFileError::Ron(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for LayoutFileError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        LayoutFileError::Ron(e.code)
+    }
+}
+
+/// Serializable mirror of a [`Tree`], which only stores what is needed to rebuild it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum LayoutNode {
+    Leaf {
+        tabs: Vec<EguiWindow>,
+        active: usize,
+    },
+    Horizontal {
+        fraction: f32,
+        left: Box<LayoutNode>,
+        right: Box<LayoutNode>,
+    },
+    Vertical {
+        fraction: f32,
+        top: Box<LayoutNode>,
+        bottom: Box<LayoutNode>,
+    },
+}
+
+fn layout_node(nodes: &[&Node<EguiWindow>], index: NodeIndex) -> Option<LayoutNode> {
+    let children = || {
+        (
+            layout_node(nodes, index.left()).map(Box::new),
+            layout_node(nodes, index.right()).map(Box::new),
+        )
+    };
+    match nodes.get(index.0)? {
+        Node::Empty => None,
+        Node::Leaf { tabs, active, .. } => (!tabs.is_empty()).then(|| LayoutNode::Leaf {
+            tabs: tabs.clone(),
+            active: active.0,
+        }),
+        // a parent with a single child collapses into that child
+        Node::Horizontal { fraction, .. } => match children() {
+            (Some(left), Some(right)) => Some(LayoutNode::Horizontal {
+                fraction: *fraction,
+                left,
+                right,
+            }),
+            (Some(child), None) | (None, Some(child)) => Some(*child),
+            (None, None) => None,
+        },
+        Node::Vertical { fraction, .. } => match children() {
+            (Some(top), Some(bottom)) => Some(LayoutNode::Vertical {
+                fraction: *fraction,
+                top,
+                bottom,
+            }),
+            (Some(child), None) | (None, Some(child)) => Some(*child),
+            (None, None) => None,
+        },
+    }
+}
+
+fn build_node(tree: &mut Tree<EguiWindow>, index: NodeIndex, layout: LayoutNode) {
+    match layout {
+        LayoutNode::Leaf { tabs, active } => {
+            let active = TabIndex(active.min(tabs.len().saturating_sub(1)));
+            tree[index] = Node::leaf_with(tabs);
+            if let Node::Leaf {
+                active: current, ..
+            } = &mut tree[index]
+            {
+                *current = active;
+            }
+        }
+        LayoutNode::Horizontal {
+            fraction,
+            left,
+            right,
+        } => {
+            let [left_index, right_index] =
+                tree.split(index, Split::Right, fraction, Node::leaf_with(Vec::new()));
+            build_node(tree, left_index, *left);
+            build_node(tree, right_index, *right);
+        }
+        LayoutNode::Vertical {
+            fraction,
+            top,
+            bottom,
+        } => {
+            let [top_index, bottom_index] =
+                tree.split(index, Split::Below, fraction, Node::leaf_with(Vec::new()));
+            build_node(tree, top_index, *top);
+            build_node(tree, bottom_index, *bottom);
+        }
+    }
+}
+
+fn layout(tree: &Tree<EguiWindow>) -> Option<LayoutNode> {
+    let nodes: Vec<_> = tree.iter().collect();
+    layout_node(&nodes, NodeIndex::root())
+}
+
+fn build_tree(layout: LayoutNode) -> Tree<EguiWindow> {
+    let mut tree = Tree::new(Vec::new());
+    build_node(&mut tree, NodeIndex::root(), layout);
+    tree
+}
+
+pub(crate) fn save_layout(
+    tree: &Tree<EguiWindow>,
+    path: impl AsRef<Path>,
+) -> Result<(), LayoutFileError> {
+    let Some(layout) = layout(tree) else {
+        return Ok(());
+    };
+    let ron = ron::ser::to_string_pretty(&layout, ron::ser::PrettyConfig::default())?;
+    fs::write(path, ron)?;
+    Ok(())
+}
+
+pub(crate) fn load_layout(path: impl AsRef<Path>) -> Result<Tree<EguiWindow>, LayoutFileError> {
+    let ron = fs::read_to_string(path)?;
+    let layout: LayoutNode = ron::from_str(&ron)?;
+    Ok(build_tree(layout))
+}
+
+fn restore_layout(mut ui_state: ResMut<UiState>, tabs: Res<EditorTabs>) {
+    if !Path::new(LAYOUT_PATH).exists() {
+        return;
+    }
+    match load_layout(LAYOUT_PATH) {
+        Ok(tree) => apply_layout(&mut ui_state, &tabs, tree),
+        Err(e) => warn!("Failed to restore editor layout from {LAYOUT_PATH}: {e}"),
+    }
+}
+
+fn apply_layout(ui_state: &mut UiState, tabs: &EditorTabs, mut tree: Tree<EguiWindow>) {
+    // drop tabs of plugins which are no longer registered
+    for node in tree.iter_mut() {
+        if let Node::Leaf {
+            tabs: windows,
+            active,
+            ..
+        } = node
+        {
+            windows.retain(|window| match window {
+                EguiWindow::Custom(name) => tabs.names().any(|registered| registered == name),
+                // the cameras of additional game views and what inspectors are pinned to aren't
+                // saved
+                EguiWindow::View(_) | EguiWindow::Axis(_) | EguiWindow::PinnedInspector(_) => false,
+                _ => true,
+            });
+            active.0 = active.0.min(windows.len().saturating_sub(1));
+        }
+    }
+    for window in tree.tabs() {
+        if let EguiWindow::Custom(name) = window {
+            ui_state.docked_tabs.insert(name.clone());
+        }
+    }
+    ui_state.set_tree(tree);
+}
+
+fn save_layout_on_exit(ui_state: Res<UiState>, mut exit: EventReader<AppExit>) {
+    if exit.iter().last().is_none() {
+        return;
+    }
+    match save_layout(&ui_state.tree, LAYOUT_PATH) {
+        Ok(()) => info!("Saved editor layout to {LAYOUT_PATH}"),
+        Err(e) => error!("Failed to save editor layout to {LAYOUT_PATH}: {e}"),
+    }
+}
+
+/// Layouts saved under a name, to switch between from the view menu.
+#[derive(Resource, Default)]
+pub struct LayoutPresets {
+    presets: Vec<(String, LayoutNode)>,
+    /// The name typed in the menu to save the current layout as.
+    new_name: String,
+}
+
+impl LayoutPresets {
+    fn load() -> Self {
+        if !Path::new(PRESETS_PATH).exists() {
+            return Self::default();
+        }
+        let presets = fs::read_to_string(PRESETS_PATH)
+            .map_err(LayoutFileError::from)
+            .and_then(|ron| Ok(ron::from_str(&ron)?));
+        match presets {
+            Ok(presets) => Self {
+                presets,
+                new_name: String::new(),
+            },
+            Err(e) => {
+                warn!("Failed to load layout presets from {PRESETS_PATH}: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) {
+        let result = ron::ser::to_string_pretty(&self.presets, ron::ser::PrettyConfig::default())
+            .map_err(LayoutFileError::from)
+            .and_then(|ron| Ok(fs::write(PRESETS_PATH, ron)?));
+        if let Err(e) = result {
+            error!("Failed to save layout presets to {PRESETS_PATH}: {e}");
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.presets.iter().map(|(name, _)| name.as_str())
+    }
+
+    fn tree(&self, name: &str) -> Option<Tree<EguiWindow>> {
+        let (_, layout) = self.presets.iter().find(|(preset, _)| preset == name)?;
+        Some(build_tree(layout.clone()))
+    }
+
+    /// Saves `tree` as the preset `name`, replacing the preset already named so.
+    pub(crate) fn insert(&mut self, name: String, tree: &Tree<EguiWindow>) {
+        let Some(layout) = layout(tree) else {
+            return;
+        };
+        match self.presets.iter_mut().find(|(preset, _)| *preset == name) {
+            Some((_, preset)) => *preset = layout,
+            None => self.presets.push((name, layout)),
+        }
+        self.save();
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.presets.retain(|(preset, _)| preset != name);
+        self.save();
+    }
+}
+
+/// Switches the dock to the layout saved as `name`.
+/// Loads the presets again, from the working directory of the project opened since.
+pub fn reload_presets(world: &mut World) {
+    world.insert_resource(LayoutPresets::load());
+}
+
+pub fn apply_preset(world: &mut World, name: &str) {
+    let Some(tree) = world.resource::<LayoutPresets>().tree(name) else {
+        warn!("No layout preset is named {name}");
+        return;
+    };
+    world.resource_scope::<UiState, _>(|world, mut ui_state| {
+        apply_layout(&mut ui_state, world.resource::<EditorTabs>(), tree);
+    });
+}
+
+/// Lists the layout presets to switch to or delete, and saves the current layout as a new one.
+pub(crate) fn presets_menu(world: &mut World, ui: &mut egui::Ui, ui_state: &mut UiState) {
+    let mut presets = world.resource_mut::<LayoutPresets>();
+    let mut apply = None;
+    let mut remove = None;
+    if presets.presets.is_empty() {
+        ui.weak("No saved layouts");
+    }
+    for name in presets.names() {
+        ui.horizontal(|ui| {
+            if ui.button(name).clicked() {
+                apply = Some(name.to_owned());
+            }
+            if ui
+                .small_button("🗑")
+                .on_hover_text("Delete preset")
+                .clicked()
+            {
+                remove = Some(name.to_owned());
+            }
+        });
+    }
+    if let Some(name) = remove {
+        presets.remove(&name);
+    }
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut presets.new_name)
+                .hint_text("Level design")
+                .desired_width(120.0),
+        );
+        let name = presets.new_name.trim().to_owned();
+        if ui
+            .add_enabled(!name.is_empty(), egui::Button::new("Save Current"))
+            .clicked()
+        {
+            presets.insert(name, &ui_state.tree);
+            presets.new_name.clear();
+            ui.close_menu();
+        }
+    });
+
+    if let Some(tree) = apply.and_then(|name| presets.tree(&name)) {
+        apply_layout(ui_state, world.resource::<EditorTabs>(), tree);
+        ui.close_menu();
+    }
+}
@@ -1,26 +1,144 @@
-use std::any::TypeId;
+use std::{
+    any::TypeId,
+    collections::{HashMap, HashSet},
+};
 
+use animation_graph::EditorAnimationGraphPlugin;
+use archetypes::EditorArchetypesPlugin;
+use asset_browser::{AssetBrowser, EditorAssetBrowserPlugin};
+use asset_preview::EditorAssetPreviewPlugin;
+use autosave::EditorAutosavePlugin;
 use bevy::{
     asset::{HandleId, ReflectAsset},
     prelude::*,
-    render::camera::{CameraProjection, Viewport},
+    render::camera::Viewport,
     window::PrimaryWindow,
 };
 use bevy_inspector_egui::{
     bevy_egui::{self, EguiContext, EguiSet},
-    bevy_inspector::{
-        self,
-        hierarchy::{hierarchy_ui, SelectedEntities},
-        ui_for_entities_shared_components, ui_for_entity_with_children,
-    },
+    bevy_inspector::{self, hierarchy::SelectedEntities},
     DefaultInspectorConfigPlugin,
 };
 use bevy_reflect::TypeRegistry;
-use egui_dock::{DockArea, NodeIndex, Style, Tree};
-use egui_gizmo::{Gizmo, GizmoMode, GizmoOrientation};
-use input::EditorInputPlugin;
+use camera::{EditorCameraController, EditorCameraPlugin};
+use clipboard::EditorClipboardPlugin;
+use commands::{CommandPalette, EditorCommands, EditorCommandsPlugin};
+use curve::EditorCurvePlugin;
+use debug_view::EditorDebugViewPlugin;
+use editor_id::EditorIdPlugin;
+use egui_dock::{DockArea, Node, NodeIndex, Style, TabDestination, Tree};
+use egui_gizmo::{GizmoMode, GizmoOrientation};
+use frustum::EditorFrustumPlugin;
+use gamepad::EditorGamepadPlugin;
+use gizmo::GizmoPivot;
+use gltf_export::{EditorGltfExportPlugin, GltfExport};
+use grid::EditorGridPlugin;
+use hierarchy::{EditorLocked, HierarchyState};
+use history::EditorHistoryPlugin;
+use hot_reload::EditorHotReloadPlugin;
+use input::{EditorAction, EditorInputCapture, EditorInputPlugin, EditorKeybinds};
+use layout::EditorLayoutPlugin;
+use lights::EditorLightsPlugin;
+use macros::EditorMacrosPlugin;
+use measure::EditorMeasurePlugin;
+use mode2d::EditorMode2dPlugin;
+use navigation::{EditorNavigationPlugin, SelectionHistory};
+use nested_scene::EditorNestedScenePlugin;
+use outline::EditorOutlinePlugin;
+use particles::EditorParticlesPlugin;
+use play::EditorPlayPlugin;
+use prefab::EditorPrefabPlugin;
+use preview::EditorPreviewPlugin;
+use profiler::EditorProfilerPlugin;
+use project::{EditorProjectPlugin, Project};
+use query::EditorQueryPlugin;
+use recovery::EditorRecoveryPlugin;
+use replay::EditorReplayPlugin;
+use scene_diff::EditorSceneDiffPlugin;
+use schedules::EditorSchedulesPlugin;
+use screenshot::EditorScreenshotPlugin;
+use select_by_component::{EditorSelectByComponentPlugin, SelectByComponent};
+use settings::{EditorSettings, EditorSettingsPlugin};
+use shaders::EditorShadersPlugin;
+use snap::EditorSnapPlugin;
+use states::EditorStatesPlugin;
+use stats::EditorStatsPlugin;
+use tabs::EditorTabs;
+use thumbnails::EditorThumbnailsPlugin;
+use timeline::EditorTimelinePlugin;
+use validation::EditorValidationPlugin;
+use views::{AxisView, EditorView, EditorViewport, EditorViewsPlugin};
+use wireframe::EditorWireframePlugin;
 
+pub mod animation_graph;
+pub mod archetypes;
+pub mod asset_browser;
+pub mod asset_preview;
+pub mod asset_usages;
+pub mod autosave;
+pub mod camera;
+pub mod clipboard;
+#[cfg(feature = "remote")]
+pub mod collab;
+pub mod colliders;
+pub mod commands;
+pub mod console;
+pub mod curve;
+pub mod debug_view;
+pub mod editor_id;
+pub mod frustum;
+pub mod gamepad;
+pub mod git_status;
+pub mod gizmo;
+pub mod gltf_export;
+pub mod grid;
+pub mod hierarchy;
+pub mod history;
+pub mod hot_reload;
 pub mod input;
+pub mod inspector;
+pub mod layout;
+pub mod lights;
+pub mod macros;
+pub mod measure;
+pub mod mode2d;
+pub mod navigation;
+pub mod nested_scene;
+pub mod orientation;
+pub mod outline;
+pub mod particles;
+pub mod picking;
+pub mod play;
+pub mod prefab;
+pub mod preview;
+pub mod profiler;
+pub mod project;
+pub mod query;
+pub mod recovery;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "remote")]
+pub mod remote_client;
+pub mod replay;
+pub mod scene;
+pub mod scene_diff;
+pub mod schedules;
+pub mod screenshot;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod select_by_component;
+pub mod settings;
+pub mod shaders;
+pub mod snap;
+pub mod states;
+pub mod stats;
+pub mod tabs;
+pub mod thumbnails;
+pub mod timeline;
+pub mod validation;
+pub mod views;
+pub mod widgets;
+pub mod wireframe;
 
 pub struct EditorPlugin;
 
@@ -31,6 +149,55 @@ impl Plugin for EditorPlugin {
             .add_plugins(DefaultInspectorConfigPlugin)
             .add_plugins(bevy_egui::EguiPlugin)
             .add_plugins(EditorInputPlugin)
+            .add_plugins(EditorHistoryPlugin)
+            .add_plugins(EditorIdPlugin)
+            .add_plugins(EditorCameraPlugin)
+            .add_plugins(EditorProjectPlugin)
+            .add_plugins(EditorSettingsPlugin)
+            .add_plugins(EditorPlayPlugin)
+            .add_plugins(EditorReplayPlugin)
+            .add_plugins(EditorLayoutPlugin)
+            .add_plugins(EditorCommandsPlugin)
+            .add_plugins(EditorMacrosPlugin)
+            .add_plugins(EditorStatsPlugin)
+            .add_plugins(EditorClipboardPlugin)
+            .add_plugins(EditorPrefabPlugin)
+            .add_plugins(EditorNestedScenePlugin)
+            .add_plugins(EditorAutosavePlugin)
+            .add_plugins(EditorRecoveryPlugin)
+            .add_plugins(EditorOutlinePlugin)
+            .add_plugins(EditorGridPlugin)
+            .add_plugins(EditorViewsPlugin)
+            .add_plugins(EditorWireframePlugin)
+            .add_plugins(EditorDebugViewPlugin)
+            .add_plugins(EditorPreviewPlugin)
+            .add_plugins(EditorFrustumPlugin)
+            .add_plugins(EditorLightsPlugin)
+            .add_plugins(EditorScreenshotPlugin)
+            .add_plugins(EditorMode2dPlugin)
+            .add_plugins(EditorSnapPlugin)
+            .add_plugins(EditorMeasurePlugin)
+            .add_plugins(EditorAssetBrowserPlugin)
+            .add_plugins(EditorThumbnailsPlugin)
+            .add_plugins(EditorAssetPreviewPlugin)
+            .add_plugins(EditorShadersPlugin)
+            .add_plugins(EditorTimelinePlugin)
+            .add_plugins(EditorAnimationGraphPlugin)
+            .add_plugins(EditorCurvePlugin)
+            .add_plugins(EditorParticlesPlugin)
+            .add_plugins(EditorStatesPlugin)
+            .add_plugins(EditorSchedulesPlugin)
+            .add_plugins(EditorProfilerPlugin)
+            .add_plugins(EditorArchetypesPlugin)
+            .add_plugins(EditorQueryPlugin)
+            .add_plugins(EditorNavigationPlugin)
+            .add_plugins(EditorGamepadPlugin)
+            .add_plugins(EditorSelectByComponentPlugin)
+            .add_plugins(EditorGltfExportPlugin)
+            .add_plugins(EditorSceneDiffPlugin)
+            .add_plugins(EditorValidationPlugin)
+            .add_plugins(EditorHotReloadPlugin)
+            .init_resource::<EditorTabs>()
             .insert_resource(UiState::new())
             .add_systems(PostStartup, setup)
             .add_systems(
@@ -40,7 +207,12 @@ impl Plugin for EditorPlugin {
                     .before(bevy::transform::TransformSystem::TransformPropagate),
             )
             .add_systems(PostUpdate, set_camera_viewport.after(show_ui))
-            .add_systems(Update, set_gizmo_mode);
+            .add_systems(Update, (set_gizmo_mode, delete_selected));
+        #[cfg(feature = "remote")]
+        app.add_plugins(remote::EditorRemotePlugin)
+            .add_plugins(collab::EditorCollabPlugin);
+        #[cfg(feature = "scripting")]
+        app.add_plugins(script::EditorScriptPlugin);
     }
 }
 
@@ -52,18 +224,37 @@ pub struct EditorResource(pub bool);
 #[reflect(Component)]
 pub struct MainCamera;
 
+/// Marks the entities the editor spawns for itself, like the cameras of its views and previews
+/// and the studio rendering asset previews. They live in the game world, so game systems which
+/// shouldn't see them leave them out of their queries:
+///
+/// ```ignore
+/// fn count_cameras(cameras: Query<&Camera, Without<editor::EditorOnly>>) {}
+/// ```
+#[derive(Debug, Component, Default)]
+pub struct EditorOnly;
+
 fn setup(
     mut commands: Commands,
-    query: Query<Entity, With<Camera>>,
+    query: Query<(Entity, Option<&Transform>), With<Camera>>,
     mut app_exit_events: EventWriter<bevy::app::AppExit>,
 ) {
-    let Ok(camera) = query.get_single() else {
+    let Ok((camera, transform)) = query.get_single() else {
         error!("No Camera found, change that");
         app_exit_events.send(bevy::app::AppExit);
         return;
     };
 
-    commands.entity(camera).insert(MainCamera);
+    let transform = transform.copied().unwrap_or_default();
+    commands.entity(camera).insert((
+        MainCamera,
+        EditorCameraController {
+            focus: transform.translation
+                + transform.forward() * transform.translation.length().max(1.0),
+            ..default()
+        },
+        EditorViewport::default(),
+    ));
     commands.insert_resource(EditorResource(false));
 }
 
@@ -72,7 +263,10 @@ fn show_ui(world: &mut World) {
         return;
     }
 
-    let Ok(egui_context) = world.query_filtered::<&mut EguiContext, With<PrimaryWindow>>().get_single(world) else {
+    let Ok(egui_context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single(world)
+    else {
         return;
     };
     let mut egui_context = egui_context.clone();
@@ -83,10 +277,9 @@ fn show_ui(world: &mut World) {
 }
 
 fn set_camera_viewport(
-    ui_state: Res<UiState>,
     primary_window: Query<&Window, With<PrimaryWindow>>,
     egui_settings: Res<bevy_egui::EguiSettings>,
-    mut cameras: Query<&mut Camera, With<MainCamera>>,
+    mut cameras: Query<(&mut Camera, &EditorViewport, Option<&EditorView>)>,
     ed: Res<EditorResource>,
 ) {
     let Ok(window) = primary_window.get_single() else {
@@ -95,101 +288,870 @@ fn set_camera_viewport(
 
     let scale_factor = window.scale_factor() * egui_settings.scale_factor;
 
-    let viewport_pos = ui_state.viewport_rect.left_top().to_vec2() * scale_factor as f32;
-    let viewport_size = ui_state.viewport_rect.size() * scale_factor as f32;
+    for (mut cam, viewport, view) in &mut cameras {
+        // additional views only render while their tab is shown
+        if view.is_some() {
+            cam.is_active = ed.0 && viewport.rect.is_positive();
+        }
 
-    if let Ok(mut cam) = cameras.get_single_mut() {
-        if ed.0 {
+        if !ed.0 {
             cam.viewport = Some(Viewport {
-                physical_position: UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32),
-                physical_size: UVec2::new(viewport_size.x as u32, viewport_size.y as u32),
+                physical_position: UVec2 { x: 0, y: 0 },
+                physical_size: UVec2::new(window.physical_width(), window.physical_height()),
                 depth: 0.0..1.0,
             });
-        } else {
+        } else if viewport.rect.is_positive() {
+            let viewport_pos = viewport.rect.left_top().to_vec2() * scale_factor as f32;
+            let viewport_size = viewport.rect.size() * scale_factor as f32;
             cam.viewport = Some(Viewport {
-                physical_position: UVec2 { x: 0, y: 0 },
-                physical_size: UVec2::new(window.physical_width(), window.physical_height()),
+                physical_position: UVec2::new(viewport_pos.x as u32, viewport_pos.y as u32),
+                physical_size: UVec2::new(viewport_size.x as u32, viewport_size.y as u32),
                 depth: 0.0..1.0,
-            })
+            });
         }
     }
 }
 
-fn set_gizmo_mode(input: Res<Input<KeyCode>>, mut ui_state: ResMut<UiState>) {
-    for (key, mode) in [
-        (KeyCode::R, GizmoMode::Rotate),
-        (KeyCode::T, GizmoMode::Translate),
-        (KeyCode::S, GizmoMode::Scale),
+fn set_gizmo_mode(
+    actions: Res<Input<EditorAction>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    ed: Res<EditorResource>,
+    capture: Res<EditorInputCapture>,
+    mut ui_state: ResMut<UiState>,
+) {
+    // WASD is used for flying while the right mouse button is held
+    if !ed.0 || mouse_buttons.pressed(MouseButton::Right) {
+        return;
+    }
+    // R, T and S are left to the other panels, like a search box
+    let view_focused = ui_state
+        .tree
+        .find_active_focused()
+        .is_some_and(|(_, window)| {
+            matches!(
+                window,
+                EguiWindow::GameView | EguiWindow::View(_) | EguiWindow::Axis(_)
+            )
+        });
+    if !capture.view_hovered && !view_focused {
+        return;
+    }
+    for (action, mode) in [
+        (EditorAction::RotateGizmo, GizmoMode::Rotate),
+        (EditorAction::TranslateGizmo, GizmoMode::Translate),
+        (EditorAction::ScaleGizmo, GizmoMode::Scale),
     ] {
-        if input.just_pressed(key) {
+        if actions.just_pressed(action) {
             ui_state.gizmo_mode = mode;
         }
     }
+    if actions.just_pressed(EditorAction::ToggleGizmoOrientation) {
+        ui_state.gizmo_orientation = match ui_state.gizmo_orientation {
+            GizmoOrientation::Local => GizmoOrientation::Global,
+            GizmoOrientation::Global => GizmoOrientation::Local,
+        };
+    }
+}
+
+fn delete_selected(
+    actions: Res<Input<EditorAction>>,
+    mut ui_state: ResMut<UiState>,
+    ed: Res<EditorResource>,
+) {
+    if !ed.0 {
+        return;
+    }
+    if actions.just_pressed(EditorAction::DeleteSelected) && !ui_state.selected_entities.is_empty()
+    {
+        ui_state.pending_delete = Some(ui_state.selected_entities.as_slice().to_vec());
+    }
 }
 
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 enum InspectorSelection {
     Entities,
     Resource(TypeId, String),
     Asset(TypeId, String, HandleId),
 }
 
+/// What an inspector tab opened with the pin button keeps showing, whatever gets selected.
+struct PinnedInspector {
+    selection: InspectorSelection,
+    entities: Vec<Entity>,
+}
+
+impl PinnedInspector {
+    fn label(&self, world: &World) -> String {
+        match (&self.selection, self.entities.as_slice()) {
+            (InspectorSelection::Entities, &[entity]) => inspector::entity_name(world, entity),
+            (InspectorSelection::Entities, entities) => format!("{} entities", entities.len()),
+            (InspectorSelection::Resource(_, name), _) => name.clone(),
+            (InspectorSelection::Asset(_, name, handle), _) => world
+                .get_resource::<AssetServer>()
+                .and_then(|asset_server| asset_label(asset_server, *handle))
+                .unwrap_or_else(|| name.clone()),
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct UiState {
     tree: Tree<EguiWindow>,
-    viewport_rect: egui::Rect,
     selected_entities: SelectedEntities,
     selection: InspectorSelection,
     gizmo_mode: GizmoMode,
+    gizmo_orientation: GizmoOrientation,
+    gizmo_pivot: GizmoPivot,
+    scene_path: String,
+    pending_delete: Option<Vec<Entity>>,
+    pub confirm_delete: bool,
+    delete_dont_ask: bool,
+    settings_open: bool,
+    docked_tabs: HashSet<String>,
+    hierarchy: HierarchyState,
+    /// Entity under the pointer in the game view.
+    hovered_entity: Option<Entity>,
+    /// Whether the game views look at the XY plane orthographically, with the gizmo confined to
+    /// it.
+    pub mode_2d: bool,
+    pinned_inspectors: HashMap<u32, PinnedInspector>,
+    /// Requested by the pin button of the inspector, opened once the tabs are shown.
+    new_pin: Option<PinnedInspector>,
+    navigation: SelectionHistory,
+    /// Tabs taken out of the dock, shown in windows of their own.
+    floating: Vec<EguiWindow>,
+    /// Tabs closed by the user, most recently closed last, to reopen them.
+    closed: Vec<EguiWindow>,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl UiState {
     pub fn new() -> Self {
-        let mut tree = Tree::new(vec![EguiWindow::GameView]);
-        let [game, _inspector] =
-            tree.split_right(NodeIndex::root(), 0.75, vec![EguiWindow::Inspector]);
-        let [game, _hierarchy] = tree.split_left(game, 0.2, vec![EguiWindow::Hierarchy]);
-        let [_game, _bottom] =
-            tree.split_below(game, 0.8, vec![EguiWindow::Resources, EguiWindow::Assets]);
-
         Self {
-            tree,
+            tree: Self::default_tree(),
             selected_entities: SelectedEntities::default(),
             selection: InspectorSelection::Entities,
-            viewport_rect: egui::Rect::NOTHING,
             gizmo_mode: GizmoMode::Translate,
+            gizmo_orientation: GizmoOrientation::Local,
+            gizmo_pivot: GizmoPivot::Center,
+            scene_path: "assets/scenes/scene.scn.ron".to_owned(),
+            pending_delete: None,
+            confirm_delete: true,
+            delete_dont_ask: false,
+            settings_open: false,
+            docked_tabs: HashSet::new(),
+            hierarchy: HierarchyState::default(),
+            hovered_entity: None,
+            mode_2d: false,
+            pinned_inspectors: HashMap::new(),
+            new_pin: None,
+            navigation: SelectionHistory::default(),
+            floating: Vec::new(),
+            closed: Vec::new(),
         }
     }
 
+    fn default_tree() -> Tree<EguiWindow> {
+        let mut tree = Tree::new(vec![EguiWindow::GameView]);
+        let [game, _inspector] =
+            tree.split_right(NodeIndex::root(), 0.75, vec![EguiWindow::Inspector]);
+        let [game, _hierarchy] = tree.split_left(game, 0.2, vec![EguiWindow::Hierarchy]);
+        let [_game, _bottom] = tree.split_below(
+            game,
+            0.8,
+            vec![
+                EguiWindow::Resources,
+                EguiWindow::Assets,
+                EguiWindow::AssetBrowser,
+                EguiWindow::Prefabs,
+                EguiWindow::Console,
+                EguiWindow::Stats,
+            ],
+        );
+        tree
+    }
+
+    /// Restores the default dock layout, with custom tabs docked again.
+    pub fn reset_layout(&mut self) {
+        self.set_tree(Self::default_tree());
+        self.docked_tabs.clear();
+    }
+
+    /// Replaces the dock layout, docking the floating tabs which are also in `tree`.
+    fn set_tree(&mut self, tree: Tree<EguiWindow>) {
+        self.floating
+            .retain(|window| tree.find_tab(window).is_none());
+        self.tree = tree;
+    }
+
+    /// Whether `window` is docked or floating.
+    fn is_open(&self, window: &EguiWindow) -> bool {
+        self.tree.find_tab(window).is_some() || self.floating.contains(window)
+    }
+
     fn ui(&mut self, world: &mut World, ctx: &mut egui::Context) {
+        self.dock_new_tabs(world);
+
+        egui::TopBottomPanel::top("editor_menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| self.menu_bar(world, ui));
+        });
+        egui::TopBottomPanel::bottom("editor_status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.weak(&self.scene_path);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    autosave::autosave_status_ui(world, ui);
+                });
+            });
+        });
+
+        views::despawn_closed_views(world, self);
+
+        // set again by the game view tabs which are visible
+        self.hovered_entity = None;
+        for mut viewport in world.query::<&mut EditorViewport>().iter_mut(world) {
+            viewport.rect = egui::Rect::NOTHING;
+        }
+        let leaves = self
+            .tree
+            .iter()
+            .enumerate()
+            .filter_map(|(index, node)| match node {
+                Node::Leaf { tabs, active, .. } => Some(DockLeaf {
+                    node: NodeIndex(index),
+                    label: tabs.get(active.0)?.title(),
+                    tabs: tabs.clone(),
+                }),
+                _ => None,
+            })
+            .collect();
         let mut tab_viewer = TabViewer {
             world,
-            viewport_rect: &mut self.viewport_rect,
             selected_entities: &mut self.selected_entities,
             selection: &mut self.selection,
-            gizmo_mode: self.gizmo_mode,
+            pending_delete: &mut self.pending_delete,
+            gizmo_mode: &mut self.gizmo_mode,
+            gizmo_orientation: &mut self.gizmo_orientation,
+            gizmo_pivot: &mut self.gizmo_pivot,
+            hierarchy: &mut self.hierarchy,
+            hovered_entity: &mut self.hovered_entity,
+            mode_2d: &mut self.mode_2d,
+            pinned_inspectors: &mut self.pinned_inspectors,
+            new_pin: &mut self.new_pin,
+            navigation: &mut self.navigation,
+            closed: &mut self.closed,
+            leaves,
+            tab_action: None,
         };
         DockArea::new(&mut self.tree)
             .style(Style::from_egui(ctx.style().as_ref()))
             .show(ctx, &mut tab_viewer);
+        let docked = floating_tabs_ui(ctx, &mut tab_viewer, &mut self.floating);
+        if let Some((window, action)) = tab_viewer.tab_action.take() {
+            self.apply_tab_action(window, action);
+        }
+        if let Some(window) = docked {
+            self.tree.push_to_focused_leaf(window);
+        }
+
+        self.navigation
+            .record(world, &self.selection, &self.selected_entities);
+        let (tree, floating) = (&self.tree, &self.floating);
+        self.pinned_inspectors.retain(|&id, _| {
+            let window = EguiWindow::PinnedInspector(id);
+            tree.find_tab(&window).is_some() || floating.contains(&window)
+        });
+        if let Some(pin) = self.new_pin.take() {
+            let id = (0..)
+                .find(|id| !self.pinned_inspectors.contains_key(id))
+                .unwrap();
+            self.pinned_inspectors.insert(id, pin);
+            self.tree
+                .push_to_focused_leaf(EguiWindow::PinnedInspector(id));
+        }
+
+        if let Some(path) = world.resource_mut::<AssetBrowser>().open_scene.take() {
+            self.scene_path = path;
+            self.load_scene(world);
+        }
+
+        egui::Window::new("Settings")
+            .open(&mut self.settings_open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let path = settings::settings_path(world.get_resource::<Project>());
+                    ui.weak(format!("Saved to {}", path.display()));
+                    if ui.button("Restore defaults").clicked() {
+                        *world.resource_mut::<EditorSettings>() = EditorSettings::default();
+                        *world.resource_mut::<EditorKeybinds>() = EditorKeybinds::default();
+                    }
+                });
+                bevy_inspector::ui_for_resource::<EditorSettings>(world, ui);
+                ui.collapsing("Keybinds", |ui| {
+                    for (a, b) in world.resource::<EditorKeybinds>().conflicts() {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("{a:?} and {b:?} are bound to the same keys"),
+                        );
+                    }
+                    bevy_inspector::ui_for_resource::<EditorKeybinds>(world, ui);
+                });
+            });
+
+        commands::command_palette_ui(world, ctx);
+        self.select_by_component_window(world, ctx);
+        if let Some(project) = project::project_picker_ui(world, ctx) {
+            self.scene_path = project.default_scene.display().to_string();
+            project::open_project(world, project);
+        }
+        if let Some(scene_path) = recovery::recovery_prompt_ui(world, ctx) {
+            self.scene_path = scene_path;
+            self.selected_entities.clear();
+        }
+
+        if let Some(entities) = self.pending_delete.take() {
+            let confirmed = match self.confirm_delete {
+                true => self.delete_dialog(ctx, &entities),
+                false => Some(true),
+            };
+            match confirmed {
+                Some(true) => {
+                    hierarchy::despawn_entities(world, &entities);
+                    self.selected_entities
+                        .retain(|entity| !entities.contains(&entity));
+                }
+                Some(false) => {}
+                None => self.pending_delete = Some(entities),
+            }
+        }
+    }
+}
+
+impl UiState {
+    /// Selects `entities` in place of the selection, or in addition to it with `add`.
+    fn select_entities(&mut self, entities: Vec<Entity>, add: bool) {
+        if !add {
+            self.selected_entities.clear();
+        }
+        for entity in entities {
+            self.selected_entities.select_maybe_add(entity, true);
+        }
+        self.selection = InspectorSelection::Entities;
+    }
+
+    fn select_by_component_window(&mut self, world: &mut World, ctx: &mut egui::Context) {
+        let mut open = world.resource::<SelectByComponent>().open;
+        if !open {
+            return;
+        }
+        let mut picked = None;
+        egui::Window::new("Select by Component")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if let Some(entities) = select_by_component::component_list(ui, world) {
+                    picked = Some((entities, ui.input(|input| input.modifiers.ctrl)));
+                }
+            });
+        if let Some((entities, add)) = picked {
+            self.select_entities(entities, add);
+            open = false;
+        }
+        world.resource_mut::<SelectByComponent>().open = open;
+    }
+
+    fn menu_bar(&mut self, world: &mut World, ui: &mut egui::Ui) {
+        ui.menu_button("File", |ui| {
+            if ui.button("Open Project...").clicked() {
+                world.resource_mut::<project::ProjectPicker>().open = true;
+                ui.close_menu();
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Path");
+                ui.text_edit_singleline(&mut self.scene_path);
+            });
+            ui.separator();
+
+            if ui.button("Save Scene").clicked() {
+                let entities = scene::scene_entities(world);
+                self.save_scene(world, &entities);
+                ui.close_menu();
+            }
+            let has_selection = !self.selected_entities.is_empty();
+            if ui
+                .add_enabled(has_selection, egui::Button::new("Save Selection"))
+                .clicked()
+            {
+                let entities = scene::with_descendants(world, self.selected_entities.as_slice());
+                self.save_scene(world, &entities);
+                ui.close_menu();
+            }
+            if ui.button("Load Scene").clicked() {
+                self.load_scene(world);
+                ui.close_menu();
+            }
+            ui.separator();
+            ui.add_enabled_ui(has_selection, |ui| {
+                ui.menu_button("Export Selection to glTF", |ui| {
+                    let mut export = world.resource_mut::<GltfExport>();
+                    ui.horizontal(|ui| {
+                        ui.label("Path");
+                        ui.text_edit_singleline(&mut export.path)
+                            .on_hover_text("A .glb, or a .gltf written with a .bin next to it");
+                    });
+                    if ui.button("Export").clicked() {
+                        let path = export.path.clone();
+                        gltf_export::export_selection(
+                            world,
+                            self.selected_entities.as_slice(),
+                            &path,
+                        );
+                        ui.close_menu();
+                    }
+                });
+            });
+        });
+        ui.menu_button("Edit", |ui| {
+            let palette = world.resource::<EditorKeybinds>().command_palette;
+            if ui
+                .add(egui::Button::new("Command Palette").shortcut_text(palette.to_string()))
+                .clicked()
+            {
+                world.resource_mut::<CommandPalette>().open = true;
+                ui.close_menu();
+            }
+            ui.menu_button("Select by Component", |ui| {
+                if let Some(entities) = select_by_component::component_list(ui, world) {
+                    let add = ui.input(|input| input.modifiers.ctrl);
+                    self.select_entities(entities, add);
+                    ui.close_menu();
+                }
+            });
+            ui.menu_button("Macros", |ui| macros::macros_menu(world, ui));
+            if ui.button("Settings").clicked() {
+                self.settings_open = true;
+                ui.close_menu();
+            }
+        });
+        ui.menu_button("View", |ui| {
+            let mut settings = world.resource_mut::<EditorSettings>();
+            ui.checkbox(&mut settings.grid.visible, "Grid");
+            ui.checkbox(&mut settings.show_bounds, "Selection Bounds");
+            ui.checkbox(&mut settings.stats_overlay, "Stats Overlay");
+            ui.separator();
+            let screenshot = world.resource::<EditorKeybinds>().screenshot;
+            if ui
+                .add(egui::Button::new("Screenshot").shortcut_text(screenshot.to_string()))
+                .clicked()
+            {
+                // queued so the menu is closed by the time the frame is captured
+                world.resource_mut::<EditorCommands>().queue("Screenshot");
+                ui.close_menu();
+            }
+            ui.separator();
+            ui.menu_button("Layouts", |ui| layout::presets_menu(world, ui, self));
+        });
+        ui.menu_button("Window", |ui| {
+            let custom = world
+                .resource::<EditorTabs>()
+                .names()
+                .map(|name| EguiWindow::Custom(name.to_owned()))
+                .collect::<Vec<_>>();
+            for window in EguiWindow::BUILTIN.into_iter().chain(custom) {
+                let open = self.is_open(&window);
+                if ui.selectable_label(open, window.title()).clicked() {
+                    self.focus_window(window);
+                    ui.close_menu();
+                }
+            }
+            if ui.button("New GameView").clicked() {
+                let id = views::spawn_view(world);
+                self.tree.push_to_focused_leaf(EguiWindow::View(id));
+                ui.close_menu();
+            }
+            for axis in AxisView::ALL {
+                let window = EguiWindow::Axis(axis);
+                let open = self.is_open(&window);
+                if ui
+                    .selectable_label(open, format!("{axis:?} View"))
+                    .clicked()
+                {
+                    views::spawn_axis_view(world, axis);
+                    self.focus_window(window);
+                    ui.close_menu();
+                }
+            }
+            ui.separator();
+            // tabs reopened from this menu meanwhile are skipped
+            let reopen = self.closed.iter().rposition(|window| !self.is_open(window));
+            let reopen_text = match reopen {
+                Some(index) => format!("Reopen {}", self.closed[index].title()),
+                None => "Reopen Closed Tab".to_owned(),
+            };
+            if ui
+                .add_enabled(reopen.is_some(), egui::Button::new(reopen_text))
+                .clicked()
+            {
+                if let Some(index) = reopen {
+                    let window = self.closed.remove(index);
+                    if let EguiWindow::Axis(axis) = window {
+                        views::spawn_axis_view(world, axis);
+                    }
+                    self.focus_window(window);
+                }
+                ui.close_menu();
+            }
+            if ui.button("Reset Layout").clicked() {
+                self.reset_layout();
+                ui.close_menu();
+            }
+        });
+        #[cfg(feature = "remote")]
+        ui.menu_button("Session", |ui| collab::session_menu(world, ui));
+        ui.menu_button("Replay", |ui| replay::replay_menu(world, ui));
+        ui.separator();
+        play::play_controls(world, ui);
+    }
+
+    /// Brings `window` to the front, opening it in the focused node if it was closed. Floating
+    /// windows are always in front of the dock.
+    fn focus_window(&mut self, window: EguiWindow) {
+        match self.tree.find_tab(&window) {
+            Some((node, tab)) => {
+                self.tree.set_active_tab(node, tab);
+                self.tree.set_focused_node(node);
+            }
+            None if self.floating.contains(&window) => {}
+            None => self.tree.push_to_focused_leaf(window),
+        }
+    }
+
+    fn toggle_window(&mut self, window: EguiWindow) {
+        if let Some(index) = self
+            .floating
+            .iter()
+            .position(|floating| *floating == window)
+        {
+            self.floating.remove(index);
+            return;
+        }
+        match self.tree.find_tab(&window) {
+            Some(tab) => {
+                self.tree.remove_tab(tab);
+            }
+            None => self.tree.push_to_focused_leaf(window),
+        }
+    }
+
+    fn apply_tab_action(&mut self, window: EguiWindow, action: TabAction) {
+        // the dock may have changed since the menu was shown
+        let Some(tab) = self.tree.find_tab(&window) else {
+            return;
+        };
+        match action {
+            TabAction::Float => {
+                self.tree.remove_tab(tab);
+                self.floating.push(window);
+            }
+            TabAction::MoveTo(node) => {
+                let is_leaf = self.tree.iter().nth(node.0).is_some_and(Node::is_leaf);
+                if is_leaf && node != tab.0 {
+                    self.tree.move_tab(tab, (node, TabDestination::Append));
+                    self.focus_window(window);
+                }
+            }
+        }
+    }
+
+    /// Docks newly registered custom tabs next to the Resources and Assets tabs. Tabs closed by
+    /// the user stay closed and can be reopened from the Window menu.
+    fn dock_new_tabs(&mut self, world: &World) {
+        for name in world.resource::<EditorTabs>().names() {
+            if !self.docked_tabs.insert(name.to_owned()) {
+                continue;
+            }
+            let window = EguiWindow::Custom(name.to_owned());
+            match self.tree.find_tab(&EguiWindow::Assets) {
+                Some((node, _)) => self.tree[node].append_tab(window),
+                None => self.tree.push_to_first_leaf(window),
+            }
+        }
+    }
+
+    /// Returns `Some(true)` once confirmed, `Some(false)` if cancelled and `None` while open.
+    fn delete_dialog(&mut self, ctx: &egui::Context, entities: &[Entity]) -> Option<bool> {
+        let mut confirmed = None;
+        egui::Window::new("Delete entities")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Delete {} entities and all of their children?",
+                    entities.len()
+                ));
+                ui.checkbox(&mut self.delete_dont_ask, "Don't ask again");
+                ui.horizontal(|ui| {
+                    if ui.button("Delete").clicked() {
+                        confirmed = Some(true);
+                        self.confirm_delete = !self.delete_dont_ask;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        confirmed = Some(false);
+                    }
+                });
+            });
+        confirmed
+    }
+
+    fn load_scene(&mut self, world: &mut World) {
+        match scene::load_scene(world, &self.scene_path) {
+            Ok(roots) => {
+                info!("Loaded scene from {}", self.scene_path);
+                self.selected_entities.clear();
+                for root in roots {
+                    self.selected_entities.select_maybe_add(root, true);
+                }
+                self.selection = InspectorSelection::Entities;
+            }
+            Err(e) => error!("Failed to load scene {}: {e}", self.scene_path),
+        }
+    }
+
+    fn save_scene(&self, world: &World, entities: &[Entity]) {
+        match scene::save_scene(world, entities, &self.scene_path) {
+            Ok(()) => info!("Saved scene to {}", self.scene_path),
+            Err(e) => error!("Failed to save scene {}: {e}", self.scene_path),
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 enum EguiWindow {
     GameView,
     Hierarchy,
     Resources,
     Assets,
+    AssetBrowser,
+    Shaders,
+    Prefabs,
     Inspector,
+    Console,
+    Stats,
+    Timeline,
+    AnimationGraph,
+    Curves,
+    Particles,
+    States,
+    Schedules,
+    Profiler,
+    Archetypes,
+    Query,
+    SceneDiff,
+    HotReload,
+    Validation,
+    /// An additional game view, rendered by the camera with the same [`EditorView`].
+    View(u32),
+    /// An additional inspector, showing what it was pinned to.
+    PinnedInspector(u32),
+    /// An orthographic view along a world axis.
+    Axis(AxisView),
+    /// A tab registered through [`tabs::AddEditorTab`].
+    Custom(String),
+}
+
+impl EguiWindow {
+    const BUILTIN: [EguiWindow; 22] = [
+        EguiWindow::GameView,
+        EguiWindow::Hierarchy,
+        EguiWindow::Resources,
+        EguiWindow::Assets,
+        EguiWindow::AssetBrowser,
+        EguiWindow::Shaders,
+        EguiWindow::Prefabs,
+        EguiWindow::Inspector,
+        EguiWindow::Console,
+        EguiWindow::Stats,
+        EguiWindow::Timeline,
+        EguiWindow::AnimationGraph,
+        EguiWindow::Curves,
+        EguiWindow::Particles,
+        EguiWindow::States,
+        EguiWindow::Schedules,
+        EguiWindow::Profiler,
+        EguiWindow::Archetypes,
+        EguiWindow::Query,
+        EguiWindow::SceneDiff,
+        EguiWindow::HotReload,
+        EguiWindow::Validation,
+    ];
+
+    fn title(&self) -> String {
+        match self {
+            EguiWindow::Custom(name) => name.clone(),
+            EguiWindow::View(id) => format!("GameView {id}"),
+            EguiWindow::PinnedInspector(id) => format!("Inspector {id}"),
+            EguiWindow::Axis(axis) => format!("{axis:?}"),
+            window => format!("{window:?}"),
+        }
+    }
 }
 
 struct TabViewer<'a> {
     world: &'a mut World,
     selected_entities: &'a mut SelectedEntities,
     selection: &'a mut InspectorSelection,
-    viewport_rect: &'a mut egui::Rect,
-    gizmo_mode: GizmoMode,
+    pending_delete: &'a mut Option<Vec<Entity>>,
+    gizmo_mode: &'a mut GizmoMode,
+    gizmo_orientation: &'a mut GizmoOrientation,
+    gizmo_pivot: &'a mut GizmoPivot,
+    hierarchy: &'a mut HierarchyState,
+    hovered_entity: &'a mut Option<Entity>,
+    mode_2d: &'a mut bool,
+    pinned_inspectors: &'a mut HashMap<u32, PinnedInspector>,
+    new_pin: &'a mut Option<PinnedInspector>,
+    navigation: &'a mut SelectionHistory,
+    closed: &'a mut Vec<EguiWindow>,
+    /// The nodes of the dock tabs can be moved to.
+    leaves: Vec<DockLeaf>,
+    /// Requested from the context menu of a tab, applied once the dock is shown.
+    tab_action: Option<(EguiWindow, TabAction)>,
+}
+
+struct DockLeaf {
+    node: NodeIndex,
+    /// Title of the active tab of the node.
+    label: String,
+    tabs: Vec<EguiWindow>,
+}
+
+enum TabAction {
+    Float,
+    MoveTo(NodeIndex),
+}
+
+/// Shows the floating tabs in windows of their own, closing those whose window was closed.
+///
+/// Returns the tab to dock again, if any.
+fn floating_tabs_ui(
+    ctx: &egui::Context,
+    tab_viewer: &mut TabViewer,
+    floating: &mut Vec<EguiWindow>,
+) -> Option<EguiWindow> {
+    let mut dock = None;
+    let mut close = None;
+    for (index, window) in floating.iter_mut().enumerate() {
+        let mut open = true;
+        // the game views show the game rendered behind the window
+        let view = !egui_dock::TabViewer::clear_background(tab_viewer, window);
+        let mut frame = egui::Frame::window(&ctx.style());
+        if view {
+            frame = frame.fill(egui::Color32::TRANSPARENT);
+        }
+        egui::Window::new(egui_dock::TabViewer::title(tab_viewer, window))
+            .id(egui::Id::new(("floating_tab", window.title())))
+            .open(&mut open)
+            .frame(frame)
+            .default_size([480.0, 320.0])
+            .show(ctx, |ui| {
+                if ui
+                    .small_button("Dock")
+                    .on_hover_text("Put the tab back in the dock")
+                    .clicked()
+                {
+                    dock = Some(index);
+                }
+                match view {
+                    true => egui_dock::TabViewer::ui(tab_viewer, ui, window),
+                    false => {
+                        egui::ScrollArea::both()
+                            .show(ui, |ui| egui_dock::TabViewer::ui(tab_viewer, ui, window));
+                    }
+                }
+            });
+        if !open {
+            close = Some(index);
+        }
+    }
+    if let Some(index) = close {
+        let mut window = floating.remove(index);
+        egui_dock::TabViewer::on_close(tab_viewer, &mut window);
+        return None;
+    }
+    dock.map(|index| floating.remove(index))
+}
+
+impl TabViewer<'_> {
+    fn game_view_ui(&mut self, ui: &mut egui::Ui, camera: Entity) {
+        ui.horizontal(|ui| {
+            gizmo::gizmo_toolbar(
+                ui,
+                self.world.resource::<EditorKeybinds>(),
+                self.gizmo_mode,
+                self.gizmo_orientation,
+                self.gizmo_pivot,
+            );
+            ui.separator();
+            ui.toggle_value(self.mode_2d, "2D");
+            ui.separator();
+            wireframe::wireframe_toolbar(ui, self.world);
+            debug_view::debug_view_toolbar(ui, self.world);
+            ui.separator();
+            measure::measure_toolbar(ui, self.world);
+            particles::particles_toolbar(ui, self.world, self.selected_entities);
+        });
+        let viewport_rect = ui.clip_rect().intersect(ui.available_rect_before_wrap());
+        if let Some(mut viewport) = self.world.get_mut::<EditorViewport>(camera) {
+            viewport.rect = viewport_rect;
+        }
+
+        gizmo::draw_gizmo(
+            ui,
+            self.world,
+            camera,
+            self.selected_entities,
+            *self.gizmo_mode,
+            *self.gizmo_orientation,
+            *self.gizmo_pivot,
+        );
+
+        orientation::orientation_ui(ui, self.world, camera, viewport_rect);
+
+        // the measure tool takes the clicks which would otherwise select entities
+        if !measure::measure_ui(ui, self.world, camera, viewport_rect) {
+            let (hovered_light, light_selected) = lights::light_icons_ui(
+                ui,
+                self.world,
+                camera,
+                viewport_rect,
+                self.selected_entities,
+            );
+            let (hovered, selected) = picking::viewport_picking(
+                ui,
+                self.world,
+                camera,
+                viewport_rect,
+                self.selected_entities,
+            );
+            if let Some(hovered) = hovered_light.or(hovered) {
+                *self.hovered_entity = Some(hovered);
+            }
+            if light_selected || selected {
+                *self.selection = InspectorSelection::Entities;
+            }
+        }
+
+        preview::camera_preview_ui(ui, self.world, viewport_rect);
+        if self.world.resource::<EditorSettings>().stats_overlay {
+            stats::stats_overlay_ui(ui, self.world, camera, viewport_rect);
+        }
+    }
 }
 
 impl egui_dock::TabViewer for TabViewer<'_> {
@@ -200,134 +1162,222 @@ impl egui_dock::TabViewer for TabViewer<'_> {
         let type_registry = type_registry.read();
 
         match window {
-            EguiWindow::GameView => {
-                *self.viewport_rect = ui.clip_rect();
-
-                draw_gizmo(ui, self.world, self.selected_entities, self.gizmo_mode);
+            EguiWindow::GameView | EguiWindow::View(_) | EguiWindow::Axis(_) => {
+                match views::view_camera(self.world, window) {
+                    Some(camera) => self.game_view_ui(ui, camera),
+                    None => {
+                        ui.label("The camera of this view was removed");
+                    }
+                }
             }
             EguiWindow::Hierarchy => {
-                let selected = hierarchy_ui(self.world, ui, self.selected_entities);
+                let selected = hierarchy::hierarchy_ui(
+                    self.world,
+                    ui,
+                    self.hierarchy,
+                    self.selected_entities,
+                    self.pending_delete,
+                );
                 if selected {
                     *self.selection = InspectorSelection::Entities;
                 }
             }
             EguiWindow::Resources => select_resource(ui, &type_registry, self.selection),
-            EguiWindow::Assets => select_asset(ui, &type_registry, self.world, self.selection),
-            EguiWindow::Inspector => match *self.selection {
-                InspectorSelection::Entities => match self.selected_entities.as_slice() {
-                    &[entity] => ui_for_entity_with_children(self.world, entity, ui),
-                    entities => ui_for_entities_shared_components(self.world, entities, ui),
-                },
-                InspectorSelection::Resource(type_id, ref name) => {
-                    ui.label(name);
-                    bevy_inspector::by_type_id::ui_for_resource(
-                        self.world,
-                        type_id,
+            EguiWindow::Assets => select_asset(
+                ui,
+                &type_registry,
+                self.world,
+                self.selection,
+                self.selected_entities,
+            ),
+            EguiWindow::Inspector => {
+                ui.horizontal(|ui| {
+                    navigation::navigation_buttons(
                         ui,
-                        name,
-                        &type_registry,
-                    )
-                }
-                InspectorSelection::Asset(type_id, ref name, handle) => {
-                    ui.label(name);
-                    bevy_inspector::by_type_id::ui_for_asset(
                         self.world,
-                        type_id,
-                        handle,
-                        ui,
-                        &type_registry,
+                        self.navigation,
+                        self.selection,
+                        self.selected_entities,
                     );
+                    let pinnable = !matches!(*self.selection, InspectorSelection::Entities)
+                        || !self.selected_entities.is_empty();
+                    if !pinnable {
+                        return;
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .small_button("📌")
+                            .on_hover_text("Open an inspector which keeps showing this")
+                            .clicked()
+                        {
+                            *self.new_pin = Some(PinnedInspector {
+                                selection: self.selection.clone(),
+                                entities: self.selected_entities.as_slice().to_vec(),
+                            });
+                        }
+                    });
+                });
+                let respawned = inspector_ui(
+                    self.world,
+                    ui,
+                    self.selection,
+                    self.selected_entities.as_slice(),
+                    &type_registry,
+                );
+                prefab::remap_selection(self.selected_entities, &respawned);
+            }
+            EguiWindow::PinnedInspector(id) => {
+                let Some(pin) = self.pinned_inspectors.get_mut(id) else {
+                    return;
+                };
+                pin.entities
+                    .retain(|&entity| self.world.get_entity(entity).is_some());
+                ui.horizontal(|ui| {
+                    ui.label(format!("📌 {}", pin.label(self.world)));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .small_button("Pin selection")
+                            .on_hover_text("Show the current selection here instead")
+                            .clicked()
+                        {
+                            pin.selection = self.selection.clone();
+                            pin.entities = self.selected_entities.as_slice().to_vec();
+                        }
+                    });
+                });
+                ui.separator();
+                if pin.selection == InspectorSelection::Entities && pin.entities.is_empty() {
+                    ui.label("The pinned entities were despawned");
+                    return;
                 }
-            },
+                let respawned = inspector_ui(
+                    self.world,
+                    ui,
+                    &pin.selection,
+                    &pin.entities,
+                    &type_registry,
+                );
+                for &(old, new) in &respawned {
+                    for entity in &mut pin.entities {
+                        if *entity == old {
+                            *entity = new;
+                        }
+                    }
+                }
+                prefab::remap_selection(self.selected_entities, &respawned);
+            }
+            EguiWindow::AssetBrowser => asset_browser::asset_browser_ui(
+                self.world,
+                ui,
+                self.selected_entities,
+                self.selection,
+            ),
+            EguiWindow::Shaders => shaders::shaders_ui(self.world, ui),
+            EguiWindow::Prefabs => {
+                if let Some(entity) = prefab::prefabs_ui(self.world, ui) {
+                    self.selected_entities.select_replace(entity);
+                    *self.selection = InspectorSelection::Entities;
+                }
+            }
+            EguiWindow::Console => console::console_ui(self.world, ui),
+            EguiWindow::Stats => stats::stats_ui(self.world, ui),
+            EguiWindow::Timeline => timeline::timeline_ui(self.world, ui, self.selected_entities),
+            EguiWindow::AnimationGraph => {
+                animation_graph::animation_graph_ui(self.world, ui, self.selected_entities)
+            }
+            EguiWindow::Curves => curve::curves_ui(self.world, ui, self.selected_entities),
+            EguiWindow::Particles => particles::particles_ui(self.world, ui),
+            EguiWindow::States => states::states_ui(self.world, ui),
+            EguiWindow::Schedules => schedules::schedules_ui(self.world, ui),
+            EguiWindow::Profiler => profiler::profiler_ui(self.world, ui),
+            EguiWindow::Archetypes => archetypes::archetypes_ui(self.world, ui),
+            EguiWindow::Query => query::query_ui(self.world, ui, self.selected_entities),
+            EguiWindow::SceneDiff => {
+                scene_diff::scene_diff_ui(self.world, ui, self.selected_entities)
+            }
+            EguiWindow::HotReload => hot_reload::hot_reload_ui(self.world, ui),
+            EguiWindow::Validation => {
+                validation::asset_report_ui(self.world, ui, self.selection, self.selected_entities)
+            }
+            EguiWindow::Custom(name) => {
+                if !EditorTabs::show(self.world, name, ui) {
+                    ui.label(format!("Tab {name} is not registered"));
+                }
+            }
         }
     }
 
     fn title(&mut self, window: &mut Self::Tab) -> egui_dock::egui::WidgetText {
-        format!("{window:?}").into()
-    }
-
-    fn clear_background(&self, window: &Self::Tab) -> bool {
-        !matches!(window, EguiWindow::GameView)
+        match window {
+            EguiWindow::PinnedInspector(id) => match self.pinned_inspectors.get(id) {
+                Some(pin) => format!("📌 {}", pin.label(self.world)).into(),
+                None => window.title().into(),
+            },
+            window => window.title().into(),
+        }
     }
-}
 
-fn draw_gizmo(
-    ui: &mut egui::Ui,
-    world: &mut World,
-    selected_entities: &SelectedEntities,
-    gizmo_mode: GizmoMode,
-) {
-    let Ok((cam_transform, projection)) = world
-        .query_filtered::<(&GlobalTransform, &Projection), With<MainCamera>>()
-        .get_single(world)
-    else {
-
-        let Ok((cam_transform, projection)) = world.query_filtered::<(&GlobalTransform, &OrthographicProjection), With<MainCamera>>().get_single(world) else {
-            return;
-        };
-
-        if selected_entities.len() != 1 {
-            return;
+    fn context_menu(&mut self, ui: &mut egui_dock::egui::Ui, window: &mut Self::Tab) {
+        if ui.button("Float").clicked() {
+            self.tab_action = Some((window.clone(), TabAction::Float));
+            ui.close_menu();
         }
-        let view_matrix = Mat4::from(cam_transform.affine().inverse());
-        let projection_matrix = projection.get_projection_matrix();
-
-        for selected in selected_entities.iter() {
-            let Some(transform) = world.get::<Transform>(selected) else {
-                continue;
-            };
-            let model_matrix = transform.compute_matrix();
-
-            let Some(result) = Gizmo::new(selected)
-                .model_matrix(model_matrix.to_cols_array_2d())
-                .view_matrix(view_matrix.to_cols_array_2d())
-                .projection_matrix(projection_matrix.to_cols_array_2d())
-                .orientation(GizmoOrientation::Local)
-                .mode(gizmo_mode)
-                .interact(ui)
-            else {
-                continue;
-            };
+        let others: Vec<_> = self
+            .leaves
+            .iter()
+            .filter(|leaf| !leaf.tabs.contains(window))
+            .map(|leaf| (leaf.node, leaf.label.clone()))
+            .collect();
+        ui.add_enabled_ui(!others.is_empty(), |ui| {
+            ui.menu_button("Move To", |ui| {
+                for (node, label) in others {
+                    if ui.button(format!("Next to {label}")).clicked() {
+                        self.tab_action = Some((window.clone(), TabAction::MoveTo(node)));
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+        ui.separator();
+    }
 
-            let mut transform = world.get_mut::<Transform>(selected).unwrap();
-            *transform = Transform {
-                translation: Vec3::from(<[f32; 3]>::from(result.translation)),
-                rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
-                scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-            };
+    fn on_close(&mut self, window: &mut Self::Tab) -> bool {
+        // the cameras of closed game views are despawned and pins forgotten, so they can't be
+        // reopened as they were
+        if !matches!(window, EguiWindow::View(_) | EguiWindow::PinnedInspector(_)) {
+            self.closed.retain(|closed| closed != window);
+            self.closed.push(window.clone());
         }
-        return;
-    };
-    let view_matrix = Mat4::from(cam_transform.affine().inverse());
-    let projection_matrix = projection.get_projection_matrix();
+        true
+    }
 
-    if selected_entities.len() != 1 {
-        return;
+    fn clear_background(&self, window: &Self::Tab) -> bool {
+        !matches!(
+            window,
+            EguiWindow::GameView | EguiWindow::View(_) | EguiWindow::Axis(_)
+        )
     }
+}
 
-    for selected in selected_entities.iter() {
-        let Some(transform) = world.get::<Transform>(selected) else {
-            continue;
-        };
-        let model_matrix = transform.compute_matrix();
-
-        let Some(result) = Gizmo::new(selected)
-            .model_matrix(model_matrix.to_cols_array_2d())
-            .view_matrix(view_matrix.to_cols_array_2d())
-            .projection_matrix(projection_matrix.to_cols_array_2d())
-            .orientation(GizmoOrientation::Local)
-            .mode(gizmo_mode)
-            .interact(ui)
-        else {
-            continue;
-        };
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum ResourceGrouping {
+    #[default]
+    None,
+    Crate,
+    Module,
+}
 
-        let mut transform = world.get_mut::<Transform>(selected).unwrap();
-        *transform = Transform {
-            translation: Vec3::from(<[f32; 3]>::from(result.translation)),
-            rotation: Quat::from_array(<[f32; 4]>::from(result.rotation)),
-            scale: Vec3::from(<[f32; 3]>::from(result.scale)),
-        };
+impl ResourceGrouping {
+    /// The group of the resource with the full type name `type_name`.
+    fn group<'a>(&self, type_name: &'a str) -> &'a str {
+        // generic arguments have paths of their own
+        let path = type_name.split('<').next().unwrap_or(type_name);
+        let module = path.rsplit_once("::").map_or("", |(module, _)| module);
+        match self {
+            ResourceGrouping::None => "",
+            ResourceGrouping::Crate => module.split("::").next().unwrap_or(module),
+            ResourceGrouping::Module => module,
+        }
     }
 }
 
@@ -336,23 +1386,210 @@ fn select_resource(
     type_registry: &TypeRegistry,
     selection: &mut InspectorSelection,
 ) {
+    let filter_id = ui.id().with("resource_filter");
+    let (mut filter, mut grouping) = ui.data_mut(|data| {
+        data.get_temp::<(String, ResourceGrouping)>(filter_id)
+            .unwrap_or_default()
+    });
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut filter)
+                .hint_text("Search")
+                .desired_width(ui.available_width() - 90.0),
+        );
+        egui::ComboBox::from_id_source("resource_grouping")
+            .width(80.0)
+            .selected_text(match grouping {
+                ResourceGrouping::None => "No groups",
+                ResourceGrouping::Crate => "By crate",
+                ResourceGrouping::Module => "By module",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut grouping, ResourceGrouping::None, "No groups");
+                ui.selectable_value(&mut grouping, ResourceGrouping::Crate, "By crate");
+                ui.selectable_value(&mut grouping, ResourceGrouping::Module, "By module");
+            });
+    });
+    ui.data_mut(|data| data.insert_temp(filter_id, (filter.clone(), grouping)));
+
+    // the best matches first while searching
     let mut resources: Vec<_> = type_registry
         .iter()
         .filter(|registration| registration.data::<ReflectResource>().is_some())
-        .map(|registration| (registration.short_name().to_owned(), registration.type_id()))
+        .filter_map(|registration| {
+            let score = match filter.trim().is_empty() {
+                true => 0,
+                false => commands::fuzzy_score(&filter, registration.short_name())
+                    .max(commands::fuzzy_score(&filter, registration.type_name()))?,
+            };
+            Some((
+                grouping.group(registration.type_name()),
+                score,
+                registration.short_name().to_owned(),
+                registration.type_id(),
+            ))
+        })
         .collect();
-    resources.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
+    resources.sort_by(
+        |(group_a, score_a, name_a, _), (group_b, score_b, name_b, _)| {
+            group_a
+                .cmp(group_b)
+                .then(score_b.cmp(score_a))
+                .then(name_a.cmp(name_b))
+        },
+    );
 
-    for (resource_name, type_id) in resources {
-        let selected = match *selection {
-            InspectorSelection::Resource(selected, _) => selected == type_id,
-            _ => false,
-        };
+    let mut resource_list = |ui: &mut egui::Ui, resources: &[(&str, i32, String, TypeId)]| {
+        for (_, _, resource_name, type_id) in resources {
+            let selected = match *selection {
+                InspectorSelection::Resource(selected, _) => selected == *type_id,
+                _ => false,
+            };
 
-        if ui.selectable_label(selected, &resource_name).clicked() {
-            *selection = InspectorSelection::Resource(type_id, resource_name);
+            if ui.selectable_label(selected, resource_name).clicked() {
+                *selection = InspectorSelection::Resource(*type_id, resource_name.clone());
+            }
+        }
+    };
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            if grouping == ResourceGrouping::None {
+                resource_list(ui, &resources);
+                return;
+            }
+            for group in resources.chunk_by(|a, b| a.0 == b.0) {
+                let name = match group[0].0 {
+                    "" => "(no module)",
+                    name => name,
+                };
+                egui::CollapsingHeader::new(format!("{name} ({})", group.len()))
+                    .id_source(name)
+                    .open((!filter.trim().is_empty()).then_some(true))
+                    .show(ui, |ui| resource_list(ui, group));
+            }
+        });
+}
+
+/// Inspects the entities, resource or asset of an inspector tab, recording the edits in the
+/// history.
+///
+/// Returns the prefab instances respawned meanwhile, as `(old, new)` pairs.
+fn inspector_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selection: &InspectorSelection,
+    entities: &[Entity],
+    type_registry: &TypeRegistry,
+) -> Vec<(Entity, Entity)> {
+    let mut respawned = Vec::new();
+    match *selection {
+        InspectorSelection::Entities => {
+            let before: Vec<_> = entities
+                .iter()
+                .map(|&entity| (entity, history::snapshot_components(world, entity)))
+                .collect();
+
+            let locked: Vec<_> = entities
+                .iter()
+                .copied()
+                .filter(|&entity| world.get::<EditorLocked>(entity).is_some())
+                .collect();
+            if !locked.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.label("🔒 Locked");
+                    if ui.button("Unlock").clicked() {
+                        for &entity in &locked {
+                            hierarchy::toggle_locked(world, entity);
+                        }
+                    }
+                });
+            }
+            ui.add_enabled_ui(locked.is_empty(), |ui| match entities {
+                &[entity] => respawned = inspector::ui_for_entity_with_children(world, entity, ui),
+                entities => inspector::ui_for_entities_shared_components(world, entities, ui),
+            });
+
+            for (entity, before) in before {
+                history::record_component_changes(world, entity, before);
+            }
+        }
+        InspectorSelection::Resource(type_id, ref name) => {
+            ui.label(name);
+            let before = history::snapshot_resource(world, type_id);
+            bevy_inspector::by_type_id::ui_for_resource(world, type_id, ui, name, type_registry);
+            history::record_resource_change(world, type_id, before);
+        }
+        InspectorSelection::Asset(type_id, ref name, handle) => {
+            ui.label(name);
+            asset_preview::asset_preview_ui(world, ui, type_id, handle);
+            bevy_inspector::by_type_id::ui_for_asset(world, type_id, handle, ui, type_registry);
         }
     }
+    respawned
+}
+
+/// The path of an asset with its label, or `None` for assets made in code.
+fn asset_label(asset_server: &AssetServer, handle: HandleId) -> Option<String> {
+    let path = asset_server.get_handle_path(handle)?;
+    Some(match path.label() {
+        Some(label) => format!("{}#{label}", path.path().display()),
+        None => path.path().display().to_string(),
+    })
+}
+
+/// Lists the entities with a component holding a handle to the asset whose usages were asked
+/// for, and selects the one clicked.
+fn asset_usages_ui(
+    ui: &mut egui::Ui,
+    type_registry: &TypeRegistry,
+    world: &World,
+    selection: &mut InspectorSelection,
+    selected_entities: &mut SelectedEntities,
+) {
+    let usages_id = ui.id().with("asset_usages");
+    let Some((handle, label)) = ui.data(|data| data.get_temp::<(HandleId, String)>(usages_id))
+    else {
+        return;
+    };
+    // found again every frame, so the list follows the scene
+    let usages = asset_usages::asset_usages(world, type_registry, handle);
+    ui.horizontal(|ui| {
+        ui.strong(format!("Usages of {label}"));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.small_button("✖").on_hover_text("Close").clicked() {
+                ui.data_mut(|data| data.remove::<(HandleId, String)>(usages_id));
+            }
+            if !usages.is_empty() && ui.small_button("Select All").clicked() {
+                selected_entities.clear();
+                for usage in &usages {
+                    selected_entities.select_maybe_add(usage.entity, true);
+                }
+                *selection = InspectorSelection::Entities;
+            }
+        });
+    });
+    if usages.is_empty() {
+        ui.weak("No entity uses it");
+    }
+    egui::ScrollArea::vertical()
+        .id_source("asset_usages")
+        .max_height(160.0)
+        .show(ui, |ui| {
+            for usage in &usages {
+                let selected = selected_entities.contains(usage.entity);
+                let text = format!(
+                    "{} – {}",
+                    inspector::entity_name(world, usage.entity),
+                    usage.field
+                );
+                if ui.selectable_label(selected, text).clicked() {
+                    selected_entities.select_replace(usage.entity);
+                    *selection = InspectorSelection::Entities;
+                }
+            }
+        });
+    ui.separator();
 }
 
 fn select_asset(
@@ -360,7 +1597,20 @@ fn select_asset(
     type_registry: &TypeRegistry,
     world: &World,
     selection: &mut InspectorSelection,
+    selected_entities: &mut SelectedEntities,
 ) {
+    let filter_id = ui.id().with("asset_filter");
+    let mut filter = ui.data_mut(|data| data.get_temp::<String>(filter_id).unwrap_or_default());
+    ui.add(
+        egui::TextEdit::singleline(&mut filter)
+            .hint_text("Search")
+            .desired_width(f32::INFINITY),
+    );
+    ui.data_mut(|data| data.insert_temp(filter_id, filter.clone()));
+    let filter = filter.trim().to_lowercase();
+    asset_usages_ui(ui, type_registry, world, selection, selected_entities);
+    let usages_id = ui.id().with("asset_usages");
+
     let mut assets: Vec<_> = type_registry
         .iter()
         .filter_map(|registration| {
@@ -374,25 +1624,80 @@ fn select_asset(
         .collect();
     assets.sort_by(|(name_a, ..), (name_b, ..)| name_a.cmp(name_b));
 
-    for (asset_name, asset_type_id, reflect_asset) in assets {
-        let mut handles: Vec<_> = reflect_asset.ids(world).collect();
-        handles.sort();
-
-        ui.collapsing(format!("{asset_name} ({})", handles.len()), |ui| {
-            for handle in handles {
+    let asset_server = world.resource::<AssetServer>();
+    let mut asset_list =
+        |ui: &mut egui::Ui, asset_name: &str, asset_type_id, handles: &[(HandleId, String)]| {
+            for (handle, label) in handles {
                 let selected = match *selection {
-                    InspectorSelection::Asset(_, _, selected_id) => selected_id == handle,
+                    InspectorSelection::Asset(_, _, selected_id) => selected_id == *handle,
                     _ => false,
                 };
 
-                if ui
-                    .selectable_label(selected, format!("{:?}", handle))
-                    .clicked()
-                {
+                let response = ui.selectable_label(selected, label);
+                if response.clicked() {
                     *selection =
-                        InspectorSelection::Asset(asset_type_id, asset_name.clone(), handle);
+                        InspectorSelection::Asset(asset_type_id, asset_name.to_owned(), *handle);
                 }
+                response.context_menu(|ui| {
+                    if ui.button("Find Usages").clicked() {
+                        ui.data_mut(|data| data.insert_temp(usages_id, (*handle, label.clone())));
+                        ui.close_menu();
+                    }
+                });
+            }
+        };
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            for (asset_name, asset_type_id, reflect_asset) in assets {
+                let type_matches = asset_name.to_lowercase().contains(&filter);
+                let (mut named, mut untitled): (Vec<_>, Vec<_>) = reflect_asset
+                    .ids(world)
+                    .map(|handle| (handle, asset_label(asset_server, handle)))
+                    .filter(|(_, label)| {
+                        type_matches
+                            || label
+                                .as_ref()
+                                .is_some_and(|label| label.to_lowercase().contains(&filter))
+                    })
+                    .partition(|(_, label)| label.is_some());
+                if !filter.is_empty() && named.is_empty() && untitled.is_empty() {
+                    continue;
+                }
+                named.sort_by(|(_, a), (_, b)| a.cmp(b));
+                untitled.sort_by_key(|(handle, _)| *handle);
+                let named: Vec<_> = named
+                    .into_iter()
+                    .map(|(handle, label)| (handle, label.unwrap_or_default()))
+                    .collect();
+                let untitled: Vec<_> = untitled
+                    .into_iter()
+                    .map(|(handle, _)| {
+                        let label = match handle {
+                            HandleId::Id(_, id) => format!("Untitled {id}"),
+                            handle => format!("{handle:?}"),
+                        };
+                        (handle, label)
+                    })
+                    .collect();
+
+                egui::CollapsingHeader::new(format!(
+                    "{asset_name} ({})",
+                    named.len() + untitled.len()
+                ))
+                .id_source(&asset_name)
+                .open((!filter.is_empty()).then_some(true))
+                .show(ui, |ui| {
+                    asset_list(ui, &asset_name, asset_type_id, &named);
+                    if untitled.is_empty() {
+                        return;
+                    }
+                    egui::CollapsingHeader::new(format!("Untitled ({})", untitled.len()))
+                        .id_source((&asset_name, "untitled"))
+                        .show(ui, |ui| {
+                            asset_list(ui, &asset_name, asset_type_id, &untitled);
+                        });
+                });
             }
         });
-    }
 }
@@ -0,0 +1,173 @@
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_inspector_egui::{
+    bevy_egui::EguiSettings,
+    bevy_inspector::hierarchy::{SelectedEntities, SelectionMode},
+};
+
+use crate::{
+    asset_preview::EditorStudio, hierarchy::EditorLocked, settings::EditorSettings, EditorResource,
+    UiState,
+};
+
+/// Radius of the light icons in the game view, in egui points.
+const ICON_RADIUS: f32 = 11.0;
+/// Length of the rays drawn along the direction of a selected directional light.
+const DIRECTIONAL_RAY_LENGTH: f32 = 2.0;
+
+pub struct EditorLightsPlugin;
+impl Plugin for EditorLightsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            draw_light_shapes.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Draws the range of selected point and spot lights, and the direction of selected directional
+/// lights.
+fn draw_light_shapes(
+    editor: Res<EditorResource>,
+    ui_state: Res<UiState>,
+    settings: Res<EditorSettings>,
+    point_lights: Query<(&GlobalTransform, &PointLight)>,
+    spot_lights: Query<(&GlobalTransform, &SpotLight)>,
+    directional_lights: Query<&GlobalTransform, With<DirectionalLight>>,
+    mut gizmos: Gizmos,
+) {
+    if !editor.0 {
+        return;
+    }
+    let color = settings.selection_outline;
+    for entity in ui_state.selected_entities.iter() {
+        if let Ok((transform, light)) = point_lights.get(entity) {
+            gizmos.sphere(transform.translation(), Quat::IDENTITY, light.range, color);
+        }
+
+        if let Ok((transform, light)) = spot_lights.get(entity) {
+            let (_, rotation, position) = transform.to_scale_rotation_translation();
+            let forward = rotation * Vec3::NEG_Z;
+            for (angle, color) in [
+                (light.outer_angle, color),
+                (light.inner_angle, color.with_a(color.a() * 0.5)),
+            ] {
+                let center = position + forward * light.range * angle.cos();
+                let radius = light.range * angle.sin();
+                gizmos.circle(center, forward, radius, color);
+                for side in [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y] {
+                    gizmos.line(position, center + rotation * side * radius, color);
+                }
+            }
+        }
+
+        if let Ok(transform) = directional_lights.get(entity) {
+            let (_, rotation, position) = transform.to_scale_rotation_translation();
+            let forward = rotation * Vec3::NEG_Z;
+            gizmos.ray(position, forward * DIRECTIONAL_RAY_LENGTH, color);
+            for side in [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y] {
+                let start = position + rotation * side * 0.25;
+                gizmos.ray(start, forward * DIRECTIONAL_RAY_LENGTH * 0.75, color);
+            }
+        }
+    }
+}
+
+/// Glyph and color of the icon of light `entity`, if it is one.
+fn light_icon(world: &World, entity: Entity) -> Option<(&'static str, Color)> {
+    if let Some(light) = world.get::<PointLight>(entity) {
+        return Some(("💡", light.color));
+    }
+    if let Some(light) = world.get::<SpotLight>(entity) {
+        return Some(("🔦", light.color));
+    }
+    world
+        .get::<DirectionalLight>(entity)
+        .map(|light| ("☀", light.color))
+}
+
+/// Draws an icon at each unlocked light in the game view of `camera`, which selects the light when
+/// clicked. Ctrl or Shift add to the selection.
+///
+/// Has to run before the viewport picking, so clicks on an icon don't select what's behind it.
+///
+/// Returns the hovered light and whether the selection changed.
+pub(crate) fn light_icons_ui(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+    selected: &mut SelectedEntities,
+) -> (Option<Entity>, bool) {
+    let scale_factor = world.resource::<EguiSettings>().scale_factor as f32;
+    let selection_color = world.resource::<EditorSettings>().selection_outline;
+    let Ok((camera_component, camera_transform)) = world
+        .query::<(&Camera, &GlobalTransform)>()
+        .get(world, camera)
+    else {
+        return (None, false);
+    };
+    let camera_component = camera_component.clone();
+    let camera_transform = *camera_transform;
+
+    let lights: Vec<_> = world
+        .query_filtered::<(Entity, &GlobalTransform), (
+            Or<(With<PointLight>, With<SpotLight>, With<DirectionalLight>)>,
+            Without<EditorLocked>,
+            Without<EditorStudio>,
+        )>()
+        .iter(world)
+        .filter_map(|(entity, transform)| {
+            let position =
+                camera_component.world_to_viewport(&camera_transform, transform.translation())?;
+            let center = viewport_rect.min + egui::vec2(position.x, position.y) / scale_factor;
+            viewport_rect.contains(center).then_some((entity, center))
+        })
+        .collect();
+
+    let painter = ui.painter_at(viewport_rect);
+    let add = ui.input(|input| input.modifiers.ctrl || input.modifiers.shift);
+    let (mut hovered, mut changed) = (None, false);
+    for (entity, center) in lights {
+        let Some((glyph, color)) = light_icon(world, entity) else {
+            continue;
+        };
+        let rect = egui::Rect::from_center_size(center, egui::Vec2::splat(ICON_RADIUS * 2.0));
+        let response = ui.interact(
+            rect,
+            egui::Id::new(("light_icon", camera, entity)),
+            egui::Sense::click(),
+        );
+        if response.hovered() {
+            hovered = Some(entity);
+        }
+        if response.clicked() {
+            let mode = SelectionMode::from_ctrl_shift(add, false);
+            selected.select(mode, entity, |_, _| std::iter::empty());
+            changed = true;
+        }
+
+        let [r, g, b, _] = color.as_rgba_u8();
+        let stroke_color = match selected.contains(entity) {
+            true => {
+                let [r, g, b, a] = selection_color.as_rgba_u8();
+                egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+            }
+            false if response.hovered() => egui::Color32::WHITE,
+            false => egui::Color32::from_gray(140),
+        };
+        painter.circle(
+            center,
+            ICON_RADIUS,
+            egui::Color32::from_black_alpha(160),
+            (1.5, stroke_color),
+        );
+        painter.text(
+            center,
+            egui::Align2::CENTER_CENTER,
+            glyph,
+            egui::FontId::proportional(ICON_RADIUS * 1.2),
+            egui::Color32::from_rgb(r, g, b),
+        );
+    }
+    (hovered, changed)
+}
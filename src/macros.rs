@@ -0,0 +1,774 @@
+use std::{fs, path::Path};
+
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        GetPath, ReflectRef,
+    },
+};
+use bevy_reflect::TypeRegistry;
+use serde::{de::DeserializeSeed, Deserialize, Serialize};
+
+use crate::{
+    commands::{self, AddEditorCommand, EditorCommands},
+    hierarchy::EntityPreset,
+    history::{self, EditTarget, EditorHistory},
+    query, InspectorSelection, UiState,
+};
+
+/// Where the recorded macros are kept, next to the settings file.
+pub const MACROS_PATH: &str = "editor_macros.ron";
+/// Macros are listed in the command palette with this prefix.
+const COMMAND_PREFIX: &str = "Macro: ";
+const TOGGLE_COMMAND: &str = "Toggle Macro Recording";
+/// How deep macros can play each other, so a macro playing itself stops.
+const MAX_DEPTH: usize = 8;
+
+pub struct EditorMacrosPlugin;
+impl Plugin for EditorMacrosPlugin {
+    fn build(&self, app: &mut App) {
+        let macros = EditorMacros::load();
+        for name in macros.names() {
+            app.add_editor_command(format!("{COMMAND_PREFIX}{name}"), queue_macro(name));
+        }
+        app.insert_resource(macros)
+            .add_editor_command(TOGGLE_COMMAND, |world| {
+                world.resource_mut::<EditorMacros>().toggle_recording = true;
+            })
+            .add_systems(Update, update_macros.after(commands::run_queued_commands))
+            .add_systems(Last, record_edits.before(history::end_history_frame));
+    }
+}
+
+/// Something done in the editor, played again on the selection of the time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MacroStep {
+    /// Runs the command of the command palette with this name.
+    Command(String),
+    /// Spawns the [`EntityPreset`] of this name, as a child of the first selected entity if
+    /// `child`, and selects it.
+    Spawn { preset: String, child: bool },
+    /// Sets a field of a component of the selected entities to a value written in RON, or the
+    /// whole component if `field` is empty.
+    SetComponent {
+        component: String,
+        field: String,
+        value: String,
+    },
+    SetResource {
+        resource: String,
+        field: String,
+        value: String,
+    },
+}
+
+impl MacroStep {
+    /// The value the step sets, if it sets one.
+    fn target(&self) -> Option<(&str, &str)> {
+        match self {
+            MacroStep::SetComponent {
+                component, field, ..
+            } => Some((component, field)),
+            MacroStep::SetResource {
+                resource, field, ..
+            } => Some((resource, field)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditorMacro {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+}
+
+/// The macros recorded so far, also listed in the command palette.
+#[derive(Resource, Default)]
+pub struct EditorMacros {
+    macros: Vec<EditorMacro>,
+    /// The steps recorded so far, while recording.
+    recording: Option<Vec<MacroStep>>,
+    /// Where the steps of the edit recorded last start, which are replaced rather than added to
+    /// while the edit goes on, like while dragging a value.
+    edit_start: usize,
+    /// The name the macro recorded is saved as.
+    pub new_name: String,
+    queued: Vec<String>,
+    /// Set by the command toggling the recording, which can't list the macro recorded in the
+    /// command palette while it runs.
+    toggle_recording: bool,
+    /// Whether a macro played this frame, whose edits aren't recorded.
+    played: bool,
+}
+
+impl EditorMacros {
+    fn load() -> Self {
+        if !Path::new(MACROS_PATH).exists() {
+            return Self::default();
+        }
+        let macros = fs::read_to_string(MACROS_PATH)
+            .map_err(|e| e.to_string())
+            .and_then(|ron| ron::from_str(&ron).map_err(|e| e.to_string()));
+        match macros {
+            Ok(macros) => Self {
+                macros,
+                ..default()
+            },
+            Err(e) => {
+                warn!("Failed to load macros from {MACROS_PATH}: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) {
+        let result = ron::ser::to_string_pretty(&self.macros, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|ron| fs::write(MACROS_PATH, ron).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            error!("Failed to save macros to {MACROS_PATH}: {e}");
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.macros.iter().map(|m| m.name.clone()).collect()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn start_recording(&mut self) {
+        info!("Recording a macro");
+        self.recording = Some(Vec::new());
+        self.edit_start = 0;
+    }
+
+    /// Adds `step` to the macro being recorded, unless a macro is playing it.
+    pub fn record(&mut self, step: MacroStep) {
+        if self.played || matches!(&step, MacroStep::Command(name) if name == TOGGLE_COMMAND) {
+            return;
+        }
+        if let Some(steps) = &mut self.recording {
+            steps.push(step);
+            self.edit_start = steps.len();
+        }
+    }
+
+    /// Adds the values set by an edit, replacing those of the same edit recorded on the last
+    /// frames if `continued`.
+    fn record_edit(&mut self, steps: Vec<MacroStep>, continued: bool) {
+        let Some(recording) = &mut self.recording else {
+            return;
+        };
+        if !continued {
+            self.edit_start = recording.len();
+        }
+        for step in steps {
+            let same = recording[self.edit_start..]
+                .iter_mut()
+                .find(|recorded| recorded.target() == step.target());
+            match same {
+                Some(recorded) => *recorded = step,
+                None => recording.push(step),
+            }
+        }
+    }
+
+    /// Queues the macro `name` to play once the editor UI is drawn.
+    pub fn play(&mut self, name: impl Into<String>) {
+        self.queued.push(name.into());
+    }
+}
+
+fn queue_macro(name: String) -> impl Fn(&mut World) + Send + Sync {
+    move |world| world.resource_mut::<EditorMacros>().play(name.clone())
+}
+
+/// Saves the steps recorded as the macro `name`, replacing the macro already named so, and
+/// lists it in the command palette. Given no name, it's named after the number of macros.
+pub fn stop_recording(world: &mut World, name: String) {
+    let mut macros = world.resource_mut::<EditorMacros>();
+    let Some(steps) = macros.recording.take() else {
+        return;
+    };
+    let name = match name.is_empty() {
+        true => format!("Macro {}", macros.macros.len() + 1),
+        false => name,
+    };
+    info!("Recorded the macro {name} with {} steps", steps.len());
+    match macros.macros.iter_mut().find(|m| m.name == name) {
+        Some(recorded) => recorded.steps = steps,
+        None => macros.macros.push(EditorMacro {
+            name: name.clone(),
+            steps,
+        }),
+    }
+    macros.new_name.clear();
+    macros.save();
+
+    let command = format!("{COMMAND_PREFIX}{name}");
+    let mut commands = world.resource_mut::<EditorCommands>();
+    if !commands.iter().any(|other| other.name == command) {
+        commands.add(command, queue_macro(name));
+    }
+}
+
+pub fn remove_macro(world: &mut World, name: &str) {
+    let mut macros = world.resource_mut::<EditorMacros>();
+    macros.macros.retain(|m| m.name != name);
+    macros.save();
+    world
+        .resource_mut::<EditorCommands>()
+        .remove(&format!("{COMMAND_PREFIX}{name}"));
+}
+
+/// The fields of `after` which differ from `before`, or the whole value if it has no named
+/// fields.
+fn changed_fields<'a>(
+    before: &dyn Reflect,
+    after: &'a dyn Reflect,
+) -> Vec<(String, &'a dyn Reflect)> {
+    let (ReflectRef::Struct(before), ReflectRef::Struct(after_struct)) =
+        (before.reflect_ref(), after.reflect_ref())
+    else {
+        return vec![(String::new(), after)];
+    };
+    (0..after_struct.field_len())
+        .filter_map(|index| {
+            let name = after_struct.name_at(index)?;
+            let value = after_struct.field_at(index)?;
+            let changed = before
+                .field(name)
+                .is_none_or(|before| history::reflect_changed(before, value));
+            changed.then(|| (name.to_owned(), value))
+        })
+        .collect()
+}
+
+fn set_steps(
+    type_registry: &TypeRegistry,
+    target: EditTarget,
+    type_name: &str,
+    before: &dyn Reflect,
+    after: &dyn Reflect,
+) -> Vec<MacroStep> {
+    changed_fields(before, after)
+        .into_iter()
+        .filter_map(|(field, value)| {
+            let value = match ron::to_string(&TypedReflectSerializer::new(value, type_registry)) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("Failed to record {type_name}.{field} in the macro: {e}");
+                    return None;
+                }
+            };
+            Some(match target {
+                EditTarget::Component { .. } => MacroStep::SetComponent {
+                    component: type_name.to_owned(),
+                    field,
+                    value,
+                },
+                EditTarget::Resource { .. } => MacroStep::SetResource {
+                    resource: type_name.to_owned(),
+                    field,
+                    value,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Records the values edited this frame in the macro being recorded.
+fn record_edits(world: &mut World) {
+    let macros = world.resource::<EditorMacros>();
+    if !macros.is_recording() || macros.played {
+        world.resource_mut::<EditorMacros>().played = false;
+        return;
+    }
+    let history = world.resource::<EditorHistory>();
+    let Some(change) = history.last_change() else {
+        return;
+    };
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let mut steps = Vec::new();
+    let mut targets = history.changed().to_vec();
+    targets.dedup();
+    for target in targets {
+        // only edits of values are replayed, not added components
+        let Some(before) = change
+            .changes
+            .iter()
+            .find(|change| change.target == target)
+            .and_then(|change| change.before.as_deref())
+        else {
+            continue;
+        };
+        let (type_id, after) = match target {
+            EditTarget::Component { entity, type_id } => (
+                type_id,
+                type_registry
+                    .get_type_data::<ReflectComponent>(type_id)
+                    .and_then(|reflect_component| {
+                        reflect_component.reflect(world.get_entity(entity)?)
+                    }),
+            ),
+            EditTarget::Resource { type_id } => (
+                type_id,
+                type_registry
+                    .get_type_data::<ReflectResource>(type_id)
+                    .and_then(|reflect_resource| reflect_resource.reflect(world)),
+            ),
+        };
+        let (Some(after), Some(registration)) = (after, type_registry.get(type_id)) else {
+            continue;
+        };
+        steps.extend(set_steps(
+            &type_registry,
+            target,
+            registration.type_name(),
+            before,
+            after,
+        ));
+    }
+    let continued = history.merging();
+    drop(type_registry);
+    if !steps.is_empty() {
+        world
+            .resource_mut::<EditorMacros>()
+            .record_edit(steps, continued);
+    }
+}
+
+fn deserialize_value(
+    type_registry: &TypeRegistry,
+    type_name: &str,
+    value: &str,
+) -> Result<Box<dyn Reflect>, String> {
+    let registration = type_registry
+        .get_with_name(type_name)
+        .ok_or_else(|| format!("`{type_name}` isn't registered"))?;
+    let mut deserializer =
+        ron::de::Deserializer::from_str(value).map_err(|e| format!("Invalid value: {e}"))?;
+    TypedReflectDeserializer::new(registration, type_registry)
+        .deserialize(&mut deserializer)
+        .map_err(|e| format!("Invalid value: {e}"))
+}
+
+/// Sets `field` of `component` of `entity` to `value` written in RON, or the component if
+/// `field` is empty, inserting it if the entity has none. Recorded in the history.
+pub(crate) fn set_component_field(
+    world: &mut World,
+    entity: Entity,
+    component: &str,
+    field: &str,
+    value: &str,
+) -> Result<(), String> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let registration = query::resolve(&type_registry, component)?;
+    let reflect_component = registration
+        .data::<ReflectComponent>()
+        .ok_or_else(|| format!("`{component}` isn't a component"))?;
+    let name = registration.short_name();
+
+    // the type of a field is only known from a value
+    let type_name = match reflect_component.reflect(world.entity(entity)) {
+        Some(component) if !field.is_empty() => component
+            .reflect_path(field)
+            .map_err(|e| format!("No field `{field}` in {name}: {e}"))?
+            .type_name(),
+        Some(_) => registration.type_name(),
+        None if field.is_empty() => registration.type_name(),
+        None => return Err(format!("{entity:?} has no {name}")),
+    };
+    let value = deserialize_value(&type_registry, type_name, value.trim())?;
+
+    let before = history::snapshot_components(world, entity);
+    let mut entity_mut = world.entity_mut(entity);
+    match reflect_component.reflect_mut(&mut entity_mut) {
+        Some(mut component) => {
+            let component: &mut dyn Reflect = &mut *component;
+            match field {
+                "" => component.apply(&*value),
+                field => component
+                    .reflect_path_mut(field)
+                    .map_err(|e| format!("No field `{field}` in {name}: {e}"))?
+                    .apply(&*value),
+            }
+        }
+        None => reflect_component.insert(&mut entity_mut, &*value),
+    }
+    history::record_component_changes(world, entity, before);
+    Ok(())
+}
+
+fn set_resource_field(
+    world: &mut World,
+    resource: &str,
+    field: &str,
+    value: &str,
+) -> Result<(), String> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let registration = query::resolve(&type_registry, resource)?;
+    let reflect_resource = registration
+        .data::<ReflectResource>()
+        .ok_or_else(|| format!("`{resource}` isn't a resource"))?;
+    let current = reflect_resource
+        .reflect(world)
+        .ok_or_else(|| format!("No {} resource", registration.short_name()))?;
+    let type_name = match field {
+        "" => registration.type_name(),
+        field => current
+            .reflect_path(field)
+            .map_err(|e| format!("No field `{field}`: {e}"))?
+            .type_name(),
+    };
+    let value = deserialize_value(&type_registry, type_name, value.trim())?;
+
+    let type_id = registration.type_id();
+    let before = history::snapshot_resource(world, type_id);
+    if let Some(mut current) = reflect_resource.reflect_mut(world) {
+        let current: &mut dyn Reflect = &mut *current;
+        match field {
+            "" => current.apply(&*value),
+            field => current
+                .reflect_path_mut(field)
+                .map_err(|e| format!("No field `{field}`: {e}"))?
+                .apply(&*value),
+        }
+    }
+    history::record_resource_change(world, type_id, before);
+    Ok(())
+}
+
+fn play_step(world: &mut World, step: &MacroStep, depth: usize) -> Result<(), String> {
+    match step {
+        MacroStep::Command(name) => match name.strip_prefix(COMMAND_PREFIX) {
+            Some(name) => play_macro(world, name, depth + 1),
+            None if commands::run_command(world, name) => Ok(()),
+            None => Err(format!("No command is named `{name}`")),
+        },
+        MacroStep::Spawn { preset, child } => {
+            let preset = EntityPreset::ALL
+                .into_iter()
+                .find(|other| other.name() == preset)
+                .ok_or_else(|| format!("No preset is named `{preset}`"))?;
+            let ui_state = world.resource::<UiState>();
+            let parent = match child {
+                true => Some(
+                    ui_state
+                        .selected_entities
+                        .iter()
+                        .next()
+                        .ok_or("Nothing is selected to spawn the child of")?,
+                ),
+                false => None,
+            };
+            let entity = preset.spawn(world, parent);
+            let mut ui_state = world.resource_mut::<UiState>();
+            ui_state.selected_entities.select_replace(entity);
+            ui_state.selection = InspectorSelection::Entities;
+            Ok(())
+        }
+        MacroStep::SetComponent {
+            component,
+            field,
+            value,
+        } => {
+            let selected = world
+                .resource::<UiState>()
+                .selected_entities
+                .as_slice()
+                .to_vec();
+            for entity in selected {
+                set_component_field(world, entity, component, field, value)?;
+            }
+            Ok(())
+        }
+        MacroStep::SetResource {
+            resource,
+            field,
+            value,
+        } => set_resource_field(world, resource, field, value),
+    }
+}
+
+fn play_macro(world: &mut World, name: &str, depth: usize) -> Result<(), String> {
+    if depth > MAX_DEPTH {
+        return Err(format!(
+            "Macros play each other more than {MAX_DEPTH} times"
+        ));
+    }
+    let steps = world
+        .resource::<EditorMacros>()
+        .macros
+        .iter()
+        .find(|m| m.name == name)
+        .ok_or_else(|| format!("No macro is named {name}"))?
+        .steps
+        .clone();
+    for (index, step) in steps.iter().enumerate() {
+        play_step(world, step, depth).map_err(|e| format!("step {}: {e}", index + 1))?;
+    }
+    Ok(())
+}
+
+fn update_macros(world: &mut World) {
+    let mut macros = world.resource_mut::<EditorMacros>();
+    if std::mem::take(&mut macros.toggle_recording) {
+        match macros.is_recording() {
+            true => {
+                let name = macros.new_name.trim().to_owned();
+                stop_recording(world, name);
+            }
+            false => macros.start_recording(),
+        }
+    }
+
+    let queued = std::mem::take(&mut world.resource_mut::<EditorMacros>().queued);
+    for name in queued {
+        info!("Playing the macro {name}");
+        world.resource_mut::<EditorMacros>().played = true;
+        if let Err(e) = play_macro(world, &name, 0) {
+            error!("Failed to play the macro {name}: {e}");
+        }
+    }
+}
+
+/// Starts and stops recording macros, and plays the macro clicked.
+pub fn macros_menu(world: &mut World, ui: &mut egui::Ui) {
+    let mut macros = world.resource_mut::<EditorMacros>();
+    match macros.recording.as_ref().map(Vec::len) {
+        Some(steps) => {
+            ui.label(format!("⏺ Recording, {steps} steps"));
+            ui.add(
+                egui::TextEdit::singleline(&mut macros.new_name)
+                    .hint_text("Name")
+                    .desired_width(120.0),
+            );
+            ui.horizontal(|ui| {
+                if ui.button("Stop Recording").clicked() {
+                    macros.toggle_recording = true;
+                    ui.close_menu();
+                }
+                if ui.button("Cancel").clicked() {
+                    macros.recording = None;
+                    ui.close_menu();
+                }
+            });
+        }
+        None => {
+            if ui
+                .button("Start Recording")
+                .on_hover_text("Record the commands run, entities spawned and values edited")
+                .clicked()
+            {
+                macros.start_recording();
+                ui.close_menu();
+            }
+        }
+    }
+    ui.separator();
+
+    let macros = world.resource::<EditorMacros>();
+    if macros.macros.is_empty() {
+        ui.weak("No recorded macro");
+    }
+    let mut remove = None;
+    for name in macros.names() {
+        ui.horizontal(|ui| {
+            if ui
+                .button(&name)
+                .on_hover_text("Play on the selection")
+                .clicked()
+            {
+                world.resource_mut::<EditorMacros>().play(name.clone());
+                ui.close_menu();
+            }
+            if ui.small_button("🗑").on_hover_text("Delete macro").clicked() {
+                remove = Some(name.clone());
+            }
+        });
+    }
+    if let Some(name) = remove {
+        remove_macro(world, &name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::editor_id::EditorIds;
+
+    use super::*;
+
+    fn set(field: &str, value: &str) -> MacroStep {
+        MacroStep::SetComponent {
+            component: "Transform".to_owned(),
+            field: field.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    fn recorded(macros: &EditorMacros) -> Vec<String> {
+        macros
+            .recording
+            .iter()
+            .flatten()
+            .map(|step| format!("{step:?}"))
+            .collect()
+    }
+
+    #[test]
+    fn continued_edits_replace_their_steps() {
+        let mut macros = EditorMacros::default();
+        macros.start_recording();
+        macros.record_edit(vec![set("translation", "1")], false);
+        macros.record_edit(vec![set("translation", "2"), set("scale", "3")], true);
+        assert_eq!(
+            recorded(&macros),
+            [set("translation", "2"), set("scale", "3")].map(|step| format!("{step:?}"))
+        );
+
+        // a new edit of the same field is a step of its own
+        macros.record_edit(vec![set("translation", "4")], false);
+        assert_eq!(recorded(&macros).len(), 3);
+        macros.record(MacroStep::Command("Undo".to_owned()));
+        macros.record_edit(vec![set("translation", "5")], true);
+        assert_eq!(recorded(&macros).len(), 5);
+    }
+
+    #[test]
+    fn leaves_out_toggling_and_played_steps() {
+        let mut macros = EditorMacros::default();
+        macros.record(MacroStep::Command("Undo".to_owned()));
+        assert!(!macros.is_recording());
+
+        macros.start_recording();
+        macros.record(MacroStep::Command(TOGGLE_COMMAND.to_owned()));
+        macros.played = true;
+        macros.record(MacroStep::Command("Undo".to_owned()));
+        assert!(recorded(&macros).is_empty());
+    }
+
+    fn type_registry() -> AppTypeRegistry {
+        let type_registry = AppTypeRegistry::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Transform>();
+            type_registry.register::<Vec3>();
+            type_registry.register::<Quat>();
+            type_registry.register::<Visibility>();
+        }
+        type_registry
+    }
+
+    #[test]
+    fn records_the_fields_which_changed() {
+        let type_registry = type_registry();
+        let type_registry = type_registry.read();
+        let target = EditTarget::Component {
+            entity: Entity::PLACEHOLDER,
+            type_id: std::any::TypeId::of::<Transform>(),
+        };
+        let before = Transform::from_xyz(1.0, 0.0, 0.0);
+        let after = before.with_scale(Vec3::splat(2.0));
+        let steps = set_steps(&type_registry, target, "Transform", &before, &after);
+        assert_eq!(
+            format!("{steps:?}"),
+            format!("{:?}", [set("scale", "(x:2.0,y:2.0,z:2.0)")])
+        );
+
+        // values without named fields are set whole
+        let steps = set_steps(
+            &type_registry,
+            target,
+            "Visibility",
+            &Visibility::Hidden,
+            &Visibility::Visible,
+        );
+        let [MacroStep::SetComponent { field, value, .. }] = &steps[..] else {
+            panic!("the visibility is set");
+        };
+        assert_eq!((field.as_str(), value.as_str()), ("", "Visible"));
+    }
+
+    fn world_with(steps: Vec<MacroStep>) -> (World, [Entity; 2]) {
+        let mut world = World::new();
+        world.insert_resource(type_registry());
+        world.init_resource::<EditorHistory>();
+        world.init_resource::<EditorIds>();
+        world.insert_resource(EditorMacros {
+            macros: vec![
+                EditorMacro {
+                    name: "Move".to_owned(),
+                    steps,
+                },
+                EditorMacro {
+                    name: "Loop".to_owned(),
+                    steps: vec![MacroStep::Command(format!("{COMMAND_PREFIX}Loop"))],
+                },
+            ],
+            ..default()
+        });
+        let entities = [
+            world.spawn(Transform::default()).id(),
+            world.spawn(Transform::from_xyz(0.0, 5.0, 0.0)).id(),
+        ];
+        let mut ui_state = UiState::new();
+        for entity in entities {
+            ui_state.selected_entities.select_maybe_add(entity, true);
+        }
+        world.insert_resource(ui_state);
+        (world, entities)
+    }
+
+    #[test]
+    fn plays_steps_on_the_selection() {
+        let (mut world, [a, b]) = world_with(vec![
+            set("translation.x", "3.0"),
+            set("scale", "(x: 2.0, y: 2.0, z: 2.0)"),
+        ]);
+        play_macro(&mut world, "Move", 0).unwrap();
+        for (entity, y) in [(a, 0.0), (b, 5.0)] {
+            let transform = world.get::<Transform>(entity).unwrap();
+            assert_eq!(transform.translation, Vec3::new(3.0, y, 0.0));
+            assert_eq!(transform.scale, Vec3::splat(2.0));
+        }
+        assert!(!world.resource::<EditorHistory>().changed().is_empty());
+    }
+
+    #[test]
+    fn explains_the_step_which_failed() {
+        let (mut world, _) = world_with(vec![set("translation.x", "3.0"), set("offset", "1.0")]);
+        let error = play_macro(&mut world, "Move", 0).unwrap_err();
+        assert!(
+            error.starts_with("step 2: No field `offset` in Transform"),
+            "{error}"
+        );
+
+        let (mut world, _) = world_with(vec![set("translation.x", "\"far\"")]);
+        let error = play_macro(&mut world, "Move", 0).unwrap_err();
+        assert!(error.starts_with("step 1: Invalid value"), "{error}");
+
+        assert_eq!(
+            play_macro(&mut world, "Jump", 0),
+            Err("No macro is named Jump".to_owned())
+        );
+    }
+
+    #[test]
+    fn stops_macros_playing_themselves() {
+        let (mut world, _) = world_with(Vec::new());
+        let error = play_macro(&mut world, "Loop", 0).unwrap_err();
+        assert!(
+            error.ends_with(&format!(
+                "Macros play each other more than {MAX_DEPTH} times"
+            )),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use bevy::{math::Ray, prelude::*};
+use bevy_inspector_egui::bevy_egui::EguiSettings;
+
+use crate::{mode2d::EditorView2d, picking, snap};
+
+const MEASURE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 40);
+const AXIS_COLORS: [egui::Color32; 3] = [
+    egui::Color32::from_rgb(230, 70, 70),
+    egui::Color32::from_rgb(110, 200, 70),
+    egui::Color32::from_rgb(70, 130, 240),
+];
+
+pub struct EditorMeasurePlugin;
+impl Plugin for EditorMeasurePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MeasureTool>();
+    }
+}
+
+/// Measures the distance between two points clicked in a game view.
+#[derive(Resource, Default)]
+pub struct MeasureTool {
+    pub active: bool,
+    pub start: Option<Vec3>,
+    pub end: Option<Vec3>,
+}
+
+pub fn measure_toolbar(ui: &mut egui::Ui, world: &mut World) {
+    let mut tool = world.resource_mut::<MeasureTool>();
+    let response = ui
+        .toggle_value(&mut tool.active, "📏")
+        .on_hover_text("Measure the distance between two clicked points");
+    if response.changed() && !tool.active {
+        tool.start = None;
+        tool.end = None;
+    }
+}
+
+/// The point under the cursor along `ray`: on the closest surface, snapped to its nearest mesh
+/// vertex with `vertex`, or on the ground plane if it misses everything.
+fn measured_point(world: &mut World, camera: Entity, ray: Ray, vertex: bool) -> Option<Vec3> {
+    if let Some(hit) = snap::raycast_surface(world, ray, &[]) {
+        let nearest = snap::mesh_vertices(world, hit.entity)
+            .into_iter()
+            .min_by(|a, b| {
+                a.distance_squared(hit.point)
+                    .total_cmp(&b.distance_squared(hit.point))
+            });
+        return Some(nearest.filter(|_| vertex).unwrap_or(hit.point));
+    }
+    // the XY plane in 2D mode
+    let normal = match world.get::<EditorView2d>(camera) {
+        Some(_) => Vec3::Z,
+        None => Vec3::Y,
+    };
+    ray.intersect_plane(Vec3::ZERO, normal)
+        .map(|distance| ray.get_point(distance))
+}
+
+/// While the measure tool is active, sets its points by clicking in the game view of `camera`
+/// and draws the distance between them with its offsets along each axis. Ctrl snaps points to
+/// mesh vertices and Escape clears them.
+///
+/// Returns whether the tool is active, in which case it owns clicks in the view.
+pub fn measure_ui(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+) -> bool {
+    if !world.resource::<MeasureTool>().active {
+        return false;
+    }
+    let scale_factor = world.resource::<EguiSettings>().scale_factor as f32;
+
+    let response = ui.interact(
+        viewport_rect,
+        egui::Id::new(("measure", camera)),
+        egui::Sense::click(),
+    );
+    let (vertex, clear) =
+        ui.input(|input| (input.modifiers.ctrl, input.key_pressed(egui::Key::Escape)));
+    let hovered = response
+        .hover_pos()
+        .and_then(|pos| picking::cursor_ray(world, camera, viewport_rect, pos))
+        .and_then(|ray| measured_point(world, camera, ray, vertex));
+
+    let mut tool = world.resource_mut::<MeasureTool>();
+    if clear {
+        tool.start = None;
+        tool.end = None;
+    }
+    if let Some(point) = hovered.filter(|_| response.clicked()) {
+        match (tool.start, tool.end) {
+            (Some(_), None) => tool.end = Some(point),
+            _ => {
+                tool.start = Some(point);
+                tool.end = None;
+            }
+        }
+    }
+    let (start, end) = (tool.start, tool.end.or(hovered));
+
+    let Ok((camera_component, camera_transform)) = world
+        .query::<(&Camera, &GlobalTransform)>()
+        .get(world, camera)
+    else {
+        return true;
+    };
+    let to_egui = |point: Vec3| {
+        camera_component
+            .world_to_viewport(camera_transform, point)
+            .map(|position| viewport_rect.min + egui::vec2(position.x, position.y) / scale_factor)
+    };
+
+    let painter = ui.painter_at(viewport_rect);
+    if let Some(pos) = hovered.and_then(to_egui) {
+        painter.circle_stroke(pos, 4.0, (1.5, MEASURE_COLOR));
+    }
+    let (Some(start), Some(end)) = (start, end) else {
+        return true;
+    };
+
+    // legs along each axis from the start to the end point
+    let mut corner = start;
+    for (axis, color) in AXIS_COLORS.into_iter().enumerate() {
+        let mut next = corner;
+        next[axis] = end[axis];
+        if let (Some(a), Some(b)) = (to_egui(corner), to_egui(next)) {
+            painter.line_segment([a, b], (1.0, color));
+        }
+        corner = next;
+    }
+    let (Some(a), Some(b)) = (to_egui(start), to_egui(end)) else {
+        return true;
+    };
+    painter.line_segment([a, b], (2.0, MEASURE_COLOR));
+    painter.circle_filled(a, 3.0, MEASURE_COLOR);
+    painter.circle_filled(b, 3.0, MEASURE_COLOR);
+
+    let delta = end - start;
+    let text = format!(
+        "{:.3}\nx {:.3}  y {:.3}  z {:.3}",
+        delta.length(),
+        delta.x,
+        delta.y,
+        delta.z
+    );
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(12.0), egui::Color32::WHITE);
+    let rect = egui::Rect::from_center_size(a.lerp(b, 0.5), galley.size()).expand(4.0);
+    painter.rect_filled(rect, 3.0, egui::Color32::from_black_alpha(180));
+    painter.galley(rect.min + egui::vec2(4.0, 4.0), galley);
+    true
+}
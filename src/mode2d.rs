@@ -0,0 +1,106 @@
+use bevy::{prelude::*, render::camera::ScalingMode};
+
+use crate::{
+    camera::{self, EditorCameraController},
+    views::AxisView,
+    UiState,
+};
+
+/// Distance in front of the XY plane at which editor cameras are placed in 2D mode.
+const CAMERA_DISTANCE_2D: f32 = 500.0;
+
+pub struct EditorMode2dPlugin;
+impl Plugin for EditorMode2dPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PostStartup, detect_2d_camera).add_systems(
+            Update,
+            apply_2d_mode.before(camera::editor_camera_controller),
+        );
+    }
+}
+
+/// Marks editor cameras looking at the XY plane in 2D mode, holding what they looked like
+/// before so leaving 2D mode restores it.
+#[derive(Component)]
+pub struct EditorView2d {
+    transform: Transform,
+    projection: Option<Projection>,
+    focus: Vec3,
+}
+
+/// Starts in 2D mode if the game renders with a 2D camera.
+fn detect_2d_camera(mut ui_state: ResMut<UiState>, cameras: Query<(), With<Camera2d>>) {
+    if !cameras.is_empty() {
+        ui_state.mode_2d = true;
+    }
+}
+
+/// Switches the editor cameras between looking at the XY plane orthographically in 2D mode and
+/// their own view otherwise. Axis views keep looking along their axis.
+#[allow(clippy::type_complexity)]
+fn apply_2d_mode(
+    mut commands: Commands,
+    ui_state: Res<UiState>,
+    mut cameras: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut EditorCameraController,
+            Option<&mut Projection>,
+            Option<&EditorView2d>,
+        ),
+        Without<AxisView>,
+    >,
+) {
+    for (entity, mut transform, mut controller, projection, view_2d) in &mut cameras {
+        match (ui_state.mode_2d, view_2d) {
+            (true, None) => {
+                commands.entity(entity).insert(EditorView2d {
+                    transform: *transform,
+                    projection: projection.as_deref().cloned(),
+                    focus: controller.focus,
+                });
+                let focus = controller.focus;
+                // 2D cameras already look down the Z axis from in front of the scene
+                if let Some(mut projection) = projection {
+                    let height = transform.translation.distance(focus).max(1.0);
+                    *projection = Projection::Orthographic(OrthographicProjection {
+                        far: CAMERA_DISTANCE_2D * 2.0,
+                        scaling_mode: ScalingMode::FixedVertical(height),
+                        ..default()
+                    });
+                    transform.translation = Vec3::new(focus.x, focus.y, CAMERA_DISTANCE_2D);
+                }
+                transform.rotation = Quat::IDENTITY;
+                controller.focus = Vec3::new(transform.translation.x, transform.translation.y, 0.0);
+            }
+            (false, Some(view_2d)) => {
+                *transform = view_2d.transform;
+                if let (Some(mut projection), Some(saved)) = (projection, &view_2d.projection) {
+                    *projection = saved.clone();
+                }
+                controller.focus = view_2d.focus;
+                commands.entity(entity).remove::<EditorView2d>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Constrains a gizmo manipulation in 2D mode from `before` to `after` to the XY plane: no
+/// movement along or scaling of Z, and rotation around Z only.
+pub fn constrain_to_xy(before: Mat4, after: Mat4) -> Mat4 {
+    let (scale_before, rotation_before, translation_before) =
+        before.to_scale_rotation_translation();
+    let (mut scale, rotation, mut translation) = after.to_scale_rotation_translation();
+    translation.z = translation_before.z;
+    scale.z = scale_before.z;
+    // the twist of the rotation delta around Z
+    let delta = rotation * rotation_before.inverse();
+    let twist = Quat::from_xyzw(0.0, 0.0, delta.z, delta.w).normalize();
+    let twist = match twist.is_finite() {
+        true => twist,
+        false => Quat::IDENTITY,
+    };
+    Mat4::from_scale_rotation_translation(scale, twist * rotation_before, translation)
+}
@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+
+use crate::{
+    input::{EditorAction, EditorKeybinds},
+    EditorResource, InspectorSelection, UiState,
+};
+
+const MAX_LEN: usize = 100;
+
+pub struct EditorNavigationPlugin;
+impl Plugin for EditorNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, navigate_selection);
+    }
+}
+
+/// What was inspected before, to go back and forward through like in a browser.
+#[derive(Default)]
+pub(crate) struct SelectionHistory {
+    entries: Vec<(InspectorSelection, Vec<Entity>)>,
+    current: usize,
+}
+
+impl SelectionHistory {
+    /// Adds the selection if it changed since the last call, dropping the entries ahead of the
+    /// current one.
+    pub(crate) fn record(
+        &mut self,
+        world: &World,
+        selection: &InspectorSelection,
+        entities: &SelectedEntities,
+    ) {
+        if *selection == InspectorSelection::Entities && entities.is_empty() {
+            return;
+        }
+        let entities = entities.as_slice();
+        // the entities despawned since aren't selected by going to the entry
+        let unchanged =
+            self.entries
+                .get(self.current)
+                .is_some_and(|(current, current_entities)| {
+                    current == selection
+                        && current_entities
+                            .iter()
+                            .filter(|&&entity| world.get_entity(entity).is_some())
+                            .eq(entities)
+                });
+        if unchanged {
+            return;
+        }
+        self.entries.truncate(self.current + 1);
+        self.entries.push((selection.clone(), entities.to_vec()));
+        if self.entries.len() > MAX_LEN {
+            self.entries.remove(0);
+        }
+        self.current = self.entries.len() - 1;
+    }
+
+    pub(crate) fn can_go(&self, offset: isize) -> bool {
+        self.current
+            .checked_add_signed(offset)
+            .is_some_and(|index| index < self.entries.len())
+    }
+
+    /// Moves `offset` entries back or forward, restoring the selection there.
+    pub(crate) fn go(
+        &mut self,
+        offset: isize,
+        world: &World,
+        selection: &mut InspectorSelection,
+        selected_entities: &mut SelectedEntities,
+    ) {
+        if !self.can_go(offset) {
+            return;
+        }
+        self.current = self.current.checked_add_signed(offset).unwrap();
+        let (entry, entities) = &self.entries[self.current];
+        *selection = entry.clone();
+        selected_entities.clear();
+        for &entity in entities {
+            if world.get_entity(entity).is_some() {
+                selected_entities.select_maybe_add(entity, true);
+            }
+        }
+    }
+}
+
+/// Back and forward buttons, shown in the inspector.
+pub(crate) fn navigation_buttons(
+    ui: &mut egui::Ui,
+    world: &World,
+    history: &mut SelectionHistory,
+    selection: &mut InspectorSelection,
+    selected_entities: &mut SelectedEntities,
+) {
+    let keybinds = world.resource::<EditorKeybinds>();
+    for (offset, icon, hover, action) in [
+        (-1, "⏴", "Back", EditorAction::SelectionBack),
+        (1, "⏵", "Forward", EditorAction::SelectionForward),
+    ] {
+        let hover = format!("{hover} ({})", keybinds.chord(action));
+        if ui
+            .add_enabled(history.can_go(offset), egui::Button::new(icon).small())
+            .on_hover_text(hover)
+            .clicked()
+        {
+            history.go(offset, world, selection, selected_entities);
+        }
+    }
+}
+
+/// Goes `offset` entries back or forward through the selection history.
+pub fn go(world: &mut World, offset: isize) {
+    world.resource_scope::<UiState, _>(|world, mut ui_state| {
+        let ui_state = &mut *ui_state;
+        ui_state.navigation.go(
+            offset,
+            world,
+            &mut ui_state.selection,
+            &mut ui_state.selected_entities,
+        );
+    });
+}
+
+fn navigate_selection(world: &mut World) {
+    if !world.resource::<EditorResource>().0 {
+        return;
+    }
+    let actions = world.resource::<Input<EditorAction>>();
+    if actions.just_pressed(EditorAction::SelectionBack) {
+        go(world, -1);
+    } else if actions.just_pressed(EditorAction::SelectionForward) {
+        go(world, 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(entities: &[Entity]) -> SelectedEntities {
+        let mut selected = SelectedEntities::default();
+        for &entity in entities {
+            selected.select_maybe_add(entity, true);
+        }
+        selected
+    }
+
+    #[test]
+    fn goes_back_and_forward() {
+        let mut world = World::new();
+        let [a, b, c] = [(); 3].map(|_| world.spawn_empty().id());
+        let mut history = SelectionHistory::default();
+        let mut selection = InspectorSelection::Entities;
+        for entity in [a, b, c] {
+            history.record(&world, &selection, &select(&[entity]));
+        }
+        assert!(!history.can_go(1));
+
+        let mut selected = select(&[c]);
+        history.go(-1, &world, &mut selection, &mut selected);
+        assert_eq!(selected.as_slice(), &[b]);
+        history.record(&world, &selection, &selected);
+        history.go(-1, &world, &mut selection, &mut selected);
+        assert_eq!(selected.as_slice(), &[a]);
+        assert!(!history.can_go(-1));
+        history.go(2, &world, &mut selection, &mut selected);
+        assert_eq!(selected.as_slice(), &[c]);
+    }
+
+    #[test]
+    fn selecting_after_going_back_drops_the_entries_ahead() {
+        let mut world = World::new();
+        let [a, b, c] = [(); 3].map(|_| world.spawn_empty().id());
+        let mut history = SelectionHistory::default();
+        let mut selection = InspectorSelection::Entities;
+        history.record(&world, &selection, &select(&[a]));
+        history.record(&world, &selection, &select(&[b]));
+
+        let mut selected = select(&[b]);
+        history.go(-1, &world, &mut selection, &mut selected);
+        history.record(&world, &selection, &select(&[c]));
+        assert!(!history.can_go(1));
+        history.go(-1, &world, &mut selection, &mut selected);
+        assert_eq!(selected.as_slice(), &[a]);
+    }
+
+    #[test]
+    fn forward_still_works_after_going_back_to_despawned_entities() {
+        let mut world = World::new();
+        let [a, b, c] = [(); 3].map(|_| world.spawn_empty().id());
+        let mut history = SelectionHistory::default();
+        let mut selection = InspectorSelection::Entities;
+        history.record(&world, &selection, &select(&[a, b]));
+        history.record(&world, &selection, &select(&[c]));
+        world.despawn(b);
+
+        let mut selected = select(&[c]);
+        history.go(-1, &world, &mut selection, &mut selected);
+        assert_eq!(selected.as_slice(), &[a]);
+        // the next frame records the selection shown
+        history.record(&world, &selection, &selected);
+        assert!(history.can_go(1));
+        history.go(1, &world, &mut selection, &mut selected);
+        assert_eq!(selected.as_slice(), &[c]);
+    }
+}
@@ -0,0 +1,257 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+
+use crate::{
+    asset_browser::{ASSET_DIR, SCENE_EXTENSION},
+    hierarchy::{self, EditorHidden, EditorLocked},
+    scene::{self, SceneFileError},
+};
+
+pub struct EditorNestedScenePlugin;
+impl Plugin for EditorNestedScenePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<NestedScene>()
+            .init_resource::<SceneIsolation>()
+            .add_systems(Last, track_nested_scenes);
+    }
+}
+
+/// Marks an entity the scene asset at `path`, relative to the asset folder, is spawned under.
+/// Its entities aren't saved with the scene of the instance, they're spawned from the asset again
+/// when it's loaded.
+#[derive(Debug, Clone, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct NestedScene {
+    pub path: String,
+}
+
+impl NestedScene {
+    /// Whether the asset is a scene file the editor can write, rather than a part of a model.
+    pub fn editable(&self) -> bool {
+        self.path.ends_with(SCENE_EXTENSION)
+    }
+}
+
+/// Marks the entities spawned from the asset of a [`NestedScene`], which are locked unless the
+/// nested scene is open for editing.
+#[derive(Component)]
+pub(crate) struct NestedSceneEntity {
+    /// The closest [`NestedScene`] ancestor.
+    pub root: Entity,
+}
+
+/// The nested scene open for editing, shown in the viewport without the rest of the scene.
+#[derive(Resource, Default)]
+pub struct SceneIsolation {
+    root: Option<Entity>,
+    /// The entities hidden while editing it, shown again when it's closed.
+    hidden: Vec<Entity>,
+}
+
+impl SceneIsolation {
+    pub fn root(&self) -> Option<Entity> {
+        self.root
+    }
+}
+
+fn children(world: &World, entity: Entity) -> Vec<Entity> {
+    world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default()
+}
+
+/// Loads the assets of the nested scenes which were spawned without one, like those of a loaded
+/// scene, and marks the entities spawned from them.
+fn track_nested_scenes(world: &mut World) {
+    let isolated = world.resource::<SceneIsolation>().root;
+    if isolated.is_some_and(|root| world.get_entity(root).is_none()) {
+        close(world, None);
+    }
+
+    let roots: Vec<_> = world
+        .query::<(Entity, &NestedScene)>()
+        .iter(world)
+        .map(|(entity, nested)| (entity, nested.clone()))
+        .collect();
+    for (root, nested) in roots {
+        let asset_server = world.resource::<AssetServer>().clone();
+        if nested.editable() {
+            if world.get::<Handle<DynamicScene>>(root).is_none() {
+                let scene: Handle<DynamicScene> = asset_server.load(&nested.path);
+                world.entity_mut(root).insert(scene);
+            }
+        } else if world.get::<Handle<Scene>>(root).is_none() {
+            let scene: Handle<Scene> = asset_server.load(&nested.path);
+            world.entity_mut(root).insert(scene);
+        }
+
+        let mut stack = children(world, root);
+        while let Some(entity) = stack.pop() {
+            if world.get::<NestedSceneEntity>(entity).is_none() {
+                let mut entity_mut = world.entity_mut(entity);
+                entity_mut.insert(NestedSceneEntity { root });
+                if isolated != Some(root) {
+                    entity_mut.insert(EditorLocked);
+                }
+            }
+            // the entities of a nested scene of the nested scene belong to it
+            if world.get::<NestedScene>(entity).is_none() {
+                stack.extend(children(world, entity));
+            }
+        }
+    }
+}
+
+fn nested_entities(world: &mut World, root: Entity) -> Vec<Entity> {
+    let mut entities: Vec<_> = world
+        .query::<(Entity, &NestedSceneEntity)>()
+        .iter(world)
+        .filter(|(_, nested)| nested.root == root)
+        .map(|(entity, _)| entity)
+        .collect();
+    entities.sort();
+    entities
+}
+
+/// Unlocks the entities of the nested scene `root` and hides everything else, closing the nested
+/// scene open for editing before.
+pub fn open_for_editing(world: &mut World, root: Entity, selected: &mut SelectedEntities) {
+    close(world, Some(selected));
+
+    let mut shown = scene::with_descendants(world, &[root]);
+    shown.extend(
+        std::iter::successors(world.get::<Parent>(root), |parent| {
+            world.get::<Parent>(parent.get())
+        })
+        .map(|parent| parent.get()),
+    );
+    // the scene is still lit by its lights
+    let hidden: Vec<_> = scene::scene_entities(world)
+        .into_iter()
+        .filter(|entity| !shown.contains(entity))
+        .filter(|&entity| {
+            let entity = world.entity(entity);
+            entity.contains::<Visibility>()
+                && !entity.contains::<EditorHidden>()
+                && !entity.contains::<PointLight>()
+                && !entity.contains::<SpotLight>()
+                && !entity.contains::<DirectionalLight>()
+        })
+        .collect();
+    for &entity in &hidden {
+        hierarchy::toggle_hidden(world, entity);
+    }
+    for entity in nested_entities(world, root) {
+        world.entity_mut(entity).remove::<EditorLocked>();
+    }
+
+    if let Some(nested) = world.get::<NestedScene>(root) {
+        info!("Editing {}", nested.path);
+    }
+    *world.resource_mut::<SceneIsolation>() = SceneIsolation {
+        root: Some(root),
+        hidden,
+    };
+}
+
+/// Writes the entities of the nested scene open for editing to its asset, and respawns every
+/// instance of it.
+pub fn save_isolated(
+    world: &mut World,
+    selected: &mut SelectedEntities,
+) -> Result<(), SceneFileError> {
+    let Some(root) = world.resource::<SceneIsolation>().root else {
+        return Ok(());
+    };
+    let Some(nested) = world.get::<NestedScene>(root).cloned() else {
+        return Ok(());
+    };
+    let entities = nested_entities(world, root);
+    let scene = scene::build_scene(world, &entities);
+    scene::save_scene(world, &entities, Path::new(ASSET_DIR).join(&nested.path))?;
+    info!("Saved {}", nested.path);
+
+    // respawned rather than updated in place, which keeps the entities removed from the scene
+    let handle: Handle<DynamicScene> = world.resource::<AssetServer>().load(&nested.path);
+    world
+        .resource_mut::<Assets<DynamicScene>>()
+        .set_untracked(&handle, scene);
+    let instances: Vec<_> = world
+        .query::<(Entity, &NestedScene)>()
+        .iter(world)
+        .filter(|(_, other)| other.path == nested.path)
+        .map(|(entity, _)| entity)
+        .collect();
+    for instance in instances {
+        respawn(world, instance, selected);
+    }
+    Ok(())
+}
+
+/// Respawns the entities of the nested scene `root` from its asset.
+fn respawn(world: &mut World, root: Entity, selected: &mut SelectedEntities) {
+    let entities = nested_entities(world, root);
+    for &entity in &entities {
+        selected.remove(entity);
+    }
+    // despawned here rather than by the scene spawner, which leaves them in the children of the
+    // root, and would keep those added while editing
+    hierarchy::despawn_entities(world, &scene::top_level_entities(world, &entities));
+    if let Some(mut scene) = world.get_mut::<Handle<DynamicScene>>(root) {
+        scene.set_changed();
+    }
+}
+
+/// Shows the scene again and locks the nested scene open for editing, respawning it to discard
+/// what wasn't saved. Without a selection to update, its entities are left as they are.
+pub fn close(world: &mut World, selected: Option<&mut SelectedEntities>) {
+    let SceneIsolation { root, hidden } =
+        std::mem::take(&mut *world.resource_mut::<SceneIsolation>());
+    let Some(root) = root else {
+        return;
+    };
+    for entity in hidden {
+        if world.get::<EditorHidden>(entity).is_some() {
+            hierarchy::toggle_hidden(world, entity);
+        }
+    }
+    if world.get_entity(root).is_none() {
+        return;
+    }
+    for entity in nested_entities(world, root) {
+        world.entity_mut(entity).insert(EditorLocked);
+    }
+    if let Some(selected) = selected {
+        respawn(world, root, selected);
+    }
+}
+
+/// Bar naming the nested scene open for editing, with buttons to save and close it.
+pub fn isolation_ui(world: &mut World, ui: &mut egui::Ui, selected: &mut SelectedEntities) {
+    let Some(root) = world.resource::<SceneIsolation>().root else {
+        return;
+    };
+    let path = world
+        .get::<NestedScene>(root)
+        .map(|nested| nested.path.clone())
+        .unwrap_or_default();
+    ui.horizontal(|ui| {
+        ui.label(format!("Editing {path}"));
+        if ui.button("Save").clicked() {
+            if let Err(e) = save_isolated(world, selected) {
+                error!("Failed to save {path}: {e}");
+            }
+        }
+        if ui
+            .button("Close")
+            .on_hover_text("Show the rest of the scene again, discarding what wasn't saved")
+            .clicked()
+        {
+            close(world, Some(selected));
+        }
+    });
+    ui.separator();
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+
+use crate::{
+    camera::{EditorCameraController, Framing},
+    mode2d::EditorView2d,
+    views::AxisView,
+};
+
+/// Length of the axes of the orientation widget, in egui points.
+const WIDGET_RADIUS: f32 = 36.0;
+const HANDLE_RADIUS: f32 = 8.0;
+
+/// World axes with the color and label of their handle.
+const AXES: [(Vec3, egui::Color32, &str); 3] = [
+    (Vec3::X, egui::Color32::from_rgb(230, 70, 70), "X"),
+    (Vec3::Y, egui::Color32::from_rgb(110, 200, 70), "Y"),
+    (Vec3::Z, egui::Color32::from_rgb(70, 130, 240), "Z"),
+];
+
+/// Rotation of a camera looking at its focus from `direction`.
+fn look_from(direction: Vec3) -> Quat {
+    let up = match direction.y.abs() > 0.5 {
+        true => Vec3::NEG_Z * direction.y.signum(),
+        false => Vec3::Y,
+    };
+    Transform::IDENTITY.looking_to(-direction, up).rotation
+}
+
+/// Draws the world axes as seen by `camera` in the top right corner of its game view. Clicking the
+/// handle of an axis smoothly turns the camera to look along it at its orbit focus.
+///
+/// Has to run before the viewport picking, so clicks on a handle don't select what's behind it.
+pub fn orientation_ui(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+) {
+    // axis and 2D views always look along a fixed axis
+    if world.get::<AxisView>(camera).is_some() || world.get::<EditorView2d>(camera).is_some() {
+        return;
+    }
+    let Some(transform) = world.get::<Transform>(camera).copied() else {
+        return;
+    };
+    let margin = 8.0 + WIDGET_RADIUS + HANDLE_RADIUS;
+    if viewport_rect.width() < margin * 4.0 || viewport_rect.height() < margin * 4.0 {
+        return;
+    }
+    let center = egui::pos2(viewport_rect.max.x - margin, viewport_rect.min.y + margin);
+
+    // both ends of every axis in view space, drawn back to front
+    let view_rotation = transform.rotation.inverse();
+    let mut handles: Vec<_> = AXES
+        .into_iter()
+        .flat_map(|(axis, color, label)| [(axis, color, label, true), (-axis, color, label, false)])
+        .map(|(direction, color, label, positive)| {
+            let view = view_rotation * direction;
+            let pos = center + egui::vec2(view.x, -view.y) * WIDGET_RADIUS;
+            (view.z, direction, pos, color, label, positive)
+        })
+        .collect();
+    handles.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let painter = ui.painter_at(viewport_rect);
+    painter.circle_filled(
+        center,
+        WIDGET_RADIUS + HANDLE_RADIUS + 2.0,
+        egui::Color32::from_black_alpha(60),
+    );
+    let mut clicked = None;
+    for (_, direction, pos, color, label, positive) in handles {
+        let rect = egui::Rect::from_center_size(pos, egui::Vec2::splat(HANDLE_RADIUS * 2.0));
+        let response = ui
+            .interact(
+                rect,
+                egui::Id::new(("orientation", camera, label, positive)),
+                egui::Sense::click(),
+            )
+            .on_hover_text(format!(
+                "View from {}{label}",
+                if positive { "+" } else { "-" }
+            ));
+        if response.clicked() {
+            clicked = Some(direction);
+        }
+
+        let fill = match response.hovered() {
+            true => egui::Color32::WHITE,
+            false => color,
+        };
+        if positive {
+            painter.line_segment([center, pos], (2.0, color));
+            painter.circle_filled(pos, HANDLE_RADIUS, fill);
+            painter.text(
+                pos,
+                egui::Align2::CENTER_CENTER,
+                label,
+                egui::FontId::proportional(HANDLE_RADIUS * 1.4),
+                egui::Color32::BLACK,
+            );
+        } else {
+            painter.circle(
+                pos,
+                HANDLE_RADIUS * 0.8,
+                fill.linear_multiply(0.3),
+                (1.5, fill),
+            );
+        }
+    }
+
+    let Some(direction) = clicked else {
+        return;
+    };
+    let Some(mut controller) = world.get_mut::<EditorCameraController>(camera) else {
+        return;
+    };
+    let focus = controller.focus;
+    let distance = transform.translation.distance(focus).max(0.1);
+    controller.framing = Some(Framing {
+        translation: focus + direction * distance,
+        rotation: look_from(direction),
+        focus,
+        scale: None,
+    });
+}
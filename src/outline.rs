@@ -0,0 +1,517 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::load_internal_asset,
+    ecs::query::Has,
+    pbr::{MaterialPipeline, MaterialPipelineKey, NotShadowCaster},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    render::{
+        mesh::MeshVertexBufferLayout,
+        primitives::Aabb,
+        render_resource::{
+            AsBindGroup, Face, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+        },
+        view::VisibilitySystems,
+    },
+    sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle, Mesh2dHandle},
+    transform::TransformSystem,
+};
+
+use crate::{settings::EditorSettings, EditorOnly, EditorResource, UiState};
+
+const OUTLINE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3_870_125_417_096_281_554);
+const SPRITE_OUTLINE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 11_204_388_561_739_042_617);
+
+/// Width of the outline of meshes, relative to the distance to the camera.
+const MESH_OUTLINE_WIDTH: f32 = 0.003;
+/// Width of the outline of sprites, in the units of their size.
+const SPRITE_OUTLINE_WIDTH: f32 = 3.0;
+
+pub struct EditorOutlinePlugin;
+impl Plugin for EditorOutlinePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            OUTLINE_SHADER_HANDLE,
+            "outline.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            SPRITE_OUTLINE_SHADER_HANDLE,
+            "sprite_outline.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins((
+            MaterialPlugin::<OutlineMaterial> {
+                prepass_enabled: false,
+                ..default()
+            },
+            Material2dPlugin::<SpriteOutlineMaterial>::default(),
+        ))
+        .init_resource::<OutlineMaterials>()
+        .add_systems(
+            PostUpdate,
+            (
+                update_outlines
+                    .after(TransformSystem::TransformPropagate)
+                    .before(VisibilitySystems::CalculateBounds),
+                draw_outlined_bounds.after(TransformSystem::TransformPropagate),
+            ),
+        );
+    }
+}
+
+/// Renders the back faces of a mesh pushed out along its normals, which show around the mesh.
+#[derive(Debug, Clone, AsBindGroup, TypeUuid, TypePath)]
+#[uuid = "0f5e7a51-93c4-4d2e-b8a6-3c1d2e77b410"]
+pub struct OutlineMaterial {
+    #[uniform(0)]
+    color: Color,
+    #[uniform(0)]
+    width: f32,
+}
+
+impl Material for OutlineMaterial {
+    fn vertex_shader() -> ShaderRef {
+        OUTLINE_SHADER_HANDLE.typed().into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        OUTLINE_SHADER_HANDLE.typed().into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        match self.color.a() < 1.0 {
+            true => AlphaMode::Blend,
+            false => AlphaMode::Opaque,
+        }
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        descriptor.vertex.buffers = vec![layout.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_NORMAL.at_shader_location(1),
+        ])?];
+        descriptor.primitive.cull_mode = Some(Face::Front);
+        Ok(())
+    }
+}
+
+/// Renders where a sprite is opaque close by but not at the pixel itself, on a quad larger than
+/// the sprite.
+#[derive(Debug, Clone, AsBindGroup, TypeUuid, TypePath)]
+#[uuid = "6b2d8c0e-1f47-4a39-9e5d-84c3a1f0d2b7"]
+pub struct SpriteOutlineMaterial {
+    #[uniform(0)]
+    color: Color,
+    #[uniform(0)]
+    rect: Vec4,
+    #[uniform(0)]
+    width: Vec2,
+    #[texture(1)]
+    #[sampler(2)]
+    texture: Handle<Image>,
+}
+
+impl Material2d for SpriteOutlineMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SPRITE_OUTLINE_SHADER_HANDLE.typed().into()
+    }
+}
+
+/// The materials shared by the outlines of meshes, and the quad the outlines of sprites are
+/// drawn on.
+#[derive(Resource)]
+struct OutlineMaterials {
+    selection: Handle<OutlineMaterial>,
+    hover: Handle<OutlineMaterial>,
+    quad: Handle<Mesh>,
+}
+
+impl FromWorld for OutlineMaterials {
+    fn from_world(world: &mut World) -> Self {
+        let settings = world.resource::<EditorSettings>();
+        let (selection, hover) = (settings.selection_outline, settings.hover_outline);
+        let mut materials = world.resource_mut::<Assets<OutlineMaterial>>();
+        let mut material = |color| {
+            materials.add(OutlineMaterial {
+                color,
+                width: MESH_OUTLINE_WIDTH,
+            })
+        };
+        let (selection, hover) = (material(selection), material(hover));
+        let quad = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(shape::Quad::new(Vec2::ONE).into());
+        Self {
+            selection,
+            hover,
+            quad,
+        }
+    }
+}
+
+/// The entity drawing the outline of `target`, following it around.
+#[derive(Component)]
+struct Outline {
+    target: Entity,
+    hovered: bool,
+}
+
+/// Where the outline of a sprite is drawn, relative to the sprite's transform, and the values
+/// of its [`SpriteOutlineMaterial`].
+#[derive(Debug, PartialEq)]
+struct SpriteOutline {
+    transform: Transform,
+    rect: Vec4,
+    width: Vec2,
+}
+
+impl SpriteOutline {
+    fn new(sprite: &Sprite, image_size: Vec2) -> Self {
+        let rect = sprite.rect.unwrap_or(Rect {
+            min: Vec2::ZERO,
+            max: image_size,
+        });
+        let size = sprite.custom_size.unwrap_or(rect.size());
+        let (mut min, mut max) = (rect.min / image_size, rect.max / image_size);
+        if sprite.flip_x {
+            std::mem::swap(&mut min.x, &mut max.x);
+        }
+        if sprite.flip_y {
+            std::mem::swap(&mut min.y, &mut max.y);
+        }
+        Self {
+            // right behind the sprite
+            transform: Transform::from_translation((-sprite.anchor.as_vec() * size).extend(-1e-3))
+                .with_scale((size + 2.0 * SPRITE_OUTLINE_WIDTH).extend(1.0)),
+            rect: Vec4::new(min.x, min.y, max.x, max.y),
+            width: SPRITE_OUTLINE_WIDTH / size,
+        }
+    }
+}
+
+/// The entities to outline: the hovered one and every selected one, along with the descendants
+/// of selected entities so that selecting e.g. the root of a loaded glTF scene outlines its
+/// meshes. Whether each is only hovered.
+fn outlined_entities(ui_state: &UiState, children: &Query<&Children>) -> HashMap<Entity, bool> {
+    let mut outlined = HashMap::new();
+    let mut stack: Vec<_> = ui_state.selected_entities.as_slice().to_vec();
+    while let Some(entity) = stack.pop() {
+        if outlined.insert(entity, false).is_none() {
+            stack.extend(children.get(entity).into_iter().flatten());
+        }
+    }
+    if let Some(hovered) = ui_state.hovered_entity {
+        outlined.entry(hovered).or_insert(true);
+    }
+    outlined
+}
+
+fn outline_color(settings: &EditorSettings, hovered: bool) -> Color {
+    match hovered {
+        true => settings.hover_outline,
+        false => settings.selection_outline,
+    }
+}
+
+/// Keeps an entity drawing the outline of each outlined mesh and sprite, rendered in place of
+/// the target rather than changing its components so nothing of it ends up in the scene.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_outlines(
+    mut commands: Commands,
+    editor: Res<EditorResource>,
+    ui_state: Res<UiState>,
+    settings: Res<EditorSettings>,
+    materials: Res<OutlineMaterials>,
+    mut outline_materials: ResMut<Assets<OutlineMaterial>>,
+    mut sprite_materials: ResMut<Assets<SpriteOutlineMaterial>>,
+    images: Res<Assets<Image>>,
+    children: Query<&Children>,
+    meshes: Query<(&Handle<Mesh>, &GlobalTransform, &ComputedVisibility), Without<Outline>>,
+    sprites: Query<
+        (
+            &Sprite,
+            &Handle<Image>,
+            &GlobalTransform,
+            &ComputedVisibility,
+        ),
+        Without<Outline>,
+    >,
+    mut outlines: Query<(
+        Entity,
+        &Outline,
+        &mut Transform,
+        &mut GlobalTransform,
+        &mut Visibility,
+        Option<&mut Handle<Mesh>>,
+        Option<&Handle<SpriteOutlineMaterial>>,
+    )>,
+) {
+    for (handle, color) in [
+        (&materials.selection, settings.selection_outline),
+        (&materials.hover, settings.hover_outline),
+    ] {
+        if outline_materials
+            .get(handle)
+            .is_some_and(|material| material.color != color)
+        {
+            outline_materials.get_mut(handle).unwrap().color = color;
+        }
+    }
+
+    let mut outlined = match editor.0 {
+        true => outlined_entities(&ui_state, &children),
+        false => HashMap::new(),
+    };
+    let color = |hovered| outline_color(&settings, hovered);
+
+    for (entity, outline, mut transform, mut global_transform, mut visibility, mesh, sprite) in
+        &mut outlines
+    {
+        let target_transform;
+        let target_visibility;
+        if let (Some(mut mesh), Ok((target_mesh, target, target_visible))) =
+            (mesh, meshes.get(outline.target))
+        {
+            if outlined.get(&outline.target) != Some(&outline.hovered) {
+                commands.entity(entity).despawn();
+                continue;
+            }
+            if *mesh != *target_mesh {
+                *mesh = target_mesh.clone();
+            }
+            (target_transform, target_visibility) = (*target, target_visible);
+        } else if let (Some(sprite), Ok((target_sprite, image, target, target_visible))) =
+            (sprite, sprites.get(outline.target))
+        {
+            if outlined.get(&outline.target) != Some(&outline.hovered) {
+                commands.entity(entity).despawn();
+                continue;
+            }
+            let Some(image_size) = images.get(image).map(Image::size) else {
+                outlined.remove(&outline.target);
+                continue;
+            };
+            let placed = SpriteOutline::new(target_sprite, image_size);
+            let material = sprite_materials.get(sprite);
+            let changed = material.is_some_and(|material| {
+                material.rect != placed.rect
+                    || material.width != placed.width
+                    || material.texture != *image
+                    || material.color != color(outline.hovered)
+            });
+            if changed {
+                let material = sprite_materials.get_mut(sprite).unwrap();
+                material.rect = placed.rect;
+                material.width = placed.width;
+                material.texture = image.clone();
+                material.color = color(outline.hovered);
+            }
+            (target_transform, target_visibility) =
+                (target.mul_transform(placed.transform), target_visible);
+        } else {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        outlined.remove(&outline.target);
+
+        if *global_transform != target_transform {
+            *global_transform = target_transform;
+            *transform = target_transform.compute_transform();
+        }
+        let shown = match target_visibility.is_visible_in_hierarchy() {
+            true => Visibility::Inherited,
+            false => Visibility::Hidden,
+        };
+        if *visibility != shown {
+            *visibility = shown;
+        }
+    }
+
+    for (target, hovered) in outlined {
+        let outline = (
+            Name::new("Editor Outline"),
+            Outline { target, hovered },
+            NotShadowCaster,
+            EditorOnly,
+        );
+        if let Ok((mesh, transform, _)) = meshes.get(target) {
+            let material = match hovered {
+                true => materials.hover.clone(),
+                false => materials.selection.clone(),
+            };
+            commands.spawn((
+                MaterialMeshBundle {
+                    mesh: mesh.clone(),
+                    material,
+                    transform: transform.compute_transform(),
+                    global_transform: *transform,
+                    ..default()
+                },
+                outline,
+            ));
+        } else if let Ok((sprite, image, transform, _)) = sprites.get(target) {
+            let Some(image_size) = images.get(image).map(Image::size) else {
+                continue;
+            };
+            let placed = SpriteOutline::new(sprite, image_size);
+            let transform = transform.mul_transform(placed.transform);
+            let material = sprite_materials.add(SpriteOutlineMaterial {
+                color: color(hovered),
+                rect: placed.rect,
+                width: placed.width,
+                texture: image.clone(),
+            });
+            commands.spawn((
+                MaterialMesh2dBundle {
+                    mesh: Mesh2dHandle(materials.quad.clone()),
+                    material,
+                    transform: transform.compute_transform(),
+                    global_transform: transform,
+                    ..default()
+                },
+                outline,
+            ));
+        }
+    }
+}
+
+/// Draws the box `aabb` of an entity at `transform`, which covers meshes and sprites alike since
+/// bevy computes an [`Aabb`] for both.
+pub fn draw_bounds(gizmos: &mut Gizmos, transform: &GlobalTransform, aabb: &Aabb, color: Color) {
+    let bounds = Transform::from_translation(aabb.center.into())
+        .with_scale((aabb.half_extents * 2.0).into());
+    gizmos.cuboid(transform.mul_transform(bounds), color);
+}
+
+/// Draws the bounds of the outlined entities when turned on in the settings, and of those which
+/// have bounds but no mesh or sprite to outline, like colliders, regardless.
+#[allow(clippy::type_complexity)]
+fn draw_outlined_bounds(
+    editor: Res<EditorResource>,
+    ui_state: Res<UiState>,
+    settings: Res<EditorSettings>,
+    bounds: Query<(&GlobalTransform, &Aabb, Has<Handle<Mesh>>, Has<Sprite>), Without<Outline>>,
+    children: Query<&Children>,
+    mut gizmos: Gizmos,
+) {
+    if !editor.0 {
+        return;
+    }
+    for (entity, hovered) in outlined_entities(&ui_state, &children) {
+        let Ok((transform, aabb, mesh, sprite)) = bounds.get(entity) else {
+            continue;
+        };
+        if (settings.show_bounds && !hovered) || (!mesh && !sprite) {
+            draw_bounds(
+                &mut gizmos,
+                transform,
+                aabb,
+                outline_color(&settings, hovered),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::sprite::Anchor;
+
+    use super::*;
+
+    #[test]
+    fn outlines_selected_meshes_until_deselected() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_asset::<Mesh>()
+            .add_asset::<Image>()
+            .add_asset::<OutlineMaterial>()
+            .add_asset::<SpriteOutlineMaterial>()
+            .init_resource::<EditorSettings>()
+            .insert_resource(EditorResource(true))
+            .insert_resource(UiState::new())
+            .init_resource::<OutlineMaterials>()
+            .add_systems(Update, update_outlines);
+        let mesh = app
+            .world
+            .resource_mut::<Assets<Mesh>>()
+            .add(shape::Cube::default().into());
+        let parent = app.world.spawn(SpatialBundle::default()).id();
+        let child = app
+            .world
+            .spawn(PbrBundle {
+                mesh,
+                global_transform: GlobalTransform::from_xyz(1.0, 2.0, 3.0),
+                ..default()
+            })
+            .set_parent(parent)
+            .id();
+        let outlines = |app: &mut App| -> Vec<(Entity, bool, GlobalTransform)> {
+            app.update();
+            app.world
+                .query::<(&Outline, &GlobalTransform)>()
+                .iter(&app.world)
+                .map(|(outline, transform)| (outline.target, outline.hovered, *transform))
+                .collect()
+        };
+
+        app.world.resource_mut::<UiState>().hovered_entity = Some(child);
+        assert_eq!(
+            outlines(&mut app),
+            vec![(child, true, GlobalTransform::from_xyz(1.0, 2.0, 3.0))]
+        );
+        // selecting the parent outlines its meshes
+        let mut ui_state = app.world.resource_mut::<UiState>();
+        ui_state.hovered_entity = None;
+        ui_state.select_entities(vec![parent], false);
+        assert_eq!(
+            outlines(&mut app),
+            vec![(child, false, GlobalTransform::from_xyz(1.0, 2.0, 3.0))]
+        );
+        app.world
+            .resource_mut::<UiState>()
+            .selected_entities
+            .clear();
+        assert_eq!(outlines(&mut app), Vec::new());
+    }
+
+    #[test]
+    fn outlines_the_part_of_the_image_a_sprite_shows() {
+        let sprite = Sprite {
+            rect: Some(Rect::new(16.0, 0.0, 32.0, 8.0)),
+            flip_x: true,
+            ..default()
+        };
+        let outline = SpriteOutline::new(&sprite, Vec2::new(64.0, 32.0));
+        assert_eq!(outline.rect, Vec4::new(0.5, 0.0, 0.25, 0.25));
+        assert_eq!(outline.width, SPRITE_OUTLINE_WIDTH / Vec2::new(16.0, 8.0));
+        let scale = Vec2::new(16.0, 8.0) + 2.0 * SPRITE_OUTLINE_WIDTH;
+        assert_eq!(outline.transform.scale, scale.extend(1.0));
+    }
+
+    #[test]
+    fn follows_the_anchor_and_size_of_a_sprite() {
+        let sprite = Sprite {
+            custom_size: Some(Vec2::new(10.0, 20.0)),
+            anchor: Anchor::BottomLeft,
+            ..default()
+        };
+        let outline = SpriteOutline::new(&sprite, Vec2::new(64.0, 32.0));
+        assert_eq!(outline.rect, Vec4::new(0.0, 0.0, 1.0, 1.0));
+        assert_eq!(
+            outline.transform.translation.truncate(),
+            Vec2::new(5.0, 10.0)
+        );
+        assert!(outline.transform.translation.z < 0.0);
+    }
+}
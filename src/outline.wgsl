@@ -0,0 +1,32 @@
+#import bevy_pbr::mesh_bindings mesh
+#import bevy_pbr::mesh_functions mesh_position_local_to_world, mesh_normal_local_to_world
+#import bevy_pbr::mesh_view_bindings view
+
+struct OutlineMaterial {
+    color: vec4<f32>,
+    width: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> material: OutlineMaterial;
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+};
+
+// the mesh pushed out along its normals, of which only the back faces are drawn
+@vertex
+fn vertex(vertex: Vertex) -> @builtin(position) vec4<f32> {
+    let world_position = mesh_position_local_to_world(mesh.model, vec4(vertex.position, 1.0));
+    let world_normal = mesh_normal_local_to_world(vertex.normal);
+    // as wide on screen however far the mesh is
+    let distance = (view.view_proj * world_position).w;
+    let pushed = world_position.xyz + world_normal * material.width * distance;
+    return view.view_proj * vec4(pushed, 1.0);
+}
+
+@fragment
+fn fragment() -> @location(0) vec4<f32> {
+    return material.color;
+}
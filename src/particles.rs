@@ -0,0 +1,271 @@
+use std::any::TypeId;
+
+use bevy::{
+    asset::{Asset, HandleId},
+    prelude::*,
+};
+use bevy_inspector_egui::bevy_inspector::{self, hierarchy::SelectedEntities};
+
+use crate::inspector::entity_name;
+
+pub struct EditorParticlesPlugin;
+impl Plugin for EditorParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorParticles>();
+    }
+}
+
+/// How the editor plays the emitter component `C` of a particle engine.
+pub struct ParticleControls<C> {
+    pub is_playing: fn(&C) -> bool,
+    pub set_playing: fn(&mut C, bool),
+    /// Clears the particles of the emitter and starts it over.
+    pub restart: fn(&mut C),
+}
+
+#[derive(Resource)]
+struct ParticleControlsOf<C>(ParticleControls<C>);
+
+/// A registered particle engine, with its effect asset and emitter component types erased.
+struct ParticleEngine {
+    asset: TypeId,
+    asset_name: &'static str,
+    effects: fn(&World) -> Vec<HandleId>,
+    emitters: fn(&mut World) -> Vec<Entity>,
+    is_playing: fn(&World, Entity) -> Option<bool>,
+    set_playing: fn(&mut World, Entity, bool),
+    restart: fn(&mut World, Entity),
+}
+
+fn effects<A: Asset>(world: &World) -> Vec<HandleId> {
+    world.resource::<Assets<A>>().ids().collect()
+}
+
+fn emitters<C: Component>(world: &mut World) -> Vec<Entity> {
+    world
+        .query_filtered::<Entity, With<C>>()
+        .iter(world)
+        .collect()
+}
+
+fn is_playing<C: Component>(world: &World, entity: Entity) -> Option<bool> {
+    let controls = &world.resource::<ParticleControlsOf<C>>().0;
+    world.get::<C>(entity).map(controls.is_playing)
+}
+
+fn set_playing<C: Component>(world: &mut World, entity: Entity, playing: bool) {
+    let set_playing = world.resource::<ParticleControlsOf<C>>().0.set_playing;
+    if let Some(mut emitter) = world.get_mut::<C>(entity) {
+        set_playing(&mut emitter, playing);
+    }
+}
+
+fn restart<C: Component>(world: &mut World, entity: Entity) {
+    let restart = world.resource::<ParticleControlsOf<C>>().0.restart;
+    if let Some(mut emitter) = world.get_mut::<C>(entity) {
+        restart(&mut emitter);
+    }
+}
+
+/// The registered particle engines, and the effect selected in the particles tab.
+#[derive(Resource, Default)]
+pub struct EditorParticles {
+    engines: Vec<ParticleEngine>,
+    selected: Option<(TypeId, HandleId)>,
+}
+
+pub trait AddEditorParticles {
+    /// Lists the effect assets `A` of a particle engine in the particles tab, where their
+    /// parameters are edited through reflection, and adds playback controls for its emitter
+    /// component `C` to the tab and to the game view toolbar.
+    ///
+    /// ```ignore
+    /// app.add_editor_particles::<bevy_hanabi::EffectAsset, bevy_hanabi::EffectSpawner>(
+    ///     ParticleControls {
+    ///         is_playing: |spawner| spawner.is_active(),
+    ///         set_playing: |spawner, playing| spawner.set_active(playing),
+    ///         restart: |spawner| spawner.reset(),
+    ///     },
+    /// );
+    /// ```
+    fn add_editor_particles<A: Asset, C: Component>(
+        &mut self,
+        controls: ParticleControls<C>,
+    ) -> &mut Self;
+}
+
+impl AddEditorParticles for App {
+    fn add_editor_particles<A: Asset, C: Component>(
+        &mut self,
+        controls: ParticleControls<C>,
+    ) -> &mut Self {
+        self.insert_resource(ParticleControlsOf(controls));
+        self.world
+            .get_resource_or_insert_with(EditorParticles::default)
+            .engines
+            .push(ParticleEngine {
+                asset: TypeId::of::<A>(),
+                asset_name: std::any::type_name::<A>()
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or_default(),
+                effects: effects::<A>,
+                emitters: emitters::<C>,
+                is_playing: is_playing::<C>,
+                set_playing: set_playing::<C>,
+                restart: restart::<C>,
+            });
+        self
+    }
+}
+
+/// Whether `entity` is an emitter of a registered engine, and is playing.
+fn emitter_playing(world: &World, entity: Entity) -> Option<bool> {
+    world
+        .resource::<EditorParticles>()
+        .engines
+        .iter()
+        .find_map(|engine| (engine.is_playing)(world, entity))
+}
+
+fn set_emitter_playing(world: &mut World, entity: Entity, playing: bool) {
+    let engines: Vec<_> = world
+        .resource::<EditorParticles>()
+        .engines
+        .iter()
+        .map(|engine| engine.set_playing)
+        .collect();
+    for set_playing in engines {
+        set_playing(world, entity, playing);
+    }
+}
+
+fn restart_emitter(world: &mut World, entity: Entity) {
+    let engines: Vec<_> = world
+        .resource::<EditorParticles>()
+        .engines
+        .iter()
+        .map(|engine| engine.restart)
+        .collect();
+    for restart in engines {
+        restart(world, entity);
+    }
+}
+
+/// Play, pause and restart buttons for the selected emitters, shown in the game view toolbar
+/// while any is selected.
+pub fn particles_toolbar(ui: &mut egui::Ui, world: &mut World, selected: &SelectedEntities) {
+    let emitters: Vec<_> = selected
+        .iter()
+        .filter_map(|entity| Some((entity, emitter_playing(world, entity)?)))
+        .collect();
+    if emitters.is_empty() {
+        return;
+    }
+    ui.separator();
+    let playing = emitters.iter().any(|(_, playing)| *playing);
+    let (icon, hover) = match playing {
+        true => ("⏸", "Pause the selected particle emitters"),
+        false => ("▶", "Play the selected particle emitters"),
+    };
+    if ui.button(icon).on_hover_text(hover).clicked() {
+        for (entity, _) in &emitters {
+            set_emitter_playing(world, *entity, !playing);
+        }
+    }
+    if ui
+        .button("⟲")
+        .on_hover_text("Restart the selected particle emitters")
+        .clicked()
+    {
+        for (entity, _) in &emitters {
+            restart_emitter(world, *entity);
+        }
+    }
+}
+
+/// Lists the effect assets of the registered particle engines, edits the selected one, and
+/// controls the emitters in the scene.
+pub fn particles_ui(world: &mut World, ui: &mut egui::Ui) {
+    let particles = world.resource::<EditorParticles>();
+    if particles.engines.is_empty() {
+        ui.label("No particle engine is registered with `add_editor_particles`");
+        return;
+    }
+    let asset_server = world.resource::<AssetServer>();
+    let effects: Vec<_> = particles
+        .engines
+        .iter()
+        .flat_map(|engine| {
+            (engine.effects)(world).into_iter().map(|handle| {
+                let name = asset_server
+                    .get_handle_path(handle)
+                    .map(|path| path.path().display().to_string())
+                    .unwrap_or_else(|| format!("{} {handle:?}", engine.asset_name));
+                (engine.asset, handle, name)
+            })
+        })
+        .collect();
+    let engines: Vec<_> = particles
+        .engines
+        .iter()
+        .map(|engine| (engine.emitters, engine.is_playing))
+        .collect();
+    let mut selected = particles.selected;
+
+    egui::SidePanel::left("particle_effects")
+        .resizable(true)
+        .default_width(200.0)
+        .show_inside(ui, |ui| {
+            ui.heading("Effects");
+            egui::ScrollArea::vertical()
+                .id_source("particle_effect_list")
+                .show(ui, |ui| {
+                    for (asset, handle, name) in &effects {
+                        let is_selected = selected == Some((*asset, *handle));
+                        if ui.selectable_label(is_selected, name).clicked() {
+                            selected = Some((*asset, *handle));
+                        }
+                    }
+                });
+        });
+    world.resource_mut::<EditorParticles>().selected = selected;
+
+    ui.heading("Emitters");
+    let emitters: Vec<_> = engines
+        .into_iter()
+        .flat_map(|(emitters, is_playing)| {
+            emitters(world)
+                .into_iter()
+                .map(move |entity| (entity, is_playing))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    for (entity, is_playing) in emitters {
+        let playing = is_playing(world, entity).unwrap_or_default();
+        ui.horizontal(|ui| {
+            let icon = if playing { "⏸" } else { "▶" };
+            if ui.button(icon).clicked() {
+                set_emitter_playing(world, entity, !playing);
+            }
+            if ui.button("⟲").on_hover_text("Restart").clicked() {
+                restart_emitter(world, entity);
+            }
+            ui.label(entity_name(world, entity));
+        });
+    }
+    ui.separator();
+
+    let Some((asset, handle)) = selected else {
+        ui.label("Select an effect to edit its parameters");
+        return;
+    };
+    // edits apply to the emitters using the effect right away
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+    egui::ScrollArea::vertical()
+        .id_source("particle_effect")
+        .show(ui, |ui| {
+            bevy_inspector::by_type_id::ui_for_asset(world, asset, handle, ui, &type_registry);
+        });
+}
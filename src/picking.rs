@@ -0,0 +1,191 @@
+use bevy::{math::Ray, prelude::*, render::primitives::Aabb};
+use bevy_inspector_egui::{
+    bevy_egui::EguiSettings,
+    bevy_inspector::hierarchy::{SelectedEntities, SelectionMode},
+};
+
+use crate::{hierarchy::EditorLocked, input::EditorAction, EditorOnly, MainCamera};
+
+/// Ray from `camera` through `pos`, a point of its game view `viewport_rect` in egui coordinates.
+pub fn cursor_ray(
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+    pos: egui::Pos2,
+) -> Option<Ray> {
+    let scale_factor = world.resource::<EguiSettings>().scale_factor as f32;
+    let (camera, transform) = world
+        .query::<(&Camera, &GlobalTransform)>()
+        .get(world, camera)
+        .ok()?;
+    // the camera viewport is offset to the game view, so positions are relative to its corner
+    let viewport_position = (pos - viewport_rect.min) * scale_factor;
+    camera.viewport_to_world(
+        transform,
+        Vec2::new(viewport_position.x, viewport_position.y),
+    )
+}
+
+/// Distance along `ray` to the box `aabb` of an entity at `transform`, if they intersect.
+pub fn ray_aabb_distance(ray: Ray, transform: &GlobalTransform, aabb: &Aabb) -> Option<f32> {
+    // intersecting in the entity's local space handles rotated and scaled boxes, and keeps
+    // distances comparable since the ray is only transformed linearly
+    let world_to_local = transform.compute_matrix().inverse();
+    let origin = world_to_local.transform_point3(ray.origin);
+    let direction = world_to_local.transform_vector3(ray.direction);
+
+    let min = Vec3::from(aabb.min());
+    let max = Vec3::from(aabb.max());
+    let (mut near, mut far) = (f32::NEG_INFINITY, f32::INFINITY);
+    for axis in 0..3 {
+        if direction[axis].abs() < f32::EPSILON {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (min[axis] - origin[axis]) / direction[axis];
+        let t2 = (max[axis] - origin[axis]) / direction[axis];
+        near = near.max(t1.min(t2));
+        far = far.min(t1.max(t2));
+    }
+    (near <= far && far >= 0.0).then_some(near.max(0.0))
+}
+
+/// The closest visible and unlocked entity with bounds hit by `ray`.
+pub fn pick_entity(world: &mut World, ray: Ray) -> Option<Entity> {
+    world
+        .query_filtered::<(Entity, &GlobalTransform, &Aabb, Option<&ComputedVisibility>), (
+            Without<MainCamera>,
+            Without<EditorLocked>,
+            Without<EditorOnly>,
+        )>()
+        .iter(world)
+        .filter(|(_, _, _, visibility)| visibility.is_none_or(|visibility| visibility.is_visible()))
+        .filter_map(|(entity, transform, aabb, _)| {
+            ray_aabb_distance(ray, transform, aabb).map(|distance| (distance, entity))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, entity)| entity)
+}
+
+/// Entities whose bounds, projected to the game view of `camera`, lie entirely within `marquee`.
+pub fn entities_in_rect(
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+    marquee: egui::Rect,
+) -> Vec<Entity> {
+    let scale_factor = world.resource::<EguiSettings>().scale_factor as f32;
+    let Ok((camera, camera_transform)) = world
+        .query::<(&Camera, &GlobalTransform)>()
+        .get(world, camera)
+    else {
+        return Vec::new();
+    };
+    let camera = camera.clone();
+    let camera_transform = *camera_transform;
+
+    let to_egui = |point: Vec3| {
+        camera
+            .world_to_viewport(&camera_transform, point)
+            .map(|position| viewport_rect.min + egui::vec2(position.x, position.y) / scale_factor)
+    };
+    world
+        .query_filtered::<(Entity, &GlobalTransform, &Aabb, Option<&ComputedVisibility>), (
+            Without<MainCamera>,
+            Without<EditorLocked>,
+            Without<EditorOnly>,
+        )>()
+        .iter(world)
+        .filter(|(_, _, _, visibility)| visibility.is_none_or(|visibility| visibility.is_visible()))
+        .filter(|(_, transform, aabb, _)| {
+            let (min, max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+            (0..8).all(|corner| {
+                let local = Vec3::select(
+                    BVec3::new(corner & 1 != 0, corner & 2 != 0, corner & 4 != 0),
+                    max,
+                    min,
+                );
+                // corners behind the camera can't be inside the marquee
+                to_egui(transform.transform_point(local)).is_some_and(|pos| marquee.contains(pos))
+            })
+        })
+        .map(|(entity, ..)| entity)
+        .collect()
+}
+
+/// Picks the entity under the pointer in the game view and selects it on click, or every entity
+/// within the rectangle dragged with the left mouse button. Ctrl or Shift toggle the clicked
+/// entity and add to the selection instead of replacing it. Clicking empty space clears the
+/// selection.
+///
+/// Has to run after the gizmo, which then takes precedence when dragging its handles.
+///
+/// Returns the hovered entity and whether the selection changed.
+pub(crate) fn viewport_picking(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+    selected: &mut SelectedEntities,
+) -> (Option<Entity>, bool) {
+    let id = egui::Id::new(("viewport_picking", camera));
+    let response = ui.interact(viewport_rect, id, egui::Sense::click_and_drag());
+    let (modifiers, camera_drag, touch) = ui.input(|input| {
+        (
+            input.modifiers,
+            input.pointer.secondary_down() || input.pointer.middle_down(),
+            input.any_touches(),
+        )
+    });
+    let add = modifiers.ctrl || modifiers.shift;
+
+    // Alt + left drag, or dragging a finger, orbits the camera instead
+    let orbit = world
+        .resource::<Input<EditorAction>>()
+        .pressed(EditorAction::Orbit);
+    if response.drag_started_by(egui::PointerButton::Primary) && !orbit && !touch {
+        if let Some(origin) = ui.input(|input| input.pointer.press_origin()) {
+            ui.memory_mut(|memory| memory.data.insert_temp(id, origin));
+        }
+    }
+    let marquee_origin = ui.memory(|memory| memory.data.get_temp::<egui::Pos2>(id));
+    if let Some(origin) = marquee_origin {
+        let current = response.interact_pointer_pos().unwrap_or(origin);
+        let marquee = egui::Rect::from_two_pos(origin, current).intersect(viewport_rect);
+        let color = ui.visuals().selection.bg_fill;
+        ui.painter()
+            .rect(marquee, 0.0, color.linear_multiply(0.2), (1.0, color));
+
+        if response.drag_released() {
+            ui.memory_mut(|memory| memory.data.remove::<egui::Pos2>(id));
+            if !add {
+                selected.clear();
+            }
+            for entity in entities_in_rect(world, camera, viewport_rect, marquee) {
+                selected.select_maybe_add(entity, true);
+            }
+            return (None, true);
+        }
+        return (None, false);
+    }
+
+    let Some(pos) = response.hover_pos().filter(|_| !camera_drag) else {
+        return (None, false);
+    };
+    let hovered =
+        cursor_ray(world, camera, viewport_rect, pos).and_then(|ray| pick_entity(world, ray));
+    if !response.clicked() || orbit {
+        return (hovered, false);
+    }
+    match hovered {
+        Some(entity) => {
+            let mode = SelectionMode::from_ctrl_shift(add, false);
+            selected.select(mode, entity, |_, _| std::iter::empty());
+        }
+        None if add => return (None, false),
+        None => selected.clear(),
+    }
+    (hovered, true)
+}
@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+
+use bevy::{ecs::entity::EntityMap, prelude::*, time::TimeSystem};
+
+use crate::{
+    history::{EditorChange, EditorHistory},
+    input::{EditorAction, EditorKeybinds},
+    scene,
+    settings::EditorSettings,
+    EditorResource, UiState,
+};
+
+/// The start of the type names of the resources which aren't restored after playing: those of
+/// the editor, and those of the engine like `Time`, which would rewind the clock.
+const NOT_RESTORED: [&str; 2] = [concat!(env!("CARGO_CRATE_NAME"), "::"), "bevy_"];
+
+/// Whether the resource named `type_name` is put back as it was before playing, which only the
+/// game's own resources are.
+fn restores_resource(type_name: &str) -> bool {
+    !NOT_RESTORED
+        .iter()
+        .any(|prefix| type_name.starts_with(prefix))
+}
+
+pub struct EditorPlayPlugin;
+impl Plugin for EditorPlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<EditorState>()
+            .init_resource::<SimulationStep>()
+            .add_systems(First, apply_simulation_time.before(TimeSystem))
+            .add_systems(OnExit(EditorState::Edit), snapshot_before_play)
+            .add_systems(OnEnter(EditorState::Edit), restore_after_play)
+            .add_systems(Update, play_hotkeys)
+            .add_systems(Last, finish_step);
+    }
+}
+
+/// Whether the game is being edited, played or paused from the editor.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EditorState {
+    #[default]
+    Edit,
+    Play,
+    Paused,
+}
+
+/// Number of frames left to simulate while paused.
+#[derive(Resource, Default)]
+pub struct SimulationStep(pub u32);
+
+/// Run condition for game systems which should only run while the simulation is running:
+/// when the editor is closed, in [`EditorState::Play`], or during a single frame step.
+///
+/// ```ignore
+/// app.add_systems(Update, move_enemies.run_if(editor::play::simulating));
+/// ```
+pub fn simulating(
+    ed: Option<Res<EditorResource>>,
+    state: Res<State<EditorState>>,
+    step: Res<SimulationStep>,
+) -> bool {
+    !ed.is_some_and(|ed| ed.0) || *state.get() == EditorState::Play || step.0 > 0
+}
+
+pub fn request_step(world: &mut World) {
+    if *world.resource::<State<EditorState>>().get() == EditorState::Paused {
+        world.resource_mut::<SimulationStep>().0 += 1;
+    }
+}
+
+pub fn set_state(world: &mut World, state: EditorState) {
+    world.resource_mut::<NextState<EditorState>>().set(state);
+}
+
+impl EditorState {
+    /// The state [`EditorAction::PlayStop`] switches to.
+    pub fn play_stop(self) -> EditorState {
+        match self {
+            EditorState::Edit => EditorState::Play,
+            EditorState::Play | EditorState::Paused => EditorState::Edit,
+        }
+    }
+
+    /// The state [`EditorAction::PauseResume`] switches to, if any.
+    pub fn pause_resume(self) -> Option<EditorState> {
+        match self {
+            EditorState::Play => Some(EditorState::Paused),
+            EditorState::Paused => Some(EditorState::Play),
+            EditorState::Edit => None,
+        }
+    }
+}
+
+pub fn play_stop(world: &mut World) {
+    let state = *world.resource::<State<EditorState>>().get();
+    set_state(world, state.play_stop());
+}
+
+pub fn pause_resume(world: &mut World) {
+    let state = *world.resource::<State<EditorState>>().get();
+    if let Some(state) = state.pause_resume() {
+        set_state(world, state);
+    }
+}
+
+/// The scene and the undo history as they were before playing.
+#[derive(Resource)]
+struct PlaySnapshot {
+    scene: DynamicScene,
+    history: (Vec<EditorChange>, Vec<EditorChange>),
+}
+
+fn snapshot_before_play(world: &mut World) {
+    if !world.resource::<EditorSettings>().restore_after_play {
+        return;
+    }
+    let entities = scene::scene_entities(world);
+    let mut builder = DynamicSceneBuilder::from_world(world);
+    builder.extract_entities(entities.into_iter());
+    builder.extract_resources();
+    let mut scene = builder.build();
+    scene::restore_debug_materials(world, &mut scene);
+    scene
+        .resources
+        .retain(|resource| restores_resource(resource.type_name()));
+    let history = world.resource_mut::<EditorHistory>().take_stacks();
+    world.insert_resource(PlaySnapshot { scene, history });
+}
+
+/// Puts the scene back as it was before playing, keeping the entities which weren't despawned
+/// meanwhile so the selection and the undo history still refer to them.
+fn restore_after_play(world: &mut World) {
+    let Some(PlaySnapshot { scene, history }) = world.remove_resource::<PlaySnapshot>() else {
+        return;
+    };
+    let snapshot: HashSet<_> = scene.entities.iter().map(|entity| entity.entity).collect();
+    for entity in scene::scene_entities(world) {
+        if snapshot.contains(&entity) {
+            continue;
+        }
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let mut entity_map = EntityMap::default();
+    for dynamic_entity in &scene.entities {
+        // respawned with the same id unless it was taken by an entity of the editor
+        let entity = match world.get_or_spawn(dynamic_entity.entity) {
+            Some(entity) => entity.id(),
+            None => world.spawn_empty().id(),
+        };
+        entity_map.insert(dynamic_entity.entity, entity);
+
+        // the components added while playing, and the children which applying the snapshot
+        // would add to rather than replace
+        let added: Vec<_> = world
+            .entity(entity)
+            .archetype()
+            .components()
+            .filter_map(|id| {
+                let type_id = world.components().get_info(id)?.type_id()?;
+                let registration = type_registry.get(type_id)?;
+                let reflect_component = registration.data::<ReflectComponent>()?;
+                let saved = dynamic_entity
+                    .components
+                    .iter()
+                    .any(|component| component.type_name() == registration.type_name());
+                (!saved || type_id == std::any::TypeId::of::<Children>())
+                    .then_some(reflect_component.clone())
+            })
+            .collect();
+        let mut entity = world.entity_mut(entity);
+        for reflect_component in added {
+            reflect_component.remove(&mut entity);
+        }
+    }
+    drop(type_registry);
+    match scene.write_to_world(world, &mut entity_map) {
+        Ok(()) => info!("Restored the scene as it was before playing"),
+        Err(e) => error!("Failed to restore the scene after playing: {e}"),
+    }
+
+    world
+        .resource_mut::<EditorHistory>()
+        .restore_stacks(history);
+    let despawned: Vec<_> = world
+        .resource::<UiState>()
+        .selected_entities
+        .iter()
+        .filter(|&entity| world.get_entity(entity).is_none())
+        .collect();
+    world
+        .resource_mut::<UiState>()
+        .selected_entities
+        .retain(|entity| !despawned.contains(&entity));
+}
+
+pub(crate) fn apply_simulation_time(
+    ed: Option<Res<EditorResource>>,
+    state: Res<State<EditorState>>,
+    step: Res<SimulationStep>,
+    mut time: ResMut<Time>,
+) {
+    let running = simulating(ed, state, step);
+    if running && time.is_paused() {
+        time.unpause();
+    } else if !running && !time.is_paused() {
+        time.pause();
+    }
+}
+
+fn play_hotkeys(
+    actions: Res<Input<EditorAction>>,
+    ed: Res<EditorResource>,
+    state: Res<State<EditorState>>,
+    mut next_state: ResMut<NextState<EditorState>>,
+    mut step: ResMut<SimulationStep>,
+) {
+    if !ed.0 {
+        return;
+    }
+    let state = *state.get();
+    if actions.just_pressed(EditorAction::PlayStop) {
+        next_state.set(state.play_stop());
+    }
+    if actions.just_pressed(EditorAction::PauseResume) {
+        if let Some(state) = state.pause_resume() {
+            next_state.set(state);
+        }
+    }
+    if actions.just_pressed(EditorAction::Step) && state == EditorState::Paused {
+        step.0 += 1;
+    }
+}
+
+fn finish_step(mut step: ResMut<SimulationStep>) {
+    step.0 = step.0.saturating_sub(1);
+}
+
+/// Play/Pause/Step/Stop buttons for the menu bar.
+pub fn play_controls(world: &mut World, ui: &mut egui::Ui) {
+    let state = *world.resource::<State<EditorState>>().get();
+    let keybinds = world.resource::<EditorKeybinds>();
+    let play_stop = keybinds.play_stop.to_string();
+    let pause_resume = keybinds.pause_resume.to_string();
+    let step = keybinds.step.to_string();
+
+    match state {
+        EditorState::Edit => {
+            if ui.button("▶ Play").on_hover_text(&play_stop).clicked() {
+                set_state(world, EditorState::Play);
+            }
+        }
+        EditorState::Play => {
+            if ui.button("⏸ Pause").on_hover_text(&pause_resume).clicked() {
+                set_state(world, EditorState::Paused);
+            }
+        }
+        EditorState::Paused => {
+            if ui.button("▶ Resume").on_hover_text(&pause_resume).clicked() {
+                set_state(world, EditorState::Play);
+            }
+        }
+    }
+    if ui
+        .add_enabled(state == EditorState::Paused, egui::Button::new("⏭ Step"))
+        .on_hover_text(step)
+        .clicked()
+    {
+        request_step(world);
+    }
+    if ui
+        .add_enabled(state != EditorState::Edit, egui::Button::new("⏹ Stop"))
+        .on_hover_text(play_stop)
+        .clicked()
+    {
+        set_state(world, EditorState::Edit);
+    }
+    ui.label(format!("{state:?}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_resources_of_the_game_are_restored() {
+        assert!(restores_resource("my_game::Score"));
+        assert!(!restores_resource(std::any::type_name::<EditorSettings>()));
+        assert!(!restores_resource(std::any::type_name::<Time>()));
+    }
+
+    #[test]
+    fn time_isnt_snapshotted() {
+        let mut world = World::new();
+        let type_registry = AppTypeRegistry::default();
+        type_registry.write().register::<Time>();
+        world.insert_resource(type_registry);
+        world.init_resource::<Time>();
+        world.init_resource::<EditorSettings>();
+        world.init_resource::<EditorHistory>();
+
+        snapshot_before_play(&mut world);
+        assert!(world.resource::<PlaySnapshot>().scene.resources.is_empty());
+    }
+}
@@ -0,0 +1,800 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use bevy::{prelude::*, reflect::ReflectFromReflect, render::primitives::Aabb};
+use bevy_inspector_egui::{
+    bevy_inspector::hierarchy::SelectedEntities, inspector_egui_impls::InspectorEguiImpl,
+};
+use bevy_reflect::TypeRegistry;
+
+use crate::{
+    editor_id::EditorId,
+    history,
+    inspector::{self, entity_name},
+    scene::{self, SceneFileError},
+    UiState,
+};
+
+pub const PREFAB_DIR: &str = "assets/prefabs";
+pub const PREFAB_EXTENSION: &str = "prefab.ron";
+
+/// How often prefab files are checked for changes made outside of the editor, in seconds.
+const POLL_INTERVAL: f32 = 1.0;
+
+pub struct EditorPrefabPlugin;
+impl Plugin for EditorPrefabPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PrefabInstance>()
+            .init_resource::<Prefabs>()
+            .add_systems(Update, poll_prefab_files);
+    }
+}
+
+/// Marks the root of an instantiated prefab. When the prefab file changes, every instance is
+/// respawned from it, keeping the `Transform` and parent of its root and the [`Override`]s of its
+/// entities.
+#[derive(Debug, Clone, Default, Component, Reflect)]
+#[reflect(Component)]
+pub struct PrefabInstance {
+    pub path: String,
+}
+
+struct LoadedPrefab {
+    scene: DynamicScene,
+    modified: Option<SystemTime>,
+}
+
+/// Prefab files loaded so far, by path.
+#[derive(Resource, Default)]
+pub struct Prefabs {
+    loaded: HashMap<String, LoadedPrefab>,
+    since_poll: f32,
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Path a prefab made from an entity called `name` is saved to.
+pub fn prefab_path(name: &str) -> String {
+    let file_name: String = name
+        .chars()
+        .map(|c| match c.is_alphanumeric() || c == '-' || c == '_' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    format!("{PREFAB_DIR}/{file_name}.{PREFAB_EXTENSION}")
+}
+
+/// Every prefab file in [`PREFAB_DIR`].
+pub fn prefab_files() -> Vec<String> {
+    let Ok(dir) = fs::read_dir(PREFAB_DIR) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(PREFAB_EXTENSION))
+        })
+        .map(|path: PathBuf| path.to_string_lossy().replace('\\', "/"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn load_prefab(world: &mut World, path: &str) -> Result<(), SceneFileError> {
+    let modified = modified_time(path);
+    let ron = fs::read_to_string(path)?;
+    let scene = scene::deserialize_scene(world, &ron)?;
+    world
+        .resource_mut::<Prefabs>()
+        .loaded
+        .insert(path.to_owned(), LoadedPrefab { scene, modified });
+    Ok(())
+}
+
+/// Spawns the prefab at `path` as the instance `instance`, the [`EditorId`] of its root.
+fn spawn_prefab(
+    world: &mut World,
+    path: &str,
+    instance: EditorId,
+) -> Result<Entity, SceneFileError> {
+    if !world.resource::<Prefabs>().loaded.contains_key(path) {
+        load_prefab(world, path)?;
+    }
+    let entity_map = world.resource_scope(|world, prefabs: Mut<Prefabs>| {
+        scene::spawn_scene_mapped(world, &prefabs.loaded[path].scene)
+    })?;
+    for entity in entity_map.values() {
+        if let Some(mut id) = world.get_mut::<EditorId>(entity) {
+            *id = id.in_instance(instance);
+        }
+    }
+    let mut roots: Vec<_> = entity_map
+        .values()
+        .filter(|&entity| world.get::<Parent>(entity).is_none())
+        .collect();
+    roots.sort();
+
+    let root = match roots.as_slice() {
+        &[root] => root,
+        // prefabs saved by the editor always have a single root, but files can be edited by hand
+        roots => {
+            let root = world
+                .spawn((
+                    Name::new(entity_name_from_path(path)),
+                    SpatialBundle::default(),
+                ))
+                .id();
+            for &child in roots {
+                world.entity_mut(child).set_parent(root);
+            }
+            root
+        }
+    };
+    world.entity_mut(root).insert((
+        PrefabInstance {
+            path: path.to_owned(),
+        },
+        instance,
+    ));
+    Ok(root)
+}
+
+fn entity_name_from_path(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| {
+            name.trim_end_matches(PREFAB_EXTENSION)
+                .trim_end_matches('.')
+        })
+        .unwrap_or(path)
+        .to_owned()
+}
+
+/// Spawns a new instance of the prefab at `path`.
+pub fn instantiate_prefab(
+    world: &mut World,
+    path: &str,
+    parent: Option<Entity>,
+) -> Result<Entity, SceneFileError> {
+    let root = spawn_prefab(world, path, EditorId::new())?;
+    if let Some(parent) = parent {
+        world.entity_mut(root).set_parent(parent);
+    }
+    Ok(root)
+}
+
+/// Saves `root` and its descendants as a prefab and turns `root` into an instance of it.
+/// Other instances of the same prefab are updated.
+///
+/// Returns the instances which were respawned, as `(old, new)` pairs.
+pub fn save_prefab(
+    world: &mut World,
+    root: Entity,
+    path: &str,
+) -> Result<Vec<(Entity, Entity)>, SceneFileError> {
+    let entities = scene::with_descendants(world, &[root]);
+    let mut prefab = scene::build_scene(world, &entities);
+
+    // the root's placement belongs to each instance, not to the prefab
+    let instance_type_name = std::any::type_name::<PrefabInstance>();
+    let id_type_name = std::any::type_name::<EditorId>();
+    // saved relative to the root, so `root` keeps the ids of its entities when respawned
+    let instance = world.get::<EditorId>(root).copied().unwrap_or_default();
+    for entity in &mut prefab.entities {
+        if entity.entity == root {
+            entity
+                .components
+                .retain(|component| component.type_name() != instance_type_name);
+        }
+        for component in &mut entity.components {
+            if component.type_name() != id_type_name {
+                continue;
+            }
+            if let Some(id) = EditorId::from_reflect(component.as_reflect()) {
+                *component = Box::new(id.in_instance(instance));
+            }
+        }
+    }
+
+    let type_registry = world.resource::<AppTypeRegistry>();
+    let ron = prefab.serialize_ron(type_registry)?;
+    update_instances(world, path, Some(root), |world| {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, ron)?;
+        world.entity_mut(root).insert(PrefabInstance {
+            path: path.to_owned(),
+        });
+        load_prefab(world, path)
+    })
+}
+
+/// The overrides of every instance of the prefab at `path` except `except`.
+fn instances_overrides(
+    world: &mut World,
+    path: &str,
+    except: Option<Entity>,
+) -> Vec<(Entity, Vec<InstanceOverride>)> {
+    let instances: Vec<_> = world
+        .query::<(Entity, &PrefabInstance)>()
+        .iter(world)
+        .filter(|&(entity, instance)| instance.path == path && Some(entity) != except)
+        .map(|(entity, _)| entity)
+        .collect();
+    instances
+        .into_iter()
+        .map(|instance| (instance, instance_overrides(world, instance)))
+        .collect()
+}
+
+/// Changes the prefab at `path` with `update`, then respawns every instance of it except `except`
+/// with the overrides they had of the prefab as it was. Returns the `(old, new)` pairs.
+fn update_instances(
+    world: &mut World,
+    path: &str,
+    except: Option<Entity>,
+    update: impl FnOnce(&mut World) -> Result<(), SceneFileError>,
+) -> Result<Vec<(Entity, Entity)>, SceneFileError> {
+    let instances = instances_overrides(world, path, except);
+    update(world)?;
+    Ok(respawn_instances(world, path, instances))
+}
+
+/// Respawns every instance of the prefab at `path` except `except`, returning `(old, new)` pairs.
+pub fn sync_instances(
+    world: &mut World,
+    path: &str,
+    except: Option<Entity>,
+) -> Vec<(Entity, Entity)> {
+    let instances = instances_overrides(world, path, except);
+    respawn_instances(world, path, instances)
+}
+
+fn respawn_instances(
+    world: &mut World,
+    path: &str,
+    instances: Vec<(Entity, Vec<InstanceOverride>)>,
+) -> Vec<(Entity, Entity)> {
+    let mut respawned = Vec::new();
+    for (old, overrides) in instances {
+        let transform = world.get::<Transform>(old).copied();
+        let parent = world.get::<Parent>(old).map(|parent| parent.get());
+        let instance = world.get::<EditorId>(old).copied().unwrap_or_default();
+
+        let new = match spawn_prefab(world, path, instance) {
+            Ok(new) => new,
+            Err(e) => {
+                error!("Failed to update prefab instance {old:?} of {path}: {e}");
+                continue;
+            }
+        };
+        world.entity_mut(old).despawn_recursive();
+
+        let mut new_entity = world.entity_mut(new);
+        if let Some(transform) = transform {
+            new_entity.insert(transform);
+        }
+        if let Some(parent) = parent.filter(|&parent| parent != old) {
+            new_entity.set_parent(parent);
+        }
+        apply_instance_overrides(world, new, overrides);
+        respawned.push((old, new));
+    }
+    if !respawned.is_empty() {
+        info!("Updated {} instances of {path}", respawned.len());
+    }
+    respawned
+}
+
+/// Replaces respawned instances in the selection.
+pub fn remap_selection(selected: &mut SelectedEntities, respawned: &[(Entity, Entity)]) {
+    for &(old, new) in respawned {
+        if selected.remove(old).is_some() {
+            selected.select_maybe_add(new, true);
+        }
+    }
+}
+
+/// Reloads prefab files changed on disk and updates their instances.
+fn poll_prefab_files(world: &mut World) {
+    let delta = world.resource::<Time>().raw_delta_seconds();
+    let mut prefabs = world.resource_mut::<Prefabs>();
+    prefabs.since_poll += delta;
+    if prefabs.since_poll < POLL_INTERVAL {
+        return;
+    }
+    prefabs.since_poll = 0.0;
+
+    // the prefabs of instances loaded with a scene, to compare them with
+    let mut unloaded: Vec<_> = world
+        .query::<&PrefabInstance>()
+        .iter(world)
+        .map(|instance| instance.path.clone())
+        .filter(|path| {
+            !world.resource::<Prefabs>().loaded.contains_key(path) && Path::new(path).exists()
+        })
+        .collect();
+    unloaded.sort();
+    unloaded.dedup();
+    for path in unloaded {
+        if let Err(e) = load_prefab(world, &path) {
+            error!("Failed to load prefab {path}: {e}");
+        }
+    }
+
+    let prefabs = world.resource::<Prefabs>();
+    let changed: Vec<_> = prefabs
+        .loaded
+        .iter()
+        .filter(|(path, prefab)| modified_time(path) != prefab.modified)
+        .map(|(path, _)| path.clone())
+        .collect();
+    for path in changed {
+        let respawned =
+            match update_instances(world, &path, None, |world| load_prefab(world, &path)) {
+                Ok(respawned) => respawned,
+                Err(e) => {
+                    error!("Failed to reload prefab {path}: {e}");
+                    // don't retry every poll
+                    if let Some(prefab) = world.resource_mut::<Prefabs>().loaded.get_mut(&path) {
+                        prefab.modified = modified_time(&path);
+                    }
+                    continue;
+                }
+            };
+        let mut ui_state = world.resource_mut::<UiState>();
+        remap_selection(&mut ui_state.selected_entities, &respawned);
+    }
+}
+
+/// Lists the prefab files, each with a button to instantiate it.
+///
+/// Returns the instance spawned this frame, if any.
+pub fn prefabs_ui(world: &mut World, ui: &mut egui::Ui) -> Option<Entity> {
+    let mut spawned = None;
+    let files = prefab_files();
+    if files.is_empty() {
+        ui.label(format!(
+            "No prefabs in {PREFAB_DIR}. Right click an entity in the hierarchy to save one."
+        ));
+    }
+
+    let mut instance_counts = HashMap::<String, usize>::new();
+    for instance in world.query::<&PrefabInstance>().iter(world) {
+        *instance_counts.entry(instance.path.clone()).or_default() += 1;
+    }
+
+    for path in files {
+        ui.horizontal(|ui| {
+            if ui.button("Instantiate").clicked() {
+                match instantiate_prefab(world, &path, None) {
+                    Ok(entity) => {
+                        info!("Instantiated {path} as {entity:?}");
+                        spawned = Some(entity);
+                    }
+                    Err(e) => error!("Failed to instantiate prefab {path}: {e}"),
+                }
+            }
+            ui.label(entity_name_from_path(&path)).on_hover_text(&path);
+            let count = instance_counts.get(&path).copied().unwrap_or_default();
+            if count > 0 {
+                ui.weak(format!("{count} instances"));
+            }
+        });
+    }
+    spawned
+}
+
+/// Prefab related entries of an entity's context menu.
+pub(crate) fn prefab_menu(
+    ui: &mut egui::Ui,
+    world: &World,
+    entity: Entity,
+) -> Option<PrefabAction> {
+    let mut action = None;
+    match world.get::<PrefabInstance>(entity) {
+        Some(instance) => {
+            if ui
+                .button("Apply to Prefab")
+                .on_hover_text(&instance.path)
+                .clicked()
+            {
+                action = Some(PrefabAction::Save(instance.path.clone()));
+            }
+            if ui.button("Unpack Prefab").clicked() {
+                action = Some(PrefabAction::Unpack);
+            }
+        }
+        None => {
+            if ui.button("Save as Prefab").clicked() {
+                action = Some(PrefabAction::Save(prefab_path(&entity_name(world, entity))));
+            }
+        }
+    }
+    if action.is_some() {
+        ui.close_menu();
+    }
+    action
+}
+
+pub(crate) enum PrefabAction {
+    Save(String),
+    Unpack,
+}
+
+pub(crate) fn apply_prefab_action(
+    world: &mut World,
+    entity: Entity,
+    action: PrefabAction,
+    selected: &mut SelectedEntities,
+) {
+    match action {
+        PrefabAction::Save(path) => match save_prefab(world, entity, &path) {
+            Ok(respawned) => {
+                info!("Saved prefab {path}");
+                remap_selection(selected, &respawned);
+            }
+            Err(e) => error!("Failed to save prefab {path}: {e}"),
+        },
+        PrefabAction::Unpack => {
+            world.entity_mut(entity).remove::<PrefabInstance>();
+        }
+    }
+}
+
+/// Components of the entities of instances which aren't compared with the prefab, since they're
+/// computed or tie the instance together.
+fn compared(type_id: TypeId) -> bool {
+    ![
+        TypeId::of::<GlobalTransform>(),
+        TypeId::of::<ComputedVisibility>(),
+        TypeId::of::<Aabb>(),
+        TypeId::of::<Parent>(),
+        TypeId::of::<Children>(),
+        TypeId::of::<EditorId>(),
+        TypeId::of::<PrefabInstance>(),
+    ]
+    .contains(&type_id)
+}
+
+/// The root of the prefab instance `entity` is part of, and the id of `entity` in the prefab.
+fn instance_entity(world: &World, entity: Entity) -> Option<(Entity, EditorId)> {
+    let id = *world.get::<EditorId>(entity)?;
+    let root = std::iter::successors(Some(entity), |&entity| {
+        world.get::<Parent>(entity).map(|parent| parent.get())
+    })
+    .find(|&entity| world.get::<PrefabInstance>(entity).is_some())?;
+    Some((root, id.in_instance(*world.get::<EditorId>(root)?)))
+}
+
+/// Whether `entity` is part of a prefab instance, and so compared with the prefab.
+pub fn in_instance(world: &World, entity: Entity) -> bool {
+    instance_entity(world, entity).is_some()
+}
+
+/// The index of the entity of `scene` with the id `id`.
+fn prefab_entity(scene: &DynamicScene, id: EditorId) -> Option<usize> {
+    let id_type_name = std::any::type_name::<EditorId>();
+    scene.entities.iter().position(|entity| {
+        entity.components.iter().any(|component| {
+            component.type_name() == id_type_name
+                && EditorId::from_reflect(component.as_reflect()) == Some(id)
+        })
+    })
+}
+
+/// The component `type_id` of `entity` as it is in its prefab, built as its own type so it
+/// compares with the one of the instance. `None` if `entity` isn't part of an instance or the
+/// component isn't compared, `Some(None)` if the prefab doesn't have it.
+fn prefab_value(
+    world: &World,
+    entity: Entity,
+    type_id: TypeId,
+    type_registry: &TypeRegistry,
+) -> Option<Option<Box<dyn Reflect>>> {
+    let (root, id) = instance_entity(world, entity)?;
+    // the placement of the root belongs to the instance
+    if !compared(type_id) || (entity == root && type_id == TypeId::of::<Transform>()) {
+        return None;
+    }
+    let path = &world.get::<PrefabInstance>(root)?.path;
+    let scene = &world.resource::<Prefabs>().loaded.get(path)?.scene;
+    let prefab_entity = &scene.entities[prefab_entity(scene, id)?];
+    let type_name = type_registry.get(type_id)?.type_name();
+    let value = prefab_entity
+        .components
+        .iter()
+        .find(|component| component.type_name() == type_name)
+        .map(|value| {
+            type_registry
+                .get_type_data::<ReflectFromReflect>(type_id)
+                .and_then(|from_reflect| from_reflect.from_reflect(value.as_reflect()))
+                .unwrap_or_else(|| value.clone_value())
+        });
+    Some(value)
+}
+
+/// How a component of a prefab instance differs from the prefab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Override {
+    /// The prefab doesn't have the component.
+    Added,
+    /// The component differs as a whole, when its fields aren't shown one by one.
+    Value,
+    /// These fields of the component differ.
+    Fields(Vec<usize>),
+}
+
+fn compare_with_prefab(
+    value: &dyn Reflect,
+    prefab: Option<&dyn Reflect>,
+    whole: bool,
+) -> Option<Override> {
+    let Some(prefab) = prefab else {
+        return Some(Override::Added);
+    };
+    let fields = inspector::field_names(value).len();
+    if whole || fields == 0 {
+        return (!inspector::reflect_eq(Some(value), Some(prefab))).then_some(Override::Value);
+    }
+    let differing: Vec<_> = (0..fields)
+        .filter(|&index| {
+            !inspector::reflect_eq(
+                inspector::field(value, index),
+                inspector::field(prefab, index),
+            )
+        })
+        .collect();
+    (!differing.is_empty()).then_some(Override::Fields(differing))
+}
+
+/// The components of `entity` which differ from its prefab, if it's part of a prefab instance.
+pub fn overrides(world: &World, entity: Entity) -> HashMap<TypeId, Override> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let mut overrides = HashMap::new();
+    let Some(entity_ref) = world.get_entity(entity) else {
+        return overrides;
+    };
+    if instance_entity(world, entity).is_none() {
+        return overrides;
+    }
+    for component_id in entity_ref.archetype().components() {
+        let Some(type_id) = world
+            .components()
+            .get_info(component_id)
+            .and_then(|info| info.type_id())
+        else {
+            continue;
+        };
+        let Some(value) = type_registry
+            .get_type_data::<ReflectComponent>(type_id)
+            .and_then(|reflect_component| reflect_component.reflect(entity_ref))
+        else {
+            continue;
+        };
+        let Some(prefab) = prefab_value(world, entity, type_id, &type_registry) else {
+            continue;
+        };
+        // custom widgets show the component as a whole
+        let whole = type_registry
+            .get_type_data::<InspectorEguiImpl>(type_id)
+            .is_some();
+        if let Some(o) = compare_with_prefab(value, prefab.as_deref(), whole) {
+            overrides.insert(type_id, o);
+        }
+    }
+    overrides
+}
+
+/// A value overriding the prefab in an instance, kept when the instance is respawned.
+struct InstanceOverride {
+    /// The entity, by its id in the prefab.
+    id: EditorId,
+    type_id: TypeId,
+    /// The field overridden, or `None` for the whole component.
+    field: Option<usize>,
+    value: Box<dyn Reflect>,
+}
+
+fn instance_overrides(world: &World, root: Entity) -> Vec<InstanceOverride> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let mut instance_overrides = Vec::new();
+    for entity in scene::with_descendants(world, &[root]) {
+        // the entities of instances nested in this one are respawned from it
+        let Some((_, id)) = instance_entity(world, entity).filter(|&(r, _)| r == root) else {
+            continue;
+        };
+        for (type_id, o) in overrides(world, entity) {
+            let Some(value) = type_registry
+                .get_type_data::<ReflectComponent>(type_id)
+                .and_then(|reflect_component| reflect_component.reflect(world.entity(entity)))
+            else {
+                continue;
+            };
+            match o {
+                Override::Added | Override::Value => instance_overrides.push(InstanceOverride {
+                    id,
+                    type_id,
+                    field: None,
+                    value: value.clone_value(),
+                }),
+                Override::Fields(fields) => {
+                    instance_overrides.extend(fields.into_iter().filter_map(|index| {
+                        Some(InstanceOverride {
+                            id,
+                            type_id,
+                            field: Some(index),
+                            value: inspector::field(value, index)?.clone_value(),
+                        })
+                    }));
+                }
+            }
+        }
+    }
+    instance_overrides
+}
+
+fn apply_instance_overrides(world: &mut World, root: Entity, overrides: Vec<InstanceOverride>) {
+    let Some(&instance) = world.get::<EditorId>(root) else {
+        return;
+    };
+    let entities: HashMap<_, _> = scene::with_descendants(world, &[root])
+        .into_iter()
+        .filter_map(|entity| Some((world.get::<EditorId>(entity)?.in_instance(instance), entity)))
+        .collect();
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    for InstanceOverride {
+        id,
+        type_id,
+        field,
+        value,
+    } in overrides
+    {
+        // removed from the prefab
+        let Some(&entity) = entities.get(&id) else {
+            continue;
+        };
+        let Some(reflect_component) = type_registry.get_type_data::<ReflectComponent>(type_id)
+        else {
+            continue;
+        };
+        let mut entity_mut = world.entity_mut(entity);
+        match field {
+            None => reflect_component.insert(&mut entity_mut, value.as_ref()),
+            Some(index) => {
+                let Some(mut component) = reflect_component.reflect_mut(&mut entity_mut) else {
+                    continue;
+                };
+                if let Some(target) = inspector::field_mut(&mut *component, index) {
+                    inspector::copy_value(target, value.as_ref(), &type_registry);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum OverrideAction {
+    /// Sets the value of the instance back to the one of the prefab.
+    Revert,
+    /// Saves the value of the instance to the prefab, and updates the other instances.
+    Apply,
+}
+
+/// Reverts or applies the override of the component `type_id` of `entity`, or of the field
+/// `field_index` of it.
+///
+/// Returns the instances respawned by applying it, to remap the selection with.
+pub(crate) fn apply_override_action(
+    world: &mut World,
+    entity: Entity,
+    type_id: TypeId,
+    field_index: Option<usize>,
+    action: OverrideAction,
+) -> Vec<(Entity, Entity)> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let (Some((root, id)), Some(registration)) =
+        (instance_entity(world, entity), type_registry.get(type_id))
+    else {
+        return Vec::new();
+    };
+    let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+        return Vec::new();
+    };
+    match action {
+        OverrideAction::Revert => {
+            let Some(prefab) = prefab_value(world, entity, type_id, &type_registry) else {
+                return Vec::new();
+            };
+            let before = history::snapshot_components(world, entity);
+            let mut entity_mut = world.entity_mut(entity);
+            match (prefab, field_index) {
+                (None, _) => reflect_component.remove(&mut entity_mut),
+                (Some(prefab), None) => reflect_component.insert(&mut entity_mut, prefab.as_ref()),
+                (Some(prefab), Some(index)) => {
+                    let (Some(mut component), Some(value)) = (
+                        reflect_component.reflect_mut(&mut entity_mut),
+                        inspector::field(prefab.as_ref(), index),
+                    ) else {
+                        return Vec::new();
+                    };
+                    if let Some(target) = inspector::field_mut(&mut *component, index) {
+                        inspector::copy_value(target, value, &type_registry);
+                    }
+                }
+            }
+            history::record_component_changes(world, entity, before);
+            Vec::new()
+        }
+        OverrideAction::Apply => {
+            let Some(value) = reflect_component.reflect(world.entity(entity)) else {
+                return Vec::new();
+            };
+            let value = match field_index {
+                Some(index) => inspector::field(value, index),
+                None => Some(value),
+            };
+            let Some(value) = value.map(Reflect::clone_value) else {
+                return Vec::new();
+            };
+            let path = world.get::<PrefabInstance>(root).unwrap().path.clone();
+            let result = update_instances(world, &path, Some(root), |world| {
+                world.resource_scope(|world, mut prefabs: Mut<Prefabs>| {
+                    let Some(prefab) = prefabs.loaded.get_mut(&path) else {
+                        return Ok(());
+                    };
+                    let Some(index) = prefab_entity(&prefab.scene, id) else {
+                        return Ok(());
+                    };
+                    let components = &mut prefab.scene.entities[index].components;
+                    let component = components
+                        .iter_mut()
+                        .find(|component| component.type_name() == registration.type_name());
+                    match (component, field_index) {
+                        (Some(component), Some(index)) => {
+                            if let Some(target) = inspector::field_mut(&mut **component, index) {
+                                inspector::copy_value(target, value.as_ref(), &type_registry);
+                            }
+                        }
+                        (Some(component), None) => *component = value,
+                        (None, _) => components.push(value),
+                    }
+                    let ron = prefab
+                        .scene
+                        .serialize_ron(world.resource::<AppTypeRegistry>())?;
+                    fs::write(&path, ron)?;
+                    prefab.modified = modified_time(&path);
+                    Ok(())
+                })
+            });
+            match result {
+                Ok(respawned) => {
+                    info!("Applied {} to {path}", registration.short_name());
+                    respawned
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to apply {} to {path}: {e}",
+                        registration.short_name()
+                    );
+                    Vec::new()
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{CameraUpdateSystem, RenderTarget},
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+    },
+    transform::TransformSystem,
+};
+use bevy_inspector_egui::bevy_egui::EguiUserTextures;
+
+use crate::{EditorOnly, EditorResource, MainCamera, UiState};
+
+const PREVIEW_SIZE: Extent3d = Extent3d {
+    width: 384,
+    height: 216,
+    depth_or_array_layers: 1,
+};
+
+pub struct EditorPreviewPlugin;
+impl Plugin for EditorPreviewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_preview_image).add_systems(
+            PostUpdate,
+            update_camera_preview
+                .after(TransformSystem::TransformPropagate)
+                .before(CameraUpdateSystem),
+        );
+    }
+}
+
+/// Image the preview camera renders into, registered as an egui texture.
+#[derive(Resource)]
+struct CameraPreviewImage {
+    image: Handle<Image>,
+    texture: egui::TextureId,
+}
+
+/// Editor camera rendering what `target`, a camera of the game, sees into the
+/// [`CameraPreviewImage`].
+#[derive(Component)]
+pub struct CameraPreview {
+    target: Entity,
+}
+
+fn setup_preview_image(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut textures: ResMut<EguiUserTextures>,
+) {
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("editor_camera_preview"),
+            size: PREVIEW_SIZE,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(PREVIEW_SIZE);
+    let image = images.add(image);
+    let texture = textures.add_image(image.clone());
+    commands.insert_resource(CameraPreviewImage { image, texture });
+}
+
+/// Cameras of the game, which can be previewed, as opposed to the editor's own.
+type GameCamera = (With<Camera>, Without<MainCamera>, Without<EditorOnly>);
+
+/// Spawns, follows and despawns the preview camera of the selected game camera.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_camera_preview(
+    mut commands: Commands,
+    editor: Res<EditorResource>,
+    ui_state: Res<UiState>,
+    image: Option<Res<CameraPreviewImage>>,
+    targets: Query<
+        (
+            &GlobalTransform,
+            Option<&Projection>,
+            Option<&Camera3d>,
+            Option<&Camera2d>,
+            Option<&OrthographicProjection>,
+        ),
+        GameCamera,
+    >,
+    mut previews: Query<(
+        Entity,
+        &CameraPreview,
+        &mut Transform,
+        &mut GlobalTransform,
+        Option<&mut Projection>,
+        Option<&mut OrthographicProjection>,
+    )>,
+) {
+    let Some(image) = image else {
+        return;
+    };
+    let target = match editor.0 {
+        true => ui_state
+            .selected_entities
+            .iter()
+            .find(|&entity| targets.contains(entity)),
+        false => None,
+    };
+
+    let mut previewed = false;
+    for (entity, preview, mut transform, mut global, projection, orthographic) in &mut previews {
+        let Some((target_global, target_projection, _, _, target_orthographic)) = target
+            .filter(|&target| target == preview.target)
+            .and_then(|target| targets.get(target).ok())
+        else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        previewed = true;
+        // set the global transform too, as propagation already ran this frame
+        *global = *target_global;
+        *transform = target_global.compute_transform();
+        if let (Some(mut projection), Some(target)) = (projection, target_projection) {
+            *projection = target.clone();
+        }
+        if let (Some(mut orthographic), Some(target)) = (orthographic, target_orthographic) {
+            *orthographic = target.clone();
+        }
+    }
+    if previewed {
+        return;
+    }
+    let Some((target, (global, projection, camera_3d, camera_2d, orthographic))) =
+        target.and_then(|target| Some((target, targets.get(target).ok()?)))
+    else {
+        return;
+    };
+
+    let camera = Camera {
+        target: RenderTarget::Image(image.image.clone()),
+        // render before the editor cameras, which are drawn into the window
+        order: -1,
+        ..default()
+    };
+    let mut preview = commands.spawn((
+        Name::new("Editor Camera Preview"),
+        CameraPreview { target },
+        EditorOnly,
+    ));
+    if camera_2d.is_some() {
+        let mut bundle = Camera2dBundle {
+            camera,
+            transform: global.compute_transform(),
+            global_transform: *global,
+            ..default()
+        };
+        if let Some(orthographic) = orthographic {
+            bundle.projection = orthographic.clone();
+        }
+        bundle.camera_2d = camera_2d.cloned().unwrap_or(bundle.camera_2d);
+        preview.insert(bundle);
+    } else {
+        preview.insert(Camera3dBundle {
+            camera,
+            camera_3d: camera_3d.cloned().unwrap_or_default(),
+            projection: projection.cloned().unwrap_or_default(),
+            transform: global.compute_transform(),
+            global_transform: *global,
+            ..default()
+        });
+    }
+}
+
+/// Shows the camera preview in the bottom right corner of `viewport_rect`, if a game camera is
+/// selected.
+pub fn camera_preview_ui(ui: &mut egui::Ui, world: &mut World, viewport_rect: egui::Rect) {
+    let Some(image) = world.get_resource::<CameraPreviewImage>() else {
+        return;
+    };
+    let texture = image.texture;
+    let Some(target) = world
+        .query::<&CameraPreview>()
+        .iter(world)
+        .next()
+        .map(|preview| preview.target)
+    else {
+        return;
+    };
+    let name = world
+        .get::<Name>(target)
+        .map_or_else(|| format!("{target:?}"), |name| name.to_string());
+
+    let margin = 8.0;
+    let width = (viewport_rect.width() * 0.3).clamp(96.0, PREVIEW_SIZE.width as f32);
+    let size = egui::vec2(
+        width,
+        width * PREVIEW_SIZE.height as f32 / PREVIEW_SIZE.width as f32,
+    );
+    if viewport_rect.width() < size.x + margin * 2.0 || viewport_rect.height() < size.y * 2.0 {
+        return;
+    }
+    let rect =
+        egui::Rect::from_min_size(viewport_rect.max - size - egui::vec2(margin, margin), size);
+
+    let painter = ui.painter_at(viewport_rect);
+    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+    painter.image(texture, rect, uv, egui::Color32::WHITE);
+    painter.rect_stroke(rect, 0.0, ui.visuals().window_stroke());
+    painter.text(
+        rect.left_top() + egui::vec2(4.0, 2.0),
+        egui::Align2::LEFT_TOP,
+        name,
+        egui::FontId::proportional(12.0),
+        egui::Color32::WHITE,
+    );
+}
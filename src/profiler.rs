@@ -0,0 +1,334 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    prelude::*,
+    utils::{
+        tracing::{
+            field::{Field, Visit},
+            span::{Attributes, Id},
+            Subscriber,
+        },
+        HashMap,
+    },
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+const HISTORY_LEN: usize = 120;
+const SPARKLINE_SIZE: egui::Vec2 = egui::vec2(120.0, 16.0);
+
+pub struct EditorProfilerPlugin;
+impl Plugin for EditorProfilerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProfilerHistory>()
+            .add_systems(First, end_profiler_frame);
+    }
+}
+
+struct ProfileBuffer {
+    /// The time each system ran for since the last frame.
+    frame: HashMap<String, Duration>,
+}
+
+/// Time spent in systems, measured from their tracing spans by [`EditorLogPlugin`].
+///
+/// Bevy only opens spans for systems with its `trace` feature enabled.
+///
+/// [`EditorLogPlugin`]: crate::console::EditorLogPlugin
+#[derive(Resource, Clone)]
+pub struct SystemProfile {
+    recording: Arc<AtomicBool>,
+    buffer: Arc<Mutex<ProfileBuffer>>,
+}
+
+impl Default for SystemProfile {
+    fn default() -> Self {
+        Self {
+            recording: Arc::new(AtomicBool::new(true)),
+            buffer: Arc::new(Mutex::new(ProfileBuffer {
+                frame: HashMap::default(),
+            })),
+        }
+    }
+}
+
+impl SystemProfile {
+    pub fn is_recording(&self) -> bool {
+        self.recording.load(Ordering::Relaxed)
+    }
+
+    pub fn set_recording(&self, recording: bool) {
+        self.recording.store(recording, Ordering::Relaxed);
+    }
+
+    fn take_frame(&self) -> HashMap<String, Duration> {
+        std::mem::take(&mut self.buffer.lock().unwrap().frame)
+    }
+
+    /// The layer timing the system spans, added to the subscriber by [`EditorLogPlugin`].
+    ///
+    /// [`EditorLogPlugin`]: crate::console::EditorLogPlugin
+    pub(crate) fn layer(&self) -> ProfilerLayer {
+        ProfilerLayer {
+            profile: self.clone(),
+        }
+    }
+}
+
+/// Kept in the extensions of a system span while it's open.
+struct SystemTiming {
+    name: String,
+    entered: Option<Instant>,
+    busy: Duration,
+}
+
+#[derive(Default)]
+struct NameVisitor(Option<String>);
+
+impl Visit for NameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "name" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+pub(crate) struct ProfilerLayer {
+    profile: SystemProfile,
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for ProfilerLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "system" || !self.profile.is_recording() {
+            return;
+        }
+        let mut visitor = NameVisitor::default();
+        attrs.record(&mut visitor);
+        let (Some(name), Some(span)) = (visitor.0, ctx.span(id)) else {
+            return;
+        };
+        span.extensions_mut().insert(SystemTiming {
+            name,
+            entered: None,
+            busy: Duration::ZERO,
+        });
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<SystemTiming>() {
+            timing.entered = Some(Instant::now());
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(timing) = extensions.get_mut::<SystemTiming>() {
+            if let Some(entered) = timing.entered.take() {
+                timing.busy += entered.elapsed();
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(timing) = span.extensions_mut().remove::<SystemTiming>() else {
+            return;
+        };
+        // systems of schedules like `FixedUpdate` can run several times a frame
+        let mut buffer = self.profile.buffer.lock().unwrap();
+        *buffer.frame.entry(timing.name).or_default() += timing.busy;
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum ProfilerSort {
+    #[default]
+    Average,
+    Last,
+    Max,
+    Name,
+}
+
+/// The time each system took in recent frames, in milliseconds, and how the profiler tab shows
+/// them.
+#[derive(Resource, Default)]
+pub struct ProfilerHistory {
+    systems: HashMap<String, VecDeque<f32>>,
+    frames: usize,
+    sort: ProfilerSort,
+    filter: String,
+}
+
+fn end_profiler_frame(profile: Option<Res<SystemProfile>>, mut history: ResMut<ProfilerHistory>) {
+    let Some(profile) = profile.filter(|profile| profile.is_recording()) else {
+        return;
+    };
+    let mut frame = profile.take_frame();
+    if frame.is_empty() {
+        return;
+    }
+    history.frames += 1;
+    let frames = history.frames.min(HISTORY_LEN);
+    for (name, times) in history.systems.iter_mut() {
+        let time = frame.remove(name).unwrap_or_default();
+        if times.len() >= HISTORY_LEN {
+            times.pop_front();
+        }
+        times.push_back(time.as_secs_f32() * 1000.0);
+    }
+    // systems seen for the first time didn't run in the earlier frames
+    for (name, time) in frame {
+        let mut times = VecDeque::from(vec![0.0; frames - 1]);
+        times.push_back(time.as_secs_f32() * 1000.0);
+        history.systems.insert(name, times);
+    }
+}
+
+struct SystemStats<'a> {
+    name: &'a str,
+    times: &'a VecDeque<f32>,
+    last: f32,
+    average: f32,
+    max: f32,
+}
+
+fn sparkline(ui: &mut egui::Ui, times: &VecDeque<f32>, max: f32) {
+    let (rect, _) = ui.allocate_exact_size(SPARKLINE_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 1.0, ui.visuals().extreme_bg_color);
+    if max <= 0.0 {
+        return;
+    }
+    let step = rect.width() / (HISTORY_LEN - 1) as f32;
+    let offset = HISTORY_LEN - times.len();
+    let points = times
+        .iter()
+        .enumerate()
+        .map(|(index, time)| {
+            egui::pos2(
+                rect.left() + (index + offset) as f32 * step,
+                rect.bottom() - time / max * rect.height(),
+            )
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.0, ui.visuals().selection.bg_fill),
+    ));
+}
+
+/// A table of the time each system took in recent frames, which can be sorted, with a sparkline
+/// of each.
+pub fn profiler_ui(world: &mut World, ui: &mut egui::Ui) {
+    let Some(profile) = world.get_resource::<SystemProfile>().cloned() else {
+        ui.label("Add EditorLogPlugin in place of bevy's LogPlugin to profile systems here.");
+        return;
+    };
+    let mut history = world.resource_mut::<ProfilerHistory>();
+    let history = &mut *history;
+
+    ui.horizontal(|ui| {
+        let recording = profile.is_recording();
+        let (text, hover) = match recording {
+            true => ("⏸ Pause", "Stop recording, to look at the recorded frames"),
+            false => ("⏺ Record", "Record the time systems take"),
+        };
+        if ui.button(text).on_hover_text(hover).clicked() {
+            profile.set_recording(!recording);
+        }
+        if ui.button("Clear").clicked() {
+            history.systems.clear();
+            history.frames = 0;
+        }
+        ui.separator();
+        ui.add(
+            egui::TextEdit::singleline(&mut history.filter)
+                .hint_text("Search")
+                .desired_width(150.0),
+        );
+    });
+    ui.separator();
+    if history.systems.is_empty() {
+        ui.label("No systems recorded. Bevy only times systems with its `trace` feature enabled.");
+        return;
+    }
+
+    let filter = history.filter.to_lowercase();
+    let mut systems: Vec<_> = history
+        .systems
+        .iter()
+        .filter(|(name, _)| filter.is_empty() || name.to_lowercase().contains(&filter))
+        .map(|(name, times)| SystemStats {
+            name,
+            times,
+            last: times.back().copied().unwrap_or_default(),
+            average: times.iter().sum::<f32>() / times.len().max(1) as f32,
+            max: times.iter().copied().fold(0.0, f32::max),
+        })
+        .collect();
+    match history.sort {
+        ProfilerSort::Name => systems.sort_by(|a, b| a.name.cmp(b.name)),
+        ProfilerSort::Last => systems.sort_by(|a, b| b.last.total_cmp(&a.last)),
+        ProfilerSort::Average => systems.sort_by(|a, b| b.average.total_cmp(&a.average)),
+        ProfilerSort::Max => systems.sort_by(|a, b| b.max.total_cmp(&a.max)),
+    }
+    let total: f32 = systems.iter().map(|system| system.average).sum();
+    ui.label(format!(
+        "{} systems take {total:.3} ms a frame on average, over {} frames",
+        systems.len(),
+        history.frames.min(HISTORY_LEN)
+    ));
+
+    let mut sort = history.sort;
+    egui::ScrollArea::both()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            egui::Grid::new("profiler_systems")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (column, label) in [
+                        (ProfilerSort::Name, "System"),
+                        (ProfilerSort::Last, "Last (ms)"),
+                        (ProfilerSort::Average, "Average (ms)"),
+                        (ProfilerSort::Max, "Max (ms)"),
+                    ] {
+                        ui.selectable_value(&mut sort, column, egui::RichText::new(label).strong());
+                    }
+                    ui.strong("History");
+                    ui.end_row();
+
+                    for system in &systems {
+                        ui.label(system.name);
+                        ui.monospace(format!("{:.3}", system.last));
+                        ui.monospace(format!("{:.3}", system.average));
+                        ui.monospace(format!("{:.3}", system.max));
+                        sparkline(ui, system.times, system.max);
+                        ui.end_row();
+                    }
+                });
+        });
+    history.sort = sort;
+}
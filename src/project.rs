@@ -0,0 +1,352 @@
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{asset::FileAssetIo, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_browser::{AssetBrowser, ASSET_DIR},
+    commands::AddEditorCommand,
+    layout, settings, UiState,
+};
+
+/// The file marking the root folder of a project.
+pub const PROJECT_FILE: &str = "project.ron";
+/// Set to the root folder of a project to open it on startup.
+pub const PROJECT_ENV: &str = "BEVY_EDITOR_PROJECT";
+const MAX_RECENT_PROJECTS: usize = 10;
+
+pub struct EditorProjectPlugin;
+impl Plugin for EditorProjectPlugin {
+    fn build(&self, app: &mut App) {
+        let root = env::var_os(PROJECT_ENV).map_or_else(|| PathBuf::from("."), PathBuf::from);
+        let mut recent = RecentProjects::load();
+        let project = match root.join(PROJECT_FILE).exists() {
+            true => match Project::open(&root) {
+                Ok(project) => Some(project),
+                Err(e) => {
+                    warn!("Failed to open the project in {}: {e}", root.display());
+                    None
+                }
+            },
+            false => None,
+        };
+        // the settings and layouts are loaded relative to the working directory
+        if let Some(project) = &project {
+            if let Err(e) = env::set_current_dir(&project.root) {
+                error!("Failed to enter {}: {e}", project.root.display());
+            }
+            recent.push(&project.root);
+            recent.save();
+        }
+        app.insert_resource(ProjectPicker {
+            open: project.is_none(),
+            path: env::current_dir()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default(),
+        })
+        .insert_resource(recent)
+        .add_editor_command("Open Project...", |world| {
+            world.resource_mut::<ProjectPicker>().open = true;
+        });
+        if let Some(project) = project {
+            app.insert_resource(project);
+        }
+        app.add_systems(Startup, use_default_scene);
+    }
+}
+
+fn use_default_scene(project: Option<Res<Project>>, mut ui_state: ResMut<UiState>) {
+    if let Some(project) = project {
+        ui_state.scene_path = project.default_scene.display().to_string();
+    }
+}
+
+#[derive(Debug)]
+pub enum ProjectFileError {
+    Io(io::Error),
+    Ron(ron::Error),
+}
+
+impl fmt::Display for ProjectFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectFileError::Io(e) => write!(f, "{e}"),
+            ProjectFileError::Ron(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ProjectFileError {}
+
+impl From<io::Error> for ProjectFileError {
+    fn from(e: io::Error) -> Self {
+        ProjectFileError::Io(e)
+    }
+}
+
+impl From<ron::Error> for ProjectFileError {
+    fn from(e: ron::Error) -> Self {
+        ProjectFileError::Ron(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for ProjectFileError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        ProjectFileError::Ron(e.code)
+    }
+}
+
+/// The game being edited: the folder its assets, scenes and editor files live in, read from the
+/// [`PROJECT_FILE`] in it. Paths in the project are relative to [`Project::root`].
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    #[serde(skip)]
+    pub root: PathBuf,
+    pub name: String,
+    /// The scene the file menu saves and loads once the project is opened.
+    pub default_scene: PathBuf,
+    /// Where the editor settings and keybinds are saved.
+    pub settings: PathBuf,
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::new(),
+            name: String::new(),
+            default_scene: PathBuf::from("assets/scenes/scene.scn.ron"),
+            settings: PathBuf::from(settings::SETTINGS_PATH),
+        }
+    }
+}
+
+impl Project {
+    /// Reads the project in the folder `root`.
+    pub fn open(root: &Path) -> Result<Project, ProjectFileError> {
+        let ron = fs::read_to_string(root.join(PROJECT_FILE))?;
+        let mut project: Project = ron::from_str(&ron)?;
+        project.root = root.canonicalize()?;
+        if project.name.is_empty() {
+            project.name = folder_name(&project.root);
+        }
+        Ok(project)
+    }
+
+    /// Makes the folder `root` a project, with the default paths.
+    pub fn create(root: &Path) -> Result<Project, ProjectFileError> {
+        let root = root.canonicalize()?;
+        let project = Project {
+            name: folder_name(&root),
+            root,
+            ..default()
+        };
+        let ron = ron::ser::to_string_pretty(&project, ron::ser::PrettyConfig::default())?;
+        fs::write(project.root.join(PROJECT_FILE), ron)?;
+        info!(
+            "Created project {} in {}",
+            project.name,
+            project.root.display()
+        );
+        Ok(project)
+    }
+
+    pub fn path(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.root.join(path)
+    }
+
+    pub fn settings_path(&self) -> PathBuf {
+        self.path(&self.settings)
+    }
+}
+
+fn folder_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+/// The projects opened last, most recent first, kept in the home folder so every game knows
+/// them.
+#[derive(Resource, Default)]
+pub struct RecentProjects {
+    roots: Vec<PathBuf>,
+}
+
+fn recent_projects_path() -> Option<PathBuf> {
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+    Some(
+        Path::new(&home)
+            .join(".bevy_editor")
+            .join("recent_projects.ron"),
+    )
+}
+
+impl RecentProjects {
+    fn load() -> Self {
+        let Some(path) = recent_projects_path().filter(|path| path.exists()) else {
+            return Self::default();
+        };
+        let roots = fs::read_to_string(&path)
+            .map_err(ProjectFileError::from)
+            .and_then(|ron| Ok(ron::from_str(&ron)?));
+        match roots {
+            Ok(roots) => Self { roots },
+            Err(e) => {
+                warn!(
+                    "Failed to load recent projects from {}: {e}",
+                    path.display()
+                );
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = recent_projects_path() else {
+            return;
+        };
+        let result = ron::ser::to_string_pretty(&self.roots, ron::ser::PrettyConfig::default())
+            .map_err(ProjectFileError::from)
+            .and_then(|ron| {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                Ok(fs::write(&path, ron)?)
+            });
+        if let Err(e) = result {
+            error!("Failed to save recent projects to {}: {e}", path.display());
+        }
+    }
+
+    fn push(&mut self, root: &Path) {
+        self.roots.retain(|recent| recent != root);
+        self.roots.insert(0, root.to_owned());
+        self.roots.truncate(MAX_RECENT_PROJECTS);
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+}
+
+/// The window opening a project, shown on startup when the editor was launched without one.
+#[derive(Resource)]
+pub struct ProjectPicker {
+    pub open: bool,
+    /// The folder typed in the window.
+    path: String,
+}
+
+/// Makes `project` the one edited: the working directory, settings, layout presets
+/// and asset browser follow it.
+///
+/// The asset server keeps loading from the folder it started with, so the assets of a project
+/// in another folder only load once the game is started from it.
+pub fn open_project(world: &mut World, project: Project) {
+    if let Err(e) = env::set_current_dir(&project.root) {
+        error!("Failed to enter {}: {e}", project.root.display());
+        return;
+    }
+    info!(
+        "Opened project {} in {}",
+        project.name,
+        project.root.display()
+    );
+    if let Some(asset_server) = world.get_resource::<AssetServer>() {
+        let asset_dir = project.path(ASSET_DIR);
+        let served = asset_server
+            .asset_io()
+            .downcast_ref::<FileAssetIo>()
+            .and_then(|io| io.root_path().canonicalize().ok());
+        if served.is_some_and(|served| Some(served) != asset_dir.canonicalize().ok()) {
+            warn!(
+                "The assets are still loaded from the folder the game started in, restart it from {} to load the assets of the project",
+                project.root.display()
+            );
+        }
+    }
+
+    let mut recent = world.resource_mut::<RecentProjects>();
+    recent.push(&project.root);
+    recent.save();
+    world.insert_resource(project);
+    settings::reload_settings(world);
+    layout::reload_presets(world);
+    *world.resource_mut::<AssetBrowser>() = AssetBrowser::default();
+    world.resource_mut::<ProjectPicker>().open = false;
+}
+
+/// Shows the project picker if it's open, returning the project opened with it.
+pub fn project_picker_ui(world: &mut World, ctx: &egui::Context) -> Option<Project> {
+    let mut open = world.resource::<ProjectPicker>().open;
+    if !open {
+        return None;
+    }
+    let mut opened = None;
+    egui::Window::new("Open Project")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.heading("Recent Projects");
+            let current = world.get_resource::<Project>().map(|project| &project.root);
+            let recent = world.resource::<RecentProjects>();
+            if recent.roots().is_empty() {
+                ui.weak("No project opened yet");
+            }
+            for root in recent.roots() {
+                let exists = root.join(PROJECT_FILE).exists();
+                let response = ui
+                    .add_enabled(
+                        exists && current != Some(root),
+                        egui::Button::new(folder_name(root)),
+                    )
+                    .on_hover_text(root.display().to_string())
+                    .on_disabled_hover_text(match exists {
+                        true => "Already open".to_owned(),
+                        false => format!("{} has no {PROJECT_FILE}", root.display()),
+                    });
+                if response.clicked() {
+                    opened = Some(Project::open(root));
+                }
+            }
+            ui.separator();
+
+            let mut picker = world.resource_mut::<ProjectPicker>();
+            ui.horizontal(|ui| {
+                ui.label("Folder");
+                ui.text_edit_singleline(&mut picker.path);
+            });
+            let root = PathBuf::from(&picker.path);
+            let has_project = root.join(PROJECT_FILE).exists();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(has_project, egui::Button::new("Open"))
+                    .clicked()
+                {
+                    opened = Some(Project::open(&root));
+                }
+                if ui
+                    .add_enabled(!has_project && root.is_dir(), egui::Button::new("Create"))
+                    .on_hover_text(format!("Write a {PROJECT_FILE} to the folder"))
+                    .clicked()
+                {
+                    opened = Some(Project::create(&root));
+                }
+            });
+        });
+    world.resource_mut::<ProjectPicker>().open = open;
+    match opened? {
+        Ok(project) => Some(project),
+        Err(e) => {
+            error!("Failed to open the project: {e}");
+            None
+        }
+    }
+}
@@ -0,0 +1,357 @@
+use bevy::{ecs::component::ComponentId, prelude::*, reflect::TypeRegistration};
+use bevy_inspector_egui::bevy_inspector::hierarchy::{SelectedEntities, SelectionMode};
+use bevy_reflect::TypeRegistry;
+
+use crate::inspector::entity_name;
+
+/// Values longer than this are cut short in the results.
+const MAX_VALUE_LEN: usize = 80;
+
+pub struct EditorQueryPlugin;
+impl Plugin for EditorQueryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<QueryConsole>();
+    }
+}
+
+/// The query typed in the query tab.
+#[derive(Resource)]
+pub struct QueryConsole {
+    pub query: String,
+}
+
+impl Default for QueryConsole {
+    fn default() -> Self {
+        Self {
+            query: "Transform, With<Name>".to_string(),
+        }
+    }
+}
+
+/// A parsed query, matching the entities with every component of `with` and none of `without`.
+#[derive(Default)]
+pub(crate) struct WorldQuery {
+    with: Vec<ComponentId>,
+    without: Vec<ComponentId>,
+    /// The components fetched by the query, shown for every entity.
+    columns: Vec<(String, ReflectComponent)>,
+    /// Whether a required component isn't in the world yet, so no entity matches.
+    empty: bool,
+}
+
+#[derive(Clone, Copy)]
+enum TermKind {
+    /// `T` or `&T`, which entities need and are shown with.
+    Fetch,
+    /// `Option<T>`, shown when entities have it.
+    Optional,
+    With,
+    Without,
+}
+
+/// Splits `query` at the commas which aren't inside generics.
+fn terms(query: &str) -> Vec<&str> {
+    let mut terms = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (index, char) in query.char_indices() {
+        match char {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                terms.push(&query[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    terms.push(&query[start..]);
+    terms
+        .into_iter()
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+/// The type in `term` if it's wrapped as `wrapper<T>`.
+fn unwrap<'a>(term: &'a str, wrapper: &str) -> Option<&'a str> {
+    term.strip_prefix(wrapper)?
+        .trim_start()
+        .strip_prefix('<')?
+        .strip_suffix('>')
+        .map(str::trim)
+}
+
+pub(crate) fn resolve<'a>(
+    type_registry: &'a TypeRegistry,
+    name: &str,
+) -> Result<&'a TypeRegistration, String> {
+    if let Some(registration) = type_registry
+        .get_with_name(name)
+        .or_else(|| type_registry.get_with_short_name(name))
+    {
+        return Ok(registration);
+    }
+    let lowercase = name.to_lowercase();
+    let mut similar: Vec<_> = type_registry
+        .iter()
+        .filter(|registration| registration.data::<ReflectComponent>().is_some())
+        .map(|registration| registration.short_name())
+        .filter(|short_name| short_name.to_lowercase().contains(&lowercase))
+        .collect();
+    similar.sort();
+    similar.truncate(5);
+    Err(match similar.is_empty() {
+        true => format!("No registered type is named `{name}`"),
+        false => format!(
+            "No registered type is named `{name}`, did you mean {}?",
+            similar.join(", ")
+        ),
+    })
+}
+
+impl WorldQuery {
+    pub(crate) fn parse(
+        query: &str,
+        world: &World,
+        type_registry: &TypeRegistry,
+    ) -> Result<Self, String> {
+        let mut parsed = WorldQuery::default();
+        for term in terms(query) {
+            let (name, kind) = if let Some(name) = unwrap(term, "Without") {
+                (name, TermKind::Without)
+            } else if let Some(name) = unwrap(term, "With") {
+                (name, TermKind::With)
+            } else if let Some(name) = unwrap(term, "Option") {
+                (name, TermKind::Optional)
+            } else if ["Added", "Changed", "Or"]
+                .iter()
+                .any(|filter| unwrap(term, filter).is_some())
+            {
+                return Err(format!(
+                    "`{term}` isn't supported, only `With` and `Without` filters are"
+                ));
+            } else {
+                let name = term.trim_start_matches('&').trim_start_matches("mut ");
+                (name.trim(), TermKind::Fetch)
+            };
+            let registration = resolve(type_registry, name)?;
+            let reflect_component = registration.data::<ReflectComponent>();
+            let id = world.components().get_id(registration.type_id());
+            if reflect_component.is_none() && id.is_none() {
+                return Err(format!("`{}` isn't a component", registration.short_name()));
+            }
+            match (kind, id) {
+                (TermKind::Without, Some(id)) => parsed.without.push(id),
+                (TermKind::Without | TermKind::Optional, _) => {}
+                (_, Some(id)) => parsed.with.push(id),
+                (_, None) => parsed.empty = true,
+            }
+            if let (TermKind::Fetch | TermKind::Optional, Some(reflect_component)) =
+                (kind, reflect_component)
+            {
+                parsed.columns.push((
+                    registration.short_name().to_owned(),
+                    reflect_component.clone(),
+                ));
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub(crate) fn entities(&self, world: &World) -> Vec<Entity> {
+        if self.empty {
+            return Vec::new();
+        }
+        let mut entities: Vec<_> = world
+            .archetypes()
+            .iter()
+            .filter(|archetype| {
+                self.with.iter().all(|&id| archetype.contains(id))
+                    && !self.without.iter().any(|&id| archetype.contains(id))
+            })
+            .flat_map(|archetype| archetype.entities().iter().map(|entity| entity.entity()))
+            .collect();
+        entities.sort();
+        entities
+    }
+}
+
+fn value(world: &World, entity: Entity, reflect_component: &ReflectComponent) -> String {
+    let Some(value) = reflect_component.reflect(world.entity(entity)) else {
+        return "None".to_string();
+    };
+    let value = format!("{value:?}");
+    match value.char_indices().nth(MAX_VALUE_LEN) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value,
+    }
+}
+
+/// Lists the entities matching the query typed in the tab, like a query in a system would, and
+/// selects them when clicked.
+pub fn query_ui(world: &mut World, ui: &mut egui::Ui, selected: &mut SelectedEntities) {
+    let mut console = world.resource_mut::<QueryConsole>();
+    ui.horizontal(|ui| {
+        ui.label("Query");
+        ui.add(
+            egui::TextEdit::singleline(&mut console.query)
+                .hint_text("Transform, With<Enemy>, Without<Dead>")
+                .code_editor()
+                .desired_width(f32::INFINITY),
+        );
+    });
+    let query = console.query.clone();
+
+    let type_registry = world.resource::<AppTypeRegistry>().0.clone();
+    let type_registry = type_registry.read();
+    let parsed = match WorldQuery::parse(&query, world, &type_registry) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+            return;
+        }
+    };
+    let entities = parsed.entities(world);
+    ui.horizontal(|ui| {
+        ui.label(format!("{} entities", entities.len()));
+        if ui
+            .add_enabled(!entities.is_empty(), egui::Button::new("Select all"))
+            .clicked()
+        {
+            selected.clear();
+            for &entity in &entities {
+                selected.select_maybe_add(entity, true);
+            }
+        }
+    });
+    ui.separator();
+
+    let row_height = ui.text_style_height(&egui::TextStyle::Body) + ui.spacing().item_spacing.y;
+    egui::ScrollArea::both().auto_shrink([false; 2]).show_rows(
+        ui,
+        row_height,
+        entities.len(),
+        |ui, rows| {
+            for &entity in &entities[rows] {
+                ui.horizontal(|ui| {
+                    let response = ui.selectable_label(
+                        selected.contains(entity),
+                        format!("{} ({entity:?})", entity_name(world, entity)),
+                    );
+                    if response.clicked() {
+                        let add = ui.input(|input| input.modifiers.ctrl);
+                        selected.select(
+                            SelectionMode::from_ctrl_shift(add, false),
+                            entity,
+                            |_, _| None,
+                        );
+                    }
+                    for (name, reflect_component) in &parsed.columns {
+                        ui.separator();
+                        ui.weak(name);
+                        ui.monospace(value(world, entity, reflect_component));
+                    }
+                });
+            }
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world() -> (World, TypeRegistry, [Entity; 3]) {
+        let mut world = World::new();
+        let named = world
+            .spawn((Name::new("Named"), Transform::from_xyz(1.0, 0.0, 0.0)))
+            .id();
+        let unnamed = world.spawn(Transform::default()).id();
+        let hidden = world
+            .spawn((Name::new("Hidden"), Transform::default(), HiddenMarker))
+            .id();
+        let mut type_registry = TypeRegistry::new();
+        type_registry.register::<Name>();
+        type_registry.register::<Transform>();
+        type_registry.register::<Visibility>();
+        type_registry.register::<HiddenMarker>();
+        (world, type_registry, [named, unnamed, hidden])
+    }
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct HiddenMarker;
+
+    fn query(query: &str) -> Result<Vec<Entity>, String> {
+        let (world, type_registry, _) = world();
+        WorldQuery::parse(query, &world, &type_registry).map(|parsed| parsed.entities(&world))
+    }
+
+    #[test]
+    fn splits_terms_outside_of_generics() {
+        assert_eq!(
+            terms(" &Transform, With<Name>,,Option<Foo<A, B>> ,"),
+            ["&Transform", "With<Name>", "Option<Foo<A, B>>"]
+        );
+        assert_eq!(unwrap("With <Name >", "With"), Some("Name"));
+        assert_eq!(unwrap("Without<Name>", "With"), None);
+        assert_eq!(unwrap("Name", "With"), None);
+    }
+
+    #[test]
+    fn matches_entities_like_a_query() {
+        let (_, _, [named, unnamed, hidden]) = world();
+        assert_eq!(query("Transform"), Ok(vec![named, unnamed, hidden]));
+        assert_eq!(query("&Transform, With<Name>"), Ok(vec![named, hidden]));
+        assert_eq!(
+            query("&mut bevy_transform::components::transform::Transform, Without<HiddenMarker>"),
+            Ok(vec![named, unnamed])
+        );
+        assert_eq!(query("Option<Name>, Without<Name>"), Ok(vec![unnamed]));
+        // no entity has a visibility yet
+        assert_eq!(query("Transform, With<Visibility>"), Ok(Vec::new()));
+        assert_eq!(
+            query("Transform, Without<Visibility>"),
+            Ok(vec![named, unnamed, hidden])
+        );
+    }
+
+    #[test]
+    fn shows_the_fetched_components() {
+        let (world, type_registry, [named, ..]) = world();
+        let parsed =
+            WorldQuery::parse("Option<Name>, With<Transform>", &world, &type_registry).unwrap();
+        let columns: Vec<_> = parsed
+            .columns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(columns, ["Name"]);
+        assert_eq!(value(&world, named, &parsed.columns[0].1), "\"Named\"");
+
+        let parsed = WorldQuery::parse("Transform", &world, &type_registry).unwrap();
+        let transform = value(&world, named, &parsed.columns[0].1);
+        assert_eq!(transform.chars().count(), MAX_VALUE_LEN + 1);
+        assert!(transform.ends_with('…'));
+    }
+
+    #[test]
+    fn explains_invalid_queries() {
+        assert_eq!(
+            query("Changed<Transform>"),
+            Err(
+                "`Changed<Transform>` isn't supported, only `With` and `Without` filters are"
+                    .into()
+            )
+        );
+        assert_eq!(
+            query("Trans"),
+            Err("No registered type is named `Trans`, did you mean Transform?".into())
+        );
+        assert_eq!(
+            query("Enemy"),
+            Err("No registered type is named `Enemy`".into())
+        );
+        assert_eq!(query("f32"), Err("`f32` isn't a component".into()));
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    env, fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{app::AppExit, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{history::EditorHistory, play::EditorState, scene, settings::EditorSettings};
+
+const SESSION_FILE: &str = "session.ron";
+const SNAPSHOT_FILE: &str = "snapshot.scn.ron";
+
+pub struct EditorRecoveryPlugin;
+impl Plugin for EditorRecoveryPlugin {
+    fn build(&self, app: &mut App) {
+        let recovery = Recovery {
+            pending: find_crashed_session(),
+            ..default()
+        };
+        if let Some(session) = &recovery.pending {
+            warn!(
+                "The editor didn't exit cleanly, a snapshot of {} can be restored",
+                session.scene_path
+            );
+        }
+        app.register_type::<RecoverySettings>()
+            .insert_resource(recovery)
+            .add_systems(Last, (snapshot, end_session_on_exit).chain());
+    }
+}
+
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecoverySettings {
+    pub enabled: bool,
+    /// Seconds between the snapshots restored after a crash, much shorter than the autosave
+    /// interval since a snapshot is overwritten and not kept as a backup.
+    pub interval_seconds: f32,
+}
+
+impl Default for RecoverySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_seconds: 30.0,
+        }
+    }
+}
+
+/// Written next to the snapshot while the editor runs, and removed when it exits cleanly, so
+/// finding it on startup means the last session crashed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Session {
+    scene_path: String,
+    /// When the snapshot was taken, in seconds since the unix epoch.
+    snapshot_at: Option<u64>,
+}
+
+#[derive(Resource, Default)]
+pub struct Recovery {
+    since_snapshot: Duration,
+    /// The session which crashed, until its snapshot is restored or discarded. No snapshot is
+    /// taken meanwhile, so it isn't overwritten.
+    pending: Option<Session>,
+}
+
+/// Where the snapshots of the editor run from the working directory are kept, a folder of the
+/// temp directory named after it, so editors of different projects don't share one.
+fn recovery_dir() -> PathBuf {
+    let working_dir = env::current_dir().unwrap_or_default();
+    let name: String = working_dir
+        .display()
+        .to_string()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    env::temp_dir().join("bevy_editor").join(name)
+}
+
+fn find_crashed_session() -> Option<Session> {
+    let dir = recovery_dir();
+    let ron = fs::read_to_string(dir.join(SESSION_FILE)).ok()?;
+    let session: Session = match ron::from_str(&ron) {
+        Ok(session) => session,
+        Err(e) => {
+            warn!("Failed to read the last editor session: {e}");
+            return None;
+        }
+    };
+    // crashed before the first snapshot
+    session.snapshot_at?;
+    dir.join(SNAPSHOT_FILE).exists().then_some(session)
+}
+
+fn write_session(session: &Session) -> Result<(), scene::SceneFileError> {
+    let dir = recovery_dir();
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(SESSION_FILE), ron::to_string(session)?)?;
+    Ok(())
+}
+
+fn snapshot(world: &mut World) {
+    let settings = world.resource::<EditorSettings>().recovery.clone();
+    let editing = *world.resource::<State<EditorState>>().get() == EditorState::Edit;
+    if !settings.enabled || !editing || world.resource::<Recovery>().pending.is_some() {
+        return;
+    }
+
+    let delta = world.resource::<Time>().raw_delta();
+    let mut recovery = world.resource_mut::<Recovery>();
+    recovery.since_snapshot += delta;
+    if recovery.since_snapshot.as_secs_f32() < settings.interval_seconds.max(1.0) {
+        return;
+    }
+    recovery.since_snapshot = Duration::ZERO;
+
+    let entities = scene::scene_entities(world);
+    let result =
+        scene::save_scene(world, &entities, recovery_dir().join(SNAPSHOT_FILE)).and_then(|()| {
+            write_session(&Session {
+                scene_path: world.resource::<crate::UiState>().scene_path.clone(),
+                snapshot_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .ok()
+                    .map(|time| time.as_secs()),
+            })
+        });
+    if let Err(e) = result {
+        error!("Failed to snapshot the scene for crash recovery: {e}");
+    }
+}
+
+fn end_session_on_exit(mut exit_events: EventReader<AppExit>, recovery: Res<Recovery>) {
+    // the snapshot of a crashed session is still offered next time if it wasn't looked at
+    if exit_events.iter().next().is_none() || recovery.pending.is_some() {
+        return;
+    }
+    let path = recovery_dir().join(SESSION_FILE);
+    if path.exists() {
+        if let Err(e) = fs::remove_file(&path) {
+            warn!("Failed to remove {}: {e}", path.display());
+        }
+    }
+}
+
+/// Replaces the scene with the snapshot of the crashed session.
+fn restore(world: &mut World) -> Result<(), scene::SceneFileError> {
+    let ron = fs::read_to_string(recovery_dir().join(SNAPSHOT_FILE))?;
+    let snapshot = scene::deserialize_scene(world, &ron)?;
+    let entities = scene::scene_entities(world);
+    for entity in scene::top_level_entities(world, &entities) {
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+    scene::spawn_scene(world, &snapshot)?;
+    world.resource_mut::<EditorHistory>().clear();
+    Ok(())
+}
+
+/// Asks whether to restore the snapshot left by a crashed session. Returns the scene path of the
+/// session once restored, to save it where it was loaded from.
+pub fn recovery_prompt_ui(world: &mut World, ctx: &egui::Context) -> Option<String> {
+    let session = world.resource::<Recovery>().pending.clone()?;
+    let mut decided = false;
+    let mut restored = None;
+    egui::Window::new("Restore Scene")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label("The editor didn't exit cleanly last time.");
+            let age = session.snapshot_at.and_then(|at| {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+                Some(now.as_secs().saturating_sub(at) / 60)
+            });
+            ui.label(match age {
+                Some(0) => format!(
+                    "{} was snapshot less than a minute ago.",
+                    session.scene_path
+                ),
+                Some(1) => format!("{} was snapshot 1 minute ago.", session.scene_path),
+                Some(minutes) => {
+                    format!("{} was snapshot {minutes} minutes ago.", session.scene_path)
+                }
+                None => format!("{} was snapshot before.", session.scene_path),
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Restore").clicked() {
+                    decided = true;
+                    match restore(world) {
+                        Ok(()) => {
+                            info!("Restored the snapshot of {}", session.scene_path);
+                            restored = Some(session.scene_path.clone());
+                        }
+                        Err(e) => error!("Failed to restore the snapshot: {e}"),
+                    }
+                }
+                if ui
+                    .button("Discard")
+                    .on_hover_text("Keep the scene as loaded, the snapshot is overwritten")
+                    .clicked()
+                {
+                    decided = true;
+                }
+            });
+        });
+    if decided {
+        world.resource_mut::<Recovery>().pending = None;
+    }
+    restored
+}
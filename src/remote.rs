@@ -0,0 +1,352 @@
+//! A server letting other programs inspect and edit the world, enabled with the `remote`
+//! feature.
+//!
+//! Clients connect over TCP and send one JSON request per line, answered by one JSON response
+//! per line with the same `id`:
+//!
+//! ```text
+//! > {"id": 1, "method": "list_entities"}
+//! < {"id": 1, "result": [{"entity": 4294967296, "id": "67e55044-10b1-426f-9247-bb680e5fe0c8", "name": "Cube", "parent": null}]}
+//! > {"id": 2, "method": "get_component", "entity": 4294967296, "component": "Transform"}
+//! < {"id": 2, "result": {"translation": {"x": 0.0, "y": 0.5, "z": 0.0}, ...}}
+//! > {"id": 3, "method": "set_component", "entity": 4294967296, "component": "Visibility", "value": "Hidden"}
+//! < {"id": 3, "result": null}
+//! ```
+//!
+//! Entities are given by their [`Entity::to_bits`] or their [`EditorId`], which stays the same
+//! when they're respawned, components by their short or full type name.
+
+use std::{
+    env, fmt,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use bevy::{
+    prelude::*,
+    reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer},
+    utils::Uuid,
+};
+use bevy_reflect::{TypeRegistration, TypeRegistry};
+use serde::{de::DeserializeSeed, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{
+    collab::{self, Edit},
+    editor_id::{EditorId, EditorIds},
+    history, inspector, scene, UiState,
+};
+
+pub const REMOTE_ADDRESS: &str = "127.0.0.1:15702";
+/// Set to the address to listen on in place of [`REMOTE_ADDRESS`].
+pub const REMOTE_ENV: &str = "BEVY_EDITOR_REMOTE";
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+
+pub struct EditorRemotePlugin;
+impl Plugin for EditorRemotePlugin {
+    fn build(&self, app: &mut App) {
+        let address = env::var(REMOTE_ENV).unwrap_or_else(|_| REMOTE_ADDRESS.to_owned());
+        let listener = match TcpListener::bind(&address) {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to start the remote editing server on {address}: {e}");
+                return;
+            }
+        };
+        info!("Remote editing server listening on {address}");
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => {
+                        let sender = sender.clone();
+                        thread::spawn(move || {
+                            if let Err(e) = serve_client(stream, sender) {
+                                warn!("Remote editing client disconnected: {e}");
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Failed to accept a remote editing client: {e}"),
+                }
+            }
+        });
+        app.insert_resource(RemoteServer {
+            requests: Mutex::new(receiver),
+        })
+        .add_systems(Update, handle_requests);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum Method {
+    ListEntities,
+    ListComponents {
+        entity: EntityRef,
+    },
+    GetComponent {
+        entity: EntityRef,
+        component: String,
+    },
+    SetComponent {
+        entity: EntityRef,
+        component: String,
+        value: Value,
+    },
+    Select {
+        entities: Vec<EntityRef>,
+    },
+    /// Joins the editing session, see [`collab`].
+    Join {
+        name: String,
+        color: [f32; 4],
+    },
+    SessionUpdate {
+        user: u64,
+        since: u64,
+        #[serde(default)]
+        edits: Vec<Edit>,
+        #[serde(default)]
+        selection: Vec<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    #[serde(flatten)]
+    method: Method,
+}
+
+type Reply = Sender<Result<Value, String>>;
+
+/// The requests of the clients, answered by the world once a frame.
+#[derive(Resource)]
+struct RemoteServer {
+    requests: Mutex<Receiver<(Method, Reply)>>,
+}
+
+fn serve_client(stream: TcpStream, requests: Sender<(Method, Reply)>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(Request { id, method }) => {
+                let (reply, result) = mpsc::channel();
+                // the app closed
+                if requests.send((method, reply)).is_err() {
+                    return Ok(());
+                }
+                match result.recv() {
+                    Ok(Ok(result)) => json!({ "id": id, "result": result }),
+                    Ok(Err(error)) => json!({ "id": id, "error": error }),
+                    Err(_) => return Ok(()),
+                }
+            }
+            Err(e) => json!({ "id": Value::Null, "error": format!("Invalid request: {e}") }),
+        };
+        writeln!(writer, "{response}")?;
+    }
+    Ok(())
+}
+
+fn handle_requests(world: &mut World) {
+    let requests: Vec<_> = world
+        .resource::<RemoteServer>()
+        .requests
+        .lock()
+        .unwrap()
+        .try_iter()
+        .collect();
+    for (method, reply) in requests {
+        // the client can be gone already
+        let _ = reply.send(handle(world, method));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+enum EntityRef {
+    Bits(u64),
+    Id(Uuid),
+}
+
+impl fmt::Display for EntityRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntityRef::Bits(bits) => write!(f, "{bits}"),
+            EntityRef::Id(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+fn entity(world: &World, entity_ref: EntityRef) -> Result<Entity, String> {
+    let entity = match entity_ref {
+        EntityRef::Bits(bits) => Some(Entity::from_bits(bits)),
+        EntityRef::Id(id) => world.resource::<EditorIds>().entity(world, EditorId(id)),
+    };
+    match entity.filter(|&entity| world.get_entity(entity).is_some()) {
+        Some(entity) => Ok(entity),
+        None => Err(format!("No entity {entity_ref}")),
+    }
+}
+
+fn handle(world: &mut World, method: Method) -> Result<Value, String> {
+    match method {
+        Method::ListEntities => Ok(scene::scene_entities(world)
+            .into_iter()
+            .map(|entity| {
+                json!({
+                    "entity": entity.to_bits(),
+                    "id": world.get::<EditorId>(entity).map(|id| id.0.to_string()),
+                    "name": inspector::entity_name(world, entity),
+                    "parent": world.get::<Parent>(entity).map(|parent| parent.get().to_bits()),
+                })
+            })
+            .collect()),
+        Method::ListComponents { entity: entity_ref } => {
+            let entity = entity(world, entity_ref)?;
+            let type_registry = world.resource::<AppTypeRegistry>().read();
+            Ok(world
+                .entity(entity)
+                .archetype()
+                .components()
+                .filter_map(|id| {
+                    let type_id = world.components().get_info(id)?.type_id()?;
+                    let registration = type_registry.get(type_id)?;
+                    registration.data::<ReflectComponent>()?;
+                    Some(Value::from(registration.type_name()))
+                })
+                .collect())
+        }
+        Method::GetComponent {
+            entity: entity_ref,
+            component,
+        } => {
+            let entity = entity(world, entity_ref)?;
+            let type_registry = world.resource::<AppTypeRegistry>().read();
+            let (_, reflect_component) = component_type(&type_registry, &component)?;
+            let value = reflect_component
+                .reflect(world.entity(entity))
+                .ok_or_else(|| format!("Entity {entity_ref} has no {component}"))?;
+            serde_json::to_value(TypedReflectSerializer::new(value, &type_registry))
+                .map_err(|e| format!("Failed to serialize {component}: {e}"))
+        }
+        Method::SetComponent {
+            entity: entity_ref,
+            component,
+            value,
+        } => {
+            let entity = entity(world, entity_ref)?;
+            let type_registry = world.resource::<AppTypeRegistry>().clone();
+            let type_registry = type_registry.read();
+            let (registration, reflect_component) = component_type(&type_registry, &component)?;
+            let value = TypedReflectDeserializer::new(registration, &type_registry)
+                .deserialize(&value)
+                .map_err(|e| format!("Invalid {component}: {e}"))?;
+            let before = history::snapshot_components(world, entity);
+            reflect_component.apply_or_insert(&mut world.entity_mut(entity), &*value);
+            history::record_component_changes(world, entity, before);
+            Ok(Value::Null)
+        }
+        Method::Select { entities } => {
+            let entities = entities
+                .into_iter()
+                .map(|entity_ref| entity(world, entity_ref))
+                .collect::<Result<Vec<_>, _>>()?;
+            world
+                .resource_mut::<UiState>()
+                .select_entities(entities, false);
+            Ok(Value::Null)
+        }
+        Method::Join { name, color } => Ok(collab::join(world, name, color)),
+        Method::SessionUpdate {
+            user,
+            since,
+            edits,
+            selection,
+        } => collab::session_update(world, user, since, edits, selection),
+    }
+}
+
+fn component_type<'a>(
+    type_registry: &'a TypeRegistry,
+    name: &str,
+) -> Result<(&'a TypeRegistration, &'a ReflectComponent), String> {
+    let registration = type_registry
+        .get_with_short_name(name)
+        .or_else(|| type_registry.get_with_name(name))
+        .ok_or_else(|| format!("No registered type {name}"))?;
+    let reflect_component = registration
+        .data::<ReflectComponent>()
+        .ok_or_else(|| format!("{name} isn't a component"))?;
+    Ok((registration, reflect_component))
+}
+
+/// A connection to the server of another app, whose responses are read on another thread.
+pub(crate) struct RemoteConnection {
+    writer: TcpStream,
+    responses: Mutex<Receiver<Value>>,
+}
+
+impl RemoteConnection {
+    pub(crate) fn connect(address: &str) -> Result<Self, String> {
+        let address = address
+            .to_socket_addrs()
+            .map_err(|e| e.to_string())?
+            .next()
+            .ok_or_else(|| "No address".to_owned())?;
+        let writer =
+            TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+        let reader = writer.try_clone().map_err(|e| e.to_string())?;
+
+        let (sender, responses) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                match serde_json::from_str(&line) {
+                    Ok(response) => {
+                        if sender.send(response).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => warn!("Invalid response from {address}: {e}"),
+                }
+            }
+        });
+        Ok(Self {
+            writer,
+            responses: Mutex::new(responses),
+        })
+    }
+
+    pub(crate) fn send(&mut self, request: &Value) -> io::Result<()> {
+        writeln!(self.writer, "{request}")
+    }
+
+    /// The responses received since, and whether the connection was closed.
+    pub(crate) fn receive(&self) -> (Vec<Value>, bool) {
+        let receiver = self.responses.lock().unwrap();
+        let mut responses = Vec::new();
+        loop {
+            match receiver.try_recv() {
+                Ok(response) => responses.push(response),
+                Err(TryRecvError::Empty) => return (responses, false),
+                Err(TryRecvError::Disconnected) => return (responses, true),
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! An editor running in its own process, showing the hierarchy and components of a game
+//! running the [`remote`](crate::remote) server, so editing doesn't take from the frames of the
+//! game. Enabled with the `remote` feature, and run with `cargo run --bin remote_client
+//! --features remote`.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContexts, EguiPlugin};
+use serde_json::{json, Value};
+
+use crate::remote::{RemoteConnection, REMOTE_ADDRESS, REMOTE_ENV};
+
+/// How often the hierarchy and the components of the selected entity are read again.
+const REFRESH_INTERVAL: f32 = 0.25;
+
+pub struct EditorRemoteClientPlugin;
+impl Plugin for EditorRemoteClientPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin);
+        }
+        let address = std::env::var(REMOTE_ENV).unwrap_or_else(|_| REMOTE_ADDRESS.to_owned());
+        app.insert_resource(RemoteClient {
+            address,
+            ..default()
+        })
+        .add_systems(Update, (poll_responses, client_ui).chain());
+    }
+}
+
+#[derive(Clone)]
+struct RemoteEntity {
+    entity: u64,
+    name: String,
+    parent: Option<u64>,
+}
+
+/// What a request sent to the game was for, to know what to do with its response.
+enum Pending {
+    Entities,
+    Components(u64),
+    Component(u64, String),
+    Edit,
+}
+
+/// The connection to the game and what was read from it.
+#[derive(Resource, Default)]
+pub struct RemoteClient {
+    pub address: String,
+    connection: Option<RemoteConnection>,
+    /// Why the last connection failed or ended.
+    error: Option<String>,
+    next_id: u64,
+    pending: HashMap<u64, Pending>,
+    since_refresh: f32,
+    entities: Vec<RemoteEntity>,
+    selected: Option<u64>,
+    /// The components of the selected entity, with their value or why it couldn't be read.
+    components: Vec<(String, Result<Value, String>)>,
+}
+
+impl RemoteClient {
+    pub fn connect(&mut self) {
+        self.disconnect();
+        match RemoteConnection::connect(&self.address) {
+            Ok(connection) => {
+                info!("Connected to {}", self.address);
+                self.connection = Some(connection);
+                self.error = None;
+                self.since_refresh = REFRESH_INTERVAL;
+            }
+            Err(e) => {
+                warn!("Failed to connect to {}: {e}", self.address);
+                self.error = Some(e);
+            }
+        }
+    }
+
+    pub fn disconnect(&mut self) {
+        self.connection = None;
+        self.pending.clear();
+        self.entities.clear();
+        self.components.clear();
+        self.selected = None;
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    fn send(&mut self, mut request: Value, pending: Pending) {
+        let Some(connection) = &mut self.connection else {
+            return;
+        };
+        let id = self.next_id;
+        self.next_id += 1;
+        request["id"] = id.into();
+        if let Err(e) = connection.send(&request) {
+            self.error = Some(e.to_string());
+            self.disconnect();
+            return;
+        }
+        self.pending.insert(id, pending);
+    }
+
+    /// Whether responses to `pending` requests are still awaited, so the same isn't asked twice.
+    fn awaiting(&self, pending: fn(&Pending) -> bool) -> bool {
+        self.pending.values().any(pending)
+    }
+
+    fn refresh(&mut self) {
+        self.since_refresh = 0.0;
+        if !self.awaiting(|pending| matches!(pending, Pending::Entities)) {
+            self.send(json!({ "method": "list_entities" }), Pending::Entities);
+        }
+        let Some(entity) = self.selected else {
+            return;
+        };
+        // don't overwrite the edits on their way to the game
+        if !self.awaiting(|pending| !matches!(pending, Pending::Entities)) {
+            self.send(
+                json!({ "method": "list_components", "entity": entity }),
+                Pending::Components(entity),
+            );
+        }
+    }
+
+    fn select(&mut self, entity: u64) {
+        self.selected = Some(entity);
+        self.components.clear();
+        self.refresh();
+        self.send(
+            json!({ "method": "select", "entities": [entity] }),
+            Pending::Edit,
+        );
+    }
+
+    fn receive(&mut self, response: Value) {
+        let Some(pending) = response["id"]
+            .as_u64()
+            .and_then(|id| self.pending.remove(&id))
+        else {
+            return;
+        };
+        let result = match response.get("error") {
+            Some(error) => Err(error.as_str().unwrap_or_default().to_owned()),
+            None => Ok(response["result"].clone()),
+        };
+        match (pending, result) {
+            (Pending::Entities, Ok(Value::Array(entities))) => {
+                self.entities = entities
+                    .iter()
+                    .filter_map(|entity| {
+                        Some(RemoteEntity {
+                            entity: entity["entity"].as_u64()?,
+                            name: entity["name"].as_str()?.to_owned(),
+                            parent: entity["parent"].as_u64(),
+                        })
+                    })
+                    .collect();
+                if self
+                    .selected
+                    .is_some_and(|selected| !self.entities.iter().any(|e| e.entity == selected))
+                {
+                    self.selected = None;
+                    self.components.clear();
+                }
+            }
+            (Pending::Components(entity), Ok(Value::Array(names))) => {
+                if self.selected != Some(entity) {
+                    return;
+                }
+                let names: Vec<_> = names
+                    .iter()
+                    .filter_map(|name| Some(name.as_str()?.to_owned()))
+                    .collect();
+                self.components
+                    .retain(|(name, _)| names.iter().any(|listed| listed == name));
+                for name in names {
+                    self.send(
+                        json!({ "method": "get_component", "entity": entity, "component": name }),
+                        Pending::Component(entity, name),
+                    );
+                }
+            }
+            (Pending::Component(entity, name), result) => {
+                if self.selected != Some(entity) {
+                    return;
+                }
+                match self
+                    .components
+                    .iter_mut()
+                    .find(|(listed, _)| *listed == name)
+                {
+                    Some((_, value)) => *value = result,
+                    None => self.components.push((name, result)),
+                }
+            }
+            (Pending::Edit, Err(e)) => warn!("Edit rejected by the game: {e}"),
+            (_, Err(e)) => warn!("Request failed: {e}"),
+            _ => {}
+        }
+    }
+}
+
+fn poll_responses(mut client: ResMut<RemoteClient>, time: Res<Time>) {
+    let Some(connection) = &client.connection else {
+        return;
+    };
+    let (responses, disconnected) = connection.receive();
+    for response in responses {
+        client.receive(response);
+    }
+    if disconnected {
+        info!("Disconnected from {}", client.address);
+        client.error = Some("The game closed the connection".to_owned());
+        client.disconnect();
+        return;
+    }
+    client.since_refresh += time.delta_seconds();
+    if client.since_refresh >= REFRESH_INTERVAL {
+        client.refresh();
+    }
+}
+
+/// Shows `value` with widgets editing it in place, returning whether it changed.
+fn json_ui(ui: &mut egui::Ui, id: egui::Id, value: &mut Value) -> bool {
+    match value {
+        Value::Null => {
+            ui.weak("None");
+            false
+        }
+        Value::Bool(value) => ui.checkbox(value, "").changed(),
+        Value::Number(number) => {
+            let mut float = number.as_f64().unwrap_or_default();
+            let integer = !number.is_f64();
+            let mut drag = egui::DragValue::new(&mut float);
+            if integer {
+                drag = drag.speed(1.0).fixed_decimals(0);
+            } else {
+                drag = drag.speed(0.01);
+            }
+            if !ui.add(drag).changed() {
+                return false;
+            }
+            *value = match integer {
+                true if float >= 0.0 => (float.round() as u64).into(),
+                true => (float.round() as i64).into(),
+                false => float.into(),
+            };
+            true
+        }
+        Value::String(string) => ui.text_edit_singleline(string).changed(),
+        Value::Array(values) => {
+            let mut changed = false;
+            ui.horizontal_wrapped(|ui| {
+                for (index, value) in values.iter_mut().enumerate() {
+                    changed |= json_ui(ui, id.with(index), value);
+                }
+            });
+            changed
+        }
+        Value::Object(fields) => {
+            let mut changed = false;
+            egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+                for (name, value) in fields.iter_mut() {
+                    ui.label(name);
+                    changed |= json_ui(ui, id.with(name), value);
+                    ui.end_row();
+                }
+            });
+            changed
+        }
+    }
+}
+
+fn hierarchy_ui(ui: &mut egui::Ui, client: &mut RemoteClient, parent: Option<u64>) {
+    let children: Vec<_> = client
+        .entities
+        .iter()
+        .filter(|entity| {
+            entity.parent == parent
+                // show entities whose parent isn't listed at the top
+                || (parent.is_none()
+                    && entity
+                        .parent
+                        .is_some_and(|p| !client.entities.iter().any(|e| e.entity == p)))
+        })
+        .cloned()
+        .collect();
+    for child in children {
+        let has_children = client
+            .entities
+            .iter()
+            .any(|entity| entity.parent == Some(child.entity));
+        let selected = client.selected == Some(child.entity);
+        if !has_children {
+            if ui.selectable_label(selected, &child.name).clicked() {
+                client.select(child.entity);
+            }
+            continue;
+        }
+        let id = ui.make_persistent_id(child.entity);
+        egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false)
+            .show_header(ui, |ui| {
+                if ui.selectable_label(selected, &child.name).clicked() {
+                    client.select(child.entity);
+                }
+            })
+            .body(|ui| hierarchy_ui(ui, client, Some(child.entity)));
+    }
+}
+
+fn client_ui(mut contexts: EguiContexts, mut client: ResMut<RemoteClient>) {
+    let ctx = contexts.ctx_mut();
+    egui::TopBottomPanel::top("remote_client_connection").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Game");
+            ui.add_enabled(
+                !client.is_connected(),
+                egui::TextEdit::singleline(&mut client.address).desired_width(160.0),
+            );
+            match client.is_connected() {
+                true => {
+                    if ui.button("Disconnect").clicked() {
+                        client.disconnect();
+                    }
+                }
+                false => {
+                    if ui.button("Connect").clicked() {
+                        client.connect();
+                    }
+                }
+            }
+            if let Some(error) = &client.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+    });
+    if !client.is_connected() {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.weak("Connect to a game running with the remote editing server");
+        });
+        return;
+    }
+
+    egui::SidePanel::left("remote_client_hierarchy")
+        .default_width(220.0)
+        .show(ctx, |ui| {
+            ui.heading("Hierarchy");
+            egui::ScrollArea::vertical().show(ui, |ui| hierarchy_ui(ui, &mut client, None));
+        });
+    egui::CentralPanel::default().show(ctx, |ui| {
+        let Some(entity) = client.selected else {
+            ui.weak("Select an entity");
+            return;
+        };
+        let mut edited = Vec::new();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (name, value) in &mut client.components {
+                let short_name = bevy::utils::get_short_name(name);
+                egui::CollapsingHeader::new(&short_name)
+                    .id_source(&*name)
+                    .default_open(true)
+                    .show(ui, |ui| match value {
+                        Ok(value) => {
+                            if json_ui(ui, egui::Id::new(&*name), value) {
+                                edited.push((name.clone(), value.clone()));
+                            }
+                        }
+                        Err(e) => {
+                            ui.weak(e.as_str());
+                        }
+                    });
+            }
+        });
+        for (name, value) in edited {
+            client.send(
+                json!({
+                    "method": "set_component",
+                    "entity": entity,
+                    "component": name,
+                    "value": value,
+                }),
+                Pending::Edit,
+            );
+        }
+    });
+}
@@ -0,0 +1,332 @@
+use std::{fs, path::Path, time::Duration};
+
+use bevy::{
+    input::{
+        mouse::{MouseMotion, MouseWheel},
+        InputSystem,
+    },
+    prelude::*,
+    time::{TimeSystem, TimeUpdateStrategy},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commands::{AddEditorCommand, EditorCommands},
+    input,
+    play::{self, EditorState},
+};
+
+/// Where the last recording is saved to and loaded from.
+pub const REPLAY_PATH: &str = "editor_replay.ron";
+
+pub struct EditorReplayPlugin;
+impl Plugin for EditorReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputRecorder>()
+            .add_editor_command("Replay: Record Next Play", |world| {
+                let mut recorder = world.resource_mut::<InputRecorder>();
+                recorder.record_next_play = !recorder.record_next_play;
+            })
+            .add_editor_command("Replay: Start", start_replay)
+            .add_systems(OnEnter(EditorState::Play), start_recording)
+            .add_systems(OnEnter(EditorState::Edit), stop)
+            .add_systems(
+                First,
+                replay_frame_time
+                    .run_if(play::simulating)
+                    .after(play::apply_simulation_time)
+                    .before(TimeSystem),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    record_input
+                        .after(InputSystem)
+                        .after(input::capture_game_input),
+                    // the editor actions are read from the real input before it's replaced
+                    replay_input.after(input::update_editor_actions),
+                )
+                    .run_if(play::simulating),
+            );
+    }
+}
+
+/// The input of a frame simulated while playing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// How long the frame lasted, which the replayed frame lasts too.
+    pub delta: Duration,
+    pub keys: Vec<KeyCode>,
+    pub mouse_buttons: Vec<MouseButton>,
+    pub mouse_motion: Vec<MouseMotion>,
+    pub mouse_wheel: Vec<MouseWheel>,
+    /// The editor commands run during the frame.
+    pub commands: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// Where a replay is at, with the input it feeds the game.
+struct Replay {
+    frame: usize,
+    keys: Input<KeyCode>,
+    mouse_buttons: Input<MouseButton>,
+}
+
+/// Records the input of the game while playing, to replay it frame by frame later and reproduce
+/// what happened.
+///
+/// The replay drives the [`Input`] resources and the mouse events the game reads, once the editor
+/// actions were updated from the real input so the replay can be stopped, and so the editor keys
+/// recorded don't trigger their actions again.
+#[derive(Resource, Default)]
+pub struct InputRecorder {
+    /// Whether to record the input of the next play session.
+    pub record_next_play: bool,
+    recording: Option<InputRecording>,
+    /// The last recording made or loaded.
+    last: Option<InputRecording>,
+    replay: Option<Replay>,
+}
+
+impl InputRecorder {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    pub fn last(&self) -> Option<&InputRecording> {
+        self.last.as_ref()
+    }
+
+    /// Adds a command run while recording to the current frame.
+    pub fn record_command(&mut self, name: &str) {
+        if let Some(frame) = self
+            .recording
+            .as_mut()
+            .and_then(|recording| recording.frames.last_mut())
+        {
+            frame.commands.push(name.to_owned());
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(recording) = &self.last else {
+            return;
+        };
+        let result = ron::ser::to_string_pretty(recording, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|ron| fs::write(REPLAY_PATH, ron).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => info!("Saved the recording to {REPLAY_PATH}"),
+            Err(e) => error!("Failed to save the recording to {REPLAY_PATH}: {e}"),
+        }
+    }
+
+    pub fn load(&mut self) {
+        let recording = fs::read_to_string(REPLAY_PATH)
+            .map_err(|e| e.to_string())
+            .and_then(|ron| ron::from_str::<InputRecording>(&ron).map_err(|e| e.to_string()));
+        match recording {
+            Ok(recording) => {
+                info!(
+                    "Loaded a recording of {} frames from {REPLAY_PATH}",
+                    recording.frames.len()
+                );
+                self.last = Some(recording);
+            }
+            Err(e) => error!("Failed to load the recording from {REPLAY_PATH}: {e}"),
+        }
+    }
+}
+
+/// Plays the last recording from the scene as it is. That's the scene the recording started from
+/// when it was put back after playing, see [`EditorSettings::restore_after_play`], and wasn't
+/// edited since.
+///
+/// [`EditorSettings::restore_after_play`]: crate::settings::EditorSettings::restore_after_play
+pub fn start_replay(world: &mut World) {
+    if *world.resource::<State<EditorState>>().get() != EditorState::Edit {
+        warn!("Stop playing to replay the last recording");
+        return;
+    }
+    let mut recorder = world.resource_mut::<InputRecorder>();
+    if recorder.last.is_none() {
+        warn!("Nothing was recorded to replay");
+        return;
+    }
+    info!("Replaying the last recording");
+    recorder.record_next_play = false;
+    recorder.replay = Some(Replay {
+        frame: 0,
+        keys: Input::default(),
+        mouse_buttons: Input::default(),
+    });
+    play::set_state(world, EditorState::Play);
+}
+
+fn start_recording(mut recorder: ResMut<InputRecorder>) {
+    if recorder.record_next_play && recorder.replay.is_none() && recorder.recording.is_none() {
+        info!("Recording the input while playing");
+        recorder.recording = Some(InputRecording::default());
+    }
+}
+
+fn stop(mut recorder: ResMut<InputRecorder>, mut strategy: ResMut<TimeUpdateStrategy>) {
+    if let Some(recording) = recorder.recording.take() {
+        info!("Recorded {} frames of input", recording.frames.len());
+        recorder.last = Some(recording);
+        recorder.record_next_play = false;
+    }
+    if recorder.replay.take().is_some() {
+        info!("Stopped replaying");
+        *strategy = TimeUpdateStrategy::Automatic;
+    }
+}
+
+/// Makes the frames of the replay last as long as the recorded ones.
+fn replay_frame_time(world: &mut World) {
+    let recorder = world.resource::<InputRecorder>();
+    let (Some(replay), Some(recording)) = (&recorder.replay, &recorder.last) else {
+        return;
+    };
+    if let Some(frame) = recording.frames.get(replay.frame) {
+        world.insert_resource(TimeUpdateStrategy::ManualDuration(frame.delta));
+    }
+}
+
+fn record_input(
+    mut recorder: ResMut<InputRecorder>,
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut mouse_wheel: EventReader<MouseWheel>,
+) {
+    let Some(recording) = &mut recorder.recording else {
+        return;
+    };
+    recording.frames.push(RecordedFrame {
+        delta: time.delta(),
+        keys: keys.get_pressed().copied().collect(),
+        mouse_buttons: mouse_buttons.get_pressed().copied().collect(),
+        mouse_motion: mouse_motion.iter().cloned().collect(),
+        mouse_wheel: mouse_wheel.iter().cloned().collect(),
+        commands: Vec::new(),
+    });
+}
+
+/// Moves `input` to the buttons pressed in the recorded frame, so they're just pressed or
+/// released as they were.
+fn replay_buttons<T: Copy + Eq + std::hash::Hash + Send + Sync + 'static>(
+    input: &mut Input<T>,
+    pressed: &[T],
+) {
+    input.clear();
+    let released: Vec<_> = input
+        .get_pressed()
+        .filter(|button| !pressed.contains(button))
+        .copied()
+        .collect();
+    for button in released {
+        input.release(button);
+    }
+    for &button in pressed {
+        input.press(button);
+    }
+}
+
+/// Feeds the game the input of the next recorded frame, ending the replay paused on the last
+/// frame so what happened can be inspected.
+fn replay_input(world: &mut World) {
+    world.resource_scope(|world, mut recorder: Mut<InputRecorder>| {
+        let InputRecorder { last, replay, .. } = &mut *recorder;
+        let (Some(replay_state), Some(recording)) = (replay.as_mut(), last.as_ref()) else {
+            return;
+        };
+        let Some(frame) = recording.frames.get(replay_state.frame) else {
+            info!("Replayed {} frames", recording.frames.len());
+            *replay = None;
+            world.insert_resource(TimeUpdateStrategy::Automatic);
+            play::set_state(world, EditorState::Paused);
+            return;
+        };
+        replay_state.frame += 1;
+
+        replay_buttons(&mut replay_state.keys, &frame.keys);
+        replay_buttons(&mut replay_state.mouse_buttons, &frame.mouse_buttons);
+        *world.resource_mut::<Input<KeyCode>>() = replay_state.keys.clone();
+        *world.resource_mut::<Input<MouseButton>>() = replay_state.mouse_buttons.clone();
+
+        let mut motion = world.resource_mut::<Events<MouseMotion>>();
+        motion.clear();
+        motion.extend(frame.mouse_motion.iter().cloned());
+        let mut wheel = world.resource_mut::<Events<MouseWheel>>();
+        wheel.clear();
+        wheel.extend(frame.mouse_wheel.iter().cloned());
+
+        let mut commands = world.resource_mut::<EditorCommands>();
+        for name in &frame.commands {
+            if !commands.queue(name) {
+                warn!("No editor command is named {name}, skipping it");
+            }
+        }
+    });
+}
+
+/// Menu to record the next play session and replay the last one.
+pub fn replay_menu(world: &mut World, ui: &mut egui::Ui) {
+    let mut recorder = world.resource_mut::<InputRecorder>();
+    if recorder.is_recording() {
+        let frames = recorder.recording.as_ref().map_or(0, |r| r.frames.len());
+        ui.label(format!("⏺ Recording, {frames} frames"));
+    } else if let Some(replay) = &recorder.replay {
+        ui.label(format!("Replaying frame {}", replay.frame));
+    } else {
+        ui.checkbox(&mut recorder.record_next_play, "Record Next Play")
+            .on_hover_text("Record the input and the editor commands while playing");
+    }
+    ui.separator();
+
+    let recorder = world.resource::<InputRecorder>();
+    let state = *world.resource::<State<EditorState>>().get();
+    let idle = state == EditorState::Edit && !recorder.is_replaying();
+    match recorder.last() {
+        Some(recording) => ui.weak(format!("Last recording: {} frames", recording.frames.len())),
+        None => ui.weak("Nothing recorded"),
+    };
+    let has_recording = recorder.last.is_some();
+    if ui
+        .add_enabled(idle && has_recording, egui::Button::new("▶ Replay"))
+        .on_hover_text("Play again from the scene as it is with the recorded input")
+        .clicked()
+    {
+        start_replay(world);
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(has_recording, egui::Button::new("Save"))
+        .on_hover_text(REPLAY_PATH)
+        .clicked()
+    {
+        world.resource::<InputRecorder>().save();
+        ui.close_menu();
+    }
+    if ui
+        .add_enabled(
+            idle && Path::new(REPLAY_PATH).exists(),
+            egui::Button::new("Load"),
+        )
+        .on_hover_text(REPLAY_PATH)
+        .clicked()
+    {
+        world.resource_mut::<InputRecorder>().load();
+        ui.close_menu();
+    }
+}
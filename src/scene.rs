@@ -0,0 +1,399 @@
+use std::{fmt, fs, io, path::Path};
+
+use bevy::{
+    ecs::entity::EntityMap,
+    pbr::wireframe::Wireframe,
+    prelude::*,
+    reflect::ReflectMut,
+    render::primitives::Aabb,
+    scene::{serde::SceneDeserializer, DynamicEntity, SceneSpawnError},
+    window::Window,
+};
+use serde::de::DeserializeSeed;
+
+use crate::{
+    colliders::EditorColliderBounds,
+    debug_view::{DebugMaterial, OriginalMaterial},
+    hierarchy::EditorHidden,
+    nested_scene::{NestedScene, NestedSceneEntity},
+    wireframe::EditorWireframe,
+    EditorOnly, MainCamera,
+};
+
+#[derive(Debug)]
+pub enum SceneFileError {
+    Io(io::Error),
+    Ron(ron::Error),
+    Spawn(SceneSpawnError),
+}
+
+impl fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneFileError::Io(e) => write!(f, "io error: {e}"),
+            SceneFileError::Ron(e) => write!(f, "ron error: {e}"),
+            SceneFileError::Spawn(e) => write!(f, "spawn error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SceneFileError {}
+
+impl From<io::Error> for SceneFileError {
+    fn from(e: io::Error) -> Self {
+        SceneFileError::Io(e)
+    }
+}
+
+impl From<ron::Error> for SceneFileError {
+    fn from(e: ron::Error) -> Self {
+        SceneFileError::Ron(e)
+    }
+}
+
+impl From<ron::error::SpannedError> for SceneFileError {
+    fn from(e: ron::error::SpannedError) -> Self {
+        SceneFileError::Ron(e.code)
+    }
+}
+
+impl From<SceneSpawnError> for SceneFileError {
+    fn from(e: SceneSpawnError) -> Self {
+        SceneFileError::Spawn(e)
+    }
+}
+
+/// Entities that end up in a world save: everything but windows and the entities of the editor.
+pub fn scene_entities(world: &mut World) -> Vec<Entity> {
+    let mut entities: Vec<_> = world
+        .query_filtered::<Entity, (Without<Window>, Without<MainCamera>, Without<EditorOnly>)>()
+        .iter(world)
+        .collect();
+    entities.sort();
+    entities
+}
+
+/// `entities` without those whose ancestor is also in `entities`.
+pub fn top_level_entities(world: &World, entities: &[Entity]) -> Vec<Entity> {
+    entities
+        .iter()
+        .copied()
+        .filter(|&entity| {
+            !std::iter::successors(world.get::<Parent>(entity), |parent| {
+                world.get::<Parent>(parent.get())
+            })
+            .any(|parent| entities.contains(&parent.get()))
+        })
+        .collect()
+}
+
+/// Collects `roots` and all of their descendants.
+pub fn with_descendants(world: &World, roots: &[Entity]) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut stack: Vec<_> = roots.iter().rev().copied().collect();
+    while let Some(entity) = stack.pop() {
+        if entities.contains(&entity) {
+            continue;
+        }
+        entities.push(entity);
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(children.iter().rev());
+        }
+    }
+    entities
+}
+
+/// Builds a [`DynamicScene`] out of `entities`.
+///
+/// `Parent` components and children pointing outside of the extracted set are dropped so the
+/// scene doesn't reference entities it doesn't contain, as are wireframes and collider bounds
+/// added by the editor. Entities hidden from the hierarchy are saved with their own visibility,
+/// and those shown with a debug view with their own material.
+/// The entities of a [`NestedScene`] are left out along with its handle, since they're spawned
+/// from its asset.
+pub fn build_scene(world: &World, entities: &[Entity]) -> DynamicScene {
+    let entities: Vec<_> = entities
+        .iter()
+        .copied()
+        .filter(|&entity| {
+            world
+                .get::<NestedSceneEntity>(entity)
+                .is_none_or(|nested| !entities.contains(&nested.root))
+        })
+        .collect();
+    let entities = &entities[..];
+    let mut builder = DynamicSceneBuilder::from_world(world);
+    builder.extract_entities(entities.iter().copied());
+    let mut scene = builder.build();
+
+    let parent_type_name = std::any::type_name::<Parent>();
+    let children_type_name = std::any::type_name::<Children>();
+    let scene_handle_type_names = [
+        std::any::type_name::<Handle<Scene>>(),
+        std::any::type_name::<Handle<DynamicScene>>(),
+    ];
+    let wireframe_type_name = std::any::type_name::<Wireframe>();
+    let aabb_type_name = std::any::type_name::<Aabb>();
+    let visibility_type_name = std::any::type_name::<Visibility>();
+    for DynamicEntity { entity, components } in &mut scene.entities {
+        let parent_outside = world
+            .get::<Parent>(*entity)
+            .is_some_and(|parent| !entities.contains(&parent.get()));
+        if parent_outside {
+            components.retain(|component| component.type_name() != parent_type_name);
+        }
+        if let Some(children) = world.get::<Children>(*entity) {
+            let inside: Vec<_> = children
+                .iter()
+                .map(|child| entities.contains(child))
+                .collect();
+            if !inside.iter().any(|&inside| inside) {
+                components.retain(|component| component.type_name() != children_type_name);
+            }
+            for component in components.iter_mut() {
+                if component.type_name() != children_type_name {
+                    continue;
+                }
+                let ReflectMut::TupleStruct(children) = component.reflect_mut() else {
+                    continue;
+                };
+                let Some(ReflectMut::List(list)) = children.field_mut(0).map(Reflect::reflect_mut)
+                else {
+                    continue;
+                };
+                for index in (0..inside.len()).rev() {
+                    if !inside[index] {
+                        list.remove(index);
+                    }
+                }
+            }
+        }
+        if world.get::<NestedScene>(*entity).is_some() {
+            components
+                .retain(|component| !scene_handle_type_names.contains(&component.type_name()));
+        }
+        if world.get::<EditorWireframe>(*entity).is_some() {
+            components.retain(|component| component.type_name() != wireframe_type_name);
+        }
+        if world.get::<EditorColliderBounds>(*entity).is_some() {
+            components.retain(|component| component.type_name() != aabb_type_name);
+        }
+        if let Some(hidden) = world.get::<EditorHidden>(*entity) {
+            for component in components.iter_mut() {
+                if component.type_name() == visibility_type_name {
+                    *component = Box::new(hidden.0);
+                }
+            }
+        }
+    }
+    restore_debug_materials(world, &mut scene);
+    scene
+}
+
+/// Puts back the standard materials which the debug view swapped out of the entities of `scene`.
+pub(crate) fn restore_debug_materials(world: &World, scene: &mut DynamicScene) {
+    let debug_material_type_name = std::any::type_name::<Handle<DebugMaterial>>();
+    for DynamicEntity { entity, components } in &mut scene.entities {
+        if let Some(original) = world.get::<OriginalMaterial>(*entity) {
+            components.retain(|component| component.type_name() != debug_material_type_name);
+            components.push(Box::new(original.0.clone()));
+        }
+    }
+}
+
+pub fn serialize_scene(world: &World, entities: &[Entity]) -> Result<String, SceneFileError> {
+    let type_registry = world.resource::<AppTypeRegistry>();
+    Ok(build_scene(world, entities).serialize_ron(type_registry)?)
+}
+
+pub fn deserialize_scene(world: &World, ron: &str) -> Result<DynamicScene, SceneFileError> {
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let mut deserializer = ron::de::Deserializer::from_str(ron)?;
+    let scene = SceneDeserializer {
+        type_registry: &type_registry,
+    }
+    .deserialize(&mut deserializer)?;
+    Ok(scene)
+}
+
+/// Spawns `scene` into the world, returning the map from scene entities to spawned ones.
+pub fn spawn_scene_mapped(
+    world: &mut World,
+    scene: &DynamicScene,
+) -> Result<EntityMap, SceneFileError> {
+    let mut entity_map = EntityMap::default();
+    scene.write_to_world(world, &mut entity_map)?;
+    Ok(entity_map)
+}
+
+/// Spawns `scene` into the world and returns the spawned entities which have no parent.
+pub fn spawn_scene(world: &mut World, scene: &DynamicScene) -> Result<Vec<Entity>, SceneFileError> {
+    let entity_map = spawn_scene_mapped(world, scene)?;
+
+    let mut roots: Vec<_> = entity_map
+        .values()
+        .filter(|&entity| world.get::<Parent>(entity).is_none())
+        .collect();
+    roots.sort();
+    Ok(roots)
+}
+
+pub fn save_scene(
+    world: &World,
+    entities: &[Entity],
+    path: impl AsRef<Path>,
+) -> Result<(), SceneFileError> {
+    let path = path.as_ref();
+    let ron = serialize_scene(world, entities)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, ron)?;
+    Ok(())
+}
+
+pub fn load_scene(
+    world: &mut World,
+    path: impl AsRef<Path>,
+) -> Result<Vec<Entity>, SceneFileError> {
+    let ron = fs::read_to_string(path)?;
+    let scene = deserialize_scene(world, &ron)?;
+    spawn_scene(world, &scene)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{asset::HandleId, reflect::ReflectRef};
+
+    use super::*;
+
+    fn world() -> World {
+        let mut world = World::new();
+        let type_registry = AppTypeRegistry::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Name>();
+            type_registry.register::<Parent>();
+            type_registry.register::<Children>();
+            type_registry.register::<Visibility>();
+            type_registry.register::<Wireframe>();
+            type_registry.register::<Aabb>();
+            type_registry.register::<Handle<StandardMaterial>>();
+        }
+        world.insert_resource(type_registry);
+        world
+    }
+
+    fn has<T: 'static>(scene: &DynamicScene, entity: Entity) -> bool {
+        let type_name = std::any::type_name::<T>();
+        scene
+            .entities
+            .iter()
+            .find(|dynamic| dynamic.entity == entity)
+            .unwrap()
+            .components
+            .iter()
+            .any(|component| component.type_name() == type_name)
+    }
+
+    #[test]
+    fn drops_references_to_entities_left_out() {
+        let mut world = world();
+        let parent = world.spawn(Name::new("Parent")).id();
+        let saved = world.spawn(Name::new("Saved")).set_parent(parent).id();
+        world.spawn(Name::new("Left Out")).set_parent(parent);
+        let child = world.spawn(Name::new("Child")).set_parent(saved).id();
+
+        let scene = build_scene(&world, &[saved, child]);
+        assert_eq!(scene.entities.len(), 2);
+        assert!(!has::<Parent>(&scene, saved));
+        assert!(has::<Parent>(&scene, child));
+        assert!(has::<Children>(&scene, saved));
+
+        // children outside of the scene are removed from the list
+        let scene = build_scene(&world, &[parent, saved]);
+        let parent_entity = scene.entities.iter().find(|e| e.entity == parent).unwrap();
+        let children = parent_entity
+            .components
+            .iter()
+            .find(|component| component.type_name() == std::any::type_name::<Children>())
+            .unwrap();
+        let ReflectRef::TupleStruct(children) = children.reflect_ref() else {
+            panic!("Children isn't a tuple struct");
+        };
+        let ReflectRef::List(list) = children.field(0).unwrap().reflect_ref() else {
+            panic!("Children doesn't hold a list");
+        };
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.get(0).unwrap().downcast_ref::<Entity>(), Some(&saved));
+        assert!(!has::<Children>(&scene, saved));
+    }
+
+    #[test]
+    fn drops_what_the_editor_added() {
+        let mut world = world();
+        let wireframe = world.spawn((Wireframe, EditorWireframe)).id();
+        let own_wireframe = world.spawn(Wireframe).id();
+        let bounds = world.spawn((Aabb::default(), EditorColliderBounds)).id();
+
+        let scene = build_scene(&world, &[wireframe, own_wireframe, bounds]);
+        assert!(!has::<Wireframe>(&scene, wireframe));
+        assert!(has::<Wireframe>(&scene, own_wireframe));
+        assert!(!has::<Aabb>(&scene, bounds));
+    }
+
+    #[test]
+    fn saves_hidden_entities_with_their_own_visibility() {
+        let mut world = world();
+        let hidden = world
+            .spawn((Visibility::Hidden, EditorHidden(Visibility::Visible)))
+            .id();
+
+        let scene = build_scene(&world, &[hidden]);
+        let visibility = scene.entities[0]
+            .components
+            .iter()
+            .find(|component| component.type_name() == std::any::type_name::<Visibility>())
+            .unwrap();
+        assert_eq!(
+            visibility.downcast_ref::<Visibility>(),
+            Some(&Visibility::Visible)
+        );
+    }
+
+    #[test]
+    fn saves_entities_shown_with_a_debug_view_with_their_own_material() {
+        let mut world = world();
+        let material = Handle::<StandardMaterial>::weak(HandleId::random::<StandardMaterial>());
+        let shaded = world
+            .spawn((
+                Handle::<DebugMaterial>::default(),
+                OriginalMaterial(material.clone()),
+            ))
+            .id();
+
+        let scene = build_scene(&world, &[shaded]);
+        assert!(!has::<Handle<DebugMaterial>>(&scene, shaded));
+        let saved = scene.entities[0]
+            .components
+            .iter()
+            .find_map(|component| component.downcast_ref::<Handle<StandardMaterial>>());
+        assert_eq!(saved, Some(&material));
+    }
+
+    #[test]
+    fn leaves_out_the_entities_of_nested_scenes() {
+        let mut world = world();
+        let root = world.spawn(Name::new("Nested")).id();
+        let nested = world
+            .spawn((Name::new("From Asset"), NestedSceneEntity { root }))
+            .set_parent(root)
+            .id();
+
+        let scene = build_scene(&world, &[root, nested]);
+        assert_eq!(scene.entities.len(), 1);
+        assert_eq!(scene.entities[0].entity, root);
+        // kept when the nested scene itself isn't saved
+        assert_eq!(build_scene(&world, &[nested]).entities.len(), 1);
+    }
+}
@@ -0,0 +1,587 @@
+use std::{collections::BTreeMap, fs};
+
+use bevy::{
+    prelude::*,
+    reflect::ReflectRef,
+    scene::DynamicEntity,
+    utils::{get_short_name, HashMap},
+};
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+
+use crate::scene;
+
+pub struct EditorSceneDiffPlugin;
+impl Plugin for EditorSceneDiffPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SceneDiffTab>();
+    }
+}
+
+/// The scene compared against the one before it.
+#[derive(Default, PartialEq, Eq)]
+enum DiffTarget {
+    /// The entities of the world, as they would be saved.
+    #[default]
+    World,
+    File,
+}
+
+/// The scenes compared in the scene diff tab, and their differences.
+#[derive(Resource, Default)]
+pub struct SceneDiffTab {
+    pub before: String,
+    pub after: String,
+    target: DiffTarget,
+    diff: Option<Result<SceneDiff, String>>,
+}
+
+/// An entity of a compared scene, with its components flattened to the values of their fields.
+struct DiffEntity {
+    /// The entity in the world, for the entities of the world.
+    entity: Option<Entity>,
+    components: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+enum ComponentChange {
+    Added,
+    Removed,
+    /// The fields which changed, with their values before and after.
+    Changed(Vec<(String, Option<String>, Option<String>)>),
+}
+
+struct EntityChanges {
+    entity: Option<Entity>,
+    components: Vec<(String, ComponentChange)>,
+}
+
+/// The entities added to, removed from and changed in a scene, by their path in the hierarchy.
+#[derive(Default)]
+struct SceneDiff {
+    added: Vec<(String, Option<Entity>)>,
+    removed: Vec<String>,
+    changed: Vec<(String, EntityChanges)>,
+}
+
+/// Flattens `value` into the values of its fields, keyed by their path within it. Values written
+/// in scene files and taken from the world are compared through these, since one is dynamic and
+/// the other isn't.
+fn flatten(value: &dyn Reflect, path: String, fields: &mut BTreeMap<String, String>) {
+    let join = |field: &str| match path.is_empty() {
+        true => field.to_owned(),
+        false => format!("{path}.{field}"),
+    };
+    match value.reflect_ref() {
+        ReflectRef::Struct(value) => {
+            for index in 0..value.field_len() {
+                let name = value.name_at(index).unwrap();
+                flatten(value.field_at(index).unwrap(), join(name), fields);
+            }
+        }
+        ReflectRef::TupleStruct(value) => {
+            for (index, field) in value.iter_fields().enumerate() {
+                flatten(field, join(&index.to_string()), fields);
+            }
+        }
+        ReflectRef::Tuple(value) => {
+            for (index, field) in value.iter_fields().enumerate() {
+                flatten(field, join(&index.to_string()), fields);
+            }
+        }
+        ReflectRef::List(value) => {
+            fields.insert(join("len"), value.len().to_string());
+            for (index, item) in value.iter().enumerate() {
+                flatten(item, format!("{path}[{index}]"), fields);
+            }
+        }
+        ReflectRef::Array(value) => {
+            for (index, item) in value.iter().enumerate() {
+                flatten(item, format!("{path}[{index}]"), fields);
+            }
+        }
+        ReflectRef::Map(value) => {
+            fields.insert(join("len"), value.len().to_string());
+            for (key, item) in value.iter() {
+                flatten(item, format!("{path}[{key:?}]"), fields);
+            }
+        }
+        ReflectRef::Enum(value) => {
+            fields.insert(path.clone(), value.variant_name().to_owned());
+            for (index, field) in value.iter_fields().enumerate() {
+                let name = field
+                    .name()
+                    .map_or_else(|| index.to_string(), str::to_owned);
+                flatten(
+                    field.value(),
+                    join(&format!("{}.{name}", value.variant_name())),
+                    fields,
+                );
+            }
+        }
+        ReflectRef::Value(value) => {
+            fields.insert(path, format!("{value:?}"));
+        }
+    }
+}
+
+/// The entity a component points to, for `Parent` components of a scene.
+fn parent_of(components: &[Box<dyn Reflect>]) -> Option<Entity> {
+    let parent_type_name = std::any::type_name::<Parent>();
+    let parent = components
+        .iter()
+        .find(|component| component.type_name() == parent_type_name)?;
+    match parent.reflect_ref() {
+        ReflectRef::TupleStruct(parent) => parent.field(0)?.downcast_ref::<Entity>().copied(),
+        _ => None,
+    }
+}
+
+fn name_of(components: &[Box<dyn Reflect>]) -> Option<String> {
+    let name_type_name = std::any::type_name::<Name>();
+    let name = components
+        .iter()
+        .find(|component| component.type_name() == name_type_name)?;
+    Name::from_reflect(name.as_ref()).map(|name| name.as_str().to_owned())
+}
+
+/// Keys the entities of `scene` by their path of names in its hierarchy, numbering the siblings
+/// which share a name. Entity ids change every time a scene is loaded, so they can't be compared.
+fn diff_entities(scene: &DynamicScene, live: bool) -> BTreeMap<String, DiffEntity> {
+    let by_id: HashMap<Entity, &DynamicEntity> = scene
+        .entities
+        .iter()
+        .map(|entity| (entity.entity, entity))
+        .collect();
+    let mut paths: HashMap<Entity, String> = HashMap::default();
+    fn path(
+        entity: &DynamicEntity,
+        by_id: &HashMap<Entity, &DynamicEntity>,
+        paths: &mut HashMap<Entity, String>,
+    ) -> String {
+        if let Some(path) = paths.get(&entity.entity) {
+            return path.clone();
+        }
+        let name = name_of(&entity.components).unwrap_or_else(|| "<unnamed>".to_owned());
+        let path = match parent_of(&entity.components).and_then(|parent| by_id.get(&parent)) {
+            Some(parent) => format!("{}/{name}", path(parent, by_id, paths)),
+            None => name,
+        };
+        paths.insert(entity.entity, path.clone());
+        path
+    }
+
+    let skipped = [
+        std::any::type_name::<Parent>(),
+        std::any::type_name::<Children>(),
+    ];
+    let mut entities = BTreeMap::new();
+    let mut sorted: Vec<_> = scene.entities.iter().collect();
+    sorted.sort_by_key(|entity| entity.entity);
+    for entity in sorted {
+        let path = path(entity, &by_id, &mut paths);
+        let mut key = path.clone();
+        let mut index = 1;
+        while entities.contains_key(&key) {
+            index += 1;
+            key = format!("{path} #{index}");
+        }
+        let components = entity
+            .components
+            .iter()
+            .filter(|component| !skipped.contains(&component.type_name()))
+            .map(|component| {
+                let mut fields = BTreeMap::new();
+                flatten(component.as_ref(), String::new(), &mut fields);
+                (get_short_name(component.type_name()), fields)
+            })
+            .collect();
+        entities.insert(
+            key,
+            DiffEntity {
+                entity: live.then_some(entity.entity),
+                components,
+            },
+        );
+    }
+    entities
+}
+
+fn compare(before: &DynamicScene, after: &DynamicScene, live: bool) -> SceneDiff {
+    let before = diff_entities(before, false);
+    let after = diff_entities(after, live);
+    let mut diff = SceneDiff::default();
+    for path in before.keys().filter(|path| !after.contains_key(*path)) {
+        diff.removed.push(path.clone());
+    }
+    for (path, entity) in &after {
+        let Some(old) = before.get(path) else {
+            diff.added.push((path.clone(), entity.entity));
+            continue;
+        };
+        let mut components = Vec::new();
+        for name in old.components.keys() {
+            if !entity.components.contains_key(name) {
+                components.push((name.clone(), ComponentChange::Removed));
+            }
+        }
+        for (name, fields) in &entity.components {
+            let Some(old_fields) = old.components.get(name) else {
+                components.push((name.clone(), ComponentChange::Added));
+                continue;
+            };
+            let mut changed: Vec<_> = old_fields
+                .iter()
+                .filter(|(field, value)| fields.get(*field) != Some(value))
+                .map(|(field, value)| {
+                    (
+                        field.clone(),
+                        Some(value.clone()),
+                        fields.get(field).cloned(),
+                    )
+                })
+                .collect();
+            changed.extend(
+                fields
+                    .iter()
+                    .filter(|(field, _)| !old_fields.contains_key(*field))
+                    .map(|(field, value)| (field.clone(), None, Some(value.clone()))),
+            );
+            if !changed.is_empty() {
+                components.push((name.clone(), ComponentChange::Changed(changed)));
+            }
+        }
+        if !components.is_empty() {
+            components.sort_by(|(a, _), (b, _)| a.cmp(b));
+            diff.changed.push((
+                path.clone(),
+                EntityChanges {
+                    entity: entity.entity,
+                    components,
+                },
+            ));
+        }
+    }
+    diff
+}
+
+fn read_scene(world: &World, path: &str) -> Result<DynamicScene, String> {
+    let ron = fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    scene::deserialize_scene(world, &ron).map_err(|e| format!("Failed to read {path}: {e}"))
+}
+
+fn diff_scenes(world: &mut World, tab: &SceneDiffTab) -> Result<SceneDiff, String> {
+    let before = read_scene(world, &tab.before)?;
+    let (after, live) = match tab.target {
+        DiffTarget::World => {
+            let entities = scene::scene_entities(world);
+            (scene::build_scene(world, &entities), true)
+        }
+        DiffTarget::File => (read_scene(world, &tab.after)?, false),
+    };
+    Ok(compare(&before, &after, live))
+}
+
+fn entity_label(
+    ui: &mut egui::Ui,
+    text: egui::RichText,
+    entity: Option<Entity>,
+    selected: &mut SelectedEntities,
+) {
+    match entity {
+        Some(entity) => {
+            if ui
+                .selectable_label(selected.contains(entity), text)
+                .clicked()
+            {
+                selected.select_replace(entity);
+            }
+        }
+        None => {
+            ui.label(text);
+        }
+    }
+}
+
+/// Compares a saved scene with the world or another scene file, listing the entities added and
+/// removed and the component fields changed. Entities of the world can be clicked to select them.
+pub fn scene_diff_ui(world: &mut World, ui: &mut egui::Ui, selected: &mut SelectedEntities) {
+    world.resource_scope::<SceneDiffTab, _>(|world, mut tab| {
+        if tab.before.is_empty() {
+            tab.before = world.resource::<crate::UiState>().scene_path.clone();
+        }
+        egui::Grid::new("scene_diff_sources")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Before");
+                ui.text_edit_singleline(&mut tab.before);
+                ui.end_row();
+                ui.label("After");
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut tab.target, DiffTarget::World, "World");
+                    ui.selectable_value(&mut tab.target, DiffTarget::File, "File");
+                    if tab.target == DiffTarget::File {
+                        ui.text_edit_singleline(&mut tab.after);
+                    }
+                });
+                ui.end_row();
+            });
+        if ui.button("Compare").clicked() {
+            tab.diff = Some(diff_scenes(world, &tab));
+        }
+        ui.separator();
+
+        let diff = match &tab.diff {
+            None => return,
+            Some(Err(error)) => {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+                return;
+            }
+            Some(Ok(diff)) => diff,
+        };
+        ui.label(format!(
+            "{} added, {} removed, {} changed",
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        ));
+        let added_color = egui::Color32::from_rgb(110, 200, 110);
+        let removed_color = ui.visuals().error_fg_color;
+        let changed_color = ui.visuals().warn_fg_color;
+        egui::ScrollArea::both()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                for (path, entity) in &diff.added {
+                    let text = egui::RichText::new(format!("+ {path}")).color(added_color);
+                    entity_label(ui, text, *entity, selected);
+                }
+                for path in &diff.removed {
+                    ui.label(egui::RichText::new(format!("- {path}")).color(removed_color));
+                }
+                for (path, changes) in &diff.changed {
+                    let id = ui.make_persistent_id(path);
+                    egui::collapsing_header::CollapsingState::load_with_default_open(
+                        ui.ctx(),
+                        id,
+                        true,
+                    )
+                    .show_header(ui, |ui| {
+                        let text = egui::RichText::new(format!("~ {path}")).color(changed_color);
+                        entity_label(ui, text, changes.entity, selected);
+                    })
+                    .body(|ui| {
+                        for (component, change) in &changes.components {
+                            match change {
+                                ComponentChange::Added => {
+                                    ui.colored_label(added_color, format!("+ {component}"));
+                                }
+                                ComponentChange::Removed => {
+                                    ui.colored_label(removed_color, format!("- {component}"));
+                                }
+                                ComponentChange::Changed(fields) => {
+                                    ui.label(component);
+                                    for (field, before, after) in fields {
+                                        let field = match field.is_empty() {
+                                            true => component.clone(),
+                                            false => format!("{component}.{field}"),
+                                        };
+                                        ui.horizontal(|ui| {
+                                            ui.add_space(ui.spacing().indent);
+                                            ui.monospace(field);
+                                            ui.colored_label(
+                                                removed_color,
+                                                before.as_deref().unwrap_or("-"),
+                                            );
+                                            ui.label("→");
+                                            ui.colored_label(
+                                                added_color,
+                                                after.as_deref().unwrap_or("-"),
+                                            );
+                                        });
+                                    }
+                                }
+                            }
+                        }
+                    });
+                }
+            });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::scene::DynamicSceneBuilder;
+
+    use super::*;
+
+    fn world() -> World {
+        let mut world = World::new();
+        let type_registry = AppTypeRegistry::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Name>();
+            type_registry.register::<Transform>();
+            type_registry.register::<Visibility>();
+            type_registry.register::<Parent>();
+            type_registry.register::<Children>();
+        }
+        world.insert_resource(type_registry);
+        world
+    }
+
+    fn scene(world: &World) -> DynamicScene {
+        let entities: Vec<_> = world.iter_entities().map(|entity| entity.id()).collect();
+        let mut builder = DynamicSceneBuilder::from_world(world);
+        builder.extract_entities(entities.into_iter());
+        builder.build()
+    }
+
+    /// A level with two crates and a lamp, and an entity beside it.
+    fn level(
+        world: &mut World,
+        crate_x: f32,
+        lamp: Visibility,
+        other: &'static str,
+    ) -> [Entity; 4] {
+        let level = world.spawn(Name::new("Level")).id();
+        let mut child = |bundle: (Name, Transform)| world.spawn(bundle).set_parent(level).id();
+        let first = child((Name::new("Crate"), Transform::from_xyz(crate_x, 0.0, 0.0)));
+        let second = child((Name::new("Crate"), Transform::default()));
+        let lamp_entity = child((Name::new("Lamp"), Transform::default()));
+        world.entity_mut(lamp_entity).insert(lamp);
+        world.spawn(Name::new(other));
+        [level, first, second, lamp_entity]
+    }
+
+    #[test]
+    fn flattens_values_to_their_fields() {
+        let mut fields = BTreeMap::new();
+        flatten(
+            &(Transform::from_xyz(1.0, 2.0, 3.0), vec![Visibility::Hidden]),
+            String::new(),
+            &mut fields,
+        );
+        assert_eq!(fields["0.translation.y"], "2.0");
+        assert_eq!(fields["0.scale.x"], "1.0");
+        // quaternions are reflected as values
+        assert!(fields.contains_key("0.rotation"));
+        assert_eq!(fields["1.len"], "1");
+        assert_eq!(fields["1[0]"], "Hidden");
+        assert_eq!(fields.keys().filter(|key| key.starts_with("0.")).count(), 7);
+    }
+
+    #[test]
+    fn dynamic_values_flatten_like_their_type() {
+        let value = Transform::from_xyz(1.0, 2.0, 3.0);
+        let (mut fields, mut dynamic_fields) = (BTreeMap::new(), BTreeMap::new());
+        flatten(&value, String::new(), &mut fields);
+        flatten(
+            value.clone_value().as_ref(),
+            String::new(),
+            &mut dynamic_fields,
+        );
+        assert_eq!(fields, dynamic_fields);
+    }
+
+    #[test]
+    fn keys_entities_by_their_path_of_names() {
+        let mut world = world();
+        level(&mut world, 0.0, Visibility::Hidden, "Sun");
+        let entities = diff_entities(&scene(&world), false);
+        let paths: Vec<_> = entities.keys().map(String::as_str).collect();
+        assert_eq!(
+            paths,
+            [
+                "Level",
+                "Level/Crate",
+                "Level/Crate #2",
+                "Level/Lamp",
+                "Sun"
+            ]
+        );
+        // the hierarchy is compared through the paths
+        assert!(!entities["Level"].components.contains_key("Children"));
+        assert!(!entities["Level/Lamp"].components.contains_key("Parent"));
+        assert!(entities["Level"].entity.is_none());
+    }
+
+    #[test]
+    fn lists_added_removed_and_changed_entities() {
+        let mut before = world();
+        level(&mut before, 0.0, Visibility::Hidden, "Sun");
+        let mut after = world();
+        let [_, first, second, lamp] = level(&mut after, 2.0, Visibility::Visible, "Moon");
+        after.entity_mut(second).insert(Visibility::Visible);
+        after.entity_mut(lamp).remove::<Transform>();
+        let diff = compare(&scene(&before), &scene(&after), true);
+
+        assert_eq!(diff.removed, ["Sun"]);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].0, "Moon");
+        assert!(diff.added[0].1.is_some());
+        let changed: Vec<_> = diff.changed.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(changed, ["Level/Crate", "Level/Crate #2", "Level/Lamp"]);
+
+        let (_, crate_changes) = &diff.changed[0];
+        assert_eq!(crate_changes.entity, Some(first));
+        let [(component, ComponentChange::Changed(fields))] = &crate_changes.components[..] else {
+            panic!("only the transform of the crate changed");
+        };
+        assert_eq!(component, "Transform");
+        assert_eq!(
+            fields,
+            &[(
+                "translation.x".to_owned(),
+                Some("0.0".to_owned()),
+                Some("2.0".to_owned())
+            )]
+        );
+
+        let (_, second_changes) = &diff.changed[1];
+        assert!(matches!(
+            &second_changes.components[..],
+            [(name, ComponentChange::Added)] if name == "Visibility"
+        ));
+
+        let (_, lamp_changes) = &diff.changed[2];
+        let [(transform, ComponentChange::Removed), (visibility, ComponentChange::Changed(fields))] =
+            &lamp_changes.components[..]
+        else {
+            panic!("the lamp lost its transform and was shown");
+        };
+        assert_eq!(
+            (transform.as_str(), visibility.as_str()),
+            ("Transform", "Visibility")
+        );
+        assert_eq!(
+            fields,
+            &[(
+                String::new(),
+                Some("Hidden".to_owned()),
+                Some("Visible".to_owned())
+            )]
+        );
+    }
+
+    #[test]
+    fn the_same_scene_has_no_differences() {
+        let mut world = world();
+        level(&mut world, 1.0, Visibility::Hidden, "Sun");
+        let scene = scene(&world);
+        let dynamic = DynamicScene {
+            resources: Vec::new(),
+            entities: scene
+                .entities
+                .iter()
+                .map(|entity| DynamicEntity {
+                    entity: entity.entity,
+                    components: entity
+                        .components
+                        .iter()
+                        .map(|component| component.clone_value())
+                        .collect(),
+                })
+                .collect(),
+        };
+        let diff = compare(&scene, &dynamic, false);
+        assert!(diff.added.is_empty() && diff.removed.is_empty() && diff.changed.is_empty());
+    }
+}
@@ -0,0 +1,498 @@
+use bevy::{
+    ecs::{
+        component::Components,
+        schedule::{NodeId, ScheduleGraph, Schedules},
+    },
+    prelude::*,
+    utils::HashMap,
+};
+
+pub struct EditorSchedulesPlugin;
+impl Plugin for EditorSchedulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SchedulesTab>().add_systems(
+            Update,
+            snapshot_running_schedules.run_if(|tab: Res<SchedulesTab>| tab.pending),
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    System,
+    Set,
+    Anonymous,
+    /// The set every system is in for its type, standing for the system in orderings.
+    SystemType,
+}
+
+/// A system or system set of a schedule, with the other nodes indexed in the schedule.
+struct ScheduleNode {
+    name: String,
+    kind: NodeKind,
+    /// The position of a system in the order the schedule runs them.
+    order: Option<usize>,
+    conditions: Vec<String>,
+    parents: Vec<usize>,
+    children: Vec<usize>,
+    /// The nodes this one runs after.
+    after: Vec<usize>,
+    /// The nodes this one runs before.
+    before: Vec<usize>,
+}
+
+/// The graph of a schedule, copied out of it since schedules can't be read while they run.
+struct ScheduleSnapshot {
+    name: String,
+    nodes: Vec<ScheduleNode>,
+    roots: Vec<usize>,
+    /// Pairs of systems without an order between them, and the components they both access.
+    ambiguities: Vec<(usize, usize, Vec<String>)>,
+}
+
+impl ScheduleSnapshot {
+    fn new(name: String, graph: &ScheduleGraph, components: &Components) -> Self {
+        let mut ids = HashMap::default();
+        let mut nodes = Vec::new();
+        let mut push = |id: NodeId, name: String, kind: NodeKind, conditions: Vec<String>| {
+            ids.insert(id, nodes.len());
+            nodes.push(ScheduleNode {
+                name,
+                kind,
+                order: None,
+                conditions,
+                parents: Vec::new(),
+                children: Vec::new(),
+                after: Vec::new(),
+                before: Vec::new(),
+            });
+        };
+        for (id, system, conditions) in graph.systems() {
+            let conditions = conditions.iter().map(|c| c.name().into_owned()).collect();
+            push(id, system.name().into_owned(), NodeKind::System, conditions);
+        }
+        for (id, set, conditions) in graph.system_sets() {
+            let kind = match (set.system_type().is_some(), set.is_anonymous()) {
+                (true, _) => NodeKind::SystemType,
+                (_, true) => NodeKind::Anonymous,
+                _ => NodeKind::Set,
+            };
+            let conditions = conditions.iter().map(|c| c.name().into_owned()).collect();
+            push(id, format!("{set:?}"), kind, conditions);
+        }
+
+        for (parent, child, _) in graph.hierarchy().graph().all_edges() {
+            let (Some(&parent), Some(&child)) = (ids.get(&parent), ids.get(&child)) else {
+                continue;
+            };
+            nodes[parent].children.push(child);
+            nodes[child].parents.push(parent);
+        }
+        // orderings against a system type go to every system of the type
+        let members = |node: usize, nodes: &[ScheduleNode]| match nodes[node].kind {
+            NodeKind::SystemType => nodes[node].children.clone(),
+            _ => vec![node],
+        };
+        for (first, then, _) in graph.dependency().graph().all_edges() {
+            let (Some(&first), Some(&then)) = (ids.get(&first), ids.get(&then)) else {
+                continue;
+            };
+            for first in members(first, &nodes) {
+                for then in members(then, &nodes) {
+                    nodes[first].before.push(then);
+                    nodes[then].after.push(first);
+                }
+            }
+        }
+        // only built schedules have an order
+        let topsort: Vec<usize> = graph
+            .dependency()
+            .cached_topsort()
+            .iter()
+            .filter_map(|id| ids.get(id))
+            .copied()
+            .collect();
+        for (order, node) in run_order(&nodes, &topsort).into_iter().enumerate() {
+            nodes[node].order = Some(order);
+        }
+
+        let shown = |node: &usize| nodes[*node].kind != NodeKind::SystemType;
+        let parents: Vec<Vec<usize>> = nodes
+            .iter()
+            .map(|node| node.parents.iter().copied().filter(shown).collect())
+            .collect();
+        let mut roots: Vec<usize> = (0..nodes.len())
+            .filter(|node| shown(node) && parents[*node].is_empty())
+            .collect();
+        sort_nodes(&mut roots, &nodes);
+        for (node, parents) in parents.into_iter().enumerate() {
+            nodes[node].parents = parents;
+            let mut children = std::mem::take(&mut nodes[node].children);
+            sort_nodes(&mut children, &nodes);
+            nodes[node].children = children;
+        }
+
+        let ambiguities = graph
+            .conflicting_systems()
+            .iter()
+            .filter_map(|(a, b, conflicts)| {
+                let conflicts = conflicts
+                    .iter()
+                    .filter_map(|id| components.get_info(*id))
+                    .map(|info| info.name().to_string())
+                    .collect();
+                Some((*ids.get(a)?, *ids.get(b)?, conflicts))
+            })
+            .collect();
+
+        ScheduleSnapshot {
+            name,
+            nodes,
+            roots,
+            ambiguities,
+        }
+    }
+
+    /// Whether the node or any below it has a name containing `filter`.
+    fn matches(&self, node: usize, filter: &str) -> bool {
+        filter.is_empty()
+            || self.nodes[node].name.to_lowercase().contains(filter)
+            || self.nodes[node]
+                .children
+                .iter()
+                .any(|&child| self.matches(child, filter))
+    }
+}
+
+/// The systems in `node`, or below it if it's a set.
+fn systems_in(node: usize, nodes: &[ScheduleNode], systems: &mut Vec<usize>) {
+    match nodes[node].kind {
+        NodeKind::System if !systems.contains(&node) => systems.push(node),
+        NodeKind::System => {}
+        _ => {
+            for &child in &nodes[node].children {
+                systems_in(child, nodes, systems);
+            }
+        }
+    }
+}
+
+/// The systems in an order the schedule can run them in one after another, with the orderings of
+/// sets applied to the systems in them, and ties broken by the order of the dependency graph.
+fn run_order(nodes: &[ScheduleNode], topsort: &[usize]) -> Vec<usize> {
+    let mut rank = vec![usize::MAX; nodes.len()];
+    for (position, &node) in topsort.iter().enumerate() {
+        rank[node] = position;
+    }
+    let mut incoming = vec![0; nodes.len()];
+    let mut outgoing = vec![Vec::new(); nodes.len()];
+    for (node, entry) in nodes.iter().enumerate() {
+        let (mut firsts, mut thens) = (Vec::new(), Vec::new());
+        systems_in(node, nodes, &mut firsts);
+        for &then in &entry.before {
+            systems_in(then, nodes, &mut thens);
+        }
+        for &first in &firsts {
+            for &then in &thens {
+                outgoing[first].push(then);
+                incoming[then] += 1;
+            }
+        }
+    }
+
+    let mut ready: Vec<usize> = topsort
+        .iter()
+        .copied()
+        .filter(|&node| nodes[node].kind == NodeKind::System && incoming[node] == 0)
+        .collect();
+    let mut order = Vec::new();
+    while !ready.is_empty() {
+        let (index, _) = ready
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, node)| rank[**node])
+            .unwrap();
+        let node = ready.swap_remove(index);
+        order.push(node);
+        for &then in &outgoing[node] {
+            incoming[then] -= 1;
+            if incoming[then] == 0 {
+                ready.push(then);
+            }
+        }
+    }
+    order
+}
+
+/// Sets before systems, and systems in the order they run.
+fn sort_nodes(indices: &mut [usize], nodes: &[ScheduleNode]) {
+    indices.sort_by_key(|&node| {
+        let node = &nodes[node];
+        (node.kind == NodeKind::System, node.order, node.name.clone())
+    });
+}
+
+#[derive(Default, PartialEq, Eq)]
+enum SchedulesView {
+    #[default]
+    Hierarchy,
+    Order,
+}
+
+/// The schedules shown in the schedules tab, and the node selected in it.
+#[derive(Resource, Default)]
+pub struct SchedulesTab {
+    schedules: Vec<ScheduleSnapshot>,
+    /// Whether to copy the schedules which were running while the tab was refreshed.
+    pending: bool,
+    view: SchedulesView,
+    filter: String,
+    selected: Option<(String, usize)>,
+}
+
+impl SchedulesTab {
+    /// Copies the graphs of the schedules which aren't running.
+    fn refresh(&mut self, world: &World) {
+        let components = world.components();
+        for (label, schedule) in world.resource::<Schedules>().iter() {
+            let snapshot =
+                ScheduleSnapshot::new(format!("{label:?}"), schedule.graph(), components);
+            match self
+                .schedules
+                .iter_mut()
+                .find(|schedule| schedule.name == snapshot.name)
+            {
+                Some(schedule) => *schedule = snapshot,
+                None => self.schedules.push(snapshot),
+            }
+        }
+        self.schedules.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+/// Copies the schedules running while the editor ui is shown, which are out of [`Schedules`]
+/// at the time.
+fn snapshot_running_schedules(world: &mut World) {
+    world.resource_scope::<SchedulesTab, _>(|world, mut tab| {
+        tab.refresh(world);
+        tab.pending = false;
+    });
+}
+
+/// Shows the systems and sets of every schedule in their hierarchy or in the order they run, and
+/// the orderings of the selected one.
+pub fn schedules_ui(world: &mut World, ui: &mut egui::Ui) {
+    world.resource_scope::<SchedulesTab, _>(|world, mut tab| {
+        let mut refresh = tab.schedules.is_empty();
+        ui.horizontal(|ui| {
+            refresh |= ui
+                .button("⟲ Refresh")
+                .on_hover_text("Schedules are copied when the tab opens, refresh to see changes")
+                .clicked();
+            ui.selectable_value(&mut tab.view, SchedulesView::Hierarchy, "Hierarchy");
+            ui.selectable_value(&mut tab.view, SchedulesView::Order, "Order");
+            ui.separator();
+            ui.label("🔍");
+            ui.text_edit_singleline(&mut tab.filter);
+        });
+        if refresh {
+            tab.refresh(world);
+            tab.pending = true;
+        }
+        ui.separator();
+
+        let tab = &mut *tab;
+        let filter = tab.filter.to_lowercase();
+        let selected = &mut tab.selected;
+        if let Some((name, node)) = selected.clone() {
+            if let Some(schedule) = tab.schedules.iter().find(|s| s.name == name) {
+                egui::SidePanel::right("schedule_node")
+                    .resizable(true)
+                    .default_width(280.0)
+                    .show_inside(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_source("schedule_node_details")
+                            .show(ui, |ui| node_details(ui, schedule, node, selected));
+                    });
+            }
+        }
+
+        egui::ScrollArea::vertical()
+            .id_source("schedules")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                for schedule in &tab.schedules {
+                    let systems = schedule
+                        .nodes
+                        .iter()
+                        .filter(|node| node.kind == NodeKind::System)
+                        .count();
+                    let header = format!("{} ({systems} systems)", schedule.name);
+                    let mut header = egui::CollapsingHeader::new(header).id_source(&schedule.name);
+                    if !filter.is_empty() {
+                        header = header.open(Some(true));
+                    }
+                    header.show(ui, |ui| match tab.view {
+                        SchedulesView::Hierarchy => {
+                            for &root in &schedule.roots {
+                                node_tree(ui, schedule, root, &filter, selected);
+                            }
+                        }
+                        SchedulesView::Order => order_list(ui, schedule, &filter, selected),
+                    });
+                }
+            });
+    });
+}
+
+fn node_label(
+    ui: &mut egui::Ui,
+    schedule: &ScheduleSnapshot,
+    node: usize,
+    selected: &mut Option<(String, usize)>,
+) {
+    let entry = &schedule.nodes[node];
+    let text = match (entry.kind, entry.order) {
+        (NodeKind::Anonymous, _) => "(anonymous set)".to_string(),
+        (_, Some(order)) => format!("#{order} {}", entry.name),
+        _ => entry.name.clone(),
+    };
+    let is_selected = selected
+        .as_ref()
+        .is_some_and(|(name, selected)| *name == schedule.name && *selected == node);
+    let response = ui.selectable_label(is_selected, text);
+    if response.clicked() {
+        *selected = Some((schedule.name.clone(), node));
+    }
+    if !entry.conditions.is_empty() {
+        ui.weak(format!("if {}", entry.conditions.join(", ")));
+    }
+}
+
+fn node_tree(
+    ui: &mut egui::Ui,
+    schedule: &ScheduleSnapshot,
+    node: usize,
+    filter: &str,
+    selected: &mut Option<(String, usize)>,
+) {
+    if !schedule.matches(node, filter) {
+        return;
+    }
+    let entry = &schedule.nodes[node];
+    if entry.kind == NodeKind::System {
+        ui.horizontal(|ui| node_label(ui, schedule, node, selected));
+        return;
+    }
+    let id = ui.make_persistent_id(node);
+    let mut state =
+        egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, false);
+    if !filter.is_empty() {
+        state.set_open(true);
+    }
+    state
+        .show_header(ui, |ui| node_label(ui, schedule, node, selected))
+        .body(|ui| {
+            for &child in &entry.children {
+                node_tree(ui, schedule, child, filter, selected);
+            }
+        });
+}
+
+fn order_list(
+    ui: &mut egui::Ui,
+    schedule: &ScheduleSnapshot,
+    filter: &str,
+    selected: &mut Option<(String, usize)>,
+) {
+    let mut systems: Vec<_> = (0..schedule.nodes.len())
+        .filter(|&node| schedule.nodes[node].kind == NodeKind::System)
+        .filter(|&node| schedule.matches(node, filter))
+        .collect();
+    if systems
+        .iter()
+        .all(|&node| schedule.nodes[node].order.is_none())
+    {
+        ui.label("The schedule hasn't run yet, so it has no order");
+    }
+    sort_nodes(&mut systems, &schedule.nodes);
+    for node in systems {
+        ui.horizontal(|ui| node_label(ui, schedule, node, selected));
+    }
+}
+
+fn node_links(
+    ui: &mut egui::Ui,
+    title: &str,
+    schedule: &ScheduleSnapshot,
+    nodes: &[usize],
+    selected: &mut Option<(String, usize)>,
+) {
+    if nodes.is_empty() {
+        return;
+    }
+    ui.strong(title);
+    for &node in nodes {
+        ui.horizontal(|ui| node_label(ui, schedule, node, selected));
+    }
+    ui.add_space(4.0);
+}
+
+/// The sets, run conditions and orderings of the selected node.
+fn node_details(
+    ui: &mut egui::Ui,
+    schedule: &ScheduleSnapshot,
+    node: usize,
+    selected: &mut Option<(String, usize)>,
+) {
+    let Some(entry) = schedule.nodes.get(node) else {
+        return;
+    };
+    ui.heading(&entry.name);
+    let kind = match entry.kind {
+        NodeKind::System => "System",
+        NodeKind::Set => "System set",
+        NodeKind::Anonymous => "Anonymous system set",
+        NodeKind::SystemType => "System type set",
+    };
+    ui.label(format!("{kind} in {}", schedule.name));
+    if let Some(order) = entry.order {
+        ui.label(format!("Runs #{order}"));
+    }
+    ui.separator();
+    if !entry.conditions.is_empty() {
+        ui.strong("Run conditions");
+        for condition in &entry.conditions {
+            ui.monospace(condition);
+        }
+        ui.add_space(4.0);
+    }
+    node_links(ui, "In sets", schedule, &entry.parents, selected);
+    node_links(ui, "Contains", schedule, &entry.children, selected);
+    node_links(ui, "Runs after", schedule, &entry.after, selected);
+    node_links(ui, "Runs before", schedule, &entry.before, selected);
+
+    let ambiguities: Vec<_> = schedule
+        .ambiguities
+        .iter()
+        .filter_map(|(a, b, conflicts)| match (*a == node, *b == node) {
+            (true, _) => Some((*b, conflicts)),
+            (_, true) => Some((*a, conflicts)),
+            _ => None,
+        })
+        .collect();
+    if ambiguities.is_empty() {
+        return;
+    }
+    ui.strong("Ambiguous with").on_hover_text(
+        "Systems without an order which access the same data, so they can run in any order",
+    );
+    for (other, conflicts) in ambiguities {
+        ui.horizontal(|ui| node_label(ui, schedule, other, selected));
+        let conflicts = match conflicts.is_empty() {
+            true => "World".to_string(),
+            false => conflicts.join(", "),
+        };
+        ui.weak(format!("on {conflicts}"));
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow};
+
+use crate::{input::EditorAction, MainCamera};
+
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+pub struct EditorScreenshotPlugin;
+impl Plugin for EditorScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, screenshot_hotkey);
+    }
+}
+
+fn screenshot_hotkey(world: &mut World) {
+    if world
+        .resource::<Input<EditorAction>>()
+        .just_pressed(EditorAction::Screenshot)
+    {
+        take_screenshot(world);
+    }
+}
+
+/// Saves what the [`MainCamera`] renders this frame, without the editor panels around it, to a
+/// PNG in [`SCREENSHOT_DIR`].
+pub fn take_screenshot(world: &mut World) {
+    let Ok(window) = world
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .get_single(world)
+    else {
+        return;
+    };
+    let viewport = world
+        .query_filtered::<&Camera, With<MainCamera>>()
+        .get_single(world)
+        .ok()
+        .and_then(|camera| camera.viewport.clone());
+    let Some(mut screenshots) = world.get_resource_mut::<ScreenshotManager>() else {
+        warn!("Screenshots need the render plugin");
+        return;
+    };
+
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let path = PathBuf::from(SCREENSHOT_DIR).join(format!("screenshot_{seconds}.png"));
+    let requested = screenshots.take_screenshot(window, move |image| {
+        let image = match image.try_into_dynamic() {
+            Ok(image) => image,
+            Err(e) => {
+                error!("Cannot convert screenshot: {e}");
+                return;
+            }
+        };
+        let image = match viewport {
+            Some(viewport) => image.crop_imm(
+                viewport.physical_position.x,
+                viewport.physical_position.y,
+                viewport.physical_size.x,
+                viewport.physical_size.y,
+            ),
+            None => image,
+        };
+        // the alpha channel holds brightness with HDR, not transparency
+        let result = fs::create_dir_all(SCREENSHOT_DIR)
+            .map_err(|e| e.to_string())
+            .and_then(|_| image.to_rgb8().save(&path).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => info!("Saved screenshot to {}", path.display()),
+            Err(e) => error!("Cannot save screenshot to {}: {e}", path.display()),
+        }
+    });
+    if requested.is_err() {
+        warn!("A screenshot was already requested this frame");
+    }
+}
@@ -0,0 +1,454 @@
+//! A scripting console for repetitive editor tasks, enabled with the `scripting` feature.
+//!
+//! Lines typed below the console log each run a command, whose output is logged:
+//!
+//! ```text
+//! spawn cube "Crate"
+//! query Transform, With<Enemy>
+//! set [Transform, With<Enemy>] Transform.scale = (x: 2.0, y: 2.0, z: 2.0)
+//! get selected Transform.translation.y
+//! source scripts/cleanup.txt
+//! ```
+//!
+//! Entities are given as `selected`, as logged like `12v0`, by their `"name"`, or as the results
+//! of a query in brackets, written like in the query tab. Values are written in RON, like in
+//! scene files.
+
+use std::fs;
+
+use bevy::{
+    prelude::*,
+    reflect::{
+        serde::{TypedReflectDeserializer, TypedReflectSerializer},
+        GetPath,
+    },
+};
+use bevy_reflect::TypeRegistry;
+use serde::de::DeserializeSeed;
+
+use crate::{
+    commands::EditorCommands,
+    hierarchy::{self, EntityPreset},
+    history,
+    inspector::entity_name,
+    query::{self, WorldQuery},
+    scene, UiState,
+};
+
+const HELP: &str = "\
+spawn [empty|camera|pointlight|cube] [\"name\"]: spawns an entity and selects it
+query <query>: lists the entities matching a query like `Transform, With<Enemy>`
+select <entities>: selects the entities
+get <entities> <Component>[.field]: logs the value of a component or of one of its fields
+set <entities> <Component>[.field] = <value>: sets it to a RON value, adding missing components
+delete <entities>: deletes the entities and their children
+run <command>: runs a command of the command palette
+source <file>: runs the lines of a file
+entities are `selected`, `12v0`, `\"name\"` or `[query]`";
+
+pub struct EditorScriptPlugin;
+impl Plugin for EditorScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScriptConsole>()
+            .add_systems(Update, run_queued_lines);
+    }
+}
+
+/// The line typed in the console, and the lines queued to run.
+///
+/// Lines never run while the editor UI is drawn, they're queued and run by an exclusive system
+/// afterwards, like editor commands.
+#[derive(Resource, Default)]
+pub struct ScriptConsole {
+    pub input: String,
+    /// The lines typed so far, the most recent last, recalled with the arrow keys.
+    history: Vec<String>,
+    /// Where in `history` the line being recalled is.
+    recalled: Option<usize>,
+    queued: Vec<String>,
+}
+
+impl ScriptConsole {
+    pub fn run(&mut self, line: impl Into<String>) {
+        self.queued.push(line.into());
+    }
+}
+
+fn run_queued_lines(world: &mut World) {
+    let lines = std::mem::take(&mut world.resource_mut::<ScriptConsole>().queued);
+    for line in lines {
+        info!("> {line}");
+        if let Err(e) = run_line(world, &line) {
+            error!("{e}");
+        }
+    }
+}
+
+/// Runs the command on `line`, ignoring blank lines and `#` comments.
+pub fn run_line(world: &mut World, line: &str) -> Result<(), String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(());
+    }
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    match command {
+        "help" => {
+            info!("{HELP}");
+            Ok(())
+        }
+        "spawn" => spawn(world, rest),
+        "query" => {
+            let entities = query_entities(world, rest)?;
+            log_entities(world, &entities);
+            Ok(())
+        }
+        "select" => {
+            let entities = entities(world, rest)?.0;
+            world
+                .resource_mut::<UiState>()
+                .select_entities(entities, false);
+            Ok(())
+        }
+        "get" => get(world, rest),
+        "set" => set(world, rest),
+        "delete" => {
+            let entities = entities(world, rest)?.0;
+            hierarchy::despawn_entities(world, &entities);
+            let mut ui_state = world.resource_mut::<UiState>();
+            for entity in entities {
+                ui_state.selected_entities.remove(entity);
+            }
+            Ok(())
+        }
+        "run" => match world.resource_mut::<EditorCommands>().queue(rest) {
+            true => Ok(()),
+            false => Err(format!("No command is named `{rest}`")),
+        },
+        "source" => {
+            let script =
+                fs::read_to_string(rest).map_err(|e| format!("Failed to read {rest}: {e}"))?;
+            for (number, line) in script.lines().enumerate() {
+                run_line(world, line).map_err(|e| format!("{rest}:{}: {e}", number + 1))?;
+            }
+            Ok(())
+        }
+        _ => Err(format!("Unknown command `{command}`, see `help`")),
+    }
+}
+
+fn log_entities(world: &World, entities: &[Entity]) {
+    for &entity in entities {
+        info!("{} ({entity:?})", entity_name(world, entity));
+    }
+    info!("{} entities", entities.len());
+}
+
+/// The entities of the scene matching `query`.
+fn query_entities(world: &mut World, query: &str) -> Result<Vec<Entity>, String> {
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let matching = WorldQuery::parse(query, world, &type_registry.read())?.entities(world);
+    Ok(scene::scene_entities(world)
+        .into_iter()
+        .filter(|entity| matching.contains(entity))
+        .collect())
+}
+
+/// Entities are logged like `12v0`.
+fn parse_entity(word: &str) -> Option<Entity> {
+    let (index, generation) = word.split_once('v')?;
+    let bits = (generation.parse::<u64>().ok()? << 32) | index.parse::<u64>().ok()?;
+    Some(Entity::from_bits(bits))
+}
+
+/// The entities `rest` starts with, and what follows them.
+fn entities<'a>(world: &mut World, rest: &'a str) -> Result<(Vec<Entity>, &'a str), String> {
+    let rest = rest.trim_start();
+    if let Some(query) = rest.strip_prefix('[') {
+        let end = query.find(']').ok_or("Missing `]` after the query")?;
+        return Ok((query_entities(world, &query[..end])?, &query[end + 1..]));
+    }
+    if let Some(name) = rest.strip_prefix('"') {
+        let end = name.find('"').ok_or("Missing `\"` after the name")?;
+        let entities: Vec<_> = scene::scene_entities(world)
+            .into_iter()
+            .filter(|&entity| {
+                world
+                    .get::<Name>(entity)
+                    .is_some_and(|n| n.as_str() == &name[..end])
+            })
+            .collect();
+        if entities.is_empty() {
+            return Err(format!("No entity is named \"{}\"", &name[..end]));
+        }
+        return Ok((entities, &name[end + 1..]));
+    }
+    let (word, after) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if word == "selected" {
+        let selected = world
+            .resource::<UiState>()
+            .selected_entities
+            .as_slice()
+            .to_vec();
+        return Ok((selected, after));
+    }
+    match parse_entity(word).filter(|&entity| world.get_entity(entity).is_some()) {
+        Some(entity) => Ok((vec![entity], after)),
+        None if word.is_empty() => Err("Missing the entities, see `help`".to_owned()),
+        None => Err(format!("No entity {word}")),
+    }
+}
+
+fn spawn(world: &mut World, rest: &str) -> Result<(), String> {
+    let (preset, name) = match rest.strip_prefix('"') {
+        Some(_) => (EntityPreset::Empty, rest),
+        None => {
+            let (word, name) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let preset = match word {
+                "" => EntityPreset::Empty,
+                word => EntityPreset::ALL
+                    .into_iter()
+                    .find(|preset| preset.name().replace(' ', "").eq_ignore_ascii_case(word))
+                    .ok_or_else(|| format!("No preset is named `{word}`"))?,
+            };
+            (preset, name.trim())
+        }
+    };
+    let entity = preset.spawn(world, None);
+    if let Some(name) = name
+        .strip_prefix('"')
+        .and_then(|name| name.strip_suffix('"'))
+    {
+        hierarchy::rename_entity(world, entity, name.to_owned());
+    }
+    info!("Spawned {} ({entity:?})", entity_name(world, entity));
+    world
+        .resource_mut::<UiState>()
+        .select_entities(vec![entity], false);
+    Ok(())
+}
+
+/// The registration of the component in `path`, and the path of the field in it.
+fn component_path<'a, 'r>(
+    type_registry: &'r TypeRegistry,
+    path: &'a str,
+) -> Result<(&'r ReflectComponent, &'a str, &'a str), String> {
+    let (name, field) = path.trim().split_once('.').unwrap_or((path.trim(), ""));
+    let registration = query::resolve(type_registry, name)?;
+    let reflect_component = registration
+        .data::<ReflectComponent>()
+        .ok_or_else(|| format!("`{name}` isn't a component"))?;
+    Ok((reflect_component, name, field))
+}
+
+fn field<'a>(value: &'a dyn Reflect, path: &str) -> Result<&'a dyn Reflect, String> {
+    match path {
+        "" => Ok(value),
+        path => value
+            .reflect_path(path)
+            .map_err(|e| format!("No field `{path}`: {e}")),
+    }
+}
+
+fn get(world: &mut World, rest: &str) -> Result<(), String> {
+    let (entities, path) = entities(world, rest)?;
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let (reflect_component, name, field_path) = component_path(&type_registry, path)?;
+    for entity in entities {
+        let Some(component) = reflect_component.reflect(world.entity(entity)) else {
+            info!("{} ({entity:?}) has no {name}", entity_name(world, entity));
+            continue;
+        };
+        let value = field(component, field_path)?;
+        let ron = ron::to_string(&TypedReflectSerializer::new(value, &type_registry))
+            .unwrap_or_else(|_| format!("{value:?}"));
+        info!(
+            "{} ({entity:?}) {}: {ron}",
+            entity_name(world, entity),
+            path.trim()
+        );
+    }
+    Ok(())
+}
+
+fn set(world: &mut World, rest: &str) -> Result<(), String> {
+    let (entities, rest) = entities(world, rest)?;
+    let (path, value) = rest
+        .split_once('=')
+        .ok_or("Missing `= value`, see `help`")?;
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let (reflect_component, name, field_path) = component_path(&type_registry, path)?;
+    let registration = query::resolve(&type_registry, name)?;
+
+    for entity in entities {
+        // the type of a field is only known from a value
+        let type_name = match reflect_component.reflect(world.entity(entity)) {
+            Some(component) => field(component, field_path)?.type_name(),
+            None if field_path.is_empty() => registration.type_name(),
+            None => {
+                warn!("{} ({entity:?}) has no {name}", entity_name(world, entity));
+                continue;
+            }
+        };
+        let field_registration = type_registry
+            .get_with_name(type_name)
+            .ok_or_else(|| format!("`{type_name}` isn't registered"))?;
+        let mut deserializer = ron::de::Deserializer::from_str(value.trim())
+            .map_err(|e| format!("Invalid value: {e}"))?;
+        let value = TypedReflectDeserializer::new(field_registration, &type_registry)
+            .deserialize(&mut deserializer)
+            .map_err(|e| format!("Invalid value: {e}"))?;
+
+        let before = history::snapshot_components(world, entity);
+        let mut entity_mut = world.entity_mut(entity);
+        match reflect_component.reflect_mut(&mut entity_mut) {
+            Some(mut component) => {
+                let component: &mut dyn Reflect = &mut *component;
+                match field_path {
+                    "" => component.apply(&*value),
+                    path => component
+                        .reflect_path_mut(path)
+                        .map_err(|e| format!("No field `{path}`: {e}"))?
+                        .apply(&*value),
+                }
+            }
+            None => reflect_component.insert(&mut entity_mut, &*value),
+        }
+        history::record_component_changes(world, entity, before);
+    }
+    Ok(())
+}
+
+/// The line to type commands in, run when pressing enter.
+pub fn script_input_ui(world: &mut World, ui: &mut egui::Ui) {
+    let mut console = world.resource_mut::<ScriptConsole>();
+    let response = ui.add(
+        egui::TextEdit::singleline(&mut console.input)
+            .hint_text("Command, `help` to list them")
+            .code_editor()
+            .desired_width(f32::INFINITY),
+    );
+    if response.has_focus() && !console.history.is_empty() {
+        let (up, down) = ui.input(|input| {
+            (
+                input.key_pressed(egui::Key::ArrowUp),
+                input.key_pressed(egui::Key::ArrowDown),
+            )
+        });
+        let last = console.history.len() - 1;
+        let recalled = match (up, down, console.recalled) {
+            (true, _, None) => Some(last),
+            (true, _, Some(index)) => Some(index.saturating_sub(1)),
+            (_, true, Some(index)) if index < last => Some(index + 1),
+            (_, true, Some(_)) => None,
+            _ => console.recalled,
+        };
+        if recalled != console.recalled {
+            console.recalled = recalled;
+            console.input = recalled
+                .map(|index| console.history[index].clone())
+                .unwrap_or_default();
+        }
+    }
+    if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+        let line = std::mem::take(&mut console.input);
+        if !line.trim().is_empty() {
+            console.history.push(line.clone());
+            console.run(line);
+        }
+        console.recalled = None;
+        response.request_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_id::EditorIds, history::EditorHistory};
+
+    fn world() -> World {
+        let mut world = World::new();
+        let type_registry = AppTypeRegistry::default();
+        {
+            let mut type_registry = type_registry.write();
+            type_registry.register::<Name>();
+            type_registry.register::<Transform>();
+            type_registry.register::<Vec3>();
+            type_registry.register::<Quat>();
+            type_registry.register::<f32>();
+        }
+        world.insert_resource(type_registry);
+        world.init_resource::<EditorHistory>();
+        world.init_resource::<EditorIds>();
+        world.insert_resource(UiState::new());
+        world
+    }
+
+    #[test]
+    fn parses_entities_as_logged() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        assert_eq!(parse_entity(&format!("{entity:?}")), Some(entity));
+        assert_eq!(parse_entity("12v3"), Some(Entity::from_bits(3 << 32 | 12)));
+        assert_eq!(parse_entity("12"), None);
+        assert_eq!(parse_entity("av0"), None);
+    }
+
+    #[test]
+    fn parses_the_entities_a_line_starts_with() {
+        let mut world = world();
+        let named = world.spawn(Name::new("Crate A")).id();
+        let transform = world.spawn(Transform::default()).id();
+        world
+            .resource_mut::<UiState>()
+            .select_entities(vec![transform], false);
+
+        let (found, rest) = entities(&mut world, "\"Crate A\" Name").unwrap();
+        assert_eq!((found, rest), (vec![named], " Name"));
+        let (found, rest) = entities(&mut world, "[Transform] Transform.scale").unwrap();
+        assert_eq!((found, rest), (vec![transform], " Transform.scale"));
+        let (found, rest) = entities(&mut world, "selected Name").unwrap();
+        assert_eq!((found, rest), (vec![transform], "Name"));
+        let line = format!("{named:?} Name");
+        let (found, rest) = entities(&mut world, &line).unwrap();
+        assert_eq!((found, rest), (vec![named], "Name"));
+
+        assert!(entities(&mut world, "[Transform Name").is_err());
+        assert!(entities(&mut world, "\"Crate B\" Name").is_err());
+        assert!(entities(&mut world, "99v0 Name").is_err());
+        assert!(entities(&mut world, "").is_err());
+    }
+
+    #[test]
+    fn sets_components_and_their_fields() {
+        let mut world = world();
+        let entity = world.spawn((Name::new("Crate"), Transform::default())).id();
+
+        run_line(&mut world, "set \"Crate\" Transform.translation.y = 2.0").unwrap();
+        assert_eq!(world.get::<Transform>(entity).unwrap().translation.y, 2.0);
+        run_line(
+            &mut world,
+            "set \"Crate\" Transform.scale = (x: 3.0, y: 3.0, z: 3.0)",
+        )
+        .unwrap();
+        assert_eq!(
+            world.get::<Transform>(entity).unwrap().scale,
+            Vec3::splat(3.0)
+        );
+
+        assert!(run_line(&mut world, "set \"Crate\" Transform.translation.y 2.0").is_err());
+        assert!(run_line(&mut world, "set \"Crate\" Transform.nothing = 2.0").is_err());
+        assert!(run_line(&mut world, "set \"Crate\" Transform.translation.y = two").is_err());
+        assert!(run_line(&mut world, "set \"Crate\" Nothing = 2.0").is_err());
+    }
+
+    #[test]
+    fn ignores_blank_lines_and_comments() {
+        let mut world = world();
+        assert!(run_line(&mut world, "   ").is_ok());
+        assert!(run_line(&mut world, "# spawn cube").is_ok());
+        assert!(run_line(&mut world, "spawnn cube").is_err());
+    }
+}
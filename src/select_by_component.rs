@@ -0,0 +1,76 @@
+use bevy::{ecs::component::ComponentId, prelude::*, utils::HashMap};
+
+use crate::{commands::AddEditorCommand, scene};
+
+pub struct EditorSelectByComponentPlugin;
+impl Plugin for EditorSelectByComponentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectByComponent>().add_editor_command(
+            "Select by Component...",
+            |world| {
+                world.resource_mut::<SelectByComponent>().open = true;
+            },
+        );
+    }
+}
+
+/// The window picking a component type to select every entity with.
+#[derive(Resource, Default)]
+pub struct SelectByComponent {
+    pub open: bool,
+    filter: String,
+}
+
+/// Lists the reflected component types of the entities of the scene with how many entities have
+/// each, returning the entities with the type clicked.
+pub fn component_list(ui: &mut egui::Ui, world: &mut World) -> Option<Vec<Entity>> {
+    let entities = scene::scene_entities(world);
+    let mut counts: HashMap<ComponentId, usize> = HashMap::default();
+    for &entity in &entities {
+        for id in world.entity(entity).archetype().components() {
+            *counts.entry(id).or_default() += 1;
+        }
+    }
+
+    let mut state = world.resource_mut::<SelectByComponent>();
+    ui.add(
+        egui::TextEdit::singleline(&mut state.filter)
+            .hint_text("Component")
+            .desired_width(200.0),
+    )
+    .request_focus();
+    let filter = state.filter.to_lowercase();
+
+    let type_registry = world.resource::<AppTypeRegistry>().read();
+    let mut components: Vec<_> = type_registry
+        .iter()
+        .filter(|registration| registration.data::<ReflectComponent>().is_some())
+        .filter(|registration| registration.short_name().to_lowercase().contains(&filter))
+        .filter_map(|registration| {
+            let id = world.components().get_id(registration.type_id())?;
+            Some((registration.short_name(), id, *counts.get(&id)?))
+        })
+        .collect();
+    components.sort_by_key(|(name, ..)| *name);
+
+    let mut clicked = None;
+    egui::ScrollArea::vertical()
+        .max_height(300.0)
+        .show(ui, |ui| {
+            if components.is_empty() {
+                ui.weak("No entity has a matching component");
+            }
+            for (name, id, count) in components {
+                if ui.button(format!("{name} ({count})")).clicked() {
+                    clicked = Some(id);
+                }
+            }
+        });
+    let id = clicked?;
+    Some(
+        entities
+            .into_iter()
+            .filter(|&entity| world.entity(entity).contains_id(id))
+            .collect(),
+    )
+}
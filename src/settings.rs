@@ -0,0 +1,320 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::EguiContext;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    autosave::AutosaveSettings, camera::CameraSettings, grid::GridSettings, input::EditorKeybinds,
+    project::Project, recovery::RecoverySettings,
+};
+
+pub const SETTINGS_PATH: &str = "editor_settings.toml";
+/// How long the settings have to stay unchanged before they are saved, so dragging a value
+/// doesn't write the file every frame.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+pub struct EditorSettingsPlugin;
+impl Plugin for EditorSettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<EditorSettings>()
+            .register_type::<SnapSettings>()
+            .register_type::<EditorTheme>();
+
+        let file = read_settings(&settings_path(app.world.get_resource()));
+        app.insert_resource(file.settings)
+            .insert_resource(file.keybinds)
+            .add_systems(Update, apply_theme)
+            .add_systems(Last, save_settings_on_change);
+    }
+}
+
+/// Where the settings are saved, in the project if one is open.
+pub fn settings_path(project: Option<&Project>) -> PathBuf {
+    project.map_or_else(|| PathBuf::from(SETTINGS_PATH), Project::settings_path)
+}
+
+fn read_settings(path: &Path) -> SettingsFile {
+    if !path.exists() {
+        return SettingsFile::default();
+    }
+    load_settings(path).unwrap_or_else(|e| {
+        warn!(
+            "Failed to load editor settings from {}: {e}",
+            path.display()
+        );
+        SettingsFile::default()
+    })
+}
+
+/// Loads the settings and keybinds again, from the file of the project opened since.
+pub fn reload_settings(world: &mut World) {
+    let file = read_settings(&settings_path(world.get_resource()));
+    world.insert_resource(file.settings);
+    world.insert_resource(file.keybinds);
+}
+
+#[derive(Debug, Clone, Resource, Reflect, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct EditorSettings {
+    pub theme: EditorTheme,
+    pub camera: CameraSettings,
+    pub snapping: SnapSettings,
+    /// Added to the local translation of duplicated entities, zero to duplicate in place.
+    pub duplicate_offset: Vec3,
+    /// Whether the scene is put back as it was before playing when returning to Edit mode.
+    pub restore_after_play: bool,
+    pub autosave: AutosaveSettings,
+    pub recovery: RecoverySettings,
+    /// Whether the bounds of selected entities are drawn in the viewport.
+    pub show_bounds: bool,
+    /// Color of the outline and the bounds of selected entities in the viewport.
+    pub selection_outline: Color,
+    /// Color of the outline of the entity under the pointer.
+    pub hover_outline: Color,
+    pub grid: GridSettings,
+    /// Whether FPS, entity counts and the camera position are shown on top of the game views.
+    pub stats_overlay: bool,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            theme: EditorTheme::default(),
+            camera: CameraSettings::default(),
+            snapping: SnapSettings::default(),
+            duplicate_offset: Vec3::new(0.5, 0.0, 0.0),
+            restore_after_play: true,
+            autosave: AutosaveSettings::default(),
+            recovery: RecoverySettings::default(),
+            show_bounds: true,
+            selection_outline: Color::rgb(1.0, 0.6, 0.1),
+            hover_outline: Color::rgba(1.0, 1.0, 1.0, 0.6),
+            grid: GridSettings::default(),
+            stats_overlay: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum EditorTheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// Increments used by the gizmo while Ctrl is held.
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapSettings {
+    pub translate: f32,
+    pub rotate_degrees: f32,
+    pub scale: f32,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            translate: 0.5,
+            rotate_degrees: 15.0,
+            scale: 0.1,
+        }
+    }
+}
+
+fn apply_theme(settings: Res<EditorSettings>, mut contexts: Query<&mut EguiContext>) {
+    for mut ctx in &mut contexts {
+        if settings.is_changed() || ctx.is_added() {
+            ctx.get_mut().set_visuals(match settings.theme {
+                EditorTheme::Dark => egui::Visuals::dark(),
+                EditorTheme::Light => egui::Visuals::light(),
+            });
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsFileError {
+    Io(io::Error),
+    Toml(toml_edit::TomlError),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for SettingsFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsFileError::Io(e) => write!(f, "io error: {e}"),
+            SettingsFileError::Toml(e) => write!(f, "toml error: {e}"),
+            SettingsFileError::Json(e) => write!(f, "invalid settings: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsFileError {}
+
+impl From<io::Error> for SettingsFileError {
+    fn from(e: io::Error) -> Self {
+        SettingsFileError::Io(e)
+    }
+}
+
+impl From<toml_edit::TomlError> for SettingsFileError {
+    fn from(e: toml_edit::TomlError) -> Self {
+        SettingsFileError::Toml(e)
+    }
+}
+
+impl From<serde_json::Error> for SettingsFileError {
+    fn from(e: serde_json::Error) -> Self {
+        SettingsFileError::Json(e)
+    }
+}
+
+/// What is stored in [`SETTINGS_PATH`]. Missing values are left to their defaults, so the file
+/// keeps loading as settings are added.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SettingsFile {
+    settings: EditorSettings,
+    keybinds: EditorKeybinds,
+}
+
+// toml_edit is used without its serde support, so the settings go through `serde_json::Value`
+
+fn toml_number(number: &serde_json::Number) -> toml_edit::Value {
+    if let Some(int) = number.as_i64() {
+        return int.into();
+    }
+    let float = number.as_f64().unwrap_or_default();
+    // f32 fields are widened to f64, write them as short as they were
+    match float == float as f32 as f64 {
+        true => (float as f32)
+            .to_string()
+            .parse::<f64>()
+            .unwrap_or(float)
+            .into(),
+        false => float.into(),
+    }
+}
+
+fn toml_value(json: serde_json::Value) -> Option<toml_edit::Value> {
+    Some(match json {
+        serde_json::Value::Null => return None,
+        serde_json::Value::Bool(bool) => bool.into(),
+        serde_json::Value::Number(number) => toml_number(&number),
+        serde_json::Value::String(string) => string.into(),
+        serde_json::Value::Array(array) => array
+            .into_iter()
+            .filter_map(toml_value)
+            .collect::<toml_edit::Array>()
+            .into(),
+        serde_json::Value::Object(object) => object
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, toml_value(value)?)))
+            .collect::<toml_edit::InlineTable>()
+            .into(),
+    })
+}
+
+/// Objects holding other objects become tables, the others are written inline. Objects of a
+/// single field, like enum variants, stay inline.
+fn toml_item(json: serde_json::Value, root: bool) -> Option<toml_edit::Item> {
+    match json {
+        serde_json::Value::Object(object)
+            if root || (object.len() > 1 && object.values().any(|value| value.is_object())) =>
+        {
+            let mut table = toml_edit::Table::new();
+            for (key, value) in object {
+                if let Some(item) = toml_item(value, false) {
+                    table.insert(&key, item);
+                }
+            }
+            Some(toml_edit::Item::Table(table))
+        }
+        json => toml_value(json).map(toml_edit::Item::Value),
+    }
+}
+
+fn json_value(value: &toml_edit::Value) -> serde_json::Value {
+    match value {
+        toml_edit::Value::String(string) => string.value().clone().into(),
+        toml_edit::Value::Integer(int) => (*int.value()).into(),
+        toml_edit::Value::Float(float) => (*float.value()).into(),
+        toml_edit::Value::Boolean(bool) => (*bool.value()).into(),
+        toml_edit::Value::Datetime(datetime) => datetime.value().to_string().into(),
+        toml_edit::Value::Array(array) => array.iter().map(json_value).collect(),
+        toml_edit::Value::InlineTable(table) => table
+            .iter()
+            .map(|(key, value)| (key.to_owned(), json_value(value)))
+            .collect(),
+    }
+}
+
+fn json_table(table: &toml_edit::Table) -> serde_json::Value {
+    table
+        .iter()
+        .map(|(key, item)| (key.to_owned(), json_item(item)))
+        .collect()
+}
+
+fn json_item(item: &toml_edit::Item) -> serde_json::Value {
+    match item {
+        toml_edit::Item::None => serde_json::Value::Null,
+        toml_edit::Item::Value(value) => json_value(value),
+        toml_edit::Item::Table(table) => json_table(table),
+        toml_edit::Item::ArrayOfTables(tables) => tables.iter().map(json_table).collect(),
+    }
+}
+
+fn save_settings(
+    settings: &EditorSettings,
+    keybinds: &EditorKeybinds,
+    path: impl AsRef<Path>,
+) -> Result<(), SettingsFileError> {
+    let file = SettingsFile {
+        settings: settings.clone(),
+        keybinds: keybinds.clone(),
+    };
+    let mut document = toml_edit::Document::new();
+    if let Some(toml_edit::Item::Table(table)) = toml_item(serde_json::to_value(file)?, true) {
+        *document.as_table_mut() = table;
+    }
+    fs::write(path, document.to_string())?;
+    Ok(())
+}
+
+fn load_settings(path: impl AsRef<Path>) -> Result<SettingsFile, SettingsFileError> {
+    let toml = fs::read_to_string(path)?;
+    let document: toml_edit::Document = toml.parse()?;
+    let json = json_item(document.as_item());
+    Ok(serde_json::from_value(json)?)
+}
+
+fn save_settings_on_change(
+    settings: Res<EditorSettings>,
+    keybinds: Res<EditorKeybinds>,
+    project: Option<Res<Project>>,
+    time: Res<Time>,
+    mut changed_at: Local<Option<Duration>>,
+) {
+    let now = time.raw_elapsed();
+    if (settings.is_changed() && !settings.is_added())
+        || (keybinds.is_changed() && !keybinds.is_added())
+    {
+        *changed_at = Some(now);
+    }
+    if changed_at.is_none_or(|changed_at| now - changed_at < SAVE_DELAY) {
+        return;
+    }
+    *changed_at = None;
+    let path = settings_path(project.as_deref());
+    match save_settings(&settings, &keybinds, &path) {
+        Ok(()) => info!("Saved editor settings to {}", path.display()),
+        Err(e) => error!("Failed to save editor settings to {}: {e}", path.display()),
+    }
+}
@@ -0,0 +1,192 @@
+use std::path::Path;
+
+use bevy::{
+    asset::HandleId,
+    prelude::*,
+    render::render_resource::{Shader, Source},
+};
+
+use crate::asset_browser::ASSET_DIR;
+
+pub struct EditorShadersPlugin;
+impl Plugin for EditorShadersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ShaderEditor>();
+    }
+}
+
+/// State of the shader tab, which edits the source of loaded shaders.
+#[derive(Resource, Default)]
+pub struct ShaderEditor {
+    /// Also list the shaders built into bevy and plugins, which have no file.
+    pub show_builtin: bool,
+    selected: Option<HandleId>,
+    /// The edited source of the selected shader.
+    source: String,
+    /// Whether `source` has edits which aren't applied to the shader yet.
+    modified: bool,
+}
+
+fn shader_text(shader: &Shader) -> Option<&str> {
+    match &shader.source {
+        Source::Wgsl(source) | Source::Glsl(source, _) => Some(source),
+        Source::SpirV(_) => None,
+    }
+}
+
+/// Replaces the source of `shader`, which recompiles the pipelines using it.
+fn apply_source(shader: &mut Shader, source: String) {
+    // imports are parsed from the source
+    let mut edited = match &shader.source {
+        Source::Wgsl(_) => Shader::from_wgsl(source, shader.path.clone()),
+        Source::Glsl(_, stage) => Shader::from_glsl(source, *stage, shader.path.clone()),
+        Source::SpirV(_) => return,
+    };
+    edited.additional_imports = shader.additional_imports.clone();
+    edited.shader_defs = shader.shader_defs.clone();
+    *shader = edited;
+}
+
+/// Applies the edited source of the selected shader, and with `save` also writes it to its file.
+fn apply(world: &mut World, save: bool) {
+    let editor = world.resource::<ShaderEditor>();
+    let Some(handle) = editor.selected else {
+        return;
+    };
+    let source = editor.source.clone();
+    if save {
+        let Some(path) = world.resource::<AssetServer>().get_handle_path(handle) else {
+            warn!("Cannot save a shader which wasn't loaded from a file");
+            return;
+        };
+        let file = Path::new(ASSET_DIR).join(path.path());
+        match std::fs::write(&file, &source) {
+            Ok(()) => info!("Saved shader to {}", file.display()),
+            Err(e) => {
+                error!("Failed to save shader {}: {e}", file.display());
+                return;
+            }
+        }
+    }
+    if let Some(shader) = world
+        .resource_mut::<Assets<Shader>>()
+        .get_mut(&Handle::weak(handle))
+    {
+        apply_source(shader, source);
+    }
+    world.resource_mut::<ShaderEditor>().modified = false;
+}
+
+/// Lists the loaded shaders, and edits the source of the selected one. Applied edits recompile
+/// the pipelines using the shader right away.
+pub fn shaders_ui(world: &mut World, ui: &mut egui::Ui) {
+    let asset_server = world.resource::<AssetServer>().clone();
+    let show_builtin = world.resource::<ShaderEditor>().show_builtin;
+    let mut shaders: Vec<_> = world
+        .resource::<Assets<Shader>>()
+        .iter()
+        .filter_map(|(id, shader)| match asset_server.get_handle_path(id) {
+            Some(path) => Some((id, path.path().display().to_string(), true)),
+            None => show_builtin.then(|| (id, shader.path.clone(), false)),
+        })
+        .collect();
+    shaders.sort_by(|a, b| (!a.2, &a.1).cmp(&(!b.2, &b.1)));
+
+    let mut selected = world.resource::<ShaderEditor>().selected;
+    egui::SidePanel::left("shader_list")
+        .resizable(true)
+        .default_width(200.0)
+        .show_inside(ui, |ui| {
+            let mut editor = world.resource_mut::<ShaderEditor>();
+            ui.checkbox(&mut editor.show_builtin, "Built-in shaders");
+            ui.separator();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (id, name, _) in &shaders {
+                    if ui
+                        .selectable_label(selected == Some(*id), name.as_str())
+                        .clicked()
+                    {
+                        selected = Some(*id);
+                    }
+                }
+            });
+        });
+
+    let mut editor = world.resource_mut::<ShaderEditor>();
+    if editor.selected != selected {
+        editor.selected = selected;
+        editor.modified = false;
+    }
+    let Some(handle) = selected else {
+        ui.label("Select a shader to edit it");
+        return;
+    };
+    // unchanged sources follow the shader, like when it's reloaded from its file
+    let Some(source) = world
+        .resource::<Assets<Shader>>()
+        .get(&Handle::weak(handle))
+        .map(|shader| shader_text(shader).map(str::to_owned))
+    else {
+        ui.label("The shader was unloaded");
+        return;
+    };
+    let Some(source) = source else {
+        ui.label("SPIR-V shaders can't be edited");
+        return;
+    };
+    let mut editor = world.resource_mut::<ShaderEditor>();
+    if !editor.modified && editor.source != source {
+        editor.source = source;
+    }
+    let has_file = asset_server.get_handle_path(handle).is_some();
+
+    let (mut apply_clicked, mut save_clicked, mut revert_clicked) = (false, false, false);
+    ui.horizontal(|ui| {
+        apply_clicked = ui
+            .add_enabled(editor.modified, egui::Button::new("Apply"))
+            .on_hover_text("Recompile the shader with the edited source (Ctrl+Enter)")
+            .clicked();
+        save_clicked = ui
+            .add_enabled(has_file, egui::Button::new("Save"))
+            .on_hover_text("Write the source to the shader file and apply it")
+            .clicked();
+        revert_clicked = ui
+            .add_enabled(editor.modified, egui::Button::new("Revert"))
+            .clicked();
+        if has_file && ui.button("Reload from disk").clicked() {
+            if let Some(path) = asset_server.get_handle_path(handle) {
+                asset_server.reload_asset(path);
+                editor.modified = false;
+            }
+        }
+        if editor.modified {
+            ui.label("Modified");
+        }
+    });
+    ui.separator();
+
+    let response = egui::ScrollArea::both()
+        .show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut editor.source)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(30),
+            )
+        })
+        .inner;
+    if response.changed() {
+        editor.modified = true;
+    }
+    if revert_clicked {
+        editor.modified = false;
+    }
+    let apply_key = response.has_focus()
+        && ui.input(|input| input.modifiers.ctrl && input.key_pressed(egui::Key::Enter));
+    let modified = editor.modified;
+    if save_clicked {
+        apply(world, true);
+    } else if (apply_clicked || apply_key) && modified {
+        apply(world, false);
+    }
+}
@@ -0,0 +1,309 @@
+use bevy::{
+    math::Ray,
+    prelude::*,
+    render::{primitives::Aabb, render_resource::PrimitiveTopology},
+};
+use bevy_inspector_egui::bevy_egui::EguiSettings;
+
+use crate::{
+    gizmo, input::EditorAction, picking, scene, views::EditorViewport, EditorOnly, EditorResource,
+    MainCamera, UiState,
+};
+
+pub struct EditorSnapPlugin;
+impl Plugin for EditorSnapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, snap_hotkeys);
+    }
+}
+
+fn snap_hotkeys(world: &mut World) {
+    if !world.resource::<EditorResource>().0 {
+        return;
+    }
+
+    let actions = world.resource::<Input<EditorAction>>();
+    if actions.just_pressed(EditorAction::DropToGroundAligned) {
+        drop_to_ground(world, true);
+    } else if actions.just_pressed(EditorAction::DropToGround) {
+        drop_to_ground(world, false);
+    }
+}
+
+/// A point where a ray hit a surface.
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceHit {
+    pub entity: Entity,
+    pub distance: f32,
+    pub point: Vec3,
+    /// Normal of the surface at `point`, facing the ray.
+    pub normal: Vec3,
+}
+
+/// Distance along `ray`, in the local space of a mesh, to its closest triangle and the normal
+/// of that triangle.
+fn ray_mesh_hit(ray: Ray, mesh: &Mesh) -> Option<(f32, Vec3)> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?;
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(indices) => indices.iter().collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    indices
+        .chunks_exact(3)
+        .filter_map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|i| positions.get(triangle[i]).copied());
+            let [a, b, c] = [Vec3::from(a?), Vec3::from(b?), Vec3::from(c?)];
+            ray_triangle_distance(ray, a, b, c).map(|distance| (distance, (b - a).cross(c - a)))
+        })
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+}
+
+/// Möller–Trumbore intersection of `ray` with the triangle `a`, `b`, `c`, from either side.
+fn ray_triangle_distance(ray: Ray, a: Vec3, b: Vec3, c: Vec3) -> Option<f32> {
+    let (edge1, edge2) = (b - a, c - a);
+    let p = ray.direction.cross(edge2);
+    let determinant = edge1.dot(p);
+    if determinant.abs() < f32::EPSILON {
+        return None;
+    }
+    let to_origin = ray.origin - a;
+    let u = to_origin.dot(p) / determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = to_origin.cross(edge1);
+    let v = ray.direction.dot(q) / determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = edge2.dot(q) / determinant;
+    (distance >= 0.0).then_some(distance)
+}
+
+/// Normal of the face of the box `aabb` closest to `point`, both in local space.
+fn aabb_face_normal(aabb: &Aabb, point: Vec3) -> Vec3 {
+    let local = (point - Vec3::from(aabb.center))
+        / Vec3::from(aabb.half_extents).max(Vec3::splat(f32::EPSILON));
+    let axis = local.abs().max_element();
+    Vec3::select(
+        local.abs().cmpeq(Vec3::splat(axis)),
+        local.signum(),
+        Vec3::ZERO,
+    )
+    .normalize_or_zero()
+}
+
+/// The closest surface of a visible entity hit by `ray`, ignoring the entities in `exclude`.
+///
+/// Meshes are hit on their triangles, other entities with bounds on their box.
+pub fn raycast_surface(world: &mut World, ray: Ray, exclude: &[Entity]) -> Option<SurfaceHit> {
+    let candidates: Vec<_> = world
+        .query_filtered::<(
+            Entity,
+            &GlobalTransform,
+            &Aabb,
+            Option<&Handle<Mesh>>,
+            Option<&ComputedVisibility>,
+        ), (Without<MainCamera>, Without<EditorOnly>)>()
+        .iter(world)
+        .filter(|(entity, _, _, _, visibility)| {
+            !exclude.contains(entity) && visibility.is_none_or(|visibility| visibility.is_visible())
+        })
+        // the bounds reject most entities before testing their triangles
+        .filter(|(_, transform, aabb, ..)| {
+            picking::ray_aabb_distance(ray, transform, aabb).is_some()
+        })
+        .map(|(entity, transform, aabb, mesh, _)| (entity, *transform, *aabb, mesh.cloned()))
+        .collect();
+
+    let meshes = world.get_resource::<Assets<Mesh>>();
+    candidates
+        .into_iter()
+        .filter_map(|(entity, transform, aabb, mesh)| {
+            let world_to_local = transform.compute_matrix().inverse();
+            let local_ray = Ray {
+                origin: world_to_local.transform_point3(ray.origin),
+                direction: world_to_local.transform_vector3(ray.direction),
+            };
+            let (distance, local_normal) = match mesh.and_then(|mesh| meshes?.get(&mesh)) {
+                Some(mesh) => ray_mesh_hit(local_ray, mesh)?,
+                None => {
+                    let distance = picking::ray_aabb_distance(ray, &transform, &aabb)?;
+                    (
+                        distance,
+                        aabb_face_normal(&aabb, local_ray.get_point(distance)),
+                    )
+                }
+            };
+            // normals transform with the inverse transpose, which keeps them perpendicular to
+            // non-uniformly scaled surfaces
+            let normal =
+                (Mat3::from_mat4(world_to_local).transpose() * local_normal).normalize_or_zero();
+            let normal = match normal.dot(ray.direction) > 0.0 {
+                true => -normal,
+                false => normal,
+            };
+            Some(SurfaceHit {
+                entity,
+                distance,
+                point: ray.get_point(distance),
+                normal,
+            })
+        })
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+}
+
+/// Corners of the bounds of `entity` in its local space, or just its origin without bounds.
+fn local_corners(world: &World, entity: Entity) -> Vec<Vec3> {
+    let Some(aabb) = world.get::<Aabb>(entity) else {
+        return vec![Vec3::ZERO];
+    };
+    let (min, max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+    (0..8)
+        .map(|corner| {
+            Vec3::select(
+                BVec3::new(corner & 1 != 0, corner & 2 != 0, corner & 4 != 0),
+                max,
+                min,
+            )
+        })
+        .collect()
+}
+
+/// Moves every selected entity straight down until its bounds rest on the surface below it,
+/// optionally rotating it so its up axis follows the surface normal. Entities with nothing below
+/// them stay where they are.
+pub fn drop_to_ground(world: &mut World, align_to_normal: bool) {
+    let selected = world
+        .resource::<UiState>()
+        .selected_entities
+        .as_slice()
+        .to_vec();
+    let targets = gizmo::gizmo_targets(world, &selected);
+
+    for entity in targets {
+        let global = gizmo::global_matrix(world, entity);
+        let corners = local_corners(world, entity);
+        let center = corners.iter().sum::<Vec3>() / corners.len() as f32;
+        let ray = Ray {
+            origin: global.transform_point3(center),
+            direction: Vec3::NEG_Y,
+        };
+        // don't land on the entity itself or its children
+        let exclude = scene::with_descendants(world, &[entity]);
+        let Some(hit) = raycast_surface(world, ray, &exclude) else {
+            continue;
+        };
+
+        let (scale, mut rotation, mut translation) = global.to_scale_rotation_translation();
+        let up = match align_to_normal {
+            true => {
+                rotation = Quat::from_rotation_arc(rotation * Vec3::Y, hit.normal) * rotation;
+                hit.normal
+            }
+            false => Vec3::Y,
+        };
+        // the lowest corner along the surface up axis touches the surface
+        let bottom = corners
+            .iter()
+            .map(|&corner| (rotation * (scale * corner)).dot(up))
+            .fold(f32::INFINITY, f32::min);
+        translation += up * (hit.point.dot(up) - translation.dot(up) - bottom);
+
+        let new_global = Mat4::from_scale_rotation_translation(scale, rotation, translation);
+        let new_local =
+            Transform::from_matrix(gizmo::parent_matrix(world, entity).inverse() * new_global);
+        let mut transform = world.get_mut::<Transform>(entity).unwrap();
+        let before = *transform;
+        *transform = new_local;
+        gizmo::record_transform_change(world, entity, before, new_local);
+    }
+}
+
+/// World space positions of the vertices of the mesh of `entity`.
+pub fn mesh_vertices(world: &World, entity: Entity) -> Vec<Vec3> {
+    let (Some(mesh), Some(transform), Some(meshes)) = (
+        world.get::<Handle<Mesh>>(entity),
+        world.get::<GlobalTransform>(entity),
+        world.get_resource::<Assets<Mesh>>(),
+    ) else {
+        return Vec::new();
+    };
+    meshes
+        .get(mesh)
+        .and_then(|mesh| mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3())
+        .map_or_else(Vec::new, |positions| {
+            positions
+                .iter()
+                .map(|&position| transform.transform_point(Vec3::from(position)))
+                .collect()
+        })
+}
+
+/// Translation of the gizmo pivot at `pivot` that puts a vertex of the dragged `targets` onto
+/// the closest vertex of another mesh under the cursor, or `None` with nothing to snap to.
+///
+/// The dragged vertex is the one closest to the cursor when snapping starts, and is kept until
+/// [`end_vertex_snap`].
+pub(crate) fn vertex_snap_translation(
+    ui: &egui::Ui,
+    world: &mut World,
+    camera: Entity,
+    targets: &[Entity],
+    pivot: Vec3,
+) -> Option<Vec3> {
+    let viewport_rect = world.get::<EditorViewport>(camera)?.rect;
+    let cursor = ui.input(|input| input.pointer.hover_pos())?;
+    let dragged = scene::with_descendants(world, targets);
+
+    let id = egui::Id::new(("vertex_snap", camera));
+    let offset = match ui.memory(|memory| memory.data.get_temp::<Vec3>(id)) {
+        Some(offset) => offset,
+        None => {
+            let scale_factor = world.resource::<EguiSettings>().scale_factor as f32;
+            let (camera_component, camera_transform) = world
+                .query::<(&Camera, &GlobalTransform)>()
+                .get(world, camera)
+                .ok()?;
+            let screen_distance = |vertex: Vec3| {
+                camera_component
+                    .world_to_viewport(camera_transform, vertex)
+                    .map(|position| {
+                        let position =
+                            viewport_rect.min + egui::vec2(position.x, position.y) / scale_factor;
+                        position.distance_sq(cursor)
+                    })
+            };
+            let vertex = dragged
+                .iter()
+                .flat_map(|&entity| mesh_vertices(world, entity))
+                .filter_map(|vertex| screen_distance(vertex).map(|distance| (distance, vertex)))
+                .min_by(|(a, _), (b, _)| a.total_cmp(b))
+                .map(|(_, vertex)| vertex)?;
+            // relative to the pivot, so it follows the selection while dragging
+            let offset = vertex - pivot;
+            ui.memory_mut(|memory| memory.data.insert_temp(id, offset));
+            offset
+        }
+    };
+
+    let ray = picking::cursor_ray(world, camera, viewport_rect, cursor)?;
+    let hit = raycast_surface(world, ray, &dragged)?;
+    let target = mesh_vertices(world, hit.entity)
+        .into_iter()
+        .min_by(|a, b| {
+            a.distance_squared(hit.point)
+                .total_cmp(&b.distance_squared(hit.point))
+        })?;
+    Some(target - offset)
+}
+
+/// Forgets the vertex dragged by [`vertex_snap_translation`] in the game view of `camera`.
+pub(crate) fn end_vertex_snap(ui: &egui::Ui, camera: Entity) {
+    let id = egui::Id::new(("vertex_snap", camera));
+    ui.memory_mut(|memory| memory.data.remove::<Vec3>(id));
+}
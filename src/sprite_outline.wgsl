@@ -0,0 +1,41 @@
+#import bevy_sprite::mesh2d_vertex_output MeshVertexOutput
+
+struct SpriteOutlineMaterial {
+    color: vec4<f32>,
+    // the uvs of the texture at the top left and bottom right corners of the sprite
+    rect: vec4<f32>,
+    // how much wider than the sprite the outline is on each side, relative to its size
+    width: vec2<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> material: SpriteOutlineMaterial;
+@group(1) @binding(1)
+var texture: texture_2d<f32>;
+@group(1) @binding(2)
+var texture_sampler: sampler;
+
+// the opacity of the sprite at `uv`, from 0 to 1 over the sprite
+fn alpha(uv: vec2<f32>) -> f32 {
+    if any(uv < vec2(0.0)) || any(uv > vec2(1.0)) {
+        return 0.0;
+    }
+    let texture_uv = mix(material.rect.xy, material.rect.zw, uv);
+    return textureSampleLevel(texture, texture_sampler, texture_uv, 0.0).a;
+}
+
+// drawn on a quad larger than the sprite, where the sprite is opaque within the width of the
+// outline but not at the pixel itself
+@fragment
+fn fragment(mesh: MeshVertexOutput) -> @location(0) vec4<f32> {
+    let uv = (mesh.uv - 0.5) * (1.0 + 2.0 * material.width) + 0.5;
+    var coverage = 0.0;
+    for (var i = 0; i < 8; i++) {
+        let angle = f32(i) * 0.7853982;
+        coverage = max(coverage, alpha(uv + vec2(cos(angle), sin(angle)) * material.width));
+    }
+    if coverage < 0.5 || alpha(uv) >= 0.5 {
+        discard;
+    }
+    return material.color;
+}
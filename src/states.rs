@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+
+pub struct EditorStatesPlugin;
+impl Plugin for EditorStatesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorStates>();
+    }
+}
+
+/// A registered [`States`] type, with its type erased.
+struct EditorStateType {
+    name: &'static str,
+    /// The current state, or `None` if the state isn't added to the app.
+    current: fn(&World) -> Option<String>,
+    /// The state queued to transition to.
+    next: fn(&World) -> Option<String>,
+    variants: fn() -> Vec<String>,
+    /// Queues a transition to the variant with the index.
+    set: fn(&mut World, usize),
+}
+
+fn current<S: States>(world: &World) -> Option<String> {
+    let current = world.get_resource::<State<S>>()?;
+    Some(format!("{:?}", current.get()))
+}
+
+fn next<S: States>(world: &World) -> Option<String> {
+    let next = world.get_resource::<NextState<S>>()?.0.as_ref()?;
+    Some(format!("{next:?}"))
+}
+
+fn variants<S: States>() -> Vec<String> {
+    S::variants()
+        .map(|variant| format!("{variant:?}"))
+        .collect()
+}
+
+fn set<S: States>(world: &mut World, index: usize) {
+    let Some(variant) = S::variants().nth(index) else {
+        return;
+    };
+    info!("Forcing {} to {variant:?}", std::any::type_name::<S>());
+    world.resource_mut::<NextState<S>>().set(variant);
+}
+
+/// The states listed in the states tab.
+#[derive(Resource, Default)]
+pub struct EditorStates {
+    states: Vec<EditorStateType>,
+}
+
+pub trait AddEditorState {
+    /// Lists the state `S` in the states tab, which shows its current value and forces
+    /// transitions to any of its variants through [`NextState`].
+    ///
+    /// ```ignore
+    /// app.add_state::<GameState>().add_editor_state::<GameState>();
+    /// ```
+    fn add_editor_state<S: States>(&mut self) -> &mut Self;
+}
+
+impl AddEditorState for App {
+    fn add_editor_state<S: States>(&mut self) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(EditorStates::default)
+            .states
+            .push(EditorStateType {
+                name: std::any::type_name::<S>()
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or_default(),
+                current: current::<S>,
+                next: next::<S>,
+                variants: variants::<S>,
+                set: set::<S>,
+            });
+        self
+    }
+}
+
+/// Shows the current value of every registered state, with a button per variant to transition
+/// to it.
+pub fn states_ui(world: &mut World, ui: &mut egui::Ui) {
+    let states: Vec<_> = world
+        .resource::<EditorStates>()
+        .states
+        .iter()
+        .map(|state| {
+            (
+                state.name,
+                (state.current)(world),
+                (state.next)(world),
+                state.variants,
+                state.set,
+            )
+        })
+        .collect();
+    if states.is_empty() {
+        ui.label("No states are registered with `add_editor_state`");
+        return;
+    }
+
+    egui::Grid::new("editor_states")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            for (name, current, next, variants, set) in states {
+                ui.strong(name);
+                let Some(current) = current else {
+                    ui.label("Not added to the app");
+                    ui.end_row();
+                    continue;
+                };
+                ui.horizontal_wrapped(|ui| {
+                    for (index, variant) in variants().into_iter().enumerate() {
+                        let response = ui.selectable_label(variant == current, &variant);
+                        if response.clicked() && variant != current {
+                            set(world, index);
+                        }
+                    }
+                    if let Some(next) = &next {
+                        ui.weak(format!("→ {next}"));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+}
@@ -0,0 +1,164 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    diagnostic::{
+        DiagnosticId, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+    },
+    prelude::*,
+};
+use egui::plot::{Line, Plot, PlotPoints};
+
+const HISTORY_LEN: usize = 300;
+
+pub struct EditorStatsPlugin;
+impl Plugin for EditorStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatsHistory>()
+            .add_systems(Last, record_stats);
+    }
+
+    // added in `finish` so the user gets to add them first without a duplicate plugin panic
+    fn finish(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+        if !app.is_plugin_added::<EntityCountDiagnosticsPlugin>() {
+            app.add_plugins(EntityCountDiagnosticsPlugin);
+        }
+    }
+}
+
+/// Recent values of the diagnostics shown in the Stats tab, longer than the histories kept by
+/// the diagnostics themselves.
+#[derive(Resource, Default)]
+pub struct StatsHistory {
+    fps: VecDeque<f64>,
+    frame_time: VecDeque<f64>,
+    entity_count: VecDeque<f64>,
+}
+
+fn push(history: &mut VecDeque<f64>, diagnostics: &DiagnosticsStore, id: DiagnosticId) {
+    let Some(value) = diagnostics
+        .get(id)
+        .and_then(|diagnostic| diagnostic.value())
+    else {
+        return;
+    };
+    if history.len() >= HISTORY_LEN {
+        history.pop_front();
+    }
+    history.push_back(value);
+}
+
+fn record_stats(diagnostics: Res<DiagnosticsStore>, mut history: ResMut<StatsHistory>) {
+    let history = &mut *history;
+    push(
+        &mut history.fps,
+        &diagnostics,
+        FrameTimeDiagnosticsPlugin::FPS,
+    );
+    push(
+        &mut history.frame_time,
+        &diagnostics,
+        FrameTimeDiagnosticsPlugin::FRAME_TIME,
+    );
+    push(
+        &mut history.entity_count,
+        &diagnostics,
+        EntityCountDiagnosticsPlugin::ENTITY_COUNT,
+    );
+}
+
+fn plot(ui: &mut egui::Ui, name: &str, values: &VecDeque<f64>) {
+    let points: PlotPoints = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| [i as f64, *value])
+        .collect();
+    Plot::new(name)
+        .height(80.0)
+        .include_y(0.0)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .show_x(false)
+        .show_axes([false, true])
+        .show(ui, |plot_ui| plot_ui.line(Line::new(points).name(name)));
+}
+
+pub fn stats_ui(world: &mut World, ui: &mut egui::Ui) {
+    let diagnostics = world.resource::<DiagnosticsStore>();
+    let smoothed = |id| {
+        diagnostics
+            .get(id)
+            .and_then(|diagnostic| diagnostic.smoothed())
+    };
+    let fps = smoothed(FrameTimeDiagnosticsPlugin::FPS);
+    let frame_time = smoothed(FrameTimeDiagnosticsPlugin::FRAME_TIME);
+    let entity_count = diagnostics
+        .get(EntityCountDiagnosticsPlugin::ENTITY_COUNT)
+        .and_then(|diagnostic| diagnostic.value());
+
+    let history = world.resource::<StatsHistory>();
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.label(format!("FPS: {:.1}", fps.unwrap_or_default()));
+        plot(ui, "fps", &history.fps);
+        ui.label(format!(
+            "Frame time: {:.2} ms",
+            frame_time.unwrap_or_default()
+        ));
+        plot(ui, "frame_time", &history.frame_time);
+        ui.label(format!("Entities: {}", entity_count.unwrap_or_default()));
+        plot(ui, "entity_count", &history.entity_count);
+    });
+}
+
+/// Draws FPS, frame time, entity counts and the position of `camera` in the top left corner of
+/// its game view `viewport_rect`.
+pub fn stats_overlay_ui(
+    ui: &mut egui::Ui,
+    world: &mut World,
+    camera: Entity,
+    viewport_rect: egui::Rect,
+) {
+    let visible = world
+        .query::<&ComputedVisibility>()
+        .iter(world)
+        .filter(|visibility| visibility.is_visible())
+        .count();
+    let camera_position = world
+        .get::<GlobalTransform>(camera)
+        .map(|transform| transform.translation());
+    let diagnostics = world.resource::<DiagnosticsStore>();
+    let smoothed = |id| {
+        diagnostics
+            .get(id)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or_default()
+    };
+    let entity_count = diagnostics
+        .get(EntityCountDiagnosticsPlugin::ENTITY_COUNT)
+        .and_then(|diagnostic| diagnostic.value())
+        .unwrap_or_default();
+
+    let mut text = format!(
+        "FPS: {:.0} ({:.2} ms)\nEntities: {entity_count}\nVisible: {visible}",
+        smoothed(FrameTimeDiagnosticsPlugin::FPS),
+        smoothed(FrameTimeDiagnosticsPlugin::FRAME_TIME),
+    );
+    if let Some(position) = camera_position {
+        text += &format!(
+            "\nCamera: {:.2}, {:.2}, {:.2}",
+            position.x, position.y, position.z
+        );
+    }
+
+    let painter = ui.painter_at(viewport_rect);
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(12.0), egui::Color32::WHITE);
+    let rect = egui::Rect::from_min_size(
+        viewport_rect.min + egui::vec2(8.0, 8.0),
+        galley.size() + egui::vec2(12.0, 8.0),
+    );
+    painter.rect_filled(rect, 4.0, egui::Color32::from_black_alpha(160));
+    painter.galley(rect.min + egui::vec2(6.0, 4.0), galley);
+}
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+type TabUi = Box<dyn FnMut(&mut egui::Ui, &mut World) + Send + Sync>;
+
+struct EditorTab {
+    name: String,
+    ui: TabUi,
+}
+
+/// Tabs registered by other crates, docked next to the built-in editor windows.
+#[derive(Resource, Default)]
+pub struct EditorTabs {
+    tabs: Vec<EditorTab>,
+}
+
+impl EditorTabs {
+    pub fn add(
+        &mut self,
+        name: impl Into<String>,
+        ui: impl FnMut(&mut egui::Ui, &mut World) + Send + Sync + 'static,
+    ) {
+        let name = name.into();
+        if self.tabs.iter().any(|tab| tab.name == name) {
+            warn!("Editor tab {name} is already registered");
+            return;
+        }
+        self.tabs.push(EditorTab {
+            name,
+            ui: Box::new(ui),
+        });
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.tabs.iter().map(|tab| tab.name.as_str())
+    }
+
+    /// Draws the tab called `name`, returning `false` if no such tab is registered.
+    pub(crate) fn show(world: &mut World, name: &str, ui: &mut egui::Ui) -> bool {
+        world.resource_scope(|world, mut tabs: Mut<EditorTabs>| {
+            match tabs.tabs.iter_mut().find(|tab| tab.name == name) {
+                Some(tab) => {
+                    (tab.ui)(ui, world);
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+}
+
+pub trait AddEditorTab {
+    /// Registers a tab which is docked into the editor layout.
+    ///
+    /// ```ignore
+    /// app.add_editor_tab("My Tool", |ui, world| {
+    ///     ui.label(format!("{} entities", world.entities().len()));
+    /// });
+    /// ```
+    fn add_editor_tab(
+        &mut self,
+        name: impl Into<String>,
+        ui: impl FnMut(&mut egui::Ui, &mut World) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl AddEditorTab for App {
+    fn add_editor_tab(
+        &mut self,
+        name: impl Into<String>,
+        ui: impl FnMut(&mut egui::Ui, &mut World) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(EditorTabs::default)
+            .add(name, ui);
+        self
+    }
+}
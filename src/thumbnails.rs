@@ -0,0 +1,307 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::LoadState,
+    prelude::*,
+    render::{
+        camera::RenderTarget, primitives::Aabb, render_resource::Extent3d, view::RenderLayers,
+    },
+};
+use bevy_inspector_egui::bevy_egui::EguiUserTextures;
+
+use crate::{
+    asset_browser::{self, AssetKind},
+    asset_preview::{self, EditorStudio},
+    EditorOnly,
+};
+
+const THUMBNAIL_SIZE: Extent3d = Extent3d {
+    width: 128,
+    height: 128,
+    depth_or_array_layers: 1,
+};
+/// Render layer of the thumbnail studio, which no other camera renders.
+const THUMBNAIL_LAYER: u8 = 31;
+/// Where models are rendered, far away from the scene so the studio light doesn't reach it.
+const STUDIO_ORIGIN: Vec3 = Vec3::new(0.0, -10_000.0, 0.0);
+/// Frames to wait for the bounds of a spawned model, and then for it to be rendered.
+const SETTLE_FRAMES: u32 = 3;
+/// Frames after which a model which isn't loaded yet is given up on.
+const LOAD_TIMEOUT_FRAMES: u32 = 600;
+
+pub struct EditorThumbnailsPlugin;
+impl Plugin for EditorThumbnailsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Thumbnails>()
+            // the spawned model gets marked before editor ids are given out in `Last`
+            .add_systems(
+                Update,
+                update_thumbnails.after(bevy::scene::scene_spawner_system),
+            );
+    }
+}
+
+enum JobState {
+    Loading { frames: u32 },
+    Settling { frames: u32 },
+    Rendering { frames: u32 },
+}
+
+/// A model being rendered into its thumbnail.
+struct ThumbnailJob {
+    path: PathBuf,
+    scene: Handle<Scene>,
+    image: Handle<Image>,
+    root: Entity,
+    camera: Entity,
+    light: Entity,
+    state: JobState,
+}
+
+/// Thumbnails of the files shown in the asset browser, by path. Images are shown as they are and
+/// models are rendered off-screen one at a time over a few frames, so the UI never waits.
+#[derive(Resource, Default)]
+pub struct Thumbnails {
+    /// `None` for files whose thumbnail couldn't be made.
+    cache: HashMap<PathBuf, Option<egui::TextureId>>,
+    queue: VecDeque<PathBuf>,
+    job: Option<ThumbnailJob>,
+}
+
+/// The thumbnail of the file at `path`, or `None` while it's made or if it can't be. Requests
+/// one for images and models which don't have it yet.
+pub fn thumbnail(world: &mut World, path: &Path) -> Option<egui::TextureId> {
+    let thumbnails = world.resource::<Thumbnails>();
+    if let Some(&texture) = thumbnails.cache.get(path) {
+        return texture;
+    }
+    if thumbnails.queue.iter().any(|queued| queued == path) {
+        return None;
+    }
+    match AssetKind::from_path(path) {
+        AssetKind::Image => {
+            // shown as soon as the image is loaded
+            let image: Handle<Image> = world
+                .resource::<AssetServer>()
+                .load(asset_browser::asset_path(path));
+            let texture = world.resource_mut::<EguiUserTextures>().add_image(image);
+            world
+                .resource_mut::<Thumbnails>()
+                .cache
+                .insert(path.to_owned(), Some(texture));
+            Some(texture)
+        }
+        AssetKind::Model => {
+            world
+                .resource_mut::<Thumbnails>()
+                .queue
+                .push_back(path.to_owned());
+            None
+        }
+        _ => None,
+    }
+}
+
+fn start_job(world: &mut World, path: PathBuf) -> ThumbnailJob {
+    let scene: Handle<Scene> = world
+        .resource::<AssetServer>()
+        .load(format!("{}#Scene0", asset_browser::asset_path(&path)));
+    let image = asset_preview::render_target(
+        &mut world.resource_mut::<Assets<Image>>(),
+        "editor_thumbnail",
+        THUMBNAIL_SIZE,
+    );
+    let layer = RenderLayers::layer(THUMBNAIL_LAYER);
+
+    let root = world
+        .spawn((
+            Name::new("Editor Thumbnail Model"),
+            SceneBundle {
+                scene: scene.clone(),
+                transform: Transform::from_translation(STUDIO_ORIGIN),
+                ..default()
+            },
+            layer,
+            EditorStudio,
+            EditorOnly,
+        ))
+        .id();
+    let camera = world
+        .spawn((
+            Name::new("Editor Thumbnail Camera"),
+            Camera3dBundle {
+                camera: Camera {
+                    target: RenderTarget::Image(image.clone()),
+                    // rendered once the model is framed
+                    is_active: false,
+                    order: -2,
+                    ..default()
+                },
+                ..default()
+            },
+            layer,
+            EditorStudio,
+            EditorOnly,
+        ))
+        .id();
+    let light = world
+        .spawn((
+            Name::new("Editor Thumbnail Light"),
+            PointLightBundle::default(),
+            layer,
+            EditorStudio,
+            EditorOnly,
+        ))
+        .id();
+    ThumbnailJob {
+        path,
+        scene,
+        image,
+        root,
+        camera,
+        light,
+        state: JobState::Loading { frames: 0 },
+    }
+}
+
+/// Keeps the spawned model on the studio layer and out of the scene, without the cameras and
+/// lights it may contain.
+fn isolate_model(world: &mut World, root: Entity) {
+    let layer = RenderLayers::layer(THUMBNAIL_LAYER);
+    for entity in crate::scene::with_descendants(world, &[root]) {
+        let mut entity = world.entity_mut(entity);
+        entity.insert((layer, EditorStudio, EditorOnly)).remove::<(
+            Camera,
+            PointLight,
+            SpotLight,
+            DirectionalLight,
+        )>();
+    }
+}
+
+/// Points the camera of `job` at the bounds of its model and lights it.
+fn frame_model(world: &mut World, job: &ThumbnailJob) {
+    let (mut min, mut max) = (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY));
+    for entity in crate::scene::with_descendants(world, &[job.root]) {
+        let (Some(transform), Some(aabb)) = (
+            world.get::<GlobalTransform>(entity),
+            world.get::<Aabb>(entity),
+        ) else {
+            continue;
+        };
+        let (aabb_min, aabb_max) = (Vec3::from(aabb.min()), Vec3::from(aabb.max()));
+        for corner in 0..8 {
+            let local = Vec3::select(
+                BVec3::new(corner & 1 != 0, corner & 2 != 0, corner & 4 != 0),
+                aabb_max,
+                aabb_min,
+            );
+            let point = transform.transform_point(local);
+            min = min.min(point);
+            max = max.max(point);
+        }
+    }
+    let (center, radius) = match min.x <= max.x {
+        true => ((min + max) / 2.0, ((max - min).length() / 2.0).max(0.01)),
+        false => (STUDIO_ORIGIN, 1.0),
+    };
+
+    let fov = PerspectiveProjection::default().fov;
+    let distance = radius / (fov / 2.0).sin();
+    let position = center + Vec3::new(1.0, 0.7, 1.0).normalize() * distance;
+    if let Some(mut transform) = world.get_mut::<Transform>(job.camera) {
+        *transform = Transform::from_translation(position).looking_at(center, Vec3::Y);
+    }
+    if let Some(mut camera) = world.get_mut::<Camera>(job.camera) {
+        camera.is_active = true;
+    }
+    if let Some(mut transform) = world.get_mut::<Transform>(job.light) {
+        transform.translation = position + Vec3::Y * distance;
+    }
+    if let Some(mut light) = world.get_mut::<PointLight>(job.light) {
+        // light falls off with the square of the distance
+        light.intensity = 800.0 * distance * distance;
+        light.range = distance * 4.0;
+    }
+}
+
+fn finish_job(world: &mut World, job: ThumbnailJob, rendered: bool) {
+    for entity in [job.root, job.camera, job.light] {
+        if let Some(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+    let texture = rendered.then(|| {
+        world
+            .resource_mut::<EguiUserTextures>()
+            .add_image(job.image.clone())
+    });
+    if !rendered {
+        warn!("Cannot make a thumbnail of {}", job.path.display());
+    }
+    world
+        .resource_mut::<Thumbnails>()
+        .cache
+        .insert(job.path, texture);
+}
+
+/// Advances the thumbnail being made, or starts the next queued one.
+fn update_thumbnails(world: &mut World) {
+    if world.get_resource::<EguiUserTextures>().is_none() {
+        return;
+    }
+    let Some(mut job) = world.resource_mut::<Thumbnails>().job.take() else {
+        let next = world.resource_mut::<Thumbnails>().queue.pop_front();
+        if let Some(path) = next {
+            let job = start_job(world, path);
+            world.resource_mut::<Thumbnails>().job = Some(job);
+        }
+        return;
+    };
+
+    let load_state = world.resource::<AssetServer>().get_load_state(&job.scene);
+    isolate_model(world, job.root);
+    job.state = match job.state {
+        _ if load_state == LoadState::Failed => return finish_job(world, job, false),
+        JobState::Loading { frames } if frames > LOAD_TIMEOUT_FRAMES => {
+            return finish_job(world, job, false);
+        }
+        JobState::Loading { frames } => {
+            match load_state == LoadState::Loaded && world.get::<Children>(job.root).is_some() {
+                true => JobState::Settling { frames: 0 },
+                false => JobState::Loading { frames: frames + 1 },
+            }
+        }
+        // bounds are computed for the spawned meshes in the meantime
+        JobState::Settling { frames } if frames >= SETTLE_FRAMES => {
+            frame_model(world, &job);
+            JobState::Rendering { frames: 0 }
+        }
+        JobState::Settling { frames } => JobState::Settling { frames: frames + 1 },
+        JobState::Rendering { frames } if frames >= SETTLE_FRAMES => {
+            return finish_job(world, job, true);
+        }
+        JobState::Rendering { frames } => JobState::Rendering { frames: frames + 1 },
+    };
+    world.resource_mut::<Thumbnails>().job = Some(job);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolated_models_are_left_out_of_the_scene() {
+        let mut world = World::new();
+        let authored = world.spawn_empty().id();
+        let root = world.spawn_empty().id();
+        let child = world.spawn(PointLight::default()).set_parent(root).id();
+
+        isolate_model(&mut world, root);
+        assert!(world.get::<PointLight>(child).is_none());
+        assert_eq!(crate::scene::scene_entities(&mut world), vec![authored]);
+    }
+}
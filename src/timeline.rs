@@ -0,0 +1,242 @@
+use bevy::{asset::HandleId, gltf::Gltf, prelude::*, reflect::GetPath};
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+
+use crate::{inspector::entity_name, play};
+
+const TIMELINE_HEIGHT: f32 = 36.0;
+const PLAYHEAD_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 140, 40);
+
+pub struct EditorTimelinePlugin;
+impl Plugin for EditorTimelinePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Timeline>()
+            .add_systems(Update, advance_timeline.run_if(not(play::simulating)));
+    }
+}
+
+/// The animation player shown in the timeline tab.
+#[derive(Resource, Default)]
+pub struct Timeline {
+    pub player: Option<Entity>,
+}
+
+/// Keeps the player shown in the timeline animating while the game is edited, since animations
+/// otherwise only advance while it's simulated.
+fn advance_timeline(
+    timeline: Res<Timeline>,
+    time: Res<Time>,
+    mut players: Query<&mut AnimationPlayer>,
+) {
+    let Some(mut player) = timeline
+        .player
+        .and_then(|entity| players.get_mut(entity).ok())
+    else {
+        return;
+    };
+    if !player.is_paused() {
+        let elapsed = player.elapsed() + time.raw_delta_seconds() * player.speed();
+        player.set_elapsed(elapsed);
+    }
+}
+
+/// The first animation player at or below the last selected entity, since players of models are
+/// on a child of the scene they're spawned by.
+fn selected_player(world: &mut World, selected: &SelectedEntities) -> Option<Entity> {
+    let root = selected.iter().last()?;
+    crate::scene::with_descendants(world, &[root])
+        .into_iter()
+        .find(|&entity| world.get::<AnimationPlayer>(entity).is_some())
+}
+
+/// Loaded clips which can animate the player `entity`, with their name in their model or their
+/// asset path.
+fn clips(world: &World, entity: Entity) -> Vec<(Handle<AnimationClip>, String, f32)> {
+    let name = world.get::<Name>(entity);
+    let asset_server = world.resource::<AssetServer>();
+    let named: Vec<(HandleId, &str)> = world
+        .resource::<Assets<Gltf>>()
+        .iter()
+        .flat_map(|(_, gltf)| &gltf.named_animations)
+        .map(|(name, handle)| (handle.id(), name.as_str()))
+        .collect();
+    let animations = world.resource::<Assets<AnimationClip>>();
+    let mut clips: Vec<_> = animations
+        .iter()
+        .filter(|(_, clip)| name.is_none_or(|name| clip.compatible_with(name)))
+        .map(|(id, clip)| {
+            let label = named
+                .iter()
+                .find(|(named, _)| *named == id)
+                .map(|(_, name)| name.to_string())
+                .or_else(|| {
+                    asset_server
+                        .get_handle_path(id)
+                        .map(|path| format!("{path:?}"))
+                })
+                .unwrap_or_else(|| format!("{id:?}"));
+            (animations.get_handle(id), label, clip.duration())
+        })
+        .collect();
+    clips.sort_by(|a, b| a.1.cmp(&b.1));
+    clips
+}
+
+/// Picks the clip of the animation player of the selected entity, and controls its playback
+/// along a timeline which can be scrubbed.
+pub fn timeline_ui(world: &mut World, ui: &mut egui::Ui, selected: &SelectedEntities) {
+    let entity = selected_player(world, selected);
+    world.resource_mut::<Timeline>().player = entity;
+    let Some(entity) = entity else {
+        ui.label("Select an entity with an AnimationPlayer");
+        return;
+    };
+    let clips = clips(world, entity);
+    let player = world.get::<AnimationPlayer>(entity).unwrap();
+    // the clip and looping of the player can only be read by reflection
+    let current = player
+        .path::<Handle<AnimationClip>>("animation.animation_clip")
+        .ok()
+        .cloned()
+        .unwrap_or_default();
+    let looping = player.path::<bool>("animation.repeat").ok().copied() == Some(true);
+    let mut repeat = looping;
+    let mut speed = player.speed();
+    let mut paused = player.is_paused();
+    let elapsed = player.elapsed();
+    let duration = clips
+        .iter()
+        .find(|(handle, ..)| *handle == current)
+        .map_or(0.0, |(.., duration)| *duration);
+    // the player keeps counting past the end of clips which don't loop
+    let shown = match (repeat, duration > 0.0) {
+        (true, true) => elapsed.rem_euclid(duration),
+        (false, true) => elapsed.clamp(0.0, duration),
+        (_, false) => 0.0,
+    };
+
+    let mut picked = None;
+    let mut seek = None;
+    ui.horizontal(|ui| {
+        ui.label(entity_name(world, entity));
+        let selected_text = clips
+            .iter()
+            .find(|(handle, ..)| *handle == current)
+            .map_or("None", |(_, label, _)| label.as_str());
+        egui::ComboBox::from_id_source("timeline_clip")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for (handle, label, _) in &clips {
+                    if ui.selectable_label(*handle == current, label).clicked() {
+                        picked = Some(handle.clone());
+                    }
+                }
+            });
+        if clips.is_empty() {
+            ui.label("No loaded clips animate this entity");
+        }
+    });
+    ui.horizontal(|ui| {
+        if ui.button("⏮").on_hover_text("Back to the start").clicked() {
+            seek = Some(0.0);
+        }
+        match paused {
+            true if ui.button("▶ Play").clicked() => paused = false,
+            false if ui.button("⏸ Pause").clicked() => paused = true,
+            _ => {}
+        }
+        ui.toggle_value(&mut repeat, "🔁 Loop");
+        ui.add(
+            egui::Slider::new(&mut speed, -2.0..=4.0)
+                .text("Speed")
+                .fixed_decimals(2),
+        );
+        if ui.button("1x").clicked() {
+            speed = 1.0;
+        }
+        ui.separator();
+        ui.monospace(format!("{shown:.2} / {duration:.2} s"));
+    });
+    if let Some(time) = timeline_bar(ui, shown, duration) {
+        seek = Some(time);
+    }
+
+    let mut player = world.get_mut::<AnimationPlayer>(entity).unwrap();
+    let picked_any = picked.is_some();
+    if let Some(clip) = picked {
+        // starting a clip resets the playback settings
+        player.start(clip);
+    }
+    if repeat != looping || picked_any {
+        match repeat {
+            true => player.repeat(),
+            false => player.stop_repeating(),
+        };
+    }
+    if player.speed() != speed {
+        player.set_speed(speed);
+    }
+    if paused != player.is_paused() {
+        match paused {
+            true => player.pause(),
+            false => player.resume(),
+        }
+    }
+    if let Some(time) = seek {
+        player.set_elapsed(time);
+    }
+}
+
+/// Draws the timeline of a clip lasting `duration` seconds with its playhead at `elapsed`, returning
+/// the time clicked or dragged to.
+fn timeline_bar(ui: &mut egui::Ui, elapsed: f32, duration: f32) -> Option<f32> {
+    let (rect, response) = ui.allocate_exact_size(
+        egui::vec2(ui.available_width(), TIMELINE_HEIGHT),
+        egui::Sense::click_and_drag(),
+    );
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+    if duration <= 0.0 {
+        return None;
+    }
+    let x = |time: f32| rect.left() + time / duration * rect.width();
+
+    // a tick every tenth of a second, longer ones every second
+    let step = match duration / rect.width().max(1.0) * 8.0 > 0.1 {
+        true => 1.0,
+        false => 0.1,
+    };
+    let ticks = (duration / step) as u32;
+    for tick in 0..=ticks {
+        let time = tick as f32 * step;
+        let whole = (time.round() - time).abs() < 1e-3;
+        let height = if whole { 0.5 } else { 0.25 } * rect.height();
+        painter.line_segment(
+            [
+                egui::pos2(x(time), rect.bottom()),
+                egui::pos2(x(time), rect.bottom() - height),
+            ],
+            (1.0, visuals.weak_text_color()),
+        );
+        if whole {
+            painter.text(
+                egui::pos2(x(time) + 2.0, rect.top()),
+                egui::Align2::LEFT_TOP,
+                format!("{time:.0}"),
+                egui::FontId::monospace(10.0),
+                visuals.weak_text_color(),
+            );
+        }
+    }
+    painter.line_segment(
+        [
+            egui::pos2(x(elapsed), rect.top()),
+            egui::pos2(x(elapsed), rect.bottom()),
+        ],
+        (2.0, PLAYHEAD_COLOR),
+    );
+
+    let pos = response.interact_pointer_pos()?;
+    (response.clicked() || response.dragged())
+        .then(|| ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0) * duration)
+}
@@ -0,0 +1,555 @@
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{HandleId, LoadState, SourcePathId},
+    math::{Affine3A, Vec3A},
+    prelude::*,
+    reflect::ReflectRef,
+    scene::{serde::SceneDeserializer, DynamicEntity},
+};
+use bevy_inspector_egui::bevy_inspector::hierarchy::SelectedEntities;
+use bevy_reflect::TypeRegistry;
+use serde::de::DeserializeSeed;
+
+use crate::{
+    asset_browser::ASSET_DIR,
+    asset_usages::{self, AssetUsage},
+    commands::AddEditorCommand,
+    inspector::entity_name,
+    scene::{self, SceneFileError},
+    InspectorSelection,
+};
+
+pub struct EditorValidationPlugin;
+impl Plugin for EditorValidationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetReport>()
+            .add_editor_command("Validate Scene", |world| {
+                let entities = scene::scene_entities(world);
+                let issues =
+                    validate_scene(&scene::build_scene(world, &entities), Path::new(ASSET_DIR));
+                if issues.is_empty() {
+                    info!("The scene is valid");
+                }
+                for issue in issues {
+                    match issue.severity {
+                        Severity::Warning => warn!("{issue}"),
+                        Severity::Error => error!("{issue}"),
+                    }
+                }
+            });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Likely a mistake, but the scene loads as saved.
+    Warning,
+    /// The scene won't load as saved.
+    Error,
+}
+
+/// Something wrong found in a scene by [`validate_scene`].
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    /// The entity of the scene, as saved in it.
+    pub entity: Entity,
+    /// The short type name of the component, with the path of the field in it.
+    pub component: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{severity}: entity {:?}, {}: {}",
+            self.entity, self.component, self.message
+        )
+    }
+}
+
+/// The ids of the files in `asset_dir`, as the handles of assets loaded from them refer to them.
+fn asset_sources(asset_dir: &Path) -> HashSet<SourcePathId> {
+    let mut sources = HashSet::new();
+    let mut dirs = vec![asset_dir.to_owned()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.filter_map(|entry| Some(entry.ok()?.path())) {
+            if path.is_dir() {
+                dirs.push(path);
+            } else if let Ok(relative) = path.strip_prefix(asset_dir) {
+                sources.insert(SourcePathId::from(relative));
+            }
+        }
+    }
+    sources
+}
+
+/// Whether the math value `value` is finite, for the types reflected as values rather than
+/// through their fields.
+fn finite(value: &dyn Reflect) -> Option<bool> {
+    let any = value.as_any();
+    any.downcast_ref::<f32>()
+        .map(|value| value.is_finite())
+        .or_else(|| any.downcast_ref::<f64>().map(|value| value.is_finite()))
+        .or_else(|| any.downcast_ref::<Quat>().map(|value| value.is_finite()))
+        .or_else(|| any.downcast_ref::<Vec3A>().map(|value| value.is_finite()))
+        .or_else(|| any.downcast_ref::<Mat4>().map(|value| value.is_finite()))
+        .or_else(|| {
+            any.downcast_ref::<Affine3A>()
+                .map(|value| value.is_finite())
+        })
+}
+
+struct Validator<'a> {
+    sources: HashSet<SourcePathId>,
+    entities: HashSet<Entity>,
+    issues: &'a mut Vec<ValidationIssue>,
+    entity: Entity,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, severity: Severity, component: &str, message: String) {
+        self.issues.push(ValidationIssue {
+            severity,
+            entity: self.entity,
+            component: component.to_owned(),
+            message,
+        });
+    }
+
+    fn visit(&mut self, value: &dyn Reflect, path: String) {
+        let field = |name: &dyn fmt::Display| match path.is_empty() {
+            true => name.to_string(),
+            false => format!("{path}.{name}"),
+        };
+        match value.reflect_ref() {
+            ReflectRef::Struct(value) => {
+                for index in 0..value.field_len() {
+                    let name = value.name_at(index).unwrap_or_default();
+                    self.visit(value.field_at(index).unwrap(), field(&name));
+                }
+            }
+            ReflectRef::TupleStruct(value) => {
+                for index in 0..value.field_len() {
+                    self.visit(value.field(index).unwrap(), field(&index));
+                }
+            }
+            ReflectRef::Tuple(value) => {
+                for index in 0..value.field_len() {
+                    self.visit(value.field(index).unwrap(), field(&index));
+                }
+            }
+            ReflectRef::List(value) => {
+                for (index, item) in value.iter().enumerate() {
+                    self.visit(item, format!("{path}[{index}]"));
+                }
+            }
+            ReflectRef::Array(value) => {
+                for (index, item) in value.iter().enumerate() {
+                    self.visit(item, format!("{path}[{index}]"));
+                }
+            }
+            ReflectRef::Map(value) => {
+                for (key, item) in value.iter() {
+                    self.visit(item, format!("{path}[{key:?}]"));
+                }
+            }
+            ReflectRef::Enum(value) => {
+                for (index, variant_field) in value.iter_fields().enumerate() {
+                    let name = variant_field
+                        .name()
+                        .map_or_else(|| index.to_string(), str::to_owned);
+                    self.visit(variant_field.value(), field(&name));
+                }
+            }
+            ReflectRef::Value(value) => self.visit_value(value, path),
+        }
+    }
+
+    fn visit_value(&mut self, value: &dyn Reflect, path: String) {
+        if finite(value) == Some(false) {
+            self.report(
+                Severity::Error,
+                &path,
+                format!("{value:?} isn't a finite number"),
+            );
+        }
+        if let Some(&entity) = value.as_any().downcast_ref::<Entity>() {
+            if !self.entities.contains(&entity) {
+                self.report(
+                    Severity::Error,
+                    &path,
+                    format!("refers to the entity {entity:?}, which isn't in the scene"),
+                );
+            }
+        }
+        match value.as_any().downcast_ref::<HandleId>() {
+            Some(HandleId::AssetPathId(id)) if !self.sources.contains(&id.source_path_id()) => {
+                self.report(
+                    Severity::Error,
+                    &path,
+                    "refers to an asset whose file is missing".to_owned(),
+                );
+            }
+            Some(HandleId::Id(..)) => self.report(
+                Severity::Warning,
+                &path,
+                "refers to an asset made while running, which isn't saved with the scene"
+                    .to_owned(),
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// Checks `scene` for values which won't load as they were saved: handles to files missing from
+/// `asset_dir`, numbers which aren't finite, and parents, children or other entity references to
+/// entities which aren't in the scene.
+pub fn validate_scene(scene: &DynamicScene, asset_dir: &Path) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let mut validator = Validator {
+        sources: asset_sources(asset_dir),
+        entities: scene.entities.iter().map(|entity| entity.entity).collect(),
+        issues: &mut issues,
+        entity: Entity::PLACEHOLDER,
+    };
+    for DynamicEntity { entity, components } in &scene.entities {
+        validator.entity = *entity;
+        for component in components {
+            let name = bevy::utils::get_short_name(component.type_name());
+            validator.visit(component.as_reflect(), name);
+        }
+    }
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    issues
+}
+
+/// Loads the scene at `path` with the types of `type_registry`, and checks it with
+/// [`validate_scene`]. Loading fails on types which aren't registered.
+pub fn validate_scene_file(
+    type_registry: &TypeRegistry,
+    path: impl AsRef<Path>,
+    asset_dir: impl AsRef<Path>,
+) -> Result<Vec<ValidationIssue>, SceneFileError> {
+    let ron = fs::read_to_string(path)?;
+    let mut deserializer = ron::de::Deserializer::from_str(&ron)?;
+    let scene = SceneDeserializer { type_registry }.deserialize(&mut deserializer)?;
+    Ok(validate_scene(&scene, asset_dir.as_ref()))
+}
+
+/// Validates the scene files `paths` with the types registered by `app`, printing what's found.
+/// Returns whether every scene loaded without errors, for the exit code of a CI job.
+pub fn validate_scene_files(app: &App, paths: &[PathBuf], asset_dir: &Path) -> bool {
+    let type_registry = app.world.resource::<AppTypeRegistry>().read();
+    let mut valid = true;
+    for path in paths {
+        match validate_scene_file(&type_registry, path, asset_dir) {
+            Ok(issues) => {
+                for issue in &issues {
+                    println!("{}: {issue}", path.display());
+                }
+                valid &= issues.iter().all(|issue| issue.severity != Severity::Error);
+            }
+            Err(e) => {
+                println!("{}: error: failed to load: {e}", path.display());
+                valid = false;
+            }
+        }
+    }
+    valid
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetProblem {
+    /// The file the asset is loaded from doesn't exist.
+    Missing,
+    /// The asset server failed to load the file.
+    Failed,
+}
+
+/// An asset a handle held by the scene points to, which won't be there when it's loaded.
+#[derive(Debug, Clone)]
+pub struct BrokenAsset {
+    pub handle: HandleId,
+    /// The path of the asset, or its id when the asset server doesn't know the path.
+    pub label: String,
+    pub problem: AssetProblem,
+    pub usages: Vec<AssetUsage>,
+}
+
+/// The assets held by the components of the scene which failed to load or whose file is missing
+/// from `asset_dir`, with the components holding them.
+pub fn broken_assets(
+    world: &World,
+    type_registry: &TypeRegistry,
+    asset_dir: &Path,
+) -> Vec<BrokenAsset> {
+    let sources = asset_sources(asset_dir);
+    let asset_server = world.resource::<AssetServer>();
+    let mut broken: Vec<BrokenAsset> = Vec::new();
+    for (handle, usage) in asset_usages::handle_usages(world, type_registry) {
+        if let Some(asset) = broken.iter_mut().find(|asset| asset.handle == handle) {
+            asset.usages.push(usage);
+            continue;
+        }
+        // the assets made while running have no file
+        let HandleId::AssetPathId(id) = handle else {
+            continue;
+        };
+        let problem = if !sources.contains(&id.source_path_id()) {
+            AssetProblem::Missing
+        } else if asset_server.get_load_state(handle) == LoadState::Failed {
+            AssetProblem::Failed
+        } else {
+            continue;
+        };
+        broken.push(BrokenAsset {
+            handle,
+            label: crate::asset_label(asset_server, handle).unwrap_or_else(|| format!("{id:?}")),
+            problem,
+            usages: vec![usage],
+        });
+    }
+    broken.sort_by(|a, b| a.label.cmp(&b.label));
+    broken
+}
+
+/// State of the validation tab, the broken assets found when it was last refreshed.
+#[derive(Resource, Default)]
+pub struct AssetReport {
+    broken: Option<Vec<BrokenAsset>>,
+}
+
+/// Lists the broken assets of the scene with the entities holding them, and selects the entity
+/// clicked.
+pub(crate) fn asset_report_ui(
+    world: &mut World,
+    ui: &mut egui::Ui,
+    selection: &mut InspectorSelection,
+    selected_entities: &mut SelectedEntities,
+) {
+    let refresh = ui
+        .horizontal(|ui| {
+            let refresh = ui.button("Refresh").clicked();
+            ui.weak("Assets which failed to load, or whose file is missing");
+            refresh
+        })
+        .inner;
+    ui.separator();
+
+    if refresh || world.resource::<AssetReport>().broken.is_none() {
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let broken = broken_assets(world, &type_registry.read(), Path::new(ASSET_DIR));
+        world.resource_mut::<AssetReport>().broken = Some(broken);
+    }
+    let report = world.resource::<AssetReport>();
+    let broken = report.broken.as_deref().unwrap_or_default();
+    if broken.is_empty() {
+        ui.weak("No broken asset");
+        return;
+    }
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .show(ui, |ui| {
+            for asset in broken {
+                let problem = match asset.problem {
+                    AssetProblem::Missing => "file missing",
+                    AssetProblem::Failed => "failed to load, the error is in the console",
+                };
+                egui::CollapsingHeader::new(
+                    egui::RichText::new(format!("{} ({problem})", asset.label))
+                        .color(ui.visuals().error_fg_color),
+                )
+                .id_source(asset.handle)
+                .default_open(true)
+                .show(ui, |ui| {
+                    for usage in &asset.usages {
+                        // the report may be older than the entity
+                        if world.get_entity(usage.entity).is_none() {
+                            continue;
+                        }
+                        let selected = selected_entities.contains(usage.entity);
+                        let text =
+                            format!("{} – {}", entity_name(world, usage.entity), usage.field);
+                        if ui.selectable_label(selected, text).clicked() {
+                            selected_entities.select_replace(usage.entity);
+                            *selection = InspectorSelection::Entities;
+                        }
+                    }
+                });
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use bevy::{asset::AssetPath, scene::DynamicSceneBuilder};
+
+    use super::*;
+
+    /// An asset directory holding `textures/crate.png`.
+    fn asset_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join("bevy_editor").join(name);
+        fs::create_dir_all(dir.join("textures")).unwrap();
+        fs::write(dir.join("textures/crate.png"), []).unwrap();
+        dir
+    }
+
+    fn image(path: &str) -> Handle<Image> {
+        Handle::weak(AssetPath::from(path).into())
+    }
+
+    fn scene(components: Vec<Vec<Box<dyn Reflect>>>) -> DynamicScene {
+        DynamicScene {
+            resources: Vec::new(),
+            entities: components
+                .into_iter()
+                .enumerate()
+                .map(|(index, components)| DynamicEntity {
+                    entity: Entity::from_raw(index as u32),
+                    components,
+                })
+                .collect(),
+        }
+    }
+
+    fn messages(issues: &[ValidationIssue]) -> Vec<(Severity, u32, &str, &str)> {
+        issues
+            .iter()
+            .map(|issue| {
+                (
+                    issue.severity,
+                    issue.entity.index(),
+                    issue.component.as_str(),
+                    issue.message.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    /// A crate with a texture and a child, as saved from the world.
+    fn saved_scene() -> (DynamicScene, AppTypeRegistry) {
+        let mut app = App::new();
+        app.add_plugins((TransformPlugin, HierarchyPlugin))
+            .register_type::<Entity>()
+            .register_type::<Vec3>()
+            .register_type::<Quat>()
+            .register_type::<Handle<Image>>()
+            .register_type::<HandleId>();
+        let world = &mut app.world;
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let parent = world
+            .spawn((Transform::default(), image("textures/crate.png")))
+            .id();
+        world.spawn(Transform::default()).set_parent(parent);
+        let entities: Vec<_> = world.iter_entities().map(|entity| entity.id()).collect();
+        let mut builder = DynamicSceneBuilder::from_world(world);
+        builder.extract_entities(entities.into_iter());
+        (builder.build(), type_registry)
+    }
+
+    #[test]
+    fn saved_scenes_are_valid() {
+        let dir = asset_dir("validation_valid");
+        let (scene, type_registry) = saved_scene();
+        assert_eq!(messages(&validate_scene(&scene, &dir)), []);
+
+        let path = dir.join("crate.scn.ron");
+        fs::write(&path, scene.serialize_ron(&type_registry).unwrap()).unwrap();
+        let issues = validate_scene_file(&type_registry.read(), &path, &dir).unwrap();
+        assert_eq!(messages(&issues), []);
+        // loading fails on the types which aren't registered
+        assert!(validate_scene_file(&TypeRegistry::new(), &path, &dir).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn finds_entities_missing_from_the_scene() {
+        let dir = asset_dir("validation_hierarchy");
+        let (mut scene, _) = saved_scene();
+        let parent = scene.entities.remove(0).entity;
+        let child = scene.entities[0].entity;
+        let issues = validate_scene(&scene, &dir);
+        assert_eq!(
+            messages(&issues),
+            [(
+                Severity::Error,
+                child.index(),
+                "Parent.0",
+                &*format!("refers to the entity {parent:?}, which isn't in the scene")
+            )]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn finds_what_wont_load_as_saved() {
+        let dir = asset_dir("validation_issues");
+        let mut transform = Transform::from_xyz(f32::NAN, 0.0, 0.0);
+        transform.rotation = Quat::from_xyzw(0.0, f32::INFINITY, 0.0, 1.0);
+        let scene = scene(vec![
+            vec![Box::new(transform)],
+            vec![
+                Box::new(image("textures/missing.png")),
+                Box::new(Handle::<Mesh>::weak(HandleId::random::<Mesh>())),
+            ],
+        ]);
+        let issues = validate_scene(&scene, &dir);
+        let mut messages = messages(&issues);
+        // errors come first, in the order of the scene
+        messages[..3].sort_by_key(|(_, _, component, _)| *component);
+        assert_eq!(
+            messages,
+            [
+                (
+                    Severity::Error,
+                    1,
+                    "Handle<Image>.id",
+                    "refers to an asset whose file is missing"
+                ),
+                (
+                    Severity::Error,
+                    0,
+                    "Transform.rotation",
+                    "Quat(0.0, inf, 0.0, 1.0) isn't a finite number"
+                ),
+                (
+                    Severity::Error,
+                    0,
+                    "Transform.translation.x",
+                    "NaN isn't a finite number"
+                ),
+                (
+                    Severity::Warning,
+                    1,
+                    "Handle<Mesh>.id",
+                    "refers to an asset made while running, which isn't saved with the scene"
+                ),
+            ]
+        );
+        assert_eq!(
+            issues[3].to_string(),
+            format!(
+                "warning: entity {:?}, Handle<Mesh>.id: refers to an asset made while running, \
+                 which isn't saved with the scene",
+                Entity::from_raw(1)
+            )
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,224 @@
+use bevy::{prelude::*, render::camera::ScalingMode};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera::{self, EditorCameraController},
+    EditorOnly, EguiWindow, MainCamera, UiState,
+};
+
+pub struct EditorViewsPlugin;
+impl Plugin for EditorViewsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<EditorView>()
+            .register_type::<AxisView>()
+            .add_systems(
+                Update,
+                sync_axis_views.after(camera::editor_camera_controller),
+            );
+    }
+}
+
+/// Area of the game view tab a camera renders into, in egui coordinates. Set every frame the tab
+/// is shown and [`egui::Rect::NOTHING`] otherwise.
+#[derive(Debug, Component)]
+pub struct EditorViewport {
+    pub rect: egui::Rect,
+}
+
+impl Default for EditorViewport {
+    fn default() -> Self {
+        Self {
+            rect: egui::Rect::NOTHING,
+        }
+    }
+}
+
+impl EditorViewport {
+    /// Whether the cursor, in logical window coordinates, is over the view.
+    pub fn contains_cursor(&self, cursor: Vec2, egui_scale_factor: f64) -> bool {
+        let cursor = cursor / egui_scale_factor as f32;
+        self.rect.contains(egui::pos2(cursor.x, cursor.y))
+    }
+}
+
+/// Camera of an additional game view tab, spawned by the editor next to the [`MainCamera`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Component, Reflect)]
+#[reflect(Component)]
+pub struct EditorView(pub u32);
+
+/// The camera rendering into `window`, if it is a game view.
+pub(crate) fn view_camera(world: &mut World, window: &EguiWindow) -> Option<Entity> {
+    match *window {
+        EguiWindow::GameView => world
+            .query_filtered::<Entity, With<MainCamera>>()
+            .get_single(world)
+            .ok(),
+        EguiWindow::View(id) => world
+            .query_filtered::<(Entity, &EditorView), Without<AxisView>>()
+            .iter(world)
+            .find(|(_, view)| view.0 == id)
+            .map(|(entity, _)| entity),
+        EguiWindow::Axis(axis) => world
+            .query::<(Entity, &AxisView)>()
+            .iter(world)
+            .find(|(_, view)| **view == axis)
+            .map(|(entity, _)| entity),
+        _ => None,
+    }
+}
+
+fn next_view_id(world: &mut World) -> u32 {
+    world
+        .query::<&EditorView>()
+        .iter(world)
+        .map(|view| view.0 + 1)
+        .max()
+        .unwrap_or(1)
+}
+
+fn main_camera(world: &mut World) -> Option<(Transform, Option<Projection>, Vec3)> {
+    world
+        .query_filtered::<(&Transform, Option<&Projection>, &EditorCameraController), With<MainCamera>>()
+        .get_single(world)
+        .ok()
+        .map(|(transform, projection, controller)| {
+            (*transform, projection.cloned(), controller.focus)
+        })
+}
+
+fn view_camera_bundle(id: u32, transform: Transform, projection: Projection) -> Camera3dBundle {
+    Camera3dBundle {
+        transform,
+        projection,
+        camera: Camera {
+            // rendered after the main camera, into its own part of the window
+            order: id as isize,
+            is_active: false,
+            ..default()
+        },
+        ..default()
+    }
+}
+
+/// Spawns the camera for a new game view, copying the [`MainCamera`], and returns the view's id.
+pub fn spawn_view(world: &mut World) -> u32 {
+    let id = next_view_id(world);
+    let (transform, projection, focus) = main_camera(world).unwrap_or_default();
+    world.spawn((
+        Name::new(format!("Editor View {id}")),
+        view_camera_bundle(id, transform, projection.unwrap_or_default()),
+        EditorCameraController { focus, ..default() },
+        EditorViewport::default(),
+        EditorView(id),
+        EditorOnly,
+    ));
+    id
+}
+
+/// Orthographic view along a world axis. Its camera keeps looking at the orbit focus of the
+/// [`MainCamera`] and can only be zoomed.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Component, Reflect, Serialize, Deserialize,
+)]
+#[reflect(Component)]
+pub enum AxisView {
+    #[default]
+    Top,
+    Front,
+    Right,
+}
+
+/// Distance from the focus at which axis views are placed, far enough to not clip the scene.
+const AXIS_VIEW_DISTANCE: f32 = 500.0;
+
+impl AxisView {
+    pub const ALL: [AxisView; 3] = [AxisView::Top, AxisView::Front, AxisView::Right];
+
+    /// Direction from the focus to the camera, and the camera's up direction.
+    fn direction(self) -> (Vec3, Vec3) {
+        match self {
+            AxisView::Top => (Vec3::Y, Vec3::NEG_Z),
+            AxisView::Front => (Vec3::Z, Vec3::Y),
+            AxisView::Right => (Vec3::X, Vec3::Y),
+        }
+    }
+
+    fn transform(self, focus: Vec3) -> Transform {
+        let (direction, up) = self.direction();
+        Transform::from_translation(focus + direction * AXIS_VIEW_DISTANCE).looking_at(focus, up)
+    }
+}
+
+/// Spawns the camera of the orthographic `axis` view, unless it already exists.
+pub fn spawn_axis_view(world: &mut World, axis: AxisView) {
+    if view_camera(world, &EguiWindow::Axis(axis)).is_some() {
+        return;
+    }
+    let id = next_view_id(world);
+    let focus = main_camera(world)
+        .map(|(.., focus)| focus)
+        .unwrap_or_default();
+    let projection = Projection::Orthographic(OrthographicProjection {
+        far: AXIS_VIEW_DISTANCE * 2.0,
+        scaling_mode: ScalingMode::FixedVertical(10.0),
+        ..default()
+    });
+    world.spawn((
+        Name::new(format!("Editor {axis:?} View")),
+        view_camera_bundle(id, axis.transform(focus), projection),
+        EditorCameraController { focus, ..default() },
+        EditorViewport::default(),
+        EditorView(id),
+        EditorOnly,
+        axis,
+    ));
+}
+
+/// Opens the tab of the orthographic `axis` view, or focuses it if it's already open.
+pub fn open_axis_view(world: &mut World, axis: AxisView) {
+    spawn_axis_view(world, axis);
+    world
+        .resource_mut::<UiState>()
+        .focus_window(EguiWindow::Axis(axis));
+}
+
+/// Keeps axis views looking along their axis at the main camera's focus, undoing any rotation or
+/// movement from the camera controller.
+fn sync_axis_views(
+    main: Query<&EditorCameraController, (With<MainCamera>, Without<AxisView>)>,
+    mut views: Query<(&mut Transform, &mut EditorCameraController, &AxisView)>,
+) {
+    let Ok(main) = main.get_single() else {
+        return;
+    };
+    for (mut transform, mut controller, axis) in &mut views {
+        controller.focus = main.focus;
+        *transform = axis.transform(main.focus);
+    }
+}
+
+/// Despawns the cameras of game views whose tab was closed.
+pub(crate) fn despawn_closed_views(world: &mut World, ui_state: &UiState) {
+    let closed: Vec<_> = world
+        .query::<(Entity, &EditorView, Option<&AxisView>)>()
+        .iter(world)
+        .filter(|(_, view, axis)| {
+            let window = match axis {
+                Some(&axis) => EguiWindow::Axis(axis),
+                None => EguiWindow::View(view.0),
+            };
+            !ui_state.is_open(&window)
+        })
+        .map(|(entity, ..)| entity)
+        .collect();
+    for camera in closed {
+        world.entity_mut(camera).despawn_recursive();
+    }
+}
+
+/// Opens a new game view tab next to the focused tab.
+pub fn open_view(world: &mut World) {
+    let id = spawn_view(world);
+    let mut ui_state = world.resource_mut::<UiState>();
+    ui_state.tree.push_to_focused_leaf(EguiWindow::View(id));
+}
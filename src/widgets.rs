@@ -0,0 +1,136 @@
+use std::any::{Any, TypeId};
+
+use bevy::{prelude::*, reflect::GetTypeRegistration};
+use bevy_inspector_egui::{
+    inspector_egui_impls::InspectorEguiImpl, reflect_inspector::InspectorUi,
+};
+
+/// A widget editing values of a type in the inspector, in place of the fields shown from its
+/// reflection.
+///
+/// ```ignore
+/// impl EditorWidget for ItemId {
+///     fn widget(&mut self, ui: &mut egui::Ui, cx: &mut WidgetContext) -> bool {
+///         let Some(items) = cx.resource::<ItemDatabase>() else {
+///             return false;
+///         };
+///         let mut changed = false;
+///         egui::ComboBox::from_id_source(cx.id)
+///             .selected_text(items.name(*self))
+///             .show_ui(ui, |ui| {
+///                 for item in items.iter() {
+///                     changed |= ui.selectable_value(self, item.id, &item.name).changed();
+///                 }
+///             });
+///         changed
+///     }
+/// }
+/// ```
+pub trait EditorWidget: Reflect + Clone + Sized {
+    /// Shows the widget, returning whether the value changed.
+    fn widget(&mut self, ui: &mut egui::Ui, cx: &mut WidgetContext) -> bool;
+
+    /// Shows the value where it can't be edited, like in play mode.
+    fn widget_readonly(&self, ui: &mut egui::Ui, cx: &mut WidgetContext) {
+        let mut value = self.clone();
+        ui.add_enabled_ui(false, |ui| {
+            value.widget(ui, cx);
+        });
+    }
+}
+
+/// What a widget can reach besides its value.
+pub struct WidgetContext<'a, 'b, 'c> {
+    /// An id unique to the value shown, for the state of the widget.
+    pub id: egui::Id,
+    env: &'a mut InspectorUi<'b, 'c>,
+}
+
+impl WidgetContext<'_, '_, '_> {
+    /// The resource `R`, unless it's the value being inspected or the inspector has no access to
+    /// the world.
+    pub fn resource<R: Resource>(&mut self) -> Option<Mut<'_, R>> {
+        self.env
+            .context
+            .world
+            .as_mut()?
+            .get_resource_mut::<R>()
+            .ok()
+    }
+
+    /// Shows `value` like the inspector would without the widget, for the fields of a widget.
+    pub fn reflect_ui(&mut self, value: &mut dyn Reflect, ui: &mut egui::Ui, id: egui::Id) -> bool {
+        self.env.ui_for_reflect_with_options(value, ui, id, &())
+    }
+}
+
+fn widget_ui<T: EditorWidget>(
+    value: &mut dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) -> bool {
+    let value = value.downcast_mut::<T>().unwrap();
+    value.widget(ui, &mut WidgetContext { id, env: &mut env })
+}
+
+fn widget_ui_readonly<T: EditorWidget>(
+    value: &dyn Any,
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+) {
+    let value = value.downcast_ref::<T>().unwrap();
+    value.widget_readonly(ui, &mut WidgetContext { id, env: &mut env });
+}
+
+/// Shows the widget for the first value, setting all of them to it when edited.
+fn widget_ui_many<T: EditorWidget>(
+    ui: &mut egui::Ui,
+    _: &dyn Any,
+    id: egui::Id,
+    mut env: InspectorUi<'_, '_>,
+    values: &mut [&mut dyn Reflect],
+    projector: &dyn Fn(&mut dyn Reflect) -> &mut dyn Reflect,
+) -> bool {
+    let Some(first) = values.first_mut() else {
+        return false;
+    };
+    let mut value = projector(*first).downcast_ref::<T>().unwrap().clone();
+    if !value.widget(ui, &mut WidgetContext { id, env: &mut env }) {
+        return false;
+    }
+    for other in values.iter_mut() {
+        *projector(*other).downcast_mut::<T>().unwrap() = value.clone();
+    }
+    true
+}
+
+pub trait AddEditorWidget {
+    /// Shows values of `T` with [`EditorWidget::widget`] in the inspector, wherever they are
+    /// found: as components, resources, assets or fields of them.
+    ///
+    /// ```ignore
+    /// app.add_editor_widget::<ItemId>();
+    /// ```
+    fn add_editor_widget<T: EditorWidget + GetTypeRegistration>(&mut self) -> &mut Self;
+}
+
+impl AddEditorWidget for App {
+    fn add_editor_widget<T: EditorWidget + GetTypeRegistration>(&mut self) -> &mut Self {
+        self.register_type::<T>();
+        self.world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .get_mut(TypeId::of::<T>())
+            .unwrap()
+            .insert(InspectorEguiImpl::new(
+                widget_ui::<T>,
+                widget_ui_readonly::<T>,
+                widget_ui_many::<T>,
+            ));
+        self
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashSet;
+
+use bevy::{
+    pbr::wireframe::{Wireframe, WireframeConfig, WireframePlugin},
+    prelude::*,
+    render::{renderer::RenderDevice, settings::WgpuFeatures},
+};
+
+use crate::{EditorResource, UiState};
+
+pub struct EditorWireframePlugin;
+impl Plugin for EditorWireframePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ViewportWireframe>()
+            .add_systems(PostUpdate, apply_wireframe_mode);
+    }
+
+    // the render device only exists once the render plugin finished
+    fn finish(&self, app: &mut App) {
+        if app.is_plugin_added::<WireframePlugin>() {
+            return;
+        }
+        let supported = app
+            .world
+            .get_resource::<RenderDevice>()
+            .is_some_and(|device| device.features().contains(WgpuFeatures::POLYGON_MODE_LINE));
+        if !supported {
+            info!("Wireframes are unavailable, enable WgpuFeatures::POLYGON_MODE_LINE to use them");
+            return;
+        }
+        app.add_plugins(WireframePlugin);
+        // plugins added while finishing don't get finished by the app
+        WireframePlugin.finish(app);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WireframeMode {
+    #[default]
+    Off,
+    Selection,
+    All,
+}
+
+#[derive(Resource, Default)]
+pub struct ViewportWireframe {
+    pub mode: WireframeMode,
+    /// Mode applied to [`WireframeConfig`] last, so a global wireframe set up by the game is only
+    /// overwritten when the mode changes.
+    applied: WireframeMode,
+}
+
+/// Marks [`Wireframe`]s inserted by the editor, which are removed again when no longer needed
+/// and never saved.
+#[derive(Component)]
+pub(crate) struct EditorWireframe;
+
+#[allow(clippy::too_many_arguments)]
+fn apply_wireframe_mode(
+    mut commands: Commands,
+    editor: Res<EditorResource>,
+    ui_state: Res<UiState>,
+    mut wireframe: ResMut<ViewportWireframe>,
+    config: Option<ResMut<WireframeConfig>>,
+    inserted: Query<Entity, With<EditorWireframe>>,
+    meshes: Query<(), (With<Handle<Mesh>>, Without<Wireframe>)>,
+    children: Query<&Children>,
+) {
+    let Some(mut config) = config else {
+        return;
+    };
+    let mode = match editor.0 {
+        true => wireframe.mode,
+        false => WireframeMode::Off,
+    };
+    if mode != wireframe.applied {
+        if mode == WireframeMode::All || wireframe.applied == WireframeMode::All {
+            config.global = mode == WireframeMode::All;
+        }
+        wireframe.applied = mode;
+    }
+
+    let mut wanted = HashSet::new();
+    if mode == WireframeMode::Selection {
+        let mut stack = ui_state.selected_entities.as_slice().to_vec();
+        while let Some(entity) = stack.pop() {
+            if wanted.insert(entity) {
+                stack.extend(children.get(entity).into_iter().flatten());
+            }
+        }
+    }
+    for entity in &inserted {
+        if !wanted.remove(&entity) {
+            commands
+                .entity(entity)
+                .remove::<(Wireframe, EditorWireframe)>();
+        }
+    }
+    for entity in wanted {
+        if meshes.contains(entity) {
+            commands.entity(entity).insert((Wireframe, EditorWireframe));
+        }
+    }
+}
+
+pub fn wireframe_toolbar(ui: &mut egui::Ui, world: &mut World) {
+    let available = world.contains_resource::<WireframeConfig>();
+    let mut wireframe = world.resource_mut::<ViewportWireframe>();
+    ui.add_enabled_ui(available, |ui| {
+        egui::ComboBox::from_id_source("viewport_wireframe")
+            .selected_text(match wireframe.mode {
+                WireframeMode::Off => "Shaded",
+                WireframeMode::Selection => "Wireframe: Selection",
+                WireframeMode::All => "Wireframe: All",
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut wireframe.mode, WireframeMode::Off, "Shaded");
+                ui.selectable_value(
+                    &mut wireframe.mode,
+                    WireframeMode::Selection,
+                    "Wireframe: Selection",
+                );
+                ui.selectable_value(&mut wireframe.mode, WireframeMode::All, "Wireframe: All");
+            });
+    })
+    .response
+    .on_disabled_hover_text("Requires WgpuFeatures::POLYGON_MODE_LINE");
+}